@@ -1,7 +1,7 @@
 // Extensión de StandardMaterial: aplica una paleta tonal por voxel en el
 // fragment shader. El color base llega en el vertex color RGB (uniforme por quad,
-// así el greedy meshing puede fusionar) y el vertex ALPHA lleva el discriminante
-// de VoxelType (id/255), que indexa el rango tonal del material en `spreads`.
+// así el greedy meshing puede fusionar) y el vertex ALPHA lleva el id del
+// material (id/255), que indexa su rango tonal y su brillo en `spreads`.
 //
 // Para cada fragment se deriva la CELDA sólida desde la posición mundial
 // (retrocediendo medio voxel por la normal), se hashea a un índice de tono y se
@@ -16,29 +16,11 @@
 
 const VOXEL_SIZE: f32 = 0.1; // = core::constants::VOXEL_SIZE
 
-// Rango tonal por material, indexado por el discriminante de VoxelType (llega en
-// el vertex alpha). Cada entrada: (dark_mul, light_mul, steps, 0); steps < 1 =
-// material plano. Hardcodeado aquí (no un uniform) porque el StandardMaterial
-// bindless de Bevy 0.17 descarta bindings de extensión en el grupo 2.
-// ESPEJO de `src/voxel/palette.rs::palette_of` — mantener en sync.
-var<private> SPREADS: array<vec4<f32>, 16> = array<vec4<f32>, 16>(
-    vec4<f32>(0.0, 0.0, 0.0, 0.0),   // 0  Air
-    vec4<f32>(0.80, 1.20, 4.0, 0.0), // 1  Dirt
-    vec4<f32>(0.60, 1.35, 6.0, 0.0), // 2  Stone
-    vec4<f32>(0.70, 1.25, 5.0, 0.0), // 3  Wood
-    vec4<f32>(0.0, 0.0, 0.0, 0.0),   // 4  Metal
-    vec4<f32>(0.0, 0.0, 0.0, 0.0),   // 5  Grass (color baked en CPU)
-    vec4<f32>(0.88, 1.10, 4.0, 0.0), // 6  Sand
-    vec4<f32>(0.80, 1.15, 4.0, 0.0), // 7  Leaves
-    vec4<f32>(0.0, 0.0, 0.0, 0.0),   // 8  Foliage
-    vec4<f32>(0.80, 1.20, 4.0, 0.0), // 9  Bush
-    vec4<f32>(0.80, 1.15, 4.0, 0.0), // 10 PineNeedles
-    vec4<f32>(0.80, 1.15, 4.0, 0.0), // 11 SmallLeaves
-    vec4<f32>(0.70, 1.25, 5.0, 0.0), // 12 PineWood
-    vec4<f32>(0.0, 0.0, 0.0, 0.0),   // 13
-    vec4<f32>(0.0, 0.0, 0.0, 0.0),   // 14
-    vec4<f32>(0.0, 0.0, 0.0, 0.0),   // 15
-);
+// Rango tonal y brillo por material, indexado por su id (llega en el vertex
+// alpha). Cada entrada: (dark_mul, light_mul, steps, glow); steps < 1 = material
+// plano, glow > 0 = emite luz (la lava). Lo sube `PaletteExtension` desde el
+// registro de materiales (`assets/data/materials.ron`).
+@group(#{MATERIAL_BIND_GROUP}) @binding(100) var<storage, read> spreads: array<vec4<f32>>;
 
 fn hash01(x: i32, z: i32) -> f32 {
     var h: u32 = u32(x) * 0x9e3779b9u;
@@ -65,9 +47,9 @@ fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> Fragment
     var pbr_input = pbr_input_from_standard_material(in, is_front);
 
 #ifdef VERTEX_COLORS
-    // El vertex alpha lleva el discriminante de VoxelType (id/255).
-    let id = min(u32(round(in.color.a * 255.0)), 15u);
-    let spread = SPREADS[id]; // (dark_mul, light_mul, steps, 0)
+    // El vertex alpha lleva el id del material (id/255).
+    let id = min(u32(round(in.color.a * 255.0)), arrayLength(&spreads) - 1u);
+    let spread = spreads[id]; // (dark_mul, light_mul, steps, glow)
     let steps = u32(spread.z);
     if (steps >= 1u) {
         // Centro de la celda sólida: retrocede medio voxel por la normal, trunca.
@@ -77,6 +59,9 @@ fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> Fragment
         let mul = step_multiplier(idx, steps, spread.x, spread.y);
         pbr_input.material.base_color = vec4<f32>(pbr_input.material.base_color.rgb * mul, pbr_input.material.base_color.a);
     }
+    if (spread.w > 0.0) {
+        pbr_input.material.emissive = vec4<f32>(pbr_input.material.base_color.rgb * spread.w, 1.0);
+    }
 #endif
 
     var out: FragmentOutput;
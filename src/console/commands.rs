@@ -0,0 +1,509 @@
+//! Comandos de serie de la consola.
+//!
+//! Actúan sobre el `PrimaryPlayer` (el que ve la UI) y pasan por los mismos
+//! recursos que el juego: la seed de `WorldSeed`, el `Inventory` del jugador,
+//! las herramientas como `Tool`, las ediciones por `VoxelWorld` y los chunks
+//! por `ChunkMap`. Las coordenadas van en metros y `~` es relativo a la
+//! posición del jugador (`~`, `~2`, `~-1.5`).
+//!
+//! Jugando en red el mundo y los inventarios son del servidor: `/fill`,
+//! `/sphere`, `/replace`, `/regen`, `/give` y `/tool` no se pueden usar (el
+//! resto solo toca lo local).
+
+use super::registry::{CommandRegistry, ConsoleCommand};
+use crate::core::constants::VOXEL_SIZE;
+use crate::core::{Inventory, ItemStack, WorldSeed, INVENTORY_SLOTS, MAX_VOXEL_STACK};
+use crate::net::NetClient;
+use crate::player::PrimaryPlayer;
+use crate::voxel::{
+    materials, world_pos_to_chunk_pos, world_to_voxel, ActiveWorld, BaseChunk, ChunkMap,
+    DirtyChunk, Tool, ToolTier, ToolType, VoxelDiffs, VoxelType, VoxelWorld, WorldSaveState,
+};
+use bevy::ecs::system::SystemState;
+use bevy::math::I64Vec3;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+
+/// Voxels como mucho por `/fill` y `/replace` (un cubo de 3.2 m de lado).
+pub const MAX_FILL_VOXELS: i64 = 32 * 32 * 32;
+
+/// Radio máximo (metros) de `/sphere`: su caja cabe en [`MAX_FILL_VOXELS`].
+pub const MAX_SPHERE_RADIUS: f32 = 1.5;
+
+const TP_USAGE: &str = "/tp <x> <y> <z>";
+const GIVE_USAGE: &str = "/give <material> [cantidad]";
+const TOOL_USAGE: &str = "/tool <pickaxe|axe|shovel|hoe|shears> [wood|stone|metal]";
+const FILL_USAGE: &str = "/fill <x1> <y1> <z1> <x2> <y2> <z2> <material>";
+const SPHERE_USAGE: &str = "/sphere <x> <y> <z> <radio> <material>";
+const REPLACE_USAGE: &str = "/replace <x1> <y1> <z1> <x2> <y2> <z2> <de> <a>";
+const REGEN_USAGE: &str = "/regen chunk";
+
+/// Los comandos con los que arranca el [`CommandRegistry`].
+pub fn builtin_commands() -> Vec<ConsoleCommand> {
+    vec![
+        ConsoleCommand {
+            name: "help",
+            usage: "/help",
+            help: "Lista los comandos",
+            run: help,
+        },
+        ConsoleCommand {
+            name: "tp",
+            usage: TP_USAGE,
+            help: "Teletransporta al jugador",
+            run: teleport,
+        },
+        ConsoleCommand {
+            name: "seed",
+            usage: "/seed",
+            help: "Muestra la seed del mundo",
+            run: seed,
+        },
+        ConsoleCommand {
+            name: "give",
+            usage: GIVE_USAGE,
+            help: "Da bloques de un material al inventario",
+            run: give,
+        },
+        ConsoleCommand {
+            name: "tool",
+            usage: TOOL_USAGE,
+            help: "Da una herramienta nueva (de metal si no se dice)",
+            run: tool,
+        },
+        ConsoleCommand {
+            name: "time",
+            usage: "/time",
+            help: "Tiempo jugado en este mundo",
+            run: time,
+        },
+        ConsoleCommand {
+            name: "fill",
+            usage: FILL_USAGE,
+            help: "Rellena una caja con un material (air = vaciar)",
+            run: fill,
+        },
+        ConsoleCommand {
+            name: "sphere",
+            usage: SPHERE_USAGE,
+            help: "Rellena una esfera con un material (air = vaciar)",
+            run: sphere,
+        },
+        ConsoleCommand {
+            name: "replace",
+            usage: REPLACE_USAGE,
+            help: "Cambia un material por otro dentro de una caja cargada",
+            run: replace,
+        },
+        ConsoleCommand {
+            name: "regen",
+            usage: REGEN_USAGE,
+            help: "Devuelve el chunk del jugador al terreno de la seed",
+            run: regen,
+        },
+    ]
+}
+
+// ============================================================================
+// ARGUMENTOS
+// ============================================================================
+
+fn usage(usage: &str) -> String {
+    format!("Uso: {}", usage)
+}
+
+/// Coordenada en metros: un número, o `~` / `~n` relativo a `current`.
+pub fn parse_coord(arg: &str, current: f32) -> Result<f32, String> {
+    let (base, offset) = match arg.strip_prefix('~') {
+        Some("") => return Ok(current),
+        Some(offset) => (current, offset),
+        None => (0.0, arg),
+    };
+    offset
+        .parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .map(|v| base + v)
+        .ok_or_else(|| format!("Coordenada no válida: {}", arg))
+}
+
+fn parse_position(args: [&str; 3], current: Vec3) -> Result<Vec3, String> {
+    Ok(Vec3::new(
+        parse_coord(args[0], current.x)?,
+        parse_coord(args[1], current.y)?,
+        parse_coord(args[2], current.z)?,
+    ))
+}
+
+/// Material del registro por nombre, sin distinguir mayúsculas.
+fn parse_material(name: &str) -> Result<VoxelType, String> {
+    materials()
+        .iter()
+        .find(|(_, material)| material.name.eq_ignore_ascii_case(name))
+        .map(|(voxel_type, _)| voxel_type)
+        .ok_or_else(|| format!("Material desconocido: {}", name))
+}
+
+fn parse_tool_type(name: &str) -> Result<ToolType, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "pickaxe" => Ok(ToolType::Pickaxe),
+        "axe" => Ok(ToolType::Axe),
+        "shovel" => Ok(ToolType::Shovel),
+        "hoe" => Ok(ToolType::Hoe),
+        "shears" => Ok(ToolType::Shears),
+        _ => Err(format!("Herramienta desconocida: {}", name)),
+    }
+}
+
+fn parse_tier(name: &str) -> Result<ToolTier, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "wood" => Ok(ToolTier::Wood),
+        "stone" => Ok(ToolTier::Stone),
+        "metal" => Ok(ToolTier::Metal),
+        _ => Err(format!("Material de herramienta desconocido: {}", name)),
+    }
+}
+
+/// `1h 02m 03s` (sin las horas si no llega a una).
+fn format_duration(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else {
+        format!("{}m {:02}s", m, s)
+    }
+}
+
+// ============================================================================
+// ACCESO AL MUNDO
+// ============================================================================
+
+fn primary_player(world: &mut World) -> Result<Entity, String> {
+    world
+        .query_filtered::<Entity, With<PrimaryPlayer>>()
+        .single(world)
+        .map_err(|_| "No hay jugador en el mundo".to_string())
+}
+
+fn player_position(world: &mut World) -> Result<Vec3, String> {
+    let player = primary_player(world)?;
+    Ok(world.get::<Transform>(player).map_or(Vec3::ZERO, |t| t.translation))
+}
+
+fn player_inventory(world: &mut World) -> Result<Mut<'_, Inventory>, String> {
+    let player = primary_player(world)?;
+    world
+        .get_mut::<Inventory>(player)
+        .ok_or_else(|| "El jugador no tiene inventario".to_string())
+}
+
+/// Error si se juega en red (ni el mundo ni el inventario se editan desde aquí).
+fn offline_only(world: &World) -> Result<(), String> {
+    if world.contains_resource::<NetClient>() {
+        return Err("Jugando en red el mundo y el inventario son del servidor".to_string());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// COMANDOS
+// ============================================================================
+
+fn help(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let registry = world.resource::<CommandRegistry>();
+    Ok(registry
+        .iter()
+        .map(|c| format!("{} - {}", c.usage, c.help))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn teleport(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let &[x, y, z] = args else {
+        return Err(usage(TP_USAGE));
+    };
+    let target = parse_position([x, y, z], player_position(world)?)?;
+    let player = primary_player(world)?;
+    let mut entity = world.entity_mut(player);
+    if let Some(mut transform) = entity.get_mut::<Transform>() {
+        transform.translation = target;
+    }
+    if let Some(mut velocity) = entity.get_mut::<Velocity>() {
+        *velocity = Velocity::zero();
+    }
+    Ok(format!("Teletransportado a {:.1} {:.1} {:.1}", target.x, target.y, target.z))
+}
+
+fn seed(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    Ok(format!("Seed: {}", world.resource::<WorldSeed>().0))
+}
+
+fn give(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let (name, count) = match *args {
+        [name] => (name, "1"),
+        [name, count] => (name, count),
+        _ => return Err(usage(GIVE_USAGE)),
+    };
+    offline_only(world)?;
+    let voxel_type = parse_material(name)?;
+    if voxel_type == VoxelType::Air {
+        return Err("El aire no se puede guardar".to_string());
+    }
+    let max = MAX_VOXEL_STACK * INVENTORY_SLOTS as u32;
+    let count = count
+        .parse::<u32>()
+        .ok()
+        .filter(|n| (1..=max).contains(n))
+        .ok_or_else(|| format!("Cantidad no válida: {} (de 1 a {})", count, max))?;
+
+    let leftover = player_inventory(world)?.add(voxel_type, count);
+    let name = &voxel_type.properties().name;
+    match leftover {
+        0 => Ok(format!("+{} {}", count, name)),
+        l if l == count => Err("El inventario está lleno".to_string()),
+        l => Ok(format!("+{} {} (no cabían {})", count - l, name, l)),
+    }
+}
+
+fn tool(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let (tool_type, tier) = match *args {
+        [tool_type] => (parse_tool_type(tool_type)?, ToolTier::Metal),
+        [tool_type, tier] => (parse_tool_type(tool_type)?, parse_tier(tier)?),
+        _ => return Err(usage(TOOL_USAGE)),
+    };
+    offline_only(world)?;
+    let stack = ItemStack::tool(Tool::new(tool_type, tier));
+    if player_inventory(world)?.insert(stack) > 0 {
+        return Err("El inventario está lleno".to_string());
+    }
+    Ok(format!("+{:?} de {}", tool_type, tier.name()))
+}
+
+fn time(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    // El mundo no tiene ciclo de día y noche: el tiempo es el jugado
+    let played = match world.get_resource::<ActiveWorld>() {
+        Some(active) => {
+            let unsaved = world.resource::<WorldSaveState>().unsaved_play_time();
+            active.meta.play_time_secs + unsaved as u64
+        }
+        // En red no hay mundo en disco: lo que va de sesión
+        None => world.resource::<Time<Real>>().elapsed_secs() as u64,
+    };
+    Ok(format!(
+        "Tiempo jugado: {} (sin ciclo de día y noche)",
+        format_duration(played)
+    ))
+}
+
+/// Caja de voxels `(min, max)` entre dos esquinas en metros, como mucho de
+/// [`MAX_FILL_VOXELS`].
+fn parse_box(world: &mut World, corners: [&str; 6]) -> Result<(IVec3, IVec3), String> {
+    let [x1, y1, z1, x2, y2, z2] = corners;
+    let current = player_position(world).unwrap_or(Vec3::ZERO);
+    let a = world_to_voxel(parse_position([x1, y1, z1], current)?).2;
+    let b = world_to_voxel(parse_position([x2, y2, z2], current)?).2;
+    let (min, max) = (a.min(b), a.max(b));
+
+    // En i64: con coordenadas enormes el tamaño no cabe en i32
+    let size = max.as_i64vec3() - min.as_i64vec3() + I64Vec3::ONE;
+    if size.max_element() > MAX_FILL_VOXELS || size.element_product() > MAX_FILL_VOXELS {
+        return Err(format!(
+            "Caja demasiado grande: {} voxels (máximo {})",
+            size.x.saturating_mul(size.y).saturating_mul(size.z),
+            MAX_FILL_VOXELS
+        ));
+    }
+    Ok((min, max))
+}
+
+fn fill(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let &[x1, y1, z1, x2, y2, z2, material] = args else {
+        return Err(usage(FILL_USAGE));
+    };
+    offline_only(world)?;
+    let voxel_type = parse_material(material)?;
+    let (min, max) = parse_box(world, [x1, y1, z1, x2, y2, z2])?;
+
+    let mut state = SystemState::<VoxelWorld>::new(world);
+    let changed = state.get_mut(world).fill_box(min, max, voxel_type);
+    state.apply(world);
+    Ok(format!(
+        "{} voxels cambiados a {}",
+        changed,
+        voxel_type.properties().name
+    ))
+}
+
+fn sphere(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let &[x, y, z, radius, material] = args else {
+        return Err(usage(SPHERE_USAGE));
+    };
+    offline_only(world)?;
+    let voxel_type = parse_material(material)?;
+    let current = player_position(world).unwrap_or(Vec3::ZERO);
+    let center = world_to_voxel(parse_position([x, y, z], current)?).2;
+    let radius: f32 = radius
+        .parse()
+        .map_err(|_| format!("Radio no válido: {}", radius))?;
+    if !(0.0..=MAX_SPHERE_RADIUS).contains(&radius) {
+        return Err(format!("El radio va de 0 a {} m", MAX_SPHERE_RADIUS));
+    }
+
+    let mut state = SystemState::<VoxelWorld>::new(world);
+    let changed = state
+        .get_mut(world)
+        .fill_sphere(center, radius / VOXEL_SIZE, voxel_type);
+    state.apply(world);
+    Ok(format!(
+        "{} voxels cambiados a {}",
+        changed,
+        voxel_type.properties().name
+    ))
+}
+
+fn replace(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let &[x1, y1, z1, x2, y2, z2, from, to] = args else {
+        return Err(usage(REPLACE_USAGE));
+    };
+    offline_only(world)?;
+    let (from, to) = (parse_material(from)?, parse_material(to)?);
+    let (min, max) = parse_box(world, [x1, y1, z1, x2, y2, z2])?;
+
+    let mut state = SystemState::<VoxelWorld>::new(world);
+    let changed = state.get_mut(world).replace(min, max, from, to);
+    state.apply(world);
+    Ok(format!(
+        "{} voxels de {} cambiados a {}",
+        changed,
+        from.properties().name,
+        to.properties().name
+    ))
+}
+
+fn regen(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let &["chunk"] = args else {
+        return Err(usage(REGEN_USAGE));
+    };
+    offline_only(world)?;
+    let chunk_pos = world_pos_to_chunk_pos(player_position(world)?);
+    let chunk_map = world.resource::<ChunkMap>();
+    let entity = chunk_map
+        .chunks
+        .get(&chunk_pos)
+        .copied()
+        .filter(|&entity| world.get::<BaseChunk>(entity).is_some())
+        .ok_or_else(|| format!("El chunk {} no está cargado en detalle", chunk_pos))?;
+    // El chunk y los vecinos de cada cara (su costura cambia)
+    let neighbours: Vec<Entity> = [IVec3::X, IVec3::NEG_X, IVec3::Y, IVec3::NEG_Y, IVec3::Z, IVec3::NEG_Z]
+        .into_iter()
+        .filter_map(|offset| chunk_map.chunks.get(&(chunk_pos + offset)).copied())
+        .chain([entity])
+        .collect();
+
+    // Sin diffs vuelve a ser lo que sale de la seed (el guardado lo olvida)
+    let seed = world.resource::<WorldSeed>().0;
+    let discarded = world.resource_mut::<VoxelDiffs>().discard_chunk(chunk_pos);
+    world.entity_mut(entity).insert(BaseChunk::new(chunk_pos, seed));
+    for neighbour in neighbours {
+        if let Ok(mut neighbour) = world.get_entity_mut(neighbour) {
+            neighbour.insert(DirtyChunk);
+        }
+    }
+    Ok(format!(
+        "Chunk {} regenerado ({} voxels editados descartados)",
+        chunk_pos, discarded
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{PendingVoxelEdits, VoxelEdited};
+    use bevy::ecs::message::Messages;
+    use std::collections::HashMap;
+
+    fn test_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(WorldSeed(42));
+        world.insert_resource(ChunkMap {
+            chunks: HashMap::new(),
+        });
+        world.init_resource::<VoxelDiffs>();
+        world.init_resource::<PendingVoxelEdits>();
+        world.init_resource::<Messages<VoxelEdited>>();
+        world.init_resource::<CommandRegistry>();
+        world.spawn((
+            PrimaryPlayer,
+            Inventory::default(),
+            Transform::from_xyz(1.0, 2.0, 3.0),
+        ));
+        world
+    }
+
+    fn run(world: &mut World, line: &str) -> Result<String, String> {
+        let (name, args) = super::super::parse_command(line).unwrap();
+        let command = *world.resource::<CommandRegistry>().get(name).unwrap();
+        (command.run)(world, &args)
+    }
+
+    #[test]
+    fn give_and_tool_go_to_the_player_inventory() {
+        let mut world = test_world();
+        assert!(run(&mut world, "/give stone 70").is_ok());
+        assert!(run(&mut world, "/give STONE").is_ok());
+        assert!(run(&mut world, "/tool shovel wood").is_ok());
+        assert!(run(&mut world, "/give air 1").is_err());
+        assert!(run(&mut world, "/give stone 0").is_err());
+        assert!(run(&mut world, "/tool sword").is_err());
+        assert_eq!(run(&mut world, "/seed").unwrap(), "Seed: 42");
+
+        let mut players = world.query::<&Inventory>();
+        let inventory = players.single(&world).unwrap();
+        assert_eq!(inventory.count(VoxelType::Stone), 71);
+        assert_eq!(inventory.best_tool(ToolType::Shovel), Some(ToolTier::Wood));
+    }
+
+    #[test]
+    fn tp_and_fill_take_metres_and_relative_coords() {
+        let mut world = test_world();
+        assert!(run(&mut world, "/tp ~1 ~ 5").is_ok());
+        let mut players = world.query_filtered::<&Transform, With<PrimaryPlayer>>();
+        assert_eq!(players.single(&world).unwrap().translation, Vec3::new(2.0, 2.0, 5.0));
+        assert!(run(&mut world, "/tp 1 2").is_err());
+        assert!(run(&mut world, "/tp x 2 3").is_err());
+
+        // Voxels 0..=2 × 0..=1 × 0..=1 (sin cargar: quedan en los diffs)
+        assert!(run(&mut world, "/fill 0 0 0 0.25 0.1 0.1 Dirt").is_ok());
+        let diffs = world.resource::<VoxelDiffs>();
+        let filled = diffs.chunks.values().map(|chunk| chunk.len()).sum::<usize>();
+        assert_eq!(filled, 12);
+        assert!(run(&mut world, "/fill 0 0 0 10 10 10 dirt").is_err());
+
+        // /regen solo con el chunk del jugador cargado
+        assert!(run(&mut world, "/regen chunk").is_err());
+        assert!(run(&mut world, "/regen everything").is_err());
+    }
+
+    #[test]
+    fn sphere_and_replace_edit_through_voxel_world() {
+        let mut world = test_world();
+        // Radio de 0.1 m = 1 voxel: el centro y sus 6 vecinos, sin cargar
+        assert!(run(&mut world, "/sphere 10 10 10 0.1 stone").is_ok());
+        let diffs = world.resource::<VoxelDiffs>();
+        assert_eq!(diffs.chunks.values().map(|chunk| chunk.len()).sum::<usize>(), 7);
+        assert!(run(&mut world, "/sphere 0 0 0 5 stone").is_err());
+        assert!(run(&mut world, "/sphere 0 0 0 NaN stone").is_err());
+
+        // /replace solo cambia lo cargado y del material pedido
+        let replace = "/replace 0 0 0 0.25 0.1 0.1 metal stone";
+        assert!(run(&mut world, replace).unwrap().starts_with("0 "));
+        let chunk = world.spawn(BaseChunk::new(IVec3::ZERO, 42)).id();
+        world.resource_mut::<ChunkMap>().chunks.insert(IVec3::ZERO, chunk);
+        assert!(run(&mut world, "/fill 0 0 0 0.25 0.1 0.1 metal").is_ok());
+        let replaced = run(&mut world, replace).unwrap();
+        assert!(replaced.starts_with("12 "), "{}", replaced);
+        assert!(run(&mut world, replace).unwrap().starts_with("0 "));
+        let base = world.get::<BaseChunk>(chunk).unwrap();
+        assert_eq!(base.voxel_types[2][1][1], VoxelType::Stone);
+    }
+}
@@ -0,0 +1,15 @@
+//! # Modulo de Chat y Consola
+//!
+//! ## Estructura
+//! - 'registry': El registro de la consola (`Console`: líneas, historial,
+//!   scroll y lo que se está escribiendo) y el registro de comandos
+//!   (`CommandRegistry`), con su ejecución
+//! - 'commands': Los comandos de serie (`/tp`, `/seed`, `/give`, `/tool`,
+//!   `/time`, `/fill`, `/regen chunk`, `/help`)
+//!
+//! El overlay (tecla T o `/`) vive en `ui/console.rs`.
+
+pub mod commands;
+pub mod registry;
+
+pub use registry::*;
@@ -0,0 +1,314 @@
+//! Registro de la consola y de sus comandos.
+//!
+//! Una línea que empieza por `/` es un comando: se busca su nombre en el
+//! [`CommandRegistry`] y se ejecuta con acceso exclusivo al `World`, así cada
+//! comando usa los mismos recursos que el juego (`WorldSeed`, el `Inventory`
+//! del jugador, `ChunkMap`, `VoxelWorld`...). El resto es chat: jugando en red
+//! va al servidor (y se muestra cuando vuelve); sin red se muestra tal cual.
+
+use super::commands::builtin_commands;
+use crate::net::NetClient;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// Líneas guardadas como mucho en la consola (las más viejas se pierden).
+pub const MAX_CONSOLE_LINES: usize = 200;
+
+/// Entradas del historial de lo enviado (flechas arriba/abajo).
+const MAX_HISTORY: usize = 50;
+
+// ============================================================================
+// CONSOLA
+// ============================================================================
+
+/// Qué es una línea de la consola (decide su color).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Chat, propio o de otro jugador
+    Chat,
+    /// Un comando tal como se escribió
+    Command,
+    /// Respuesta de un comando
+    Reply,
+    /// Error de un comando
+    Error,
+}
+
+/// Una línea de la consola.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsoleLine {
+    pub kind: LineKind,
+    pub text: String,
+    /// Cuándo llegó (segundos reales): el chat sobre el HUD se desvanece
+    pub time: f64,
+}
+
+/// Contenido de la consola: líneas, historial de lo enviado, scroll y lo que
+/// se está escribiendo.
+#[derive(Resource, Debug, Default)]
+pub struct Console {
+    lines: VecDeque<ConsoleLine>,
+    /// Lo que se está escribiendo
+    pub input: String,
+    /// Líneas desplazadas hacia arriba desde la última (0 = al final)
+    pub scroll: usize,
+    history: Vec<String>,
+    /// Entrada del historial en `input` (`None` = una línea nueva)
+    history_cursor: Option<usize>,
+    /// Líneas enviadas con Enter, pendientes de ejecutar
+    submitted: Vec<String>,
+}
+
+impl Console {
+    /// Añade texto (una línea por cada salto de línea). Si se está leyendo
+    /// más arriba, la vista no se mueve.
+    pub fn push(&mut self, kind: LineKind, text: &str, time: f64) {
+        for line in text.lines() {
+            self.lines.push_back(ConsoleLine {
+                kind,
+                text: line.to_string(),
+                time,
+            });
+            if self.lines.len() > MAX_CONSOLE_LINES {
+                self.lines.pop_front();
+            }
+            if self.scroll > 0 {
+                self.scroll_by(1);
+            }
+        }
+    }
+
+    /// Todas las líneas, de la más vieja a la más nueva.
+    pub fn lines(&self) -> &VecDeque<ConsoleLine> {
+        &self.lines
+    }
+
+    /// Las `count` líneas a la vista con el scroll actual (de arriba a abajo).
+    pub fn visible(&self, count: usize) -> impl Iterator<Item = &ConsoleLine> {
+        let end = self.lines.len() - self.scroll.min(self.lines.len());
+        self.lines.range(end.saturating_sub(count)..end)
+    }
+
+    /// Desplaza la vista `lines` hacia arriba (negativo = hacia abajo), sin
+    /// pasar de la primera línea ni de la última.
+    pub fn scroll_by(&mut self, lines: i32) {
+        let max = self.lines.len().saturating_sub(1) as i64;
+        self.scroll = (self.scroll as i64 + lines as i64).clamp(0, max) as usize;
+    }
+
+    /// Envía lo escrito: pasa al historial y queda pendiente de ejecutar.
+    pub fn submit(&mut self) {
+        let line = std::mem::take(&mut self.input).trim().to_string();
+        self.history_cursor = None;
+        self.scroll = 0;
+        if line.is_empty() {
+            return;
+        }
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+            if self.history.len() > MAX_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        self.submitted.push(line);
+    }
+
+    /// Recorre lo enviado: `-1` = más antiguo, `+1` = más reciente (pasada la
+    /// última entrada, la línea queda vacía).
+    pub fn browse_history(&mut self, step: i32) {
+        let Some(last) = self.history.len().checked_sub(1) else {
+            return;
+        };
+        self.history_cursor = match (self.history_cursor, step < 0) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i < last => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        self.input = self
+            .history_cursor
+            .map(|i| self.history[i].clone())
+            .unwrap_or_default();
+    }
+
+    /// Devuelve (y vacía) lo enviado pendiente de ejecutar.
+    pub fn take_submitted(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.submitted)
+    }
+}
+
+// ============================================================================
+// COMANDOS
+// ============================================================================
+
+/// Un comando recibe el `World` y sus argumentos; responde con un texto para
+/// la consola o con un error.
+pub type CommandFn = fn(&mut World, &[&str]) -> Result<String, String>;
+
+/// Un comando de la consola.
+#[derive(Clone, Copy)]
+pub struct ConsoleCommand {
+    /// Nombre sin la barra (`tp` para `/tp`)
+    pub name: &'static str,
+    /// Cómo se escribe, para `/help` y los errores
+    pub usage: &'static str,
+    pub help: &'static str,
+    pub run: CommandFn,
+}
+
+/// Comandos disponibles. Empieza con los de serie; otros módulos pueden
+/// registrar los suyos.
+#[derive(Resource, Clone)]
+pub struct CommandRegistry {
+    commands: Vec<ConsoleCommand>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for command in builtin_commands() {
+            registry.register(command);
+        }
+        registry
+    }
+}
+
+impl CommandRegistry {
+    /// Registro sin ningún comando.
+    pub fn empty() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Añade un comando; si ya había uno con ese nombre, lo reemplaza.
+    pub fn register(&mut self, command: ConsoleCommand) {
+        match self.commands.iter_mut().find(|c| c.name == command.name) {
+            Some(existing) => *existing = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    /// Comando por nombre (sin distinguir mayúsculas).
+    pub fn get(&self, name: &str) -> Option<&ConsoleCommand> {
+        self.commands.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Todos, en orden de registro.
+    pub fn iter(&self) -> impl Iterator<Item = &ConsoleCommand> {
+        self.commands.iter()
+    }
+}
+
+/// Separa un comando en nombre y argumentos. `None` si la línea no es un
+/// comando (no empieza por `/` o no tiene nombre).
+pub fn parse_command(line: &str) -> Option<(&str, Vec<&str>)> {
+    let mut words = line.trim().strip_prefix('/')?.split_whitespace();
+    let name = words.next()?;
+    Some((name, words.collect()))
+}
+
+/// Ejecuta una línea enviada: un comando del registro o chat. Lo que
+/// responde queda en la [`Console`].
+pub fn run_console_line(world: &mut World, line: &str) {
+    let now = world.resource::<Time<Real>>().elapsed_secs_f64();
+    let Some((name, args)) = parse_command(line) else {
+        match world.get_resource_mut::<NetClient>() {
+            // Se verá cuando vuelva del servidor, en orden con el de los demás
+            Some(mut client) => client.send_chat(line),
+            None => world
+                .resource_mut::<Console>()
+                .push(LineKind::Chat, &format!("<Tú> {}", line), now),
+        }
+        return;
+    };
+
+    world.resource_mut::<Console>().push(LineKind::Command, line, now);
+    let command = world.resource::<CommandRegistry>().get(name).copied();
+    let (kind, text) = match command.map(|command| (command.run)(world, &args)) {
+        Some(Ok(reply)) => (LineKind::Reply, reply),
+        Some(Err(error)) => (LineKind::Error, error),
+        None => (
+            LineKind::Error,
+            format!("Comando desconocido: /{} (prueba /help)", name),
+        ),
+    };
+    world.resource_mut::<Console>().push(kind, &text, now);
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Ejecuta lo enviado desde la consola (sistema exclusivo: los comandos tocan
+/// lo que necesiten del `World`).
+pub fn run_console_commands(world: &mut World) {
+    if world.resource::<Console>().submitted.is_empty() {
+        return;
+    }
+    let lines = world.resource_mut::<Console>().take_submitted();
+    for line in lines {
+        run_console_line(world, &line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed_and_unknown_ones_fail() {
+        assert_eq!(parse_command("  /tp 1 ~2  3 "), Some(("tp", vec!["1", "~2", "3"])));
+        assert_eq!(parse_command("/seed"), Some(("seed", vec![])));
+        assert_eq!(parse_command("hola /tp"), None);
+        assert_eq!(parse_command("/"), None);
+
+        let mut world = World::new();
+        world.init_resource::<Time<Real>>();
+        world.init_resource::<Console>();
+        world.init_resource::<CommandRegistry>();
+        run_console_line(&mut world, "/nope 1");
+        run_console_line(&mut world, "hola");
+        let lines: Vec<_> = world.resource::<Console>().lines().iter().cloned().collect();
+        assert_eq!(lines[0].kind, LineKind::Command);
+        assert_eq!(lines[1].kind, LineKind::Error);
+        // Sin red el chat se muestra tal cual
+        assert_eq!((lines[2].kind, lines[2].text.as_str()), (LineKind::Chat, "<Tú> hola"));
+        // Los nombres no distinguen mayúsculas
+        assert!(world.resource::<CommandRegistry>().get("TP").is_some());
+    }
+
+    #[test]
+    fn history_and_scroll_stay_in_range() {
+        let mut console = Console::default();
+        for text in ["/seed", "hola", "hola"] {
+            console.input = text.to_string();
+            console.submit();
+        }
+        // El repetido seguido no entra dos veces en el historial
+        assert_eq!(console.take_submitted().len(), 3);
+        console.browse_history(-1);
+        assert_eq!(console.input, "hola");
+        console.browse_history(-1);
+        console.browse_history(-1);
+        assert_eq!(console.input, "/seed");
+        console.browse_history(1);
+        console.browse_history(1);
+        assert_eq!(console.input, "");
+
+        for i in 0..10 {
+            console.push(LineKind::Reply, &format!("{}", i), 0.0);
+        }
+        console.scroll_by(3);
+        let visible: Vec<_> = console.visible(2).map(|l| l.text.as_str()).collect();
+        assert_eq!(visible, ["5", "6"]);
+        // Lo que llega no mueve la vista
+        console.push(LineKind::Chat, "nuevo", 0.0);
+        assert_eq!(console.visible(1).next().unwrap().text, "6");
+        console.scroll_by(100);
+        assert_eq!(console.visible(3).count(), 1);
+        console.scroll_by(-100);
+        assert_eq!(console.visible(1).next().unwrap().text, "nuevo");
+    }
+}
@@ -0,0 +1,357 @@
+//! Items e inventario del jugador.
+//!
+//! Todo lo que se lleva es un [`ItemStack`]: un [`ItemId`] (material o
+//! herramienta) y cuántos hay. El [`Inventory`] es una lista fija de slots; los
+//! primeros [`HOTBAR_SLOTS`] forman la barra rápida y el slot elegido en ella
+//! es lo que el jugador tiene en la mano (herramienta con la que rompe, o
+//! material que coloca).
+//!
+//! Los materiales se apilan hasta [`MAX_VOXEL_STACK`]; las herramientas no se
+//! apilan: cada una es un item con su propia durabilidad.
+
+use crate::voxel::{Tool, ToolTier, ToolType, VoxelType};
+use bevy::prelude::*;
+
+/// Slots de la barra rápida (teclas 1–9), al principio del inventario.
+pub const HOTBAR_SLOTS: usize = 9;
+
+/// Slots totales: la barra rápida más tres filas de mochila.
+pub const INVENTORY_SLOTS: usize = HOTBAR_SLOTS * 4;
+
+/// Máximo de voxels de un material en un slot.
+pub const MAX_VOXEL_STACK: u32 = 64;
+
+// ============================================================================
+// ITEMS
+// ============================================================================
+
+/// Qué es un item (sin cantidad ni desgaste).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemId {
+    /// Material recolectado, colocable con el click derecho
+    Voxel(VoxelType),
+    /// Herramienta fabricada de un tier
+    Tool(ToolType, ToolTier),
+}
+
+impl ItemId {
+    /// Cuántos caben en un slot.
+    pub fn max_stack(self) -> u32 {
+        match self {
+            ItemId::Voxel(_) => MAX_VOXEL_STACK,
+            ItemId::Tool(..) => 1,
+        }
+    }
+}
+
+/// Un slot ocupado: item, cantidad y (solo herramientas) durabilidad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u32,
+    /// Durabilidad restante de la herramienta; 0 en los materiales.
+    pub durability: u32,
+}
+
+impl ItemStack {
+    /// `count` voxels de un material.
+    pub fn voxels(voxel_type: VoxelType, count: u32) -> Self {
+        Self {
+            item: ItemId::Voxel(voxel_type),
+            count,
+            durability: 0,
+        }
+    }
+
+    /// Una herramienta, con el desgaste que tenga.
+    pub fn tool(tool: Tool) -> Self {
+        Self {
+            item: ItemId::Tool(tool.tool_type, tool.tier),
+            count: 1,
+            durability: tool.current_durability,
+        }
+    }
+
+    /// La herramienta de este slot (`None` si es un material).
+    pub fn as_tool(&self) -> Option<Tool> {
+        match self.item {
+            ItemId::Tool(tool_type, tier) => Some(Tool {
+                tool_type,
+                tier,
+                current_durability: self.durability,
+            }),
+            ItemId::Voxel(_) => None,
+        }
+    }
+
+    /// El material de este slot (`None` si es una herramienta).
+    pub fn as_voxel(&self) -> Option<VoxelType> {
+        match self.item {
+            ItemId::Voxel(voxel_type) => Some(voxel_type),
+            ItemId::Tool(..) => None,
+        }
+    }
+}
+
+// ============================================================================
+// INVENTARIO
+// ============================================================================
+
+/// Inventario de un jugador (va en su entidad): slots fijos y el slot de la
+/// barra rápida elegido.
+#[derive(Component, Clone)]
+pub struct Inventory {
+    pub slots: [Option<ItemStack>; INVENTORY_SLOTS],
+    /// Slot de la barra rápida en la mano (`0..HOTBAR_SLOTS`).
+    pub selected: usize,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            slots: [None; INVENTORY_SLOTS],
+            selected: 0,
+        }
+    }
+}
+
+impl Inventory {
+    /// Guarda un stack: primero completa los slots del mismo item, después
+    /// ocupa los vacíos (la barra rápida antes que la mochila). Devuelve lo que
+    /// no cupo (0 = todo guardado).
+    pub fn insert(&mut self, stack: ItemStack) -> u32 {
+        let max = stack.item.max_stack();
+        let mut remaining = stack.count;
+
+        for slot in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+            if slot.item == stack.item && slot.count < max {
+                let moved = (max - slot.count).min(remaining);
+                slot.count += moved;
+                remaining -= moved;
+            }
+        }
+        for slot in &mut self.slots {
+            if remaining == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let moved = max.min(remaining);
+                *slot = Some(ItemStack {
+                    count: moved,
+                    ..stack
+                });
+                remaining -= moved;
+            }
+        }
+        remaining
+    }
+
+    /// Suma `qty` voxels de un material. Devuelve los que no cupieron.
+    pub fn add(&mut self, voxel_type: VoxelType, qty: u32) -> u32 {
+        self.insert(ItemStack::voxels(voxel_type, qty))
+    }
+
+    /// Cantidad total de voxels de un material (sumando todos sus slots).
+    pub fn count(&self, voxel_type: VoxelType) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|slot| slot.item == ItemId::Voxel(voxel_type))
+            .map(|slot| slot.count)
+            .sum()
+    }
+
+    /// ¿Cabe al menos un voxel más de este material? (un slot suyo a medias o
+    /// uno vacío)
+    pub fn has_room_for(&self, voxel_type: VoxelType) -> bool {
+        let item = ItemId::Voxel(voxel_type);
+        self.slots.iter().any(|slot| match slot {
+            None => true,
+            Some(stack) => stack.item == item && stack.count < item.max_stack(),
+        })
+    }
+
+    /// Gasta `qty` voxels de un material, empezando por los últimos slots (la
+    /// barra rápida es lo último que se vacía). Devuelve `false` (sin tocar
+    /// nada) si no hay suficientes.
+    pub fn take(&mut self, voxel_type: VoxelType, qty: u32) -> bool {
+        if self.count(voxel_type) < qty {
+            return false;
+        }
+        let mut remaining = qty;
+        for index in (0..INVENTORY_SLOTS).rev() {
+            if remaining == 0 {
+                break;
+            }
+            let Some(slot) = &mut self.slots[index] else {
+                continue;
+            };
+            if slot.item != ItemId::Voxel(voxel_type) {
+                continue;
+            }
+            let taken = slot.count.min(remaining);
+            slot.count -= taken;
+            remaining -= taken;
+            if slot.count == 0 {
+                self.slots[index] = None;
+            }
+        }
+        true
+    }
+
+    /// Mejor tier que se tiene de un tipo de herramienta (`None` si ninguno).
+    pub fn best_tool(&self, tool_type: ToolType) -> Option<ToolTier> {
+        self.slots
+            .iter()
+            .flatten()
+            .filter_map(|slot| match slot.item {
+                ItemId::Tool(t, tier) if t == tool_type => Some(tier),
+                _ => None,
+            })
+            .max()
+    }
+
+    /// Lo que hay en la mano (slot elegido de la barra rápida).
+    pub fn held(&self) -> Option<&ItemStack> {
+        self.slots[self.selected].as_ref()
+    }
+
+    /// Gasta uno del stack en la mano (p. ej. al colocar un voxel).
+    pub fn take_held(&mut self) {
+        self.split_held(1);
+    }
+
+    /// Saca hasta `qty` del stack en la mano y lo devuelve (`None` con la mano
+    /// vacía). Lo que queda sigue en el slot.
+    pub fn split_held(&mut self, qty: u32) -> Option<ItemStack> {
+        let slot = &mut self.slots[self.selected];
+        let stack = slot.as_mut()?;
+        let taken = stack.count.min(qty);
+        let split = ItemStack {
+            count: taken,
+            ..*stack
+        };
+        stack.count -= taken;
+        if stack.count == 0 {
+            *slot = None;
+        }
+        Some(split)
+    }
+
+    /// Desgasta la herramienta en la mano. Si se rompe, desaparece del
+    /// inventario y devuelve `true` (el jugador queda a mano).
+    pub fn wear_held_tool(&mut self, amount: u32) -> bool {
+        let slot = &mut self.slots[self.selected];
+        let Some(mut tool) = slot.and_then(|stack| stack.as_tool()) else {
+            return false;
+        };
+        if tool.damage(amount) {
+            *slot = None;
+            return true;
+        }
+        *slot = Some(ItemStack::tool(tool));
+        false
+    }
+
+    /// Mueve el stack de `from` a `to`: si son el mismo item apilable se
+    /// juntan (lo que no cabe se queda en `from`); si no, se intercambian.
+    pub fn move_stack(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        match (self.slots[from], self.slots[to]) {
+            (Some(source), Some(mut target))
+                if source.item == target.item && target.count < target.item.max_stack() =>
+            {
+                let moved = (target.item.max_stack() - target.count).min(source.count);
+                target.count += moved;
+                self.slots[to] = Some(target);
+                self.slots[from] = (source.count > moved).then_some(ItemStack {
+                    count: source.count - moved,
+                    ..source
+                });
+            }
+            _ => self.slots.swap(from, to),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacks_fill_up_and_overflow_is_refused() {
+        let mut inventory = Inventory::default();
+        assert_eq!(inventory.add(VoxelType::Stone, MAX_VOXEL_STACK + 10), 0);
+        assert_eq!(inventory.slots[0].unwrap().count, MAX_VOXEL_STACK);
+        assert_eq!(inventory.slots[1].unwrap().count, 10);
+
+        // Se completa el slot a medias antes de abrir otro
+        inventory.add(VoxelType::Stone, 5);
+        assert_eq!(inventory.slots[1].unwrap().count, 15);
+        assert!(inventory.slots[2].is_none());
+
+        // Lleno: lo que no cabe se devuelve
+        let free = (INVENTORY_SLOTS - 2) as u32;
+        let leftover = inventory.add(VoxelType::Dirt, free * MAX_VOXEL_STACK + 7);
+        assert_eq!(leftover, 7);
+        assert!(inventory.has_room_for(VoxelType::Stone));
+        assert!(!inventory.has_room_for(VoxelType::Sand));
+        assert_eq!(inventory.add(VoxelType::Stone, 100), 100 - (MAX_VOXEL_STACK - 15));
+        assert!(!inventory.has_room_for(VoxelType::Stone));
+
+        assert!(inventory.take(VoxelType::Stone, MAX_VOXEL_STACK + 1));
+        assert_eq!(inventory.count(VoxelType::Stone), MAX_VOXEL_STACK - 1);
+        assert!(!inventory.take(VoxelType::Stone, MAX_VOXEL_STACK));
+    }
+
+    #[test]
+    fn moving_merges_same_items_and_swaps_the_rest() {
+        let mut inventory = Inventory::default();
+        inventory.slots[0] = Some(ItemStack::voxels(VoxelType::Sand, 60));
+        inventory.slots[1] = Some(ItemStack::voxels(VoxelType::Sand, 10));
+        inventory.slots[2] = Some(ItemStack::tool(Tool::new(ToolType::Axe, ToolTier::Stone)));
+
+        inventory.move_stack(1, 0);
+        assert_eq!(inventory.slots[0].unwrap().count, MAX_VOXEL_STACK);
+        assert_eq!(inventory.slots[1].unwrap().count, 6);
+
+        inventory.move_stack(2, 0);
+        assert_eq!(inventory.slots[0].unwrap().item, ItemId::Tool(ToolType::Axe, ToolTier::Stone));
+        assert_eq!(inventory.slots[2].unwrap().item, ItemId::Voxel(VoxelType::Sand));
+    }
+
+    #[test]
+    fn held_tool_wears_out_and_disappears() {
+        let mut inventory = Inventory::default();
+        let mut tool = Tool::new(ToolType::Pickaxe, ToolTier::Wood);
+        tool.current_durability = 2;
+        inventory.insert(ItemStack::tool(tool));
+        assert_eq!(inventory.best_tool(ToolType::Pickaxe), Some(ToolTier::Wood));
+
+        assert!(!inventory.wear_held_tool(1));
+        assert_eq!(inventory.held().unwrap().durability, 1);
+        assert!(inventory.wear_held_tool(1));
+        assert!(inventory.held().is_none());
+        assert_eq!(inventory.best_tool(ToolType::Pickaxe), None);
+    }
+
+    #[test]
+    fn splitting_the_held_stack_leaves_the_rest() {
+        let mut inventory = Inventory::default();
+        inventory.add(VoxelType::Wood, 5);
+
+        let one = inventory.split_held(1).unwrap();
+        assert_eq!((one.item, one.count), (ItemId::Voxel(VoxelType::Wood), 1));
+        assert_eq!(inventory.held().unwrap().count, 4);
+
+        // Pedir más de lo que hay vacía el slot
+        assert_eq!(inventory.split_held(64).unwrap().count, 4);
+        assert!(inventory.held().is_none());
+        assert!(inventory.split_held(1).is_none());
+    }
+}
@@ -1,7 +1,9 @@
 pub mod constants;
+pub mod inventory;
 pub mod resources;
 pub mod states;
 
 pub use constants::*;
+pub use inventory::*;
 pub use resources::*;
 pub use states::*;
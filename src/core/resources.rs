@@ -1,17 +1,5 @@
-use crate::voxel::{VoxelType, VOXEL_TYPE_COUNT};
 use bevy::prelude::*;
 
-/// Conteo de voxels recolectados por el jugador, indexado por `VoxelType as usize`.
-#[derive(Resource, Default)]
-pub struct Inventory(pub [u32; VOXEL_TYPE_COUNT]);
-
-impl Inventory {
-    /// Suma `qty` voxels del tipo dado al inventario.
-    pub fn add(&mut self, voxel_type: VoxelType, qty: u32) {
-        self.0[voxel_type as usize] += qty;
-    }
-}
-
 // Recurso de configuración insertado al arranque; los sistemas de cámara/movimiento
 // aún no leen estos campos (pendiente de conectar el menú de ajustes).
 #[allow(dead_code)]
@@ -20,6 +8,9 @@ pub struct GameSettings {
     pub mouse_sensitivity: f32,
     pub movement_speed: f32,
     pub fov: f32,
+    /// Jugadores locales en pantalla dividida (`--split-screen` = 2); 1 = sin
+    /// dividir.
+    pub local_players: usize,
 }
 
 impl GameSettings {
@@ -28,6 +19,7 @@ impl GameSettings {
             mouse_sensitivity: 0.002,
             movement_speed: 5.0,
             fov: 90.0,
+            local_players: 1,
         }
     }
 }
@@ -44,3 +36,11 @@ impl WorldSeed {
         Self(rand::random::<i32>())
     }
 }
+
+/// La partida corre determinista: las mismas acciones con el mismo `dt` dan el
+/// mismo mundo y la misma trayectoria. Lo ponen la grabación (`--record`) y la
+/// repetición (`--replay`); con él, jugador, mundo y drops los corre el juego
+/// determinista de `src/replay/sim.rs` en lugar de sus sistemas normales, y
+/// nada corta por el reloj real.
+#[derive(Resource, Debug, Default)]
+pub struct DeterministicGame;
@@ -13,4 +13,25 @@ pub enum GameState {
     MainMenu,
     InGame,
     Paused,
+    /// Pantalla de fabricación (tecla C): el juego queda en pausa detrás
+    Crafting,
+    /// Inventario completo (tecla Tab): cursor libre para arrastrar items
+    Inventory,
+    /// Chat y consola de comandos (tecla T o `/`): se escribe con el teclado
+    Console,
+}
+
+/// Pantallas dentro del menú principal.
+///
+/// Sub-estado de `GameState::MainMenu`: solo existe mientras estamos en el menú
+/// y vuelve a `Title` cada vez que se entra.
+#[derive(SubStates, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::MainMenu)]
+pub enum MenuScreen {
+    #[default]
+    Title,
+    /// Lista de mundos guardados
+    WorldSelect,
+    /// Formulario de mundo nuevo (nombre + seed)
+    NewWorld,
 }
@@ -0,0 +1,11 @@
+//! # Modulo de Fabricación
+//!
+//! ## Estructura
+//! - 'recipes': Tabla de recetas (datos en `assets/data/recipes.ron`) y fabricar
+//!   herramientas con el contenido del `Inventory`
+//!
+//! La pantalla de fabricación vive en `ui/crafting.rs`.
+
+pub mod recipes;
+
+pub use recipes::*;
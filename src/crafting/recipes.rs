@@ -0,0 +1,169 @@
+//! Recetas de herramientas.
+//!
+//! La tabla es DATOS, no código: se lee de [`RECIPES_PATH`] al arrancar, así
+//! que se pueden cambiar costes o añadir recetas sin recompilar. Si el archivo
+//! falta o no parsea, se usa la copia embebida en el binario (el mismo archivo,
+//! con `include_str!`) y se avisa en el log.
+//!
+//! Fabricar gasta los ingredientes del `Inventory` y guarda la herramienta en
+//! un slot libre del mismo inventario; o todo, o nada.
+
+use crate::core::{Inventory, ItemStack};
+use crate::voxel::{Tool, ToolTier, ToolType, VoxelType};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Archivo de recetas (relativo al directorio de trabajo, como los mundos).
+pub const RECIPES_PATH: &str = "assets/data/recipes.ron";
+
+/// Copia embebida de las recetas, por si el archivo no está.
+const DEFAULT_RECIPES: &str = include_str!("../../assets/data/recipes.ron");
+
+// ============================================================================
+// DATOS
+// ============================================================================
+
+/// Un ingrediente: `count` voxels de cualquiera de los materiales de `any_of`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Ingredient {
+    /// Materiales que valen (p. ej. cualquier madera); se gastan en este orden.
+    pub any_of: Vec<VoxelType>,
+    pub count: u32,
+}
+
+/// Receta de una herramienta de un tier.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recipe {
+    pub tool: ToolType,
+    pub tier: ToolTier,
+    pub ingredients: Vec<Ingredient>,
+}
+
+impl Recipe {
+    /// Gasta los ingredientes de `inventory` y guarda la herramienta. Si falta
+    /// algún ingrediente o no queda slot libre no toca nada y devuelve `false`
+    /// (se prueba sobre una copia: dos ingredientes pueden compartir material).
+    fn apply(&self, inventory: &mut Inventory) -> bool {
+        let mut trial = inventory.clone();
+        for ingredient in &self.ingredients {
+            let mut remaining = ingredient.count;
+            for &voxel_type in &ingredient.any_of {
+                let taken = trial.count(voxel_type).min(remaining);
+                trial.take(voxel_type, taken);
+                remaining -= taken;
+            }
+            if remaining > 0 {
+                return false;
+            }
+        }
+        if trial.insert(ItemStack::tool(Tool::new(self.tool, self.tier))) > 0 {
+            return false;
+        }
+        *inventory = trial;
+        true
+    }
+
+    /// ¿Alcanza el inventario para fabricarla (ingredientes y un slot libre)?
+    pub fn can_craft(&self, inventory: &Inventory) -> bool {
+        self.apply(&mut inventory.clone())
+    }
+
+    /// Fabrica la herramienta en el inventario. Devuelve `false` si no alcanza.
+    pub fn craft(&self, inventory: &mut Inventory) -> bool {
+        self.apply(inventory)
+    }
+
+    /// Nombre de lo que fabrica ("Stone Pickaxe").
+    pub fn label(&self) -> String {
+        format!("{} {}", self.tier.name(), self.tool.properties(self.tier).name)
+    }
+
+    /// Coste legible ("4 Wood/PineWood + 24 Stone").
+    pub fn cost_label(&self) -> String {
+        self.ingredients
+            .iter()
+            .map(|ingredient| {
+                let names: Vec<&str> = ingredient
+                    .any_of
+                    .iter()
+                    .map(|v| v.properties().name.as_str())
+                    .collect();
+                format!("{} {}", ingredient.count, names.join("/"))
+            })
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+}
+
+/// Todas las recetas, en el orden del archivo (el de la pantalla de fabricación).
+#[derive(Resource, Debug, Clone)]
+pub struct RecipeBook(pub Vec<Recipe>);
+
+impl RecipeBook {
+    /// Parsea una tabla de recetas en RON.
+    pub fn parse(text: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(text).map(Self)
+    }
+
+    /// Lee [`RECIPES_PATH`]; si falla, usa la copia embebida.
+    pub fn load() -> Self {
+        let parsed = std::fs::read_to_string(RECIPES_PATH)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Self::parse(&text).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(book) => {
+                info!("{} recetas cargadas de {}", book.0.len(), RECIPES_PATH);
+                book
+            }
+            Err(e) => {
+                warn!("No se pudo leer {}: {}; se usan las recetas por defecto", RECIPES_PATH, e);
+                Self::parse(DEFAULT_RECIPES).expect("las recetas embebidas deben ser válidas")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe(tool: ToolType, tier: ToolTier) -> Recipe {
+        let book = RecipeBook::parse(DEFAULT_RECIPES).unwrap();
+        book.0
+            .into_iter()
+            .find(|r| r.tool == tool && r.tier == tier)
+            .unwrap()
+    }
+
+    #[test]
+    fn default_recipes_cover_every_tool_and_tier() {
+        let book = RecipeBook::parse(DEFAULT_RECIPES).unwrap();
+        for tool in [ToolType::Pickaxe, ToolType::Axe, ToolType::Shovel, ToolType::Hoe] {
+            for tier in [ToolTier::Wood, ToolTier::Stone, ToolTier::Metal] {
+                assert!(book.0.iter().any(|r| r.tool == tool && r.tier == tier));
+            }
+        }
+        assert!(book.0.iter().all(|r| r.ingredients.iter().all(|i| i.count > 0)));
+    }
+
+    #[test]
+    fn crafting_spends_alternatives_in_order_or_nothing() {
+        let pickaxe = recipe(ToolType::Pickaxe, ToolTier::Stone); // 4 madera + 24 piedra
+        let mut inventory = Inventory::default();
+        inventory.add(VoxelType::Wood, 1);
+        inventory.add(VoxelType::PineWood, 10);
+        inventory.add(VoxelType::Stone, 23);
+
+        // Falta una piedra: no se gasta nada
+        assert!(!pickaxe.can_craft(&inventory));
+        assert!(!pickaxe.craft(&mut inventory));
+        assert_eq!(inventory.count(VoxelType::PineWood), 10);
+
+        inventory.add(VoxelType::Stone, 1);
+        assert!(pickaxe.craft(&mut inventory));
+        assert_eq!(inventory.count(VoxelType::Wood), 0);
+        assert_eq!(inventory.count(VoxelType::PineWood), 7);
+        assert_eq!(inventory.count(VoxelType::Stone), 0);
+        assert_eq!(inventory.best_tool(ToolType::Pickaxe), Some(ToolTier::Stone));
+    }
+}
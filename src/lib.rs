@@ -1,8 +1,13 @@
 // Exporta todos los módulos para tests y reutilización
+pub mod console;
 pub mod core;
+pub mod crafting;
 pub mod voxel;
+pub mod net;
 pub mod player;
 pub mod physics;
+pub mod replay;
+pub mod server;
 pub mod debug;
 pub mod ui;
 pub mod vegetation;
@@ -7,11 +7,16 @@
 // DECLARACIÓN DE MÓDULOS
 // ============================================================================
 
+mod console; // Chat y comandos (busca src/console/mod.rs)
 mod core; // Declara el módulo 'core' (busca src/core/mod.rs)
+mod crafting; // Recetas de herramientas (busca src/crafting/mod.rs)
 mod debug;
 mod ui;
 mod physics; // Declara el módulo 'physics' (busca src/physics/mod.rs)
 mod player; // Declara el módulo 'player' (busca src/player/mod.rs)
+mod replay; // Grabación y repetición `--record`/`--replay` (busca src/replay/mod.rs)
+mod net; // Protocolo cliente-servidor (busca src/net/mod.rs)
+mod server; // Servidor headless `--server` (busca src/server/mod.rs)
 mod voxel; // Declara el módulo 'voxel' (busca src/voxel/mod.rs) // Declara el módulo 'debug' (busca src/debug/mod.rs)
 mod vegetation; // Declara el módulo 'vegetation' (busca src/vegetation/mod.rs)
 
@@ -21,7 +26,7 @@ mod vegetation; // Declara el módulo 'vegetation' (busca src/vegetation/mod.rs)
 use std::collections::HashMap;
 use ui::UIPlugin;
 use bevy::prelude::*;
-use core::{GameSettings, WorldSeed}; // Importa recursos globales desde nuestro módulo core
+use core::{DeterministicGame, GameSettings, WorldSeed}; // Importa recursos globales desde nuestro módulo core
 use debug::DebugPlugin;
 use physics::{PhysicsPlugin, RigidBody, create_terrain_collider}; // Importa componentes de física
 use player::PlayerPlugin; // Importa PlayerPlugin desde nuestro módulo player
@@ -31,7 +36,13 @@ use voxel::{
     greedy_mesh_basechunk_simple, load_chunks_system, remesh_dirty_chunks_system,
     start_voxel_breaking_system, teardown_world, unload_chunks_system, update_chunk_load_queue,
     update_chunk_lod_system, update_chunk_transitions_system, update_frustum_culling,
-    update_voxel_breaking_system, TerrainGenerator, VoxelDiffs,
+    update_voxel_breaking_system, update_water_meshes, TerrainGenerator, VoxelDiffs,
+    WaterMaterial, LavaSim, burn_drops_in_lava, cull_lava_lights,
+    reset_lava_sim, update_lava_lights, place_voxel_system,
+    apply_pending_voxel_edits, reset_pending_voxel_edits, PendingVoxelEdits,
+    VoxelEdited, WorldSaveState, autosave_world_system, flush_world_on_exit, save_world_now,
+    LootRng, seed_loot_rng, DebrisAssets, StructuralChecks, teardown_debris, FallingSim,
+    reset_falling_sim, Explosion, Fuses, reset_fuses, world_simulation_systems,
 };
 
 use crate::core::GameState;
@@ -44,8 +55,46 @@ use crate::core::GameState;
 // Configura bevy con plugins por defecto, el plugin del jugador y el sistema de setup
 fn main() {
     // Función principal que ejecuta Rust al iniciar
-    App::new() // Crea una nueva aplicación de Bevy
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
+    // `--server`: el mundo sin ventana ni render (ver src/server/mod.rs)
+    if std::env::args().any(|arg| arg == "--server") {
+        match server::ServerConfig::from_args(std::env::args().skip(1)) {
+            Ok(config) => {
+                server::run_server(config);
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    // `--replay <fichero>`: repite una grabación sin ventana (ver
+    // src/replay/playback.rs); sale con 0 si reproduce lo grabado y 1 si no
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(path) = args.iter().position(|arg| arg == "--replay").map(|i| args.get(i + 1)) {
+        let Some(path) = path else {
+            eprintln!("falta el valor de --replay");
+            std::process::exit(2);
+        };
+        match replay::run_replay_file(std::path::Path::new(path)) {
+            Ok(matches) => std::process::exit(if matches { 0 } else { 1 }),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // `--split-screen`: dos jugadores locales, el segundo con mando (ver
+    // src/player/split_screen.rs)
+    let mut settings = GameSettings::new();
+    if std::env::args().any(|arg| arg == "--split-screen") {
+        settings.local_players = 2;
+    }
+
+    let mut app = App::new(); // Crea una nueva aplicación de Bevy
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Voxel game".to_string(),
                 ..default()
@@ -59,8 +108,11 @@ fn main() {
         .add_plugins(UIPlugin) // Anade el plugin de ui 
         .add_plugins(PlayerPlugin) // Añade nuestro plugin del jugador (movimiento, cámara)
         .add_plugins(DebugPlugin) // Añade herramientas de debug y profiling
-        .insert_resource(GameSettings::new()) // Inserta recurso global GameSettings en el mundo
-        .insert_resource(WorldSeed::random()) // Semilla aleatoria: mapa distinto cada arranque
+        .add_plugins(net::NetClientPlugin) // Juego en red (solo con `--connect`)
+        .add_plugins(replay::ReplayRecordPlugin) // Grabación (solo con `--record`)
+        .add_plugins(replay::DeterministicGamePlugin) // El juego mientras se graba
+        .insert_resource(settings) // Inserta recurso global GameSettings en el mundo
+        .insert_resource(WorldSeed::random()) // Se sobrescribe al elegir mundo en el menú
         .insert_resource(ChunkMap {
             chunks: HashMap::new(),
         })
@@ -68,39 +120,122 @@ fn main() {
         .insert_resource(SpatialHashGrid::default())
         .init_resource::<VoxelDiffs>()
         .init_resource::<ChunkMaterials>()
+        .init_resource::<WaterMaterial>()
+        .init_resource::<LavaSim>()
+        .init_resource::<LootRng>()
+        .init_resource::<PendingVoxelEdits>()
+        .init_resource::<StructuralChecks>()
+        .init_resource::<FallingSim>()
+        .init_resource::<Fuses>()
+        .init_resource::<DebrisAssets>()
+        .add_message::<VoxelEdited>()
+        .add_message::<Explosion>()
+        .init_resource::<WorldSaveState>()
+        .insert_resource(crafting::RecipeBook::load()) // assets/data/recipes.ron
         // El terreno se genera solo al empezar partida, no al reanudar desde pausa
         .add_systems(
             OnTransition {
                 exited: GameState::MainMenu,
                 entered: GameState::InGame,
             },
-            setup,
+            // La pantalla de selección ya fijó WorldSeed y VoxelDiffs del mundo elegido
+            (setup, seed_loot_rng),
         )
-        // Al volver al menú se destruye el mundo para que el próximo Play arranque limpio
-        .add_systems(OnEnter(GameState::MainMenu), teardown_world)
+        // Al volver al menú se guarda y se destruye el mundo para que el próximo
+        // Play arranque limpio (el guardado va primero: teardown borra los diffs)
+        .add_systems(
+            OnEnter(GameState::MainMenu),
+            (
+                save_world_now,
+                teardown_world,
+                reset_lava_sim,
+                reset_falling_sim,
+                reset_fuses,
+                reset_pending_voxel_edits,
+                teardown_debris,
+            )
+                .chain(),
+        )
+        // Autoguardado en segundo plano mientras se juega
+        .add_systems(Update, autosave_world_system.run_if(in_state(GameState::InGame)))
+        // Al cerrar la ventana, esperar a que lo pendiente llegue a disco
+        .add_systems(Last, flush_world_on_exit)
         .add_systems(
             Update,
             (
-                start_voxel_breaking_system,
-                update_voxel_breaking_system,
-                remesh_dirty_chunks_system,
-                update_chunk_lod_system,
-                // Sistemas de carga dinámica de chunks (async)
-                update_chunk_load_queue,
-                load_chunks_system,
-                complete_chunk_generation_system,
-                unload_chunks_system,
-                // Sistemas de transiciones Real ↔ LOD
-                update_chunk_transitions_system,
-                convert_lod_to_real_system,
-                convert_real_to_lod_system,
+                // Grabando (`--record`), edición, simulación y carga de chunks
+                // las corre el juego determinista (ver src/replay/sim.rs)
+                (
+                    start_voxel_breaking_system,
+                    update_voxel_breaking_system,
+                    // Construir: click derecho (o gatillo izquierdo) coloca el
+                    // material elegido de cada jugador
+                    place_voxel_system,
+                    // Ediciones encoladas para chunks que acaban de llegar
+                    apply_pending_voxel_edits,
+                    // Lava, explosiones, escombros y caída: en red los simula el
+                    // servidor y llegan como ediciones
+                    world_simulation_systems().run_if(not(resource_exists::<net::NetClient>)),
+                    remesh_dirty_chunks_system,
+                    update_chunk_lod_system,
+                    // Sistemas de carga dinámica de chunks (async)
+                    update_chunk_load_queue,
+                    load_chunks_system,
+                    complete_chunk_generation_system,
+                    unload_chunks_system,
+                    // Sistemas de transiciones Real ↔ LOD
+                    update_chunk_transitions_system,
+                    convert_lod_to_real_system,
+                    convert_real_to_lod_system,
+                )
+                    .chain()
+                    .run_if(not(resource_exists::<DeterministicGame>)),
+                // Agua translúcida y luz de lava de los chunks recién (re)mallados
+                update_water_meshes,
+                update_lava_lights,
+                cull_lava_lights,
+                burn_drops_in_lava.run_if(not(resource_exists::<DeterministicGame>)),
                 // Optimización: Frustum culling
                 update_frustum_culling,
             )
                 .chain()
                 .run_if(in_state(GameState::InGame)),
-        )
-        .run(); // Inicia el loop principal del juego
+        );
+
+    // `--connect <addr>`: el mundo es el del servidor (ver src/net/client.rs)
+    if let Some(addr) = args.iter().position(|arg| arg == "--connect").map(|i| args.get(i + 1)) {
+        let Some(addr) = addr else {
+            eprintln!("falta el valor de --connect");
+            std::process::exit(2);
+        };
+        match net::NetClient::connect(addr) {
+            Ok(client) => {
+                app.insert_resource(client);
+            }
+            Err(e) => {
+                eprintln!("No se pudo conectar con {addr}: {e}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // `--record <fichero>`: graba la partida para repetirla con `--replay`
+    if let Some(path) = args.iter().position(|arg| arg == "--record").map(|i| args.get(i + 1)) {
+        let Some(path) = path else {
+            eprintln!("falta el valor de --record");
+            std::process::exit(2);
+        };
+        if app.world().contains_resource::<net::NetClient>()
+            || app.world().resource::<GameSettings>().local_players > 1
+        {
+            eprintln!("--record solo graba partidas sin red y de un jugador");
+            std::process::exit(2);
+        }
+        app.insert_resource(replay::ReplayRecorder::new(path))
+            .insert_resource(DeterministicGame);
+    }
+
+    app.run(); // Inicia el loop principal del juego
 }
 
 // ============================================================================
@@ -115,13 +250,16 @@ fn main() {
 /// - `commands`: Comandos para crear entidades y recursos en el mundo.
 /// - `meshes`: Recursos para almacenar y gestionar las mallas 3D.
 /// - `materials`: Recursos para almacenar y gestionar los materiales estándar.
+#[allow(clippy::too_many_arguments)]
 fn setup(
     mut commands: Commands, // Sistema de comandos para crear/modificar entidades
     mut meshes: ResMut<Assets<Mesh>>, // Recurso mutable para gestionar mallas 3D
     mut materials: ResMut<Assets<StandardMaterial>>, // Para el material de la caja de referencia
     chunk_materials: Res<ChunkMaterials>, // Materiales compartidos de chunks
     mut chunk_map: ResMut<ChunkMap>,
-    world_seed: Res<WorldSeed>,
+    world_seed: Res<WorldSeed>, // Seed del mundo elegido
+    voxel_diffs: Res<VoxelDiffs>, // Sus ediciones guardadas
+    deterministic: Option<Res<DeterministicGame>>, // Si se está grabando
 ) {
     // ========================================================================
     // GENERACIÓN DE TERRENO INICIAL
@@ -136,14 +274,23 @@ fn setup(
 
     let mut temp_chunks: HashMap<IVec3, BaseChunk> = HashMap::new();
 
-    for cx in -initial_radius..=initial_radius {
-        for cz in -initial_radius..=initial_radius {
-            // Solo generar en un círculo, no un cuadrado
-            if cx * cx + cz * cz <= initial_radius * initial_radius {
-                // Generar chunks en múltiples niveles verticales
-                for cy in y_min..=y_max {
-                    let base_chunk = BaseChunk::new(IVec3::new(cx, cy, cz), world_seed.0);
-                    temp_chunks.insert(base_chunk.position, base_chunk);
+    // Grabando, los genera el juego determinista en su primer frame, igual
+    // que al repetir (ver src/replay/sim.rs)
+    if deterministic.is_none() {
+        for cx in -initial_radius..=initial_radius {
+            for cz in -initial_radius..=initial_radius {
+                // Solo generar en un círculo, no un cuadrado
+                if cx * cx + cz * cz <= initial_radius * initial_radius {
+                    // Generar chunks en múltiples niveles verticales
+                    for cy in y_min..=y_max {
+                        let chunk_pos = IVec3::new(cx, cy, cz);
+                        let mut base_chunk = BaseChunk::new(chunk_pos, world_seed.0);
+                        // Cambios guardados del jugador (mundo cargado de disco)
+                        if let Some(diffs) = voxel_diffs.chunks.get(&chunk_pos) {
+                            base_chunk.apply_diffs(diffs);
+                        }
+                        temp_chunks.insert(base_chunk.position, base_chunk);
+                    }
                 }
             }
         }
@@ -198,7 +345,7 @@ fn setup(
         let height = 1.9; // 190 cm
 
         // Apoyar la base de la caja sobre el terreno: centro = suelo + media altura
-        let mut terrain_gen = TerrainGenerator::new(world_seed.0);
+        let terrain_gen = TerrainGenerator::new(world_seed.0);
         let ground_y = terrain_gen.biome_gen.generate_height(ref_x, ref_z);
 
         commands.spawn((
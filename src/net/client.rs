@@ -0,0 +1,430 @@
+//! Cliente de red: jugar en el mundo de un servidor (`--connect <addr>`).
+//!
+//! Tras el saludo el servidor manda la seed y el cliente arranca la partida
+//! como si hubiera elegido un mundo: regenera el terreno por su cuenta y recibe
+//! solo lo que el terreno procedural no sabe (los diffs de cada chunk y las
+//! ediciones en lotes). Romper, colocar, fabricar y mover stacks no tocan ni el
+//! mundo ni el inventario locales: se manda la intención al servidor
+//! ([`NetClient::request`]) y el resultado vuelve en un `VoxelDelta`, que se
+//! aplica por `VoxelWorld` como cualquier otra edición, y en un `Inventory` con
+//! el inventario del jugador tal como lo guarda el servidor. Lava, explosiones,
+//! escombros y caída tampoco corren aquí (`world_simulation_systems` solo va
+//! sin `NetClient`): las simula el servidor y sus ediciones llegan igual, así
+//! que todas las copias del mundo acaban iguales.
+//!
+//! Los demás jugadores se dibujan como cápsulas INTERPOLADAS entre los dos
+//! últimos estados recibidos, `INTERPOLATION_DELAY_SECS` en el pasado del reloj
+//! del servidor: se mueven suave aunque los estados lleguen a saltos.
+//!
+//! El chat va y vuelve por el servidor: lo escrito en la consola se manda con
+//! [`NetClient::send_chat`] y se muestra cuando llega de vuelta (en orden con
+//! el de los demás), desde [`NetInbox::take_chat`].
+
+use super::protocol::{ClientMessage, ServerMessage, VoxelEdits, PROTOCOL_VERSION};
+use super::transport::Connection;
+use crate::core::constants::BASE_CHUNK_SIZE;
+use crate::core::{GameState, Inventory, ItemStack, WorldSeed};
+use crate::player::{Player, PrimaryPlayer, PLAYER_HALF_HEIGHT, PLAYER_RADIUS};
+use crate::voxel::{VoxelDiffs, VoxelWorld};
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+
+// ============================================================================
+// PARÁMETROS
+// ============================================================================
+
+/// Cuánto en el pasado se dibuja a los demás jugadores (segundos): margen para
+/// tener siempre un estado a cada lado.
+pub const INTERPOLATION_DELAY_SECS: f64 = 0.1;
+
+/// Cada cuánto se envía la posición del jugador (segundos).
+const PLAYER_STATE_INTERVAL_SECS: f32 = 1.0 / 20.0;
+
+/// Estados guardados como mucho por jugador remoto.
+const MAX_SAMPLES: usize = 32;
+
+// ============================================================================
+// RESOURCES / COMPONENTS
+// ============================================================================
+
+/// Conexión con el servidor. Solo existe jugando en red.
+#[derive(Resource)]
+pub struct NetClient {
+    connection: Connection,
+    /// Id que asignó el servidor (tras el `Welcome`).
+    pub player_id: Option<u32>,
+    /// `reloj del servidor - reloj local`: el mayor visto, que es el de menor
+    /// latencia.
+    clock_offset: Option<f64>,
+    /// Tiempo desde el último estado enviado.
+    since_state: f32,
+}
+
+impl NetClient {
+    /// Conecta con el servidor y le saluda.
+    pub fn connect(addr: &str) -> io::Result<Self> {
+        let mut connection = Connection::connect(addr)?;
+        connection.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+        });
+        info!("Conectando con {}", addr);
+        Ok(Self {
+            connection,
+            player_id: None,
+            clock_offset: None,
+            since_state: 0.0,
+        })
+    }
+
+    /// Manda una intención (romper, colocar, fabricar, mover un stack). Ni el
+    /// mundo ni el inventario locales cambian hasta que responde el servidor.
+    pub fn request(&mut self, intent: ClientMessage) {
+        self.connection.send(&intent);
+    }
+
+    /// Manda una línea de chat a todos los jugadores.
+    pub fn send_chat(&mut self, text: &str) {
+        self.connection.send(&ClientMessage::Chat {
+            text: text.to_string(),
+        });
+    }
+}
+
+/// Lo recibido del servidor que aplican otros sistemas.
+#[derive(Resource, Debug, Default)]
+pub struct NetInbox {
+    /// Ediciones a aplicar (de `ChunkDiffs` y `VoxelDelta`), en orden.
+    edits: VoxelEdits,
+    /// Estados de jugadores: (tiempo del servidor, id, posición, giro).
+    samples: Vec<(u32, RemoteSample)>,
+    /// Jugadores que se fueron.
+    left: Vec<u32>,
+    /// Chat recibido: (id del jugador, texto).
+    chat: Vec<(u32, String)>,
+    /// Último inventario recibido, hasta que haya jugador al que dárselo.
+    inventory: Option<Vec<Option<ItemStack>>>,
+}
+
+impl NetInbox {
+    /// Devuelve (y vacía) el chat recibido, en orden.
+    pub fn take_chat(&mut self) -> Vec<(u32, String)> {
+        std::mem::take(&mut self.chat)
+    }
+}
+
+/// Estado de un jugador remoto en un instante del reloj del servidor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RemoteSample {
+    pub time: f64,
+    pub position: Vec3,
+    pub yaw: f32,
+}
+
+/// Otro jugador del servidor, con sus últimos estados.
+#[derive(Component, Debug)]
+pub struct RemotePlayer {
+    pub player_id: u32,
+    samples: VecDeque<RemoteSample>,
+}
+
+// ============================================================================
+// INTERPOLACIÓN (función pura)
+// ============================================================================
+
+/// Posición y giro en `time` interpolando entre los estados que lo rodean
+/// (ordenados por tiempo). Antes del primero o después del último se queda en
+/// ese: no se extrapola.
+pub fn sample_at(samples: &VecDeque<RemoteSample>, time: f64) -> Option<(Vec3, f32)> {
+    let after = samples.iter().position(|s| s.time > time);
+    let (a, b) = match after {
+        Some(0) => return samples.front().map(|s| (s.position, s.yaw)),
+        Some(i) => (samples[i - 1], samples[i]),
+        None => return samples.back().map(|s| (s.position, s.yaw)),
+    };
+    let t = ((time - a.time) / (b.time - a.time)) as f32;
+    // El giro por el camino corto (de -π a π no da la vuelta entera)
+    let turn = (b.yaw - a.yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+        - std::f32::consts::PI;
+    Some((a.position.lerp(b.position, t), a.yaw + turn * t))
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+/// Sistemas del cliente de red; no hacen nada sin un [`NetClient`].
+pub struct NetClientPlugin;
+
+impl Plugin for NetClientPlugin {
+    fn build(&self, app: &mut App) {
+        let connected = resource_exists::<NetClient>;
+        app.init_resource::<NetInbox>()
+            .add_systems(
+                Update,
+                (
+                    receive_server_messages,
+                    apply_server_edits,
+                    apply_server_inventory,
+                    update_remote_players,
+                    interpolate_remote_players,
+                )
+                    .chain()
+                    .run_if(connected),
+            )
+            .add_systems(
+                Update,
+                send_player_state
+                    .run_if(connected)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(Last, flush_net_client.run_if(connected))
+            // Volver al menú es salir del servidor
+            .add_systems(OnEnter(GameState::MainMenu), leave_server);
+    }
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Corta la conexión y vuelve al menú.
+fn disconnect(
+    commands: &mut Commands,
+    state: &State<GameState>,
+    next_state: &mut NextState<GameState>,
+    error: io::Error,
+) {
+    error!("Desconectado del servidor: {}", error);
+    commands.remove_resource::<NetClient>();
+    if *state.get() != GameState::MainMenu {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+/// Lee lo que llegó del servidor. El `Welcome` fija la seed y arranca la
+/// partida; el resto va a [`NetInbox`].
+pub fn receive_server_messages(
+    mut commands: Commands,
+    mut client: ResMut<NetClient>,
+    mut inbox: ResMut<NetInbox>,
+    mut world_seed: ResMut<WorldSeed>,
+    mut voxel_diffs: ResMut<VoxelDiffs>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let messages = match client.connection.receive::<ServerMessage>() {
+        Ok(messages) => messages,
+        Err(e) => return disconnect(&mut commands, &state, &mut next_state, e),
+    };
+    for message in messages {
+        match message {
+            ServerMessage::Welcome {
+                player_id,
+                seed,
+                spawn,
+            } => {
+                info!("En el servidor como jugador {} (seed {}, spawn {:?})", player_id, seed, spawn);
+                client.player_id = Some(player_id);
+                // El mundo es el del servidor: su seed y sus diffs, que llegan después
+                world_seed.0 = seed;
+                voxel_diffs.clear();
+                inbox.edits.clear();
+                next_state.set(GameState::InGame);
+            }
+            ServerMessage::ChunkDiffs { chunk_pos, voxels } => {
+                let origin = chunk_pos * BASE_CHUNK_SIZE as i32;
+                inbox
+                    .edits
+                    .extend(voxels.into_iter().map(|(local, v)| (origin + local, v)));
+            }
+            ServerMessage::VoxelDelta { edits } => inbox.edits.extend(edits),
+            ServerMessage::PlayerStates { time, players } => {
+                inbox.samples.extend(players.into_iter().map(|p| {
+                    let sample = RemoteSample {
+                        time,
+                        position: p.position,
+                        yaw: p.yaw,
+                    };
+                    (p.player_id, sample)
+                }));
+            }
+            ServerMessage::PlayerLeft { player_id } => inbox.left.push(player_id),
+            ServerMessage::Chat { player_id, text } => inbox.chat.push((player_id, text)),
+            ServerMessage::Inventory { slots } => inbox.inventory = Some(slots),
+        }
+    }
+}
+
+/// Aplica al mundo local las ediciones del servidor. Sin chunks cargados (aún
+/// en el menú) solo quedan en `VoxelDiffs`, y el terreno las recoge al
+/// generarse.
+pub fn apply_server_edits(mut inbox: ResMut<NetInbox>, mut world: VoxelWorld) {
+    if !inbox.edits.is_empty() {
+        world.set_batch(inbox.edits.drain(..));
+    }
+}
+
+/// Copia en el `PrimaryPlayer` el inventario del servidor (el slot elegido
+/// sigue siendo del cliente).
+pub fn apply_server_inventory(
+    mut inbox: ResMut<NetInbox>,
+    mut player: Query<&mut Inventory, With<PrimaryPlayer>>,
+) {
+    let Ok(mut inventory) = player.single_mut() else {
+        return;
+    };
+    if let Some(slots) = inbox.inventory.take() {
+        for (slot, received) in inventory.slots.iter_mut().zip(slots) {
+            *slot = received;
+        }
+    }
+}
+
+/// Guarda los estados recibidos en cada jugador remoto, crea los nuevos y
+/// quita los que se fueron.
+pub fn update_remote_players(
+    mut commands: Commands,
+    mut client: ResMut<NetClient>,
+    real_time: Res<Time<Real>>,
+    mut inbox: ResMut<NetInbox>,
+    mut remote: Query<(Entity, &mut RemotePlayer)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let now = real_time.elapsed_secs_f64();
+    let mut new_players: HashMap<u32, VecDeque<RemoteSample>> = HashMap::new();
+    for (player_id, sample) in inbox.samples.drain(..) {
+        let offset = sample.time - now;
+        client.clock_offset = Some(client.clock_offset.map_or(offset, |o| o.max(offset)));
+        if client.player_id == Some(player_id) {
+            continue; // Uno mismo
+        }
+        let samples = match remote.iter_mut().find(|(_, p)| p.player_id == player_id) {
+            Some((_, player)) => &mut player.into_inner().samples,
+            None => new_players.entry(player_id).or_default(),
+        };
+        samples.push_back(sample);
+        if samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    for (player_id, samples) in new_players {
+        let position = samples.back().map_or(Vec3::ZERO, |s| s.position);
+        commands.spawn((
+            RemotePlayer { player_id, samples },
+            Mesh3d(meshes.add(Capsule3d::new(PLAYER_RADIUS, PLAYER_HALF_HEIGHT * 2.0))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(0.2, 0.4, 0.9),
+                ..default()
+            })),
+            Transform::from_translation(position),
+        ));
+        info!("Jugador {} en el mundo", player_id);
+    }
+
+    for player_id in inbox.left.drain(..) {
+        for (entity, player) in &remote {
+            if player.player_id == player_id {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Coloca a cada jugador remoto donde estaba `INTERPOLATION_DELAY_SECS` antes
+/// en el reloj del servidor.
+pub fn interpolate_remote_players(
+    client: Res<NetClient>,
+    real_time: Res<Time<Real>>,
+    mut remote: Query<(&mut RemotePlayer, &mut Transform)>,
+) {
+    let Some(offset) = client.clock_offset else {
+        return;
+    };
+    let render_time = real_time.elapsed_secs_f64() + offset - INTERPOLATION_DELAY_SECS;
+    for (mut player, mut transform) in &mut remote {
+        // Lo anterior al par que rodea `render_time` ya no se necesita
+        while player.samples.len() > 2 && player.samples[1].time <= render_time {
+            player.samples.pop_front();
+        }
+        if let Some((position, yaw)) = sample_at(&player.samples, render_time) {
+            transform.translation = position;
+            transform.rotation = Quat::from_rotation_y(yaw);
+        }
+    }
+}
+
+/// Envía la posición y el giro del jugador a ritmo fijo. Una conexión es un
+/// jugador: en pantalla dividida solo se envía el `PrimaryPlayer`.
+pub fn send_player_state(
+    time: Res<Time>,
+    mut client: ResMut<NetClient>,
+    player: Query<(&Transform, &Player), With<PrimaryPlayer>>,
+) {
+    client.since_state += time.delta_secs();
+    if client.since_state < PLAYER_STATE_INTERVAL_SECS {
+        return;
+    }
+    client.since_state = 0.0;
+    let Ok((transform, player)) = player.single() else {
+        return;
+    };
+    client.connection.send(&ClientMessage::PlayerState {
+        position: transform.translation,
+        yaw: player.yaw,
+    });
+}
+
+/// Escribe lo encolado en el frame.
+pub fn flush_net_client(
+    mut commands: Commands,
+    mut client: ResMut<NetClient>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Err(e) = client.connection.flush() {
+        disconnect(&mut commands, &state, &mut next_state, e);
+    }
+}
+
+/// Al volver al menú se sale del servidor (la conexión aún sin `Welcome`, la
+/// del arranque, se conserva) y se quitan los jugadores remotos.
+pub fn leave_server(
+    mut commands: Commands,
+    client: Option<Res<NetClient>>,
+    remote: Query<Entity, With<RemotePlayer>>,
+) {
+    if client.is_some_and(|c| c.player_id.is_some()) {
+        info!("Saliendo del servidor");
+        commands.remove_resource::<NetClient>();
+    }
+    for entity in &remote {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_players_are_interpolated_between_samples() {
+        let sample = |time, x, yaw| RemoteSample {
+            time,
+            position: Vec3::new(x, 0.0, 0.0),
+            yaw,
+        };
+        let samples: VecDeque<_> = [sample(1.0, 0.0, 3.0), sample(1.5, 10.0, -3.0)].into();
+
+        let (position, yaw) = sample_at(&samples, 1.25).unwrap();
+        assert!((position.x - 5.0).abs() < 1e-4);
+        // De 3 a -3 por el camino corto (cruzando π), no pasando por 0
+        assert!(yaw.abs() > 3.0);
+        // Sin extrapolar por ningún lado
+        assert_eq!(sample_at(&samples, 0.5).unwrap().0.x, 0.0);
+        assert_eq!(sample_at(&samples, 2.0).unwrap().0.x, 10.0);
+        assert!(sample_at(&VecDeque::new(), 1.0).is_none());
+    }
+}
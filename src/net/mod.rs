@@ -0,0 +1,19 @@
+//! Multijugador: protocolo cliente-servidor sobre TCP.
+//!
+//! El mundo autoritativo es "seed + `VoxelDiffs`" y vive en el servidor
+//! (`--server`, ver `src/server/`). El cliente (`--connect <addr>`) regenera
+//! el terreno con la seed del saludo y solo recibe diffs, ediciones en lotes y
+//! la posición de los demás jugadores.
+//!
+//! ## Estructura
+//! - [`protocol`]: mensajes y su formato binario
+//! - [`transport`]: conexión TCP no bloqueante con tramas
+//! - [`client`]: el lado del juego (plugin y sistemas)
+
+pub mod client;
+pub mod protocol;
+pub mod transport;
+
+pub use client::*;
+pub use protocol::*;
+pub use transport::*;
@@ -0,0 +1,518 @@
+//! Mensajes entre cliente y servidor y su formato binario.
+//!
+//! Como el mundo es "seed + `VoxelDiffs`", por la red solo viajan la seed (en
+//! el saludo), los diffs de cada chunk que el cliente va a cargar, las
+//! ediciones del servidor en lotes, la posición de los jugadores, el
+//! inventario de cada uno y el chat. El cliente regenera el terreno por su
+//! cuenta.
+//!
+//! El cliente no manda ediciones sino INTENCIONES (romper aquí con el slot N,
+//! colocar el slot N allí, fabricar la receta R, mover un stack): el servidor
+//! saca de su propio mundo y del inventario que guarda de cada jugador qué
+//! voxels cambian, qué botín da y qué se gasta.
+//!
+//! ## Formato (little endian, como las regiones de `world_save`)
+//! ```text
+//! trama: len: u32 | tag: u8 | campos...
+//! IVec3: x,y,z: i32 | Vec3: x,y,z: f32 | voxel: tipo: u8
+//! texto: len: u16 | bytes UTF-8
+//! diffs de chunk: pos: IVec3 | n: u32 | por voxel: local: u16 (x|y<<5|z<<10), tipo: u8
+//! slot: 0 (vacío) | 1, tipo: u8, n: u8 | 2, herramienta: u8, tier: u8, durabilidad: u32
+//! ```
+
+use crate::core::{ItemId, ItemStack};
+use crate::voxel::world_save::{pack_local, unpack_local, ByteReader};
+use crate::voxel::{ToolTier, ToolType, VoxelType};
+use bevy::prelude::*;
+use std::io;
+
+/// Versión del protocolo: el servidor corta a los clientes con otra.
+pub const PROTOCOL_VERSION: u16 = 3;
+
+/// Puerto por defecto del servidor.
+pub const DEFAULT_PORT: u16 = 7777;
+
+/// Tamaño máximo de una trama (bytes): algo más grande es un flujo corrupto.
+pub const MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+/// Longitud máxima de un mensaje de chat (caracteres); el servidor recorta.
+pub const MAX_CHAT_CHARS: usize = 256;
+
+/// Ediciones de voxels en coordenadas de voxel MUNDIALES.
+pub type VoxelEdits = Vec<(IVec3, VoxelType)>;
+
+/// Herramientas y tiers en el orden de su byte en la red.
+const TOOL_TYPES: [ToolType; 6] = [
+    ToolType::Pickaxe,
+    ToolType::Axe,
+    ToolType::Shovel,
+    ToolType::Hoe,
+    ToolType::Shears,
+    ToolType::None,
+];
+const TOOL_TIERS: [ToolTier; 3] = [ToolTier::Wood, ToolTier::Stone, ToolTier::Metal];
+
+// ============================================================================
+// MENSAJES
+// ============================================================================
+
+/// Lo que el cliente manda al servidor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    /// Primer mensaje de la conexión.
+    Hello { version: u16 },
+    /// Posición (centro de la cápsula) y giro horizontal del jugador.
+    PlayerState { position: Vec3, yaw: f32 },
+    /// Terminó de romper `voxel` (golpeado en la cara `normal`, mirando hacia
+    /// `direction`) con lo que hay en el slot `slot` de la barra rápida.
+    Break {
+        voxel: IVec3,
+        normal: IVec3,
+        direction: Vec3,
+        slot: u8,
+    },
+    /// Línea de chat para todos.
+    Chat { text: String },
+    /// Colocar el material del slot `slot` de la barra rápida en `voxel`.
+    Place { voxel: IVec3, slot: u8 },
+    /// Fabricar la receta `recipe` (índice en `recipes.ron`).
+    Craft { recipe: u16 },
+    /// Mover el stack de un slot a otro (`Inventory::move_stack`).
+    MoveStack { from: u8, to: u8 },
+}
+
+/// Lo que el servidor manda a cada cliente.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    /// Respuesta al saludo: id del jugador y mundo a regenerar.
+    Welcome { player_id: u32, seed: i32, spawn: Vec3 },
+    /// Todos los diffs de un chunk que entra en el radio del jugador.
+    ChunkDiffs {
+        chunk_pos: IVec3,
+        voxels: Vec<(IVec3, VoxelType)>,
+    },
+    /// Ediciones aplicadas por el servidor desde el último lote.
+    VoxelDelta { edits: VoxelEdits },
+    /// Posición de todos los jugadores en el instante `time` del servidor.
+    PlayerStates {
+        time: f64,
+        players: Vec<PlayerSnapshot>,
+    },
+    /// Un jugador se desconectó.
+    PlayerLeft { player_id: u32 },
+    /// Línea de chat de un jugador (también vuelve a quien la escribió).
+    Chat { player_id: u32, text: String },
+    /// El inventario del jugador tal como lo guarda el servidor, slot a slot.
+    Inventory { slots: Vec<Option<ItemStack>> },
+}
+
+/// Estado de un jugador en un `PlayerStates`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayerSnapshot {
+    pub player_id: u32,
+    pub position: Vec3,
+    pub yaw: f32,
+}
+
+/// Un mensaje que sabe escribirse y leerse en binario.
+pub trait Wire: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    fn decode(bytes: &[u8]) -> io::Result<Self>;
+}
+
+// ============================================================================
+// CODIFICACIÓN
+// ============================================================================
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_vec3(out: &mut Vec<u8>, v: Vec3) {
+    for c in v.to_array() {
+        out.extend_from_slice(&c.to_le_bytes());
+    }
+}
+
+fn put_ivec3(out: &mut Vec<u8>, v: IVec3) {
+    for c in v.to_array() {
+        out.extend_from_slice(&c.to_le_bytes());
+    }
+}
+
+fn put_edits(out: &mut Vec<u8>, edits: &[(IVec3, VoxelType)]) {
+    put_u32(out, edits.len() as u32);
+    for (voxel, voxel_type) in edits {
+        put_ivec3(out, *voxel);
+        out.push(voxel_type.id());
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, text: &str) {
+    // Cortado en un límite de carácter para que siga siendo UTF-8 válido
+    let mut len = text.len().min(u16::MAX as usize);
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    out.extend_from_slice(&(len as u16).to_le_bytes());
+    out.extend_from_slice(&text.as_bytes()[..len]);
+}
+
+fn put_slot(out: &mut Vec<u8>, slot: &Option<ItemStack>) {
+    match slot.map(|stack| (stack.item, stack)) {
+        None => out.push(0),
+        Some((ItemId::Voxel(voxel_type), stack)) => {
+            out.extend_from_slice(&[1, voxel_type.id(), stack.count.min(u8::MAX as u32) as u8]);
+        }
+        Some((ItemId::Tool(tool_type, tier), stack)) => {
+            let tool_type = TOOL_TYPES.iter().position(|&t| t == tool_type).unwrap_or(0);
+            let tier = TOOL_TIERS.iter().position(|&t| t == tier).unwrap_or(0);
+            out.extend_from_slice(&[2, tool_type as u8, tier as u8]);
+            put_u32(out, stack.durability);
+        }
+    }
+}
+
+fn read_vec3(r: &mut ByteReader) -> io::Result<Vec3> {
+    Ok(Vec3::new(r.f32()?, r.f32()?, r.f32()?))
+}
+
+fn read_ivec3(r: &mut ByteReader) -> io::Result<IVec3> {
+    Ok(IVec3::new(r.i32()?, r.i32()?, r.i32()?))
+}
+
+fn read_edits(r: &mut ByteReader) -> io::Result<VoxelEdits> {
+    let n = r.u32()?;
+    (0..n)
+        .map(|_| Ok((read_ivec3(r)?, VoxelType::from_u8(r.u8()?))))
+        .collect()
+}
+
+fn read_slot(r: &mut ByteReader) -> io::Result<Option<ItemStack>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "slot de inventario inválido");
+    match r.u8()? {
+        0 => Ok(None),
+        1 => Ok(Some(ItemStack::voxels(VoxelType::from_u8(r.u8()?), r.u8()? as u32))),
+        2 => {
+            let tool_type = *TOOL_TYPES.get(r.u8()? as usize).ok_or_else(invalid)?;
+            let tier = *TOOL_TIERS.get(r.u8()? as usize).ok_or_else(invalid)?;
+            Ok(Some(ItemStack {
+                item: ItemId::Tool(tool_type, tier),
+                count: 1,
+                durability: r.u32()?,
+            }))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn read_str(r: &mut ByteReader) -> io::Result<String> {
+    let len = r.u16()? as usize;
+    String::from_utf8(r.slice(len)?.to_vec())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn unknown_tag(tag: u8) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("mensaje desconocido: {}", tag))
+}
+
+impl Wire for ClientMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Hello { version } => {
+                out.push(0);
+                out.extend_from_slice(&version.to_le_bytes());
+            }
+            Self::PlayerState { position, yaw } => {
+                out.push(1);
+                put_vec3(out, *position);
+                out.extend_from_slice(&yaw.to_le_bytes());
+            }
+            Self::Break {
+                voxel,
+                normal,
+                direction,
+                slot,
+            } => {
+                out.push(2);
+                put_ivec3(out, *voxel);
+                put_ivec3(out, *normal);
+                put_vec3(out, *direction);
+                out.push(*slot);
+            }
+            Self::Chat { text } => {
+                out.push(3);
+                put_str(out, text);
+            }
+            Self::Place { voxel, slot } => {
+                out.push(4);
+                put_ivec3(out, *voxel);
+                out.push(*slot);
+            }
+            Self::Craft { recipe } => {
+                out.push(5);
+                out.extend_from_slice(&recipe.to_le_bytes());
+            }
+            Self::MoveStack { from, to } => {
+                out.extend_from_slice(&[6, *from, *to]);
+            }
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        let mut r = ByteReader::new(bytes);
+        match r.u8()? {
+            0 => Ok(Self::Hello { version: r.u16()? }),
+            1 => Ok(Self::PlayerState {
+                position: read_vec3(&mut r)?,
+                yaw: r.f32()?,
+            }),
+            2 => Ok(Self::Break {
+                voxel: read_ivec3(&mut r)?,
+                normal: read_ivec3(&mut r)?,
+                direction: read_vec3(&mut r)?,
+                slot: r.u8()?,
+            }),
+            3 => Ok(Self::Chat {
+                text: read_str(&mut r)?,
+            }),
+            4 => Ok(Self::Place {
+                voxel: read_ivec3(&mut r)?,
+                slot: r.u8()?,
+            }),
+            5 => Ok(Self::Craft { recipe: r.u16()? }),
+            6 => Ok(Self::MoveStack {
+                from: r.u8()?,
+                to: r.u8()?,
+            }),
+            tag => Err(unknown_tag(tag)),
+        }
+    }
+}
+
+impl Wire for ServerMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Welcome {
+                player_id,
+                seed,
+                spawn,
+            } => {
+                out.push(0);
+                put_u32(out, *player_id);
+                out.extend_from_slice(&seed.to_le_bytes());
+                put_vec3(out, *spawn);
+            }
+            Self::ChunkDiffs { chunk_pos, voxels } => {
+                out.push(1);
+                put_ivec3(out, *chunk_pos);
+                put_u32(out, voxels.len() as u32);
+                for (local, voxel_type) in voxels {
+                    out.extend_from_slice(&pack_local(*local).to_le_bytes());
+                    out.push(voxel_type.id());
+                }
+            }
+            Self::VoxelDelta { edits } => {
+                out.push(2);
+                put_edits(out, edits);
+            }
+            Self::PlayerStates { time, players } => {
+                out.push(3);
+                out.extend_from_slice(&time.to_le_bytes());
+                put_u32(out, players.len() as u32);
+                for player in players {
+                    put_u32(out, player.player_id);
+                    put_vec3(out, player.position);
+                    out.extend_from_slice(&player.yaw.to_le_bytes());
+                }
+            }
+            Self::PlayerLeft { player_id } => {
+                out.push(4);
+                put_u32(out, *player_id);
+            }
+            Self::Chat { player_id, text } => {
+                out.push(5);
+                put_u32(out, *player_id);
+                put_str(out, text);
+            }
+            Self::Inventory { slots } => {
+                out.push(6);
+                out.push(slots.len().min(u8::MAX as usize) as u8);
+                slots.iter().take(u8::MAX as usize).for_each(|slot| put_slot(out, slot));
+            }
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        let mut r = ByteReader::new(bytes);
+        match r.u8()? {
+            0 => Ok(Self::Welcome {
+                player_id: r.u32()?,
+                seed: r.i32()?,
+                spawn: read_vec3(&mut r)?,
+            }),
+            1 => {
+                let chunk_pos = read_ivec3(&mut r)?;
+                let n = r.u32()?;
+                let voxels = (0..n)
+                    .map(|_| Ok((unpack_local(r.u16()?), VoxelType::from_u8(r.u8()?))))
+                    .collect::<io::Result<_>>()?;
+                Ok(Self::ChunkDiffs { chunk_pos, voxels })
+            }
+            2 => Ok(Self::VoxelDelta {
+                edits: read_edits(&mut r)?,
+            }),
+            3 => {
+                let time = r.f64()?;
+                let n = r.u32()?;
+                let players = (0..n)
+                    .map(|_| {
+                        Ok(PlayerSnapshot {
+                            player_id: r.u32()?,
+                            position: read_vec3(&mut r)?,
+                            yaw: r.f32()?,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?;
+                Ok(Self::PlayerStates { time, players })
+            }
+            4 => Ok(Self::PlayerLeft {
+                player_id: r.u32()?,
+            }),
+            5 => Ok(Self::Chat {
+                player_id: r.u32()?,
+                text: read_str(&mut r)?,
+            }),
+            6 => {
+                let n = r.u8()?;
+                let slots = (0..n).map(|_| read_slot(&mut r)).collect::<io::Result<_>>()?;
+                Ok(Self::Inventory { slots })
+            }
+            tag => Err(unknown_tag(tag)),
+        }
+    }
+}
+
+// ============================================================================
+// TRAMAS
+// ============================================================================
+
+/// Añade a `out` un mensaje con su longitud delante.
+pub fn encode_frame<M: Wire>(message: &M, out: &mut Vec<u8>) {
+    let start = out.len();
+    put_u32(out, 0);
+    message.encode(out);
+    let len = (out.len() - start - 4) as u32;
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+}
+
+/// Lee la primera trama completa de `buffer`: el mensaje y los bytes que
+/// ocupa, o `None` si aún no llegó entera.
+pub fn decode_frame<M: Wire>(buffer: &[u8]) -> io::Result<Option<(M, usize)>> {
+    let Some(header) = buffer.get(..4) else {
+        return Ok(None);
+    };
+    let len = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("trama demasiado grande: {} bytes", len),
+        ));
+    }
+    let Some(payload) = buffer.get(4..4 + len) else {
+        return Ok(None);
+    };
+    Ok(Some((M::decode(payload)?, 4 + len)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_roundtrip_through_frames() {
+        let client = vec![
+            ClientMessage::Hello {
+                version: PROTOCOL_VERSION,
+            },
+            ClientMessage::PlayerState {
+                position: Vec3::new(1.5, -2.0, 300.25),
+                yaw: -1.2,
+            },
+            ClientMessage::Break {
+                voxel: IVec3::new(-40, 3, 7),
+                normal: IVec3::Y,
+                direction: Vec3::new(0.0, -0.6, 0.8),
+                slot: 4,
+            },
+            ClientMessage::Chat {
+                text: "hola, ¿qué tal?".to_string(),
+            },
+            ClientMessage::Place {
+                voxel: IVec3::new(0, -1, 2),
+                slot: 8,
+            },
+            ClientMessage::Craft { recipe: 3 },
+            ClientMessage::MoveStack { from: 35, to: 0 },
+        ];
+        let server = vec![
+            ServerMessage::Welcome {
+                player_id: 3,
+                seed: -12345,
+                spawn: Vec3::new(0.0, 20.0, 0.0),
+            },
+            ServerMessage::ChunkDiffs {
+                chunk_pos: IVec3::new(-3, 1, 15),
+                voxels: vec![(IVec3::new(31, 0, 5), VoxelType::Wood)],
+            },
+            ServerMessage::VoxelDelta {
+                edits: vec![(IVec3::new(1, 2, 3), VoxelType::Sand)],
+            },
+            ServerMessage::PlayerStates {
+                time: 12.5,
+                players: vec![PlayerSnapshot {
+                    player_id: 3,
+                    position: Vec3::new(4.0, 5.0, 6.0),
+                    yaw: 0.5,
+                }],
+            },
+            ServerMessage::PlayerLeft { player_id: 9 },
+            ServerMessage::Chat {
+                player_id: 2,
+                text: "ñandú".to_string(),
+            },
+            ServerMessage::Inventory {
+                slots: vec![
+                    Some(ItemStack::voxels(VoxelType::Dirt, 64)),
+                    None,
+                    Some(ItemStack {
+                        item: ItemId::Tool(ToolType::Shears, ToolTier::Stone),
+                        count: 1,
+                        durability: 117,
+                    }),
+                ],
+            },
+        ];
+
+        // Todo en un mismo flujo: cada trama sale entera y en orden
+        let mut stream = Vec::new();
+        client.iter().for_each(|m| encode_frame(m, &mut stream));
+        let mut pos = 0;
+        for expected in &client {
+            let (message, used) = decode_frame::<ClientMessage>(&stream[pos..]).unwrap().unwrap();
+            assert_eq!(&message, expected);
+            pos += used;
+        }
+        assert_eq!(pos, stream.len());
+
+        for expected in &server {
+            let mut frame = Vec::new();
+            encode_frame(expected, &mut frame);
+            // A medio llegar todavía no hay mensaje
+            assert!(decode_frame::<ServerMessage>(&frame[..frame.len() - 1]).unwrap().is_none());
+            assert_eq!(&decode_frame::<ServerMessage>(&frame).unwrap().unwrap().0, expected);
+        }
+
+        assert!(decode_frame::<ServerMessage>(&[1, 0, 0, 0, 99]).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Conexión TCP no bloqueante con tramas de `protocol`.
+//!
+//! Los sistemas no pueden esperar a la red: cada frame se lee lo que haya
+//! llegado (los mensajes completos salen, los trozos esperan en `incoming`) y
+//! se escribe lo que el socket acepte (el resto espera en `outgoing`).
+
+use super::protocol::{decode_frame, encode_frame, Wire};
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+/// Bytes sin enviar tolerados: un cliente que no lee tanto se da por perdido.
+const MAX_OUTGOING_BYTES: usize = 64 * 1024 * 1024;
+
+/// Un extremo de la conexión (cliente o servidor).
+pub struct Connection {
+    stream: TcpStream,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+}
+
+impl Connection {
+    /// Envuelve un socket ya conectado (p. ej. el de `TcpListener::accept`).
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+        })
+    }
+
+    /// Conecta con un servidor (bloquea solo durante la conexión).
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::new(TcpStream::connect(addr)?)
+    }
+
+    /// Encola un mensaje; sale en el próximo [`flush`](Self::flush).
+    pub fn send<M: Wire>(&mut self, message: &M) {
+        encode_frame(message, &mut self.outgoing);
+    }
+
+    /// Escribe lo encolado hasta que el socket no acepte más.
+    pub fn flush(&mut self) -> io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.outgoing.drain(..written);
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if self.outgoing.len() > MAX_OUTGOING_BYTES {
+            return Err(io::Error::other("el otro extremo no lee"));
+        }
+        Ok(())
+    }
+
+    /// Los mensajes completos que llegaron. Error si se cerró la conexión o
+    /// el flujo no es válido.
+    pub fn receive<M: Wire>(&mut self) -> io::Result<Vec<M>> {
+        let mut buffer = [0u8; 16 * 1024];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "conexión cerrada",
+                    ))
+                }
+                Ok(read) => self.incoming.extend_from_slice(&buffer[..read]),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut messages = Vec::new();
+        let mut used = 0;
+        while let Some((message, len)) = decode_frame(&self.incoming[used..])? {
+            messages.push(message);
+            used += len;
+        }
+        self.incoming.drain(..used);
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::protocol::{ClientMessage, ServerMessage, PROTOCOL_VERSION};
+    use bevy::math::Vec3;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn messages_cross_a_localhost_socket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = Connection::connect(listener.local_addr().unwrap()).unwrap();
+        let mut server = Connection::new(listener.accept().unwrap().0).unwrap();
+
+        let hello = ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+        };
+        client.send(&hello);
+        client.flush().unwrap();
+        let welcome = ServerMessage::Welcome {
+            player_id: 1,
+            seed: 42,
+            spawn: Vec3::Y,
+        };
+        server.send(&welcome);
+        server.flush().unwrap();
+
+        // Sin bloquear: se sondea hasta que llega
+        let start = Instant::now();
+        let (mut to_server, mut to_client) = (Vec::new(), Vec::new());
+        while to_server.is_empty() || to_client.is_empty() {
+            to_server.extend(server.receive::<ClientMessage>().unwrap());
+            to_client.extend(client.receive::<ServerMessage>().unwrap());
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+        assert_eq!(to_server, vec![hello]);
+        assert_eq!(to_client, vec![welcome]);
+
+        // Al cerrar un extremo, el otro lo ve como error
+        drop(client);
+        let start = Instant::now();
+        while server.receive::<ClientMessage>().is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+    }
+}
@@ -1,10 +1,13 @@
 //! Módulo de física
 //!
 //! Sistema de física usando Rapier para colisiones realistas de terreno y drops de voxels.
-//! Optimizado para multijugador con física determinística.
+//! Optimizado para multijugador con física determinística: con las mismas
+//! acciones y el mismo `dt` por frame, Rapier y los drops dan lo mismo (lo
+//! comprueba la repetición headless de `src/replay/`).
 
 pub mod rapier_integration;
 
+use crate::core::{DeterministicGame, GameState};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
@@ -13,8 +16,9 @@ pub use bevy_rapier3d::prelude::{Collider, RigidBody};
 
 // Re-exportar nuestras funciones personalizadas
 pub use rapier_integration::{
-    collect_rapier_drops_system, create_chunk_collider, spawn_rapier_voxel_drop,
-    update_rapier_drops_system, DropAssets,
+    attract_rapier_drops_system, collect_rapier_drops_system, create_chunk_collider,
+    despawn_all_drops, freeze_drops_on_unloaded_chunks_system, merge_rapier_drops_system,
+    spawn_rapier_voxel_drop, update_rapier_drops_system, DropAssets, DropSettings,
 };
 
 /// Plugin de física que configura Rapier para el juego de voxels
@@ -28,13 +32,25 @@ impl Plugin for PhysicsPlugin {
             // .add_plugins(RapierDebugRenderPlugin::default()) // Para debug visual
             // Assets compartidos de los drops (mesh + materiales), creados una vez
             .init_resource::<DropAssets>()
-            // Inventario del jugador (lo escribe la recolección, lo lee el HUD)
-            .init_resource::<crate::core::Inventory>()
-            // Agregar sistemas de drops
+            // Vida, fusión e imán de los drops (ajustable en caliente)
+            .init_resource::<DropSettings>()
+            // Agregar sistemas de drops: solo en juego (en pausa no envejecen)
             .add_systems(
                 Update,
-                (update_rapier_drops_system, collect_rapier_drops_system),
-            );
+                (
+                    freeze_drops_on_unloaded_chunks_system,
+                    update_rapier_drops_system,
+                    merge_rapier_drops_system,
+                    attract_rapier_drops_system,
+                    collect_rapier_drops_system,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame))
+                    // Grabando, los corre el juego determinista (src/replay/sim.rs)
+                    .run_if(not(resource_exists::<DeterministicGame>)),
+            )
+            // Los drops del mundo anterior no pasan al siguiente
+            .add_systems(OnEnter(GameState::MainMenu), despawn_all_drops);
     }
 }
 
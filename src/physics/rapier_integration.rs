@@ -2,10 +2,13 @@
 
 use crate::{
     core::{constants::VOXEL_SIZE, Inventory},
-    voxel::{VoxelType, VOXEL_TYPE_COUNT},
+    player::{Player, PLAYER_HALF_HEIGHT, PLAYER_RADIUS},
+    voxel::{materials, world_to_voxel, BaseChunk, ChunkMap, EmptyChunk, VoxelType},
 };
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Assets compartidos por todos los voxel drops.
 ///
@@ -16,8 +19,8 @@ use bevy_rapier3d::prelude::*;
 #[derive(Resource)]
 pub struct DropAssets {
     mesh: Handle<Mesh>,
-    /// Indexado por `VoxelType as usize` (una entrada por variante).
-    materials: [Handle<StandardMaterial>; VOXEL_TYPE_COUNT],
+    /// Indexado por `VoxelType::id` (una entrada por material).
+    materials: Vec<Handle<StandardMaterial>>,
 }
 
 impl FromWorld for DropAssets {
@@ -28,16 +31,19 @@ impl FromWorld for DropAssets {
             VOXEL_SIZE * 0.8,
         ));
 
-        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
-        // Una entrada por discriminante (0..COUNT), en orden del enum.
-        let materials = std::array::from_fn(|id| {
-            materials.add(StandardMaterial {
-                base_color: VoxelType::from_u8(id as u8).properties().color,
-                metallic: 0.1,
-                perceptual_roughness: 0.8,
-                ..default()
+        let mut assets = world.resource_mut::<Assets<StandardMaterial>>();
+        // Una entrada por material registrado, en orden de id.
+        let materials = materials()
+            .iter()
+            .map(|(_, material)| {
+                assets.add(StandardMaterial {
+                    base_color: material.color,
+                    metallic: 0.1,
+                    perceptual_roughness: 0.8,
+                    ..default()
+                })
             })
-        });
+            .collect();
 
         Self { mesh, materials }
     }
@@ -45,7 +51,37 @@ impl FromWorld for DropAssets {
 
 impl DropAssets {
     fn material(&self, voxel_type: VoxelType) -> Handle<StandardMaterial> {
-        self.materials[voxel_type as usize].clone()
+        self.materials[voxel_type.id() as usize].clone()
+    }
+}
+
+/// Ajustes de los drops. Es un recurso: se pueden cambiar en caliente.
+#[derive(Resource, Clone, Debug)]
+pub struct DropSettings {
+    /// Segundos de vida de un drop; solo corren mientras su chunk tiene física.
+    pub despawn_after_secs: f32,
+    /// Segundos desde que cae hasta que se puede recoger.
+    pub pickup_delay_secs: f32,
+    /// Los drops del mismo material a esta distancia (m) se juntan en uno.
+    pub merge_radius: f32,
+    /// A esta distancia (m) del cuerpo del jugador el drop vuela hacia él.
+    pub magnet_radius: f32,
+    /// Velocidad del vuelo hacia el jugador (m/s).
+    pub magnet_speed: f32,
+    /// A esta distancia (m) del eje del jugador el drop entra al inventario.
+    pub pickup_radius: f32,
+}
+
+impl Default for DropSettings {
+    fn default() -> Self {
+        Self {
+            despawn_after_secs: 60.0,
+            pickup_delay_secs: 1.0,
+            merge_radius: 0.5,
+            magnet_radius: 2.5,
+            magnet_speed: 8.0,
+            pickup_radius: PLAYER_RADIUS + 0.2,
+        }
     }
 }
 
@@ -54,33 +90,53 @@ impl DropAssets {
 pub struct RapierVoxelDrop {
     pub voxel_type: VoxelType,
     pub quantity: u32,
-    pub spawn_time: f32,
-    pub can_collect: bool,
+    /// Segundos en el mundo (no corren mientras está congelado ni en pausa).
+    pub age: f32,
 }
 
 impl RapierVoxelDrop {
-    pub fn new(voxel_type: VoxelType, quantity: u32, spawn_time: f32) -> Self {
+    pub fn new(voxel_type: VoxelType, quantity: u32) -> Self {
         Self {
             voxel_type,
             quantity,
-            spawn_time,
-            can_collect: false,
+            age: 0.0,
         }
     }
 
-    pub fn should_despawn(&self, current_time: f32) -> bool {
-        current_time - self.spawn_time > 60.0
+    pub fn can_collect(&self, settings: &DropSettings) -> bool {
+        self.age >= settings.pickup_delay_secs
+    }
+
+    pub fn should_despawn(&self, settings: &DropSettings) -> bool {
+        self.age > settings.despawn_after_secs
     }
 }
 
+/// Azar del salto y el giro de un drop, sembrado con el sitio, el tipo y la
+/// cantidad (no con `rand::random`): la misma rotura suelta el drop igual cada
+/// vez, así una repetición (`replay`) reproduce también la física de los drops.
+fn drop_rng(position: Vec3, voxel_type: VoxelType, quantity: u32) -> StdRng {
+    let seed = position
+        .to_array()
+        .iter()
+        .fold((voxel_type.id() as u64) << 32 | quantity as u64, |hash, c| {
+            hash.rotate_left(21) ^ c.to_bits() as u64
+        });
+    StdRng::seed_from_u64(seed)
+}
+
 /// Spawns a voxel drop with real Rapier physics
+///
+/// Sin `velocity` es el drop de un voxel roto: aparece 2 voxels por encima y
+/// salta hacia arriba. Con `velocity` (un item lanzado) sale justo en
+/// `world_position` con esa velocidad.
 pub fn spawn_rapier_voxel_drop(
     commands: &mut Commands,
     drop_assets: &DropAssets,
     voxel_type: VoxelType,
     quantity: u32,
     world_position: Vec3,
-    current_time: f32,
+    velocity: Option<Vec3>,
 ) {
     let properties = voxel_type.properties();
 
@@ -88,19 +144,24 @@ pub fn spawn_rapier_voxel_drop(
     let cube_mesh = drop_assets.mesh.clone();
     let material = drop_assets.material(voxel_type);
 
-    // ARREGLO: Spawnar drops siempre arriba del voxel destruido
-    let spawn_position = Vec3::new(
-        world_position.x,
-        world_position.y + VOXEL_SIZE * 2.0, // Spawnar 2 voxels arriba
-        world_position.z,
-    );
-
-    // ARREGLO: Velocidad inicial siempre hacia arriba con componentes horizontales menores
-    let initial_velocity = Vec3::new(
-        (rand::random::<f32>() - 0.5) * 2.0, // Reducir velocidad horizontal
-        rand::random::<f32>() * 2.0 + 3.0,   // Velocidad hacia arriba más consistente (3-5 m/s)
-        (rand::random::<f32>() - 0.5) * 2.0, // Reducir velocidad horizontal
-    );
+    let mut rng = drop_rng(world_position, voxel_type, quantity);
+    let (spawn_position, initial_velocity) = match velocity {
+        Some(velocity) => (world_position, velocity),
+        None => (
+            // ARREGLO: Spawnar drops siempre arriba del voxel destruido
+            Vec3::new(
+                world_position.x,
+                world_position.y + VOXEL_SIZE * 2.0, // Spawnar 2 voxels arriba
+                world_position.z,
+            ),
+            // ARREGLO: Velocidad inicial siempre hacia arriba con componentes horizontales menores
+            Vec3::new(
+                rng.gen_range(-1.0..1.0), // Reducir velocidad horizontal
+                rng.gen_range(3.0..5.0), // Velocidad hacia arriba más consistente (3-5 m/s)
+                rng.gen_range(-1.0..1.0), // Reducir velocidad horizontal
+            ),
+        ),
+    };
 
     commands.spawn((
         // Visual components
@@ -110,7 +171,7 @@ pub fn spawn_rapier_voxel_drop(
         GlobalTransform::default(),
         Visibility::default(),
         // Game logic component
-        RapierVoxelDrop::new(voxel_type, quantity, current_time),
+        RapierVoxelDrop::new(voxel_type, quantity),
         // Rapier physics components
         RigidBody::Dynamic,
         Collider::cuboid(VOXEL_SIZE * 0.4, VOXEL_SIZE * 0.4, VOXEL_SIZE * 0.4),
@@ -122,9 +183,9 @@ pub fn spawn_rapier_voxel_drop(
         Velocity {
             linvel: initial_velocity,
             angvel: Vec3::new(
-                (rand::random::<f32>() - 0.5) * 1.0, // Reducir rotación angular
-                (rand::random::<f32>() - 0.5) * 1.0,
-                (rand::random::<f32>() - 0.5) * 1.0,
+                rng.gen_range(-0.5..0.5), // Reducir rotación angular
+                rng.gen_range(-0.5..0.5),
+                rng.gen_range(-0.5..0.5),
             ),
         },
         // Collision groups for optimization
@@ -132,68 +193,216 @@ pub fn spawn_rapier_voxel_drop(
     ));
 }
 
-/// System to collect drops when player approaches
-pub fn collect_rapier_drops_system(
-    mut commands: Commands,
-    mut inventory: ResMut<Inventory>,
-    player_query: Query<
-        &Transform,
-        (
-            With<crate::player::components::Player>,
-            Without<RapierVoxelDrop>,
-        ),
-    >,
-    drop_query: Query<
-        (Entity, &Transform, &RapierVoxelDrop),
-        Without<crate::player::components::Player>,
-    >,
-) {
-    let Ok(player_transform) = player_query.single() else {
-        return;
-    };
+/// Punto del eje de la cápsula del jugador más cercano a `point`. Medir contra
+/// él (y no contra el centro) hace que un drop a los pies cuente igual de cerca
+/// que uno a la altura de la cabeza.
+fn nearest_on_player(player: Vec3, point: Vec3) -> Vec3 {
+    let y = point
+        .y
+        .clamp(player.y - PLAYER_HALF_HEIGHT, player.y + PLAYER_HALF_HEIGHT);
+    Vec3::new(player.x, y, player.z)
+}
 
-    for (entity, drop_transform, drop) in drop_query.iter() {
-        if !drop.can_collect {
+/// Parejas `(absorbido, absorbente)` de drops del mismo material a menos de
+/// `radius`. Un drop absorbido ya no absorbe a otros, así cada uno desaparece
+/// como mucho una vez.
+fn merge_pairs(drops: &[(VoxelType, Vec3)], radius: f32) -> Vec<(usize, usize)> {
+    let radius_sq = radius * radius;
+    let mut absorbed = vec![false; drops.len()];
+    let mut pairs = Vec::new();
+    for i in 0..drops.len() {
+        if absorbed[i] {
             continue;
         }
+        for j in i + 1..drops.len() {
+            if !absorbed[j]
+                && drops[j].0 == drops[i].0
+                && drops[j].1.distance_squared(drops[i].1) <= radius_sq
+            {
+                absorbed[j] = true;
+                pairs.push((j, i));
+            }
+        }
+    }
+    pairs
+}
 
-        let distance = player_transform
-            .translation
-            .distance(drop_transform.translation);
+/// Chunks con física: reales (con su collider) o enteramente de aire.
+type PhysicalChunk = Or<(With<BaseChunk>, With<EmptyChunk>)>;
 
-        if distance <= 2.0 {
-            inventory.add(drop.voxel_type, drop.quantity);
-            info!(
-                "Collected {:?} x{} (total: {})",
-                drop.voxel_type, drop.quantity, inventory.0[drop.voxel_type as usize]
-            );
-            commands.entity(entity).despawn();
+/// Drops sueltos (ni el jugador ni congelados).
+type LooseDrop = (Without<Player>, Without<RigidBodyDisabled>);
+
+/// Congela los drops cuyo chunk no tiene física (sin cargar, LOD o aún
+/// generándose): sin el collider del suelo caerían para siempre. Se quedan
+/// quietos donde están, sin envejecer, y vuelven a caer en cuanto llega el
+/// chunk real.
+pub fn freeze_drops_on_unloaded_chunks_system(
+    mut commands: Commands,
+    chunk_map: Res<ChunkMap>,
+    physical_chunks: Query<(), PhysicalChunk>,
+    drop_query: Query<(Entity, &Transform, Has<RigidBodyDisabled>), With<RapierVoxelDrop>>,
+) {
+    for (entity, transform, frozen) in &drop_query {
+        let (chunk_pos, _, _) = world_to_voxel(transform.translation);
+        let has_physics = chunk_map
+            .chunks
+            .get(&chunk_pos)
+            .is_some_and(|&chunk| physical_chunks.contains(chunk));
+
+        if !has_physics && !frozen {
+            commands
+                .entity(entity)
+                .insert((RigidBodyDisabled, Velocity::zero()));
+        } else if has_physics && frozen {
+            commands.entity(entity).remove::<RigidBodyDisabled>();
         }
     }
 }
 
-/// System to enable collection after 1 second and clean up old drops
+/// System to age drops and clean up old ones
 pub fn update_rapier_drops_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut drop_query: Query<(Entity, &mut RapierVoxelDrop)>,
+    settings: Res<DropSettings>,
+    mut drop_query: Query<(Entity, &mut RapierVoxelDrop), Without<RigidBodyDisabled>>,
 ) {
-    let current_time = time.elapsed_secs();
-
     for (entity, mut drop) in drop_query.iter_mut() {
-        // Enable collection after 1 second
-        if current_time - drop.spawn_time > 1.0 {
-            drop.can_collect = true;
-        }
+        drop.age += time.delta_secs();
 
-        // Despawn after 60 seconds
-        if drop.should_despawn(current_time) {
+        if drop.should_despawn(&settings) {
             info!("Drop despawned by timeout: {:?}", drop.voxel_type);
             commands.entity(entity).despawn();
         }
     }
 }
 
+/// Junta los drops cercanos del mismo material: el superviviente suma la
+/// cantidad y se queda con la edad del más joven (no desaparece antes).
+pub fn merge_rapier_drops_system(
+    mut commands: Commands,
+    settings: Res<DropSettings>,
+    mut drop_query: Query<(Entity, &Transform, &mut RapierVoxelDrop), Without<RigidBodyDisabled>>,
+) {
+    let drops: Vec<(Entity, VoxelType, Vec3)> = drop_query
+        .iter()
+        .map(|(entity, transform, drop)| (entity, drop.voxel_type, transform.translation))
+        .collect();
+    let positions: Vec<(VoxelType, Vec3)> = drops.iter().map(|&(_, t, p)| (t, p)).collect();
+
+    for (absorbed, into) in merge_pairs(&positions, settings.merge_radius) {
+        let Ok([(_, _, gone), (_, _, mut kept)]) =
+            drop_query.get_many_mut([drops[absorbed].0, drops[into].0])
+        else {
+            continue;
+        };
+        kept.quantity += gone.quantity;
+        kept.age = kept.age.min(gone.age);
+        commands.entity(drops[absorbed].0).despawn();
+    }
+}
+
+/// Jugadores que pueden recoger drops: su cápsula y su inventario.
+type Collector<'a> = (Entity, &'a Transform, &'a Inventory);
+type CollectorMut<'a> = (Entity, &'a Transform, &'a mut Inventory);
+
+/// De los jugadores `(id, centro, inventario)`, el más cercano (a su cápsula)
+/// a `position` dentro de `radius` al que le cabe `voxel_type`, con el punto de
+/// su cápsula más cercano.
+fn nearest_collector<'a, K>(
+    players: impl Iterator<Item = (K, Vec3, &'a Inventory)>,
+    position: Vec3,
+    voxel_type: VoxelType,
+    radius: f32,
+) -> Option<(K, Vec3)> {
+    players
+        .filter(|(_, _, inventory)| inventory.has_room_for(voxel_type))
+        .map(|(id, center, _)| (id, nearest_on_player(center, position)))
+        .filter(|(_, target)| target.distance(position) <= radius)
+        .min_by(|a, b| a.1.distance_squared(position).total_cmp(&b.1.distance_squared(position)))
+}
+
+/// Atrae los drops recogibles hacia el jugador más cercano que los tiene
+/// cerca, si le caben.
+pub fn attract_rapier_drops_system(
+    settings: Res<DropSettings>,
+    player_query: Query<Collector, (With<Player>, Without<RapierVoxelDrop>)>,
+    mut drop_query: Query<(&Transform, &RapierVoxelDrop, &mut Velocity), LooseDrop>,
+) {
+    for (drop_transform, drop, mut velocity) in drop_query.iter_mut() {
+        if !drop.can_collect(&settings) {
+            continue;
+        }
+        let players = player_query
+            .iter()
+            .map(|(player, transform, inventory)| (player, transform.translation, inventory));
+        let Some((_, target)) = nearest_collector(
+            players,
+            drop_transform.translation,
+            drop.voxel_type,
+            settings.magnet_radius,
+        ) else {
+            continue;
+        };
+        // Se pisa la velocidad cada frame: la gravedad no llega a notarse
+        velocity.linvel =
+            (target - drop_transform.translation).normalize_or_zero() * settings.magnet_speed;
+    }
+}
+
+/// System to collect drops when player approaches
+///
+/// Los voxels van a los slots libres del `Inventory` del jugador más cercano al
+/// que le caben; lo que no cabe se queda en el drop (con todos los inventarios
+/// llenos no se recoge nada).
+pub fn collect_rapier_drops_system(
+    mut commands: Commands,
+    settings: Res<DropSettings>,
+    mut player_query: Query<CollectorMut, (With<Player>, Without<RapierVoxelDrop>)>,
+    mut drop_query: Query<(Entity, &Transform, &mut RapierVoxelDrop), Without<Player>>,
+) {
+    for (entity, drop_transform, mut drop) in drop_query.iter_mut() {
+        if !drop.can_collect(&settings) {
+            continue;
+        }
+
+        let players = player_query
+            .iter()
+            .map(|(player, transform, inventory)| (player, transform.translation, inventory));
+        let Some((collector, _)) = nearest_collector(
+            players,
+            drop_transform.translation,
+            drop.voxel_type,
+            settings.pickup_radius,
+        ) else {
+            continue;
+        };
+        let Ok((_, _, mut inventory)) = player_query.get_mut(collector) else {
+            continue;
+        };
+
+        let leftover = inventory.add(drop.voxel_type, drop.quantity);
+        info!(
+            "Collected {:?} x{} (total: {})",
+            drop.voxel_type,
+            drop.quantity - leftover,
+            inventory.count(drop.voxel_type)
+        );
+        if leftover == 0 {
+            commands.entity(entity).despawn();
+        } else {
+            drop.quantity = leftover;
+        }
+    }
+}
+
+/// Quita todos los drops al volver al menú: el próximo mundo arranca limpio.
+pub fn despawn_all_drops(mut commands: Commands, drops: Query<Entity, With<RapierVoxelDrop>>) {
+    for entity in &drops {
+        commands.entity(entity).despawn();
+    }
+}
+
 /// Create terrain collider for a chunk using Rapier
 pub fn create_chunk_collider(mesh: &Mesh) -> Collider {
     // Use the correct API for creating mesh colliders
@@ -203,3 +412,53 @@ pub fn create_chunk_collider(mesh: &Mesh) -> Collider {
             Collider::cuboid(16.0, 8.0, 16.0) // Fallback box collider
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearby_drops_of_the_same_type_merge_once() {
+        let drops = [
+            (VoxelType::Stone, Vec3::ZERO),
+            (VoxelType::Stone, Vec3::new(0.3, 0.0, 0.0)),
+            (VoxelType::Dirt, Vec3::new(0.1, 0.0, 0.0)),
+            (VoxelType::Stone, Vec3::new(0.6, 0.0, 0.0)),
+            (VoxelType::Stone, Vec3::new(5.0, 0.0, 0.0)),
+        ];
+        // El 1 y el 3 caen en el 0 (el 3 está a 0.6 del 0, pero a 0.3 del 1
+        // ya absorbido: no encadena); la tierra y el lejano quedan solos
+        assert_eq!(merge_pairs(&drops, 0.5), vec![(1, 0)]);
+        assert_eq!(merge_pairs(&drops, 0.7), vec![(1, 0), (3, 0)]);
+    }
+
+    #[test]
+    fn pickup_distance_is_measured_to_the_capsule_axis() {
+        let player = Vec3::new(0.0, 10.0, 0.0);
+        // A los pies y a la altura de la cabeza: misma distancia horizontal
+        let feet = Vec3::new(0.4, 10.0 - PLAYER_HALF_HEIGHT - 0.2, 0.0);
+        let head = Vec3::new(0.4, 10.0 + PLAYER_HALF_HEIGHT, 0.0);
+        assert!((nearest_on_player(player, head).distance(head) - 0.4).abs() < 1e-5);
+        assert!(nearest_on_player(player, feet).distance(feet) < 0.5);
+        assert_eq!(nearest_on_player(player, Vec3::new(3.0, 10.0, 0.0)), player);
+    }
+
+    #[test]
+    fn drops_go_to_the_nearest_player_with_room() {
+        use crate::core::{INVENTORY_SLOTS, MAX_VOXEL_STACK};
+        let empty = Inventory::default();
+        let mut full = Inventory::default();
+        full.add(VoxelType::Dirt, INVENTORY_SLOTS as u32 * MAX_VOXEL_STACK);
+        let drop = Vec3::new(0.5, 10.0, 0.0);
+        let (near, far) = (Vec3::new(0.0, 10.0, 0.0), Vec3::new(1.2, 10.0, 0.0));
+        let closest = |players: [(i32, Vec3, &Inventory); 2], radius| {
+            nearest_collector(players.into_iter(), drop, VoxelType::Stone, radius).map(|(id, _)| id)
+        };
+
+        // El más cercano se lo lleva...
+        assert_eq!(closest([(0, near, &empty), (1, far, &empty)], 1.0), Some(0));
+        // ...salvo que no le quepa: entonces el siguiente dentro del radio
+        assert_eq!(closest([(0, near, &full), (1, far, &empty)], 1.0), Some(1));
+        assert_eq!(closest([(0, near, &full), (1, far, &empty)], 0.6), None);
+    }
+}
@@ -2,7 +2,10 @@
 // IMPORTS - TRAER CÓDIGO DE OTRAS LIBRERÍAS
 // ============================================================================
 
-use crate::voxel::{Tool, ToolType};
+use super::controls::{LocalPlayer, PlayerActions, PrimaryPlayer};
+use super::health::{Health, PLAYER_MAX_HEALTH};
+use crate::core::{GameSettings, Inventory};
+use crate::voxel::Tool;
 use bevy::prelude::*; // Importa tipos básicos de Bevy (Component, Commands, Transform, etc.)
 use bevy_rapier3d::prelude::*; // Importa tipos de física de Rapier (RigidBody, Collider, Velocity, etc.)
 
@@ -37,6 +40,24 @@ impl Default for Player {
     }
 }
 
+/// Punto de aparición (y reaparición al morir) del jugador. Y=20 está por
+/// encima de la altura máxima del terreno (~12m), así el jugador cae sobre el
+/// terreno recién generado en lugar de quedar dentro.
+pub const PLAYER_SPAWN: Vec3 = Vec3::new(0.0, 20.0, 0.0);
+
+/// Separación (m) entre los puntos de aparición de los jugadores locales, para
+/// que sus cápsulas no nazcan una dentro de otra.
+const LOCAL_SPAWN_SPACING: f32 = 1.0;
+
+/// Punto de aparición del jugador local `index`: `PLAYER_SPAWN` desplazado en X.
+pub fn local_spawn_point(index: usize) -> Vec3 {
+    PLAYER_SPAWN + Vec3::X * LOCAL_SPAWN_SPACING * index as f32
+}
+
+/// Semialtura del segmento y radio de la cápsula del jugador (centrada en su `Transform`).
+pub const PLAYER_HALF_HEIGHT: f32 = 0.9;
+pub const PLAYER_RADIUS: f32 = 0.3;
+
 /// Componente marcador para identificar entidades que son controladores de jugador
 #[derive(Component)] // Macro que hace que PlayerController sea un componente
 pub struct PlayerController; // Estructura vacía usada solo como "etiqueta" o "marcador"
@@ -45,33 +66,70 @@ pub struct PlayerController; // Estructura vacía usada solo como "etiqueta" o "
 // FUNCIÓN DE CREACIÓN DEL JUGADOR
 // ============================================================================
 
-/// Crea la entidad del jugador con cámara 3D y física.
+/// Crea las entidades de los jugadores locales con cámara 3D y física.
 ///
-/// Esta función se ejecuta al inicio del juego y crea una entidad completa
-/// del jugador con todos los componentes necesarios para movimiento, cámara y física.
-pub fn spawn_player(mut commands: Commands) {
-    // Recibe Commands mutable para crear entidades
-    commands.spawn((
-        // Crea una nueva entidad con los siguientes componentes:
+/// Esta función se ejecuta al inicio del juego y crea, por cada jugador local
+/// (`GameSettings::local_players`), una entidad completa con todos los
+/// componentes necesarios para movimiento, cámara, física e inventario. Con
+/// varios, `update_split_screen_viewports` reparte la ventana entre sus cámaras.
+pub fn spawn_player(mut commands: Commands, settings: Res<GameSettings>) {
+    for index in 0..settings.local_players.max(1) {
+        spawn_local_player(&mut commands, index);
+    }
+}
 
-        // ====================================================================
-        // COMPONENTES PERSONALIZADOS
-        // ====================================================================
-        Player::default(), // Nuestro componente Player con valores por defecto
-        PlayerController,  // Marcador para identificar esta entidad como jugador
-        Tool::new(ToolType::Shovel), // Agregar tool al jugador
+/// Crea el jugador local `index`; el 0 es el [`PrimaryPlayer`] y su cámara
+/// lleva la UI.
+fn spawn_local_player(commands: &mut Commands, index: usize) {
+    let mut player = commands.spawn((
+        // Crea una nueva entidad con los siguientes componentes:
+        player_gameplay(
+            Player::default(), // Nuestro componente Player con valores por defecto
+            Transform::from_translation(local_spawn_point(index)),
+        ),
+        LocalPlayer::new(index), // Índice en pantalla y dispositivo de entrada
         // ====================================================================
         // COMPONENTES DE BEVY
         // ====================================================================
         Camera3d::default(), // Cámara 3D con configuración por defecto
-        // Y=20 está por encima de la altura máxima del terreno (~12m), así el
-        // jugador cae sobre el terreno recién generado en lugar de quedar dentro.
-        Transform::from_xyz(0.0, 20.0, 0.0),
+        // Orden distinto por cámara: todas dibujan en la misma ventana
+        Camera {
+            order: index as isize,
+            ..default()
+        },
+    ));
+    if index == 0 {
+        player.insert((PrimaryPlayer, IsDefaultUiCamera));
+    }
+}
+
+/// Lo que hace jugar a un jugador, con o sin cámara: controles, inventario,
+/// vida y cuerpo físico. Lo comparten los jugadores locales y el de la
+/// repetición headless (`replay::playback`).
+pub fn player_gameplay(player: Player, transform: Transform) -> impl Bundle {
+    (
+        // ====================================================================
+        // COMPONENTES PERSONALIZADOS
+        // ====================================================================
+        player,
+        PlayerController, // Marcador para identificar esta entidad como jugador
+        PlayerActions::default(), // Input del frame (ver `read_player_actions`)
+        Inventory::default(), // Inventario propio (sus drops, su herramienta)
+        Tool::hands(), // Copia del item en la mano (ver `sync_held_tool`)
+        Health::new(PLAYER_MAX_HEALTH), // Vida (la lava la quita)
+        transform,
         // ====================================================================
         // COMPONENTES DE FÍSICA (RAPIER)
         // ====================================================================
+        player_body(),
+    )
+}
+
+/// Cuerpo físico del jugador.
+fn player_body() -> impl Bundle {
+    (
         RigidBody::Dynamic, // Cuerpo rígido dinámico (afectado por fuerzas y gravedad)
-        Collider::capsule_y(0.9, 0.3), // Colisionador en forma de cápsula:
+        Collider::capsule_y(PLAYER_HALF_HEIGHT, PLAYER_RADIUS), // Colisionador en forma de cápsula:
         //   - 0.9 = mitad de altura (total 1.8m)
         //   - 0.3 = radio (0.6m de diámetro)
         Velocity::zero(),                     // Velocidad inicial en cero (parado)
@@ -79,7 +137,7 @@ pub fn spawn_player(mut commands: Commands) {
         Friction::coefficient(0.7),  // Coeficiente de fricción 0.7 (realista para caminar)
         Restitution::coefficient(0.0), // Sin rebote (coeficiente 0.0 = no elástico)
         AdditionalMassProperties::Mass(70.0), // Masa de 70 kilogramos (peso humano promedio)
-    ));
+    )
 }
 
 /// Elimina al jugador al salir del juego (evita duplicados al volver al menú).
@@ -0,0 +1,213 @@
+//! Jugadores locales y sus dispositivos de entrada.
+//!
+//! Cada jugador local lleva un [`LocalPlayer`] con su dispositivo (teclado y
+//! ratón, o un mando) y un [`PlayerActions`] que `read_player_actions` rellena
+//! cada frame. El resto de sistemas (movimiento, cámara, romper, colocar,
+//! barra rápida) solo leen las acciones, así que funcionan igual para N
+//! jugadores y para cualquier dispositivo.
+//!
+//! Mando: stick izquierdo mueve, stick derecho mira, A salta/nada, gatillo
+//! derecho rompe, gatillo izquierdo coloca, bumpers cambian de slot, B tira uno
+//! y X el stack entero.
+
+use crate::core::HOTBAR_SLOTS;
+use bevy::input::gamepad::{Gamepad, GamepadButton};
+use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll};
+use bevy::prelude::*;
+
+/// Giro del stick derecho a fondo, en píxeles de ratón por segundo: así la
+/// `sensitivity` del jugador vale igual para ratón y mando.
+const GAMEPAD_LOOK_SPEED: f32 = 900.0;
+
+/// Teclas de la barra rápida, en orden de slot.
+const HOTBAR_KEYS: [KeyCode; HOTBAR_SLOTS] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+// ============================================================================
+// COMPONENTES
+// ============================================================================
+
+/// Con qué se controla un jugador local.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputDevice {
+    KeyboardMouse,
+    /// El n-ésimo mando conectado (por orden de entidad)
+    Gamepad(usize),
+}
+
+/// Jugador local: su índice en la pantalla dividida y su dispositivo.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LocalPlayer {
+    pub index: usize,
+    pub device: InputDevice,
+}
+
+impl LocalPlayer {
+    /// El jugador 0 usa teclado y ratón; los demás, un mando cada uno.
+    pub fn new(index: usize) -> Self {
+        let device = match index {
+            0 => InputDevice::KeyboardMouse,
+            n => InputDevice::Gamepad(n - 1),
+        };
+        Self { index, device }
+    }
+}
+
+/// Marca al jugador local 0: el que ve la UI a pantalla completa (HUD,
+/// inventario, crafting) y el que se envía al servidor en red.
+#[derive(Component, Debug, Default)]
+pub struct PrimaryPlayer;
+
+/// Lo que un jugador quiere hacer este frame, venga de donde venga.
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct PlayerActions {
+    /// Movimiento local: x = derecha, y = adelante (longitud ≤ 1)
+    pub movement: Vec2,
+    /// Giro de cámara en píxeles de ratón (se escala por `Player::sensitivity`)
+    pub look: Vec2,
+    /// Salto (solo el frame en que se pulsa)
+    pub jump: bool,
+    /// Subir nadando (mantenido)
+    pub swim_up: bool,
+    /// Romper (mantenido)
+    pub breaking: bool,
+    /// Colocar (solo el frame en que se pulsa)
+    pub place: bool,
+    /// Slot de la barra rápida elegido directamente
+    pub hotbar_slot: Option<usize>,
+    /// Pasos de la barra rápida (rueda / bumpers): +1 = siguiente
+    pub hotbar_step: i32,
+    /// Tirar lo que hay en la mano: uno, o el stack entero con `throw_stack`
+    pub throw: bool,
+    pub throw_stack: bool,
+}
+
+// ============================================================================
+// LECTURA DE DISPOSITIVOS
+// ============================================================================
+
+/// Acciones de teclado y ratón. `motion` y `scroll` son lo acumulado en el
+/// frame.
+pub fn keyboard_mouse_actions(
+    keys: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    motion: Vec2,
+    scroll: f32,
+) -> PlayerActions {
+    let axis = |negative, positive| {
+        keys.pressed(positive) as i32 as f32 - keys.pressed(negative) as i32 as f32
+    };
+    let movement = Vec2::new(axis(KeyCode::KeyA, KeyCode::KeyD), axis(KeyCode::KeyS, KeyCode::KeyW));
+    PlayerActions {
+        movement: movement.normalize_or_zero(),
+        look: motion,
+        jump: keys.just_pressed(KeyCode::Space),
+        swim_up: keys.pressed(KeyCode::Space),
+        breaking: mouse.pressed(MouseButton::Left),
+        place: mouse.just_pressed(MouseButton::Right),
+        hotbar_slot: HOTBAR_KEYS.iter().position(|&key| keys.just_pressed(key)),
+        // Rueda hacia abajo = siguiente
+        hotbar_step: match scroll {
+            y if y > 0.0 => -1,
+            y if y < 0.0 => 1,
+            _ => 0,
+        },
+        throw: keys.just_pressed(KeyCode::KeyQ),
+        throw_stack: keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+    }
+}
+
+/// Acciones de un mando (`delta` = segundos del frame, para el giro).
+pub fn gamepad_actions(gamepad: &Gamepad, delta: f32) -> PlayerActions {
+    let look = gamepad.right_stick() * GAMEPAD_LOOK_SPEED * delta;
+    PlayerActions {
+        movement: gamepad.left_stick().clamp_length_max(1.0),
+        // Stick arriba = mirar arriba (en el ratón es delta.y negativo)
+        look: Vec2::new(look.x, -look.y),
+        jump: gamepad.just_pressed(GamepadButton::South),
+        swim_up: gamepad.pressed(GamepadButton::South),
+        breaking: gamepad.pressed(GamepadButton::RightTrigger2),
+        place: gamepad.just_pressed(GamepadButton::LeftTrigger2),
+        hotbar_slot: None,
+        hotbar_step: gamepad.just_pressed(GamepadButton::RightTrigger) as i32
+            - gamepad.just_pressed(GamepadButton::LeftTrigger) as i32,
+        throw: gamepad.just_pressed(GamepadButton::East)
+            || gamepad.just_pressed(GamepadButton::West),
+        throw_stack: gamepad.pressed(GamepadButton::West),
+    }
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Rellena las `PlayerActions` de cada jugador local desde su dispositivo. Un
+/// mando que no está conectado deja a su jugador quieto.
+pub fn read_player_actions(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    mut players: Query<(&LocalPlayer, &mut PlayerActions)>,
+) {
+    let mut pads: Vec<(Entity, &Gamepad)> = gamepads.iter().collect();
+    pads.sort_by_key(|(entity, _)| *entity);
+
+    for (local, mut actions) in &mut players {
+        let read = match local.device {
+            InputDevice::KeyboardMouse => {
+                keyboard_mouse_actions(&keys, &mouse, motion.delta, scroll.delta.y)
+            }
+            InputDevice::Gamepad(n) => pads
+                .get(n)
+                .map(|(_, gamepad)| gamepad_actions(gamepad, time.delta_secs()))
+                .unwrap_or_default(),
+        };
+        actions.set_if_neq(read);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyboard_and_mouse_map_to_actions() {
+        let mut keys = ButtonInput::<KeyCode>::default();
+        let mut mouse = ButtonInput::<MouseButton>::default();
+        keys.press(KeyCode::KeyW);
+        keys.press(KeyCode::KeyD);
+        keys.press(KeyCode::Digit3);
+        mouse.press(MouseButton::Left);
+
+        let actions = keyboard_mouse_actions(&keys, &mouse, Vec2::new(4.0, -2.0), -1.0);
+        // Diagonal adelante-derecha, normalizada
+        assert!((actions.movement - Vec2::new(1.0, 1.0).normalize()).length() < 1e-6);
+        assert_eq!(actions.look, Vec2::new(4.0, -2.0));
+        assert_eq!(actions.hotbar_slot, Some(2));
+        assert_eq!(actions.hotbar_step, 1);
+        assert!(actions.breaking && !actions.place && !actions.throw);
+
+        // Sin teclas: quieto
+        let idle = keyboard_mouse_actions(&ButtonInput::default(), &mouse, Vec2::ZERO, 0.0);
+        assert_eq!(idle.movement, Vec2::ZERO);
+        assert_eq!(idle.hotbar_step, 0);
+    }
+
+    #[test]
+    fn devices_are_assigned_by_index() {
+        assert_eq!(LocalPlayer::new(0).device, InputDevice::KeyboardMouse);
+        assert_eq!(LocalPlayer::new(2).device, InputDevice::Gamepad(1));
+    }
+}
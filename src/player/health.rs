@@ -0,0 +1,136 @@
+//! Vida del jugador: daño por lava, quemaduras y reaparición.
+//!
+//! Tocar lava quita vida cada segundo y prende al jugador (`Burning`): sigue
+//! perdiendo vida un rato después de salir, salvo que se meta en el agua. Con
+//! la vida a cero reaparece en su punto de inicio con la vida llena.
+
+use super::components::{local_spawn_point, Player};
+use super::controls::LocalPlayer;
+use crate::voxel::{VoxelReader, VoxelType};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+
+/// Vida máxima del jugador.
+pub const PLAYER_MAX_HEALTH: f32 = 100.0;
+/// Daño por segundo dentro de la lava.
+const LAVA_DAMAGE_PER_SEC: f32 = 40.0;
+/// Daño por segundo mientras se está ardiendo.
+const BURN_DAMAGE_PER_SEC: f32 = 5.0;
+/// Segundos que dura el fuego tras salir de la lava.
+const BURN_DURATION: f32 = 4.0;
+/// Alturas bajo el centro de la cápsula donde se busca lava/agua: pies y cintura.
+const CONTACT_PROBES: [f32; 2] = [1.1, 0.6];
+
+/// Vida de una entidad.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Resta vida sin bajar de cero.
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    /// Fracción de vida restante (0.0–1.0), para la barra del HUD.
+    pub fn fraction(&self) -> f32 {
+        self.current / self.max
+    }
+}
+
+/// El jugador está ardiendo: pierde vida hasta que se agota `remaining`.
+#[derive(Component, Debug)]
+pub struct Burning {
+    pub remaining: f32,
+}
+
+/// Daña y prende al jugador que toca lava; el agua apaga el fuego.
+pub fn lava_contact_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(Entity, &Transform, &mut Health), With<Player>>,
+    world: VoxelReader,
+) {
+    for (entity, transform, mut health) in &mut players {
+        let touching: Vec<VoxelType> = CONTACT_PROBES
+            .iter()
+            .filter_map(|depth| world.get_at(transform.translation - Vec3::Y * depth))
+            .collect();
+
+        if touching.iter().any(|v| v.is_lava()) {
+            health.damage(LAVA_DAMAGE_PER_SEC * time.delta_secs());
+            commands.entity(entity).insert(Burning {
+                remaining: BURN_DURATION,
+            });
+        } else if touching.contains(&VoxelType::Water) {
+            commands.entity(entity).remove::<Burning>();
+        }
+    }
+}
+
+/// Quita vida a quien arde y apaga el fuego al agotarse.
+pub fn burning_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut burning: Query<(Entity, &mut Health, &mut Burning)>,
+) {
+    for (entity, mut health, mut burn) in &mut burning {
+        health.damage(BURN_DAMAGE_PER_SEC * time.delta_secs());
+        burn.remaining -= time.delta_secs();
+        if burn.remaining <= 0.0 {
+            commands.entity(entity).remove::<Burning>();
+        }
+    }
+}
+
+/// Lo que `respawn_on_death` lee y reinicia de cada jugador.
+type RespawnQuery<'a> = (
+    Entity,
+    Option<&'a LocalPlayer>,
+    &'a mut Health,
+    &'a mut Transform,
+    &'a mut Velocity,
+);
+
+/// Con la vida a cero, el jugador reaparece en su punto de inicio.
+pub fn respawn_on_death(
+    mut commands: Commands,
+    mut players: Query<RespawnQuery, With<Player>>,
+) {
+    for (entity, local, mut health, mut transform, mut velocity) in &mut players {
+        if !health.is_dead() {
+            continue;
+        }
+        let spawn = local_spawn_point(local.map_or(0, |local| local.index));
+        info!("Jugador muerto: reaparece en {:?}", spawn);
+        *health = Health::new(health.max);
+        transform.translation = spawn;
+        *velocity = Velocity::zero();
+        commands.entity(entity).remove::<Burning>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damage_clamps_at_zero() {
+        let mut health = Health::new(PLAYER_MAX_HEALTH);
+        health.damage(30.0);
+        assert_eq!(health.fraction(), 0.7);
+        health.damage(500.0);
+        assert!(health.is_dead());
+        assert_eq!(health.current, 0.0);
+    }
+}
@@ -1,5 +1,8 @@
-use crate::player::components::Player;
-use crate::voxel::{Tool, ToolType};
+use crate::core::{Inventory, HOTBAR_SLOTS};
+use crate::physics::{spawn_rapier_voxel_drop, DropAssets};
+use crate::player::components::{Player, PLAYER_RADIUS};
+use crate::player::controls::PlayerActions;
+use crate::voxel::Tool;
 use bevy::prelude::*;
 use bevy::window::{CursorGrabMode, CursorOptions, PrimaryWindow};
 
@@ -36,32 +39,74 @@ pub fn cursor_grab_on_click(
     }
 }
 
-/// Cambia la herramienta equipada con las teclas 1–4.
-///
-/// Reemplaza el `Tool` (durabilidad reiniciada al máximo); solo hay una
-/// herramienta equipada a la vez.
-pub fn switch_tool(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut tool_query: Query<&mut Tool, With<Player>>,
+/// Elige el slot de la barra rápida de cada jugador: teclas 1–9, rueda del
+/// ratón o bumpers del mando (siguiente/anterior, dando la vuelta).
+pub fn select_hotbar_slot(mut players: Query<(&PlayerActions, &mut Inventory), With<Player>>) {
+    for (actions, mut inventory) in &mut players {
+        let selected = match actions.hotbar_slot {
+            Some(slot) => slot,
+            None if actions.hotbar_step != 0 => (inventory.selected as i32 + actions.hotbar_step)
+                .rem_euclid(HOTBAR_SLOTS as i32) as usize,
+            None => continue,
+        };
+        if inventory.selected != selected {
+            inventory.selected = selected;
+        }
+    }
+}
+
+/// Velocidad (m/s) con la que sale un item lanzado, hacia donde mira la cámara.
+const THROW_SPEED: f32 = 6.0;
+
+/// Tira lo que hay en la mano: Q suelta uno, Ctrl+Q el stack entero (B y X en
+/// el mando). Sale delante de la cámara (fuera de la cápsula) como un drop
+/// normal, que no se puede recoger hasta pasado el retardo de `DropSettings`.
+/// Las herramientas no se tiran: un drop solo lleva materiales.
+pub fn throw_held_item(
+    mut commands: Commands,
+    drop_assets: Res<DropAssets>,
+    mut players: Query<(&Transform, &PlayerActions, &mut Inventory), With<Player>>,
 ) {
-    let Ok(mut tool) = tool_query.single_mut() else {
-        return;
-    };
+    for (transform, actions, mut inventory) in &mut players {
+        if !actions.throw {
+            continue;
+        }
+        let Some(voxel_type) = inventory.held().and_then(|stack| stack.as_voxel()) else {
+            continue;
+        };
+        let amount = if actions.throw_stack { u32::MAX } else { 1 };
+        let Some(thrown) = inventory.split_held(amount) else {
+            continue;
+        };
 
-    let new_type = if keys.just_pressed(KeyCode::Digit1) {
-        ToolType::Pickaxe
-    } else if keys.just_pressed(KeyCode::Digit2) {
-        ToolType::Axe
-    } else if keys.just_pressed(KeyCode::Digit3) {
-        ToolType::Shovel
-    } else if keys.just_pressed(KeyCode::Digit4) {
-        ToolType::Hoe
-    } else {
-        return;
-    };
+        let forward = transform.forward();
+        spawn_rapier_voxel_drop(
+            &mut commands,
+            &drop_assets,
+            voxel_type,
+            thrown.count,
+            transform.translation + forward * (PLAYER_RADIUS + 0.3),
+            // Un poco hacia arriba para que describa un arco
+            Some(forward * THROW_SPEED + Vec3::Y * 1.5),
+        );
+        info!("Tirado {:?} x{}", voxel_type, thrown.count);
+    }
+}
 
-    if tool.tool_type != new_type {
-        *tool = Tool::new(new_type);
-        info!("Herramienta equipada: {:?}", new_type);
+/// Copia en el `Tool` de cada jugador la herramienta del slot en su mano (a
+/// mano si el slot tiene un material o está vacío). La durabilidad vive en el
+/// inventario; el componente solo la refleja.
+pub fn sync_held_tool(mut players: Query<(&Inventory, &mut Tool), With<Player>>) {
+    for (inventory, mut tool) in &mut players {
+        let held = inventory
+            .held()
+            .and_then(|stack| stack.as_tool())
+            .unwrap_or_else(Tool::hands);
+        if *tool != held {
+            if tool.tool_type != held.tool_type || tool.tier != held.tier {
+                info!("En la mano: {:?} ({:?})", held.tool_type, held.tier);
+            }
+            *tool = held;
+        }
     }
 }
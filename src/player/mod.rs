@@ -1,17 +1,25 @@
 pub mod camera;
 pub mod components;
+pub mod controls;
+pub mod health;
 pub mod input;
 pub mod movement;
 pub mod reticle;
+pub mod split_screen;
 
+use bevy::input::InputSystems;
 use bevy::prelude::*;
 use camera::*;
 pub use components::*;
+pub use controls::*;
+pub use health::*;
 use input::*;
 use movement::*;
 use reticle::*;
+use split_screen::*;
 
-use crate::core::GameState;
+use crate::core::{DeterministicGame, GameState};
+use crate::net::NetClient;
 
 pub struct PlayerPlugin;
 
@@ -34,22 +42,51 @@ impl Plugin for PlayerPlugin {
             // Cursor: bloqueado siempre que estemos en juego, libre en los menús
             .add_systems(OnEnter(GameState::InGame), grab_cursor)
             .add_systems(OnEnter(GameState::Paused), release_cursor)
+            .add_systems(OnEnter(GameState::Crafting), release_cursor)
+            .add_systems(OnEnter(GameState::Inventory), release_cursor)
+            .add_systems(OnEnter(GameState::Console), release_cursor)
             .add_systems(OnEnter(GameState::MainMenu), release_cursor)
             // Crosshair: visible solo en juego (mismo ciclo de vida que el cursor)
             .add_systems(OnEnter(GameState::InGame), spawn_crosshair)
             .add_systems(OnEnter(GameState::Paused), despawn_crosshair)
+            .add_systems(OnEnter(GameState::Crafting), despawn_crosshair)
+            .add_systems(OnEnter(GameState::Inventory), despawn_crosshair)
+            .add_systems(OnEnter(GameState::Console), despawn_crosshair)
             .add_systems(OnEnter(GameState::MainMenu), despawn_crosshair)
+            // Acciones de cada jugador local, leídas de su dispositivo justo
+            // después de que Bevy procese el input del frame
+            .add_systems(PreUpdate, read_player_actions.after(InputSystems))
+            // Pantalla dividida: un viewport por jugador (sigue a la ventana)
+            .add_systems(Update, update_split_screen_viewports)
             // Movimiento, cámara y resaltado de voxel solo activos durante el juego
             .add_systems(
                 Update,
                 (
-                    player_look,
-                    player_movement,
+                    // Grabando, los corre el juego determinista (src/replay/sim.rs)
+                    (
+                        player_look,
+                        player_movement,
+                        // En red no se tira nada: los drops no viajan por la red
+                        (
+                            select_hotbar_slot,
+                            throw_held_item.run_if(not(resource_exists::<NetClient>)),
+                            sync_held_tool,
+                        )
+                            .chain(),
+                    )
+                        .run_if(not(resource_exists::<DeterministicGame>)),
                     cursor_grab_on_click,
-                    switch_tool,
                     highlight_aimed_voxel,
                 )
                     .run_if(in_state(GameState::InGame)),
+            )
+            // Vida: daño por lava, quemaduras y reaparición al morir
+            .add_systems(
+                Update,
+                (lava_contact_system, burning_system, respawn_on_death)
+                    .chain()
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(not(resource_exists::<DeterministicGame>)),
             );
     }
 }
@@ -3,110 +3,121 @@
 // ============================================================================
 
 use super::components::{Player, PlayerController};
+use super::controls::PlayerActions;
+use crate::voxel::VoxelReader;
 use bevy::prelude::*; // Tipos básicos de Bevy (Vec3, Query, Res, etc.)
 use bevy_rapier3d::prelude::*; // Tipos de física (Velocity) // Nuestros componentes desde el módulo padre
 
+// ============================================================================
+// AGUA
+// ============================================================================
+
+/// Distancia bajo la cámara (≈ cintura) donde se comprueba si hay agua.
+const WATER_PROBE_DEPTH: f32 = 0.6;
+/// Fracción de la velocidad horizontal dentro del agua.
+const WATER_SPEED_MULTIPLIER: f32 = 0.5;
+/// Velocidad de ascenso al nadar (Espacio mantenido), m/s.
+const SWIM_UP_SPEED: f32 = 2.0;
+/// Velocidad máxima de hundimiento en el agua, m/s.
+const WATER_SINK_SPEED: f32 = 1.0;
+
 // ============================================================================
 // SISTEMA DE MOVIMIENTO DEL JUGADOR
 // ============================================================================
 
-/// Sistema de movimiento del jugador con física de Rapier.
+/// Sistema de movimiento de los jugadores con física de Rapier.
 ///
-/// Este sistema se ejecuta cada frame y procesa el input del teclado
-/// para mover al jugador usando el motor de física Rapier.
+/// Este sistema se ejecuta cada frame y procesa las acciones de cada jugador
+/// (teclado o mando, ver `PlayerActions`) para moverlo usando el motor de
+/// física Rapier.
 pub fn player_movement(
     // ========================================================================
     // PARÁMETROS DEL SISTEMA
     // ========================================================================
-    keys: Res<ButtonInput<KeyCode>>, // Recurso de solo lectura para detectar teclas presionadas
     mut query: Query<
         // Query mutable para buscar entidades específicas
-        (&Player, &mut Velocity, &Transform), // Tupla de componentes que necesitamos:
+        (&Player, &PlayerActions, &mut Velocity, &Transform), // Tupla de componentes que necesitamos:
         //   - Player: propiedades del jugador (solo lectura)
+        //   - PlayerActions: input del frame de ESE jugador (solo lectura)
         //   - Velocity: velocidad física (mutable)
         //   - Transform: posición y rotación (solo lectura)
         With<PlayerController>, // Filtro: solo entidades que tengan PlayerController
     >,
+    world: VoxelReader, // Para consultar el voxel en el que está el jugador (agua)
 ) {
     // ========================================================================
-    // OBTENER LA ENTIDAD DEL JUGADOR
-    // ========================================================================
-
-    // Intenta obtener la única entidad que coincida con el query
-    let Ok((player, mut velocity, transform)) = query.single_mut() else {
-        return; // Si no hay jugador o hay más de uno, salir
-    };
-    // Explicación de la sintaxis:
-    // - query.single_mut() retorna Result<(componentes), QuerySingleError>
-    // - let Ok(...) = ... else { return; } es pattern matching
-    // - Si es Ok, extrae los componentes; si es Err, ejecuta el else
-
-    // ========================================================================
-    // PROCESAR INPUT HORIZONTAL (WASD)
-    // ========================================================================
-
-    // Input horizontal (WASD)
-    let mut input_dir = Vec3::ZERO; // Vector de dirección inicial (0, 0, 0)
-
-    if keys.pressed(KeyCode::KeyW) {
-        // Si W está presionada
-        input_dir.z -= 1.0; // Mover hacia adelante (Z negativo en Bevy)
-    }
-    if keys.pressed(KeyCode::KeyS) {
-        // Si S está presionada
-        input_dir.z += 1.0; // Mover hacia atrás (Z positivo)
-    }
-    if keys.pressed(KeyCode::KeyA) {
-        // Si A está presionada
-        input_dir.x -= 1.0; // Mover hacia la izquierda (X negativo)
-    }
-    if keys.pressed(KeyCode::KeyD) {
-        // Si D está presionada
-        input_dir.x += 1.0; // Mover hacia la derecha (X positivo)
-    }
-
-    // Nota: input_dir ahora contiene la dirección deseada en coordenadas locales
-    // Por ejemplo: W+D = (-1, 0, 1) = diagonal adelante-derecha
-
-    // ========================================================================
-    // APLICAR MOVIMIENTO RELATIVO A LA CÁMARA
-    // ========================================================================
-
-    // Movimiento horizontal relativo a la cámara
-    if input_dir != Vec3::ZERO {
-        // Si hay algún input de movimiento
-
-        // Obtener vectores de dirección de la cámara
-        let forward = transform.forward().as_vec3(); // Vector "adelante" de la cámara
-        let right = transform.right().as_vec3(); // Vector "derecha" de la cámara
-
-        // Calcular dirección de movimiento en el mundo
-        let move_dir = (forward * -input_dir.z + right * input_dir.x) // Combinar adelante/atrás + izquierda/derecha
-            .normalize_or_zero(); // Normalizar a longitud 1 (o 0 si es vector nulo)
-
-        // Explicación de la fórmula:
-        // - forward * -input_dir.z: si input_dir.z = -1 (W), entonces forward * 1 = adelante
-        // - right * input_dir.x: si input_dir.x = 1 (D), entonces right * 1 = derecha
-        // - La suma da la dirección diagonal correcta
-
-        // Aplicar velocidad horizontal
-        velocity.linvel.x = move_dir.x * player.speed; // Velocidad X = dirección X * velocidad del jugador
-        velocity.linvel.z = move_dir.z * player.speed; // Velocidad Z = dirección Z * velocidad del jugador
-    } else {
-        // Fricción horizontal cuando no hay input
-        velocity.linvel.x *= 0.8; // Reducir velocidad X al 80% (fricción)
-        velocity.linvel.z *= 0.8; // Reducir velocidad Z al 80% (fricción)
-                                  // Esto hace que el jugador se detenga gradualmente cuando no presiona teclas
-    }
-
-    // ========================================================================
-    // PROCESAR SALTO
+    // RECORRER LOS JUGADORES
     // ========================================================================
 
-    // Salto simple por ahora
-    if keys.just_pressed(KeyCode::Space) {
-        // Si Space fue presionada este frame (no mantenida)
-        velocity.linvel.y = player.jump_force; // Aplicar velocidad vertical hacia arriba
+    // Cada jugador local se mueve con sus propias acciones
+    for (player, actions, mut velocity, transform) in &mut query {
+        // ====================================================================
+        // PROCESAR INPUT HORIZONTAL (WASD / stick izquierdo)
+        // ====================================================================
+
+        // Dirección deseada en coordenadas locales: x = derecha, y = adelante.
+        // Por ejemplo: W+D = (1, 1) normalizado = diagonal adelante-derecha
+        let input_dir = actions.movement;
+
+        // ¿Está el jugador en el agua? (a la altura de la cintura). El agua no tiene
+        // collider: aquí es donde frena y permite nadar.
+        let probe = transform.translation - Vec3::Y * WATER_PROBE_DEPTH;
+        let in_water = world.get_at(probe).is_some_and(|v| v.is_liquid());
+        let speed = if in_water {
+            player.speed * WATER_SPEED_MULTIPLIER
+        } else {
+            player.speed
+        };
+
+        // ====================================================================
+        // APLICAR MOVIMIENTO RELATIVO A LA CÁMARA
+        // ====================================================================
+
+        // Movimiento horizontal relativo a la cámara
+        if input_dir != Vec2::ZERO {
+            // Si hay algún input de movimiento
+
+            // Obtener vectores de dirección de la cámara
+            let forward = transform.forward().as_vec3(); // Vector "adelante" de la cámara
+            let right = transform.right().as_vec3(); // Vector "derecha" de la cámara
+
+            // Calcular dirección de movimiento en el mundo. Con el stick, la
+            // longitud (< 1) se conserva: inclinarlo poco es caminar despacio.
+            let move_dir = (forward * input_dir.y + right * input_dir.x) // Combinar adelante/atrás + izquierda/derecha
+                .clamp_length_max(1.0); // Como mucho longitud 1
+
+            // Explicación de la fórmula:
+            // - forward * input_dir.y: si input_dir.y = 1 (W), entonces adelante
+            // - right * input_dir.x: si input_dir.x = 1 (D), entonces derecha
+            // - La suma da la dirección diagonal correcta
+
+            // Aplicar velocidad horizontal
+            velocity.linvel.x = move_dir.x * speed; // Velocidad X = dirección X * velocidad del jugador
+            velocity.linvel.z = move_dir.z * speed; // Velocidad Z = dirección Z * velocidad del jugador
+        } else {
+            // Fricción horizontal cuando no hay input
+            velocity.linvel.x *= 0.8; // Reducir velocidad X al 80% (fricción)
+            velocity.linvel.z *= 0.8; // Reducir velocidad Z al 80% (fricción)
+                                      // Esto hace que el jugador se detenga gradualmente cuando no presiona teclas
+        }
+
+        // ====================================================================
+        // PROCESAR SALTO
+        // ====================================================================
+
+        if in_water {
+            // Nadar: salto mantenido sube; si no, se hunde despacio (el agua frena
+            // la caída que Rapier aplica por gravedad).
+            if actions.swim_up {
+                velocity.linvel.y = SWIM_UP_SPEED;
+            } else {
+                velocity.linvel.y = velocity.linvel.y.max(-WATER_SINK_SPEED);
+            }
+        } else if actions.jump {
+            // Salto simple por ahora
+            // Si el salto se pulsó este frame (no mantenido)
+            velocity.linvel.y = player.jump_force; // Aplicar velocidad vertical hacia arriba
+        }
+        // Nota: La gravedad se encarga automáticamente por Rapier, no necesitamos manejarla aquí
     }
-    // Nota: La gravedad se encarga automáticamente por Rapier, no necesitamos manejarla aquí
 }
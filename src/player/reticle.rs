@@ -1,13 +1,15 @@
 //! Mira (crosshair) y resaltado del voxel apuntado.
 //!
-//! El crosshair es un "+" centrado en pantalla (UI). El resaltado se dibuja con
-//! Gizmos (modo inmediato): cada frame se lanza un raycast desde la cámara y, si
-//! golpea un voxel sólido, se dibuja un recuadro wireframe a su alrededor. Si
-//! apunta al aire, no se dibuja nada — sin entidades que gestionar.
+//! El crosshair es un "+" centrado en la vista de cada jugador (UI dirigida a
+//! su cámara). El resaltado se dibuja con Gizmos (modo inmediato): cada frame
+//! se lanza un raycast desde la cámara de cada jugador y, si golpea un voxel
+//! sólido, se dibuja un recuadro wireframe a su alrededor. Si apunta al aire,
+//! no se dibuja nada — sin entidades que gestionar.
 
+use super::components::Player;
 use crate::{
-    core::constants::{BASE_CHUNK_SIZE, VOXEL_SIZE},
-    voxel::{raycast_voxel, BaseChunk, ChunkMap},
+    core::constants::VOXEL_SIZE,
+    voxel::VoxelReader,
 };
 use bevy::prelude::*;
 
@@ -15,12 +17,20 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct Crosshair;
 
-/// Spawnea el "+" centrado en la pantalla.
-pub fn spawn_crosshair(mut commands: Commands) {
+/// Spawnea un "+" centrado en la vista de cada jugador.
+pub fn spawn_crosshair(mut commands: Commands, players: Query<Entity, With<Player>>) {
+    for camera in &players {
+        spawn_player_crosshair(&mut commands, camera);
+    }
+}
+
+/// "+" en el centro del viewport de la cámara `camera`.
+fn spawn_player_crosshair(commands: &mut Commands, camera: Entity) {
     commands
         .spawn((
             Crosshair,
-            // Contenedor a pantalla completa que centra a su hijo
+            UiTargetCamera(camera),
+            // Contenedor a toda la vista que centra a su hijo
             Node {
                 position_type: PositionType::Absolute,
                 width: Val::Percent(100.0),
@@ -49,32 +59,27 @@ pub fn despawn_crosshair(mut commands: Commands, crosshairs: Query<Entity, With<
     }
 }
 
-/// Dibuja un recuadro wireframe alrededor del voxel que el jugador apunta.
+/// Dibuja un recuadro wireframe alrededor del voxel que apunta cada jugador.
 pub fn highlight_aimed_voxel(
-    camera_query: Query<&Transform, With<Camera>>,
-    chunk_map: Res<ChunkMap>,
-    chunks: Query<&BaseChunk>,
+    camera_query: Query<&Transform, With<Player>>,
+    world: VoxelReader,
     mut gizmos: Gizmos,
 ) {
-    let Ok(camera) = camera_query.single() else {
-        return;
-    };
-
-    let origin = camera.translation;
-    let direction = camera.forward().as_vec3();
+    // La cámara va en la entidad del jugador
+    for camera in &camera_query {
+        let origin = camera.translation;
+        let direction = camera.forward().as_vec3();
 
-    // Mismo alcance que la destrucción (5 m): solo resaltamos lo que se puede romper
-    if let Some((_entity, chunk_pos, local_pos, _voxel_type)) =
-        raycast_voxel(origin, direction, 5.0, &chunk_map, &chunks)
-    {
-        // Índice global del voxel → centro en coordenadas de mundo
-        let voxel_index = chunk_pos * BASE_CHUNK_SIZE as i32 + local_pos;
-        let center = (voxel_index.as_vec3() + Vec3::splat(0.5)) * VOXEL_SIZE;
+        // Mismo alcance que la destrucción (5 m): solo resaltamos lo que se puede romper
+        if let Some((voxel_index, _voxel_type, _normal)) = world.raycast(origin, direction, 5.0) {
+            // Índice global del voxel → centro en coordenadas de mundo
+            let center = (voxel_index.as_vec3() + Vec3::splat(0.5)) * VOXEL_SIZE;
 
-        // Cubo wireframe del tamaño de un voxel (la escala del Transform = lado del cubo)
-        gizmos.cuboid(
-            Transform::from_translation(center).with_scale(Vec3::splat(VOXEL_SIZE)),
-            Color::BLACK,
-        );
+            // Cubo wireframe del tamaño de un voxel (la escala del Transform = lado del cubo)
+            gizmos.cuboid(
+                Transform::from_translation(center).with_scale(Vec3::splat(VOXEL_SIZE)),
+                Color::BLACK,
+            );
+        }
     }
 }
@@ -0,0 +1,76 @@
+//! Pantalla dividida: un viewport de la ventana por jugador local.
+//!
+//! La cámara de cada jugador va en su entidad (como con uno solo); aquí solo
+//! se reparte la ventana. Con dos jugadores la pantalla se parte arriba/abajo;
+//! con más, en una rejilla. La UI a pantalla completa (HUD, menús) se dibuja
+//! en la cámara del [`PrimaryPlayer`](super::PrimaryPlayer).
+
+use super::controls::LocalPlayer;
+use bevy::camera::Viewport;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Rectángulo (posición, tamaño) en píxeles físicos del jugador `index` de
+/// `count` en una ventana de `size`. `None` con un solo jugador: la cámara
+/// ocupa la ventana entera.
+pub fn split_screen_rect(index: usize, count: usize, size: UVec2) -> Option<(UVec2, UVec2)> {
+    if count <= 1 {
+        return None;
+    }
+    // Filas primero: dos jugadores quedan uno encima del otro
+    let rows = (count as f32).sqrt().ceil() as u32;
+    let cols = (count as u32).div_ceil(rows);
+    let cell = (size / UVec2::new(cols, rows)).max(UVec2::ONE);
+    let (col, row) = (index as u32 % cols, index as u32 / cols);
+    Some((cell * UVec2::new(col, row), cell))
+}
+
+/// Ajusta el viewport de cada cámara de jugador al número de jugadores y al
+/// tamaño de la ventana (cambia al redimensionarla).
+pub fn update_split_screen_viewports(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&LocalPlayer, &mut Camera)>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let count = cameras.iter().count();
+    for (local, mut camera) in &mut cameras {
+        let wanted = split_screen_rect(local.index, count, window.physical_size());
+        let current = camera
+            .viewport
+            .as_ref()
+            .map(|viewport| (viewport.physical_position, viewport.physical_size));
+        if current != wanted {
+            camera.viewport = wanted.map(|(physical_position, physical_size)| Viewport {
+                physical_position,
+                physical_size,
+                ..default()
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_players_split_top_and_bottom() {
+        let size = UVec2::new(1280, 720);
+        assert_eq!(split_screen_rect(0, 1, size), None);
+        assert_eq!(
+            split_screen_rect(0, 2, size),
+            Some((UVec2::ZERO, UVec2::new(1280, 360)))
+        );
+        assert_eq!(
+            split_screen_rect(1, 2, size),
+            Some((UVec2::new(0, 360), UVec2::new(1280, 360)))
+        );
+        // Cuatro: rejilla 2×2
+        assert_eq!(
+            split_screen_rect(3, 4, size),
+            Some((UVec2::new(640, 360), UVec2::new(640, 360)))
+        );
+    }
+}
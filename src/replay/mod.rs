@@ -0,0 +1,31 @@
+//! # Grabación y repetición determinista
+//!
+//! `--record <fichero>` graba la partida: la seed, los diffs de partida y, por
+//! frame, el `dt` y las `PlayerActions` del jugador principal (teclas, ratón,
+//! romper, colocar) con su posición. `--replay <fichero>` la repite sin
+//! ventana y comprueba que acaba con los mismos `VoxelDiffs` (su huella) y la
+//! misma trayectoria: reportes de bugs reproducibles y un banco de regresión
+//! para la generación, la destrucción y las simulaciones del mundo. Grabación
+//! y repetición corren el MISMO juego determinista (`sim`), lava, derrumbes y
+//! caída incluidos.
+//!
+//! ## Estructura
+//! - 'format': La repetición (`Replay`), su formato binario y la huella de
+//!   los diffs
+//! - 'sim': El juego determinista que comparten ([`DeterministicGamePlugin`])
+//! - 'record': La grabación durante la partida ([`ReplayRecorder`])
+//! - 'playback': La repetición headless y su comparación ([`ReplayReport`])
+//!
+//! ```text
+//! cargo run -- --record partida.vxrp
+//! cargo run -- --replay partida.vxrp
+//! ```
+
+pub mod format;
+pub mod playback;
+pub mod record;
+pub mod sim;
+
+pub use playback::*;
+pub use record::*;
+pub use sim::*;
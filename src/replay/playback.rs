@@ -0,0 +1,321 @@
+//! Repetición headless (`--replay <fichero>`).
+//!
+//! Rehace una [`Replay`] sin ventana ni GPU con el juego determinista de
+//! [`super::sim`], el MISMO que corre la partida mientras se graba: jugador,
+//! edición, lava, mechas, derrumbes, caída y drops, más la física de Rapier,
+//! alimentados con las `PlayerActions` grabadas y avanzando el reloj
+//! exactamente el `dt` de cada frame. Así acaba con los mismos `VoxelDiffs` y
+//! la misma trayectoria que la partida grabada.
+
+use super::format::{diff_hash, Replay, ReplayFrame};
+use super::sim::DeterministicGamePlugin;
+use crate::core::{DeterministicGame, GameState, WorldSeed};
+use crate::physics::DropAssets;
+use crate::player::{player_gameplay, Player, PlayerActions, PrimaryPlayer};
+use crate::voxel::{seed_loot_rng, ChunkMap, DebrisAssets, VoxelDiffs};
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_rapier3d::prelude::{NoUserData, RapierPhysicsPlugin};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Distancia (m) a partir de la que la trayectoria repetida se separa de la
+/// grabada.
+pub const TRAJECTORY_TOLERANCE: f32 = 0.01;
+
+/// Resultado de repetir una grabación.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayReport {
+    /// Centro de la cápsula del jugador al acabar cada frame
+    pub trajectory: Vec<Vec3>,
+    /// Huella de los `VoxelDiffs` al acabar
+    pub diff_hash: u64,
+    /// Voxels distintos del terreno procedural al acabar
+    pub diff_voxels: usize,
+    /// Primer frame en que la trayectoria se separa de la grabada, y cuánto (m)
+    pub divergence: Option<(usize, f32)>,
+}
+
+impl ReplayReport {
+    /// ¿Reproduce la grabación (mismo mundo final y misma trayectoria)?
+    pub fn matches(&self, replay: &Replay) -> bool {
+        self.diff_hash == replay.diff_hash && self.divergence.is_none()
+    }
+}
+
+// ============================================================================
+// SIMULACIÓN
+// ============================================================================
+
+/// App headless lista para repetir `replay`: el mundo de la grabación en el
+/// menú, con el paso a la partida ya pedido. Como en el juego, el jugador
+/// aparece (y se siembra el botín) en ese paso, que es el primer frame grabado.
+pub fn replay_app(replay: &Replay) -> App {
+    let mut voxel_diffs = VoxelDiffs::default();
+    for (chunk_pos, voxels) in &replay.initial_diffs {
+        for &(local, voxel_type) in voxels {
+            voxel_diffs.record(*chunk_pos, local, voxel_type);
+        }
+    }
+
+    // El jugador principal de `spawn_player`, sin cámara ni dispositivo
+    let (spawn, yaw, pitch) = (replay.spawn, replay.yaw, replay.pitch);
+    let spawn_replay_player = move |mut commands: Commands| {
+        let rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+        let player = Player {
+            yaw,
+            pitch,
+            ..default()
+        };
+        let transform = Transform::from_translation(spawn).with_rotation(rotation);
+        commands.spawn((player_gameplay(player, transform), PrimaryPlayer));
+    };
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TransformPlugin, StatesPlugin))
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+        // Drops y escombros llevan mesh y material aunque aquí no se dibujen
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .init_resource::<DropAssets>()
+        .init_resource::<DebrisAssets>()
+        .add_plugins(DeterministicGamePlugin)
+        .init_state::<GameState>()
+        .insert_resource(DeterministicGame)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO))
+        .insert_resource(WorldSeed(replay.seed))
+        .insert_resource(ChunkMap {
+            chunks: HashMap::new(),
+        })
+        .insert_resource(voxel_diffs)
+        .add_systems(
+            OnTransition {
+                exited: GameState::MainMenu,
+                entered: GameState::InGame,
+            },
+            (spawn_replay_player, seed_loot_rng),
+        );
+
+    app.finish();
+    app.cleanup();
+    app.update();
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::InGame);
+    app
+}
+
+/// Corre un frame grabado: sus acciones al jugador principal y su `dt` al
+/// reloj. Devuelve dónde acaba el jugador.
+pub fn step_replay(app: &mut App, frame: &ReplayFrame) -> Vec3 {
+    let dt = Duration::try_from_secs_f32(frame.dt).unwrap_or_default();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(dt));
+    let world = app.world_mut();
+    let mut player = world.query_filtered::<&mut PlayerActions, With<PrimaryPlayer>>();
+    // En el primer frame aún no existe: aparece en él, sin acciones
+    if let Ok(mut actions) = player.single_mut(world) {
+        *actions = frame.actions.clone();
+    }
+
+    app.update();
+
+    let world = app.world_mut();
+    let mut player = world.query_filtered::<&Transform, With<PrimaryPlayer>>();
+    player.single(world).expect("la repetición no tiene jugador").translation
+}
+
+/// Repite la grabación entera y la compara con lo grabado.
+pub fn run_replay(replay: &Replay) -> ReplayReport {
+    let mut app = replay_app(replay);
+    let trajectory: Vec<Vec3> = replay
+        .frames
+        .iter()
+        .map(|frame| step_replay(&mut app, frame))
+        .collect();
+
+    let voxel_diffs = app.world().resource::<VoxelDiffs>();
+    let divergence = trajectory
+        .iter()
+        .zip(&replay.frames)
+        .map(|(position, frame)| position.distance(frame.position))
+        .enumerate()
+        .find(|(_, distance)| *distance > TRAJECTORY_TOLERANCE || distance.is_nan());
+    ReplayReport {
+        diff_hash: diff_hash(voxel_diffs),
+        diff_voxels: voxel_diffs.chunks.values().map(|voxels| voxels.len()).sum(),
+        trajectory,
+        divergence,
+    }
+}
+
+/// `--replay <fichero>`: repite la grabación y cuenta si reproduce el mundo y
+/// la trayectoria grabados. `Ok(true)` si los reproduce.
+///
+/// El informe va a la salida estándar con `println!`: es la salida del comando
+/// `--replay`, no un log (la app headless no lleva `LogPlugin`).
+pub fn run_replay_file(path: &Path) -> Result<bool, String> {
+    let replay = Replay::load(path)
+        .map_err(|e| format!("No se pudo leer la repetición {:?}: {}", path, e))?;
+    let report = run_replay(&replay);
+
+    println!(
+        "{} frames, seed {}, {} voxels modificados",
+        replay.frames.len(),
+        replay.seed,
+        report.diff_voxels
+    );
+    println!(
+        "Huella de los diffs: {:016x} (grabada {:016x})",
+        report.diff_hash, replay.diff_hash
+    );
+    match report.divergence {
+        Some((frame, distance)) => println!(
+            "La trayectoria se separa en el frame {} ({:.3} m de lo grabado)",
+            frame, distance
+        ),
+        None => println!("Trayectoria igual a la grabada"),
+    }
+    Ok(report.matches(&replay))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::GameSettings;
+    use crate::player::spawn_player;
+    use crate::replay::ReplayRecorder;
+    use crate::replay::ReplayRecordPlugin;
+    use crate::voxel::{voxel_to_chunk, VoxelType};
+
+    /// Una partida de prueba: caer, mirar abajo, romper, coger lo roto,
+    /// colocarlo y caminar saltando.
+    fn scripted_actions() -> Vec<PlayerActions> {
+        let mut frames = Vec::new();
+        // Caer hasta el suelo
+        frames.extend((0..150).map(|_| PlayerActions::default()));
+        // Mirar hacia abajo y romper la tierra de delante de los pies (a mano
+        // tarda casi 7 s)
+        frames.push(PlayerActions {
+            look: Vec2::new(0.0, 500.0),
+            ..default()
+        });
+        frames.extend((0..450).map(|_| PlayerActions {
+            breaking: true,
+            ..default()
+        }));
+        // Esperar al drop, colocarlo y echar a andar saltando
+        frames.extend((0..60).map(|_| PlayerActions::default()));
+        frames.push(PlayerActions {
+            place: true,
+            ..default()
+        });
+        frames.extend((0..120).map(|i| PlayerActions {
+            movement: Vec2::Y,
+            look: Vec2::new(2.0, -3.0),
+            jump: i % 40 == 0,
+            ..default()
+        }));
+        frames
+    }
+
+    /// Un mundo guardado: una explanada de tierra de 4 × 4 m bajo el punto de
+    /// aparición (que en cualquier seed cae sobre el río).
+    fn dirt_platform() -> VoxelDiffs {
+        let mut voxel_diffs = VoxelDiffs::default();
+        for x in -20..20 {
+            for z in -20..20 {
+                for y in -12..0 {
+                    let (chunk_pos, local) = voxel_to_chunk(IVec3::new(x, y, z));
+                    voxel_diffs.record(chunk_pos, local, VoxelType::Dirt);
+                }
+            }
+        }
+        voxel_diffs
+    }
+
+    /// Juega `actions` con `--record` como lo hace el juego (menú → partida →
+    /// menú, con el jugador de `spawn_player` y el juego determinista) y
+    /// devuelve la grabación escrita en `path`.
+    fn record_game(seed: i32, actions: &[PlayerActions], path: &Path) -> Replay {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, StatesPlugin))
+            .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<DropAssets>()
+            .init_resource::<DebrisAssets>()
+            .add_plugins((DeterministicGamePlugin, ReplayRecordPlugin))
+            .init_state::<GameState>()
+            .insert_resource(GameSettings::new())
+            .insert_resource(WorldSeed(seed))
+            .insert_resource(dirt_platform())
+            .insert_resource(ChunkMap {
+                chunks: HashMap::new(),
+            })
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                1.0 / 60.0,
+            )))
+            .insert_resource(DeterministicGame)
+            .insert_resource(ReplayRecorder::new(path))
+            .add_systems(
+                OnTransition {
+                    exited: GameState::MainMenu,
+                    entered: GameState::InGame,
+                },
+                (spawn_player, seed_loot_rng),
+            );
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        // Play: el jugador aparece y se graba desde este frame
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::InGame);
+        app.update();
+        for frame_actions in actions {
+            // Lo que `read_player_actions` leería del teclado y el ratón
+            let world = app.world_mut();
+            let mut player = world.query_filtered::<&mut PlayerActions, With<PrimaryPlayer>>();
+            *player.single_mut(world).unwrap() = frame_actions.clone();
+            app.update();
+        }
+        // Volver al menú escribe la grabación
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::MainMenu);
+        app.update();
+        Replay::load(path).unwrap()
+    }
+
+    #[test]
+    fn a_recorded_game_replays_to_the_same_world_and_trajectory() {
+        let path = std::env::temp_dir().join(format!("replay-test-{}.vxrp", std::process::id()));
+        let replay = record_game(7, &scripted_actions(), &path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replay.frames.len(), scripted_actions().len() + 1);
+
+        let replayed = run_replay(&replay);
+        assert_ne!(
+            replay.diff_hash,
+            diff_hash(&dirt_platform()),
+            "la partida de prueba no rompió nada"
+        );
+        let walked = replay.frames.last().unwrap().position - replay.frames[150].position;
+        assert!(walked.xz().length() > 1.0, "el jugador no caminó");
+        // Exactamente lo mismo, no solo dentro de la tolerancia
+        let recorded: Vec<Vec3> = replay.frames.iter().map(|frame| frame.position).collect();
+        assert_eq!(replayed.trajectory, recorded);
+        assert_eq!(replayed.diff_hash, replay.diff_hash);
+        assert!(replayed.matches(&replay));
+
+        // Otras acciones no reproducen la grabación: la repetición lo detecta
+        let mut tampered = replay.clone();
+        for frame in &mut tampered.frames {
+            frame.actions.breaking = false;
+        }
+        assert!(!run_replay(&tampered).matches(&replay));
+    }
+}
@@ -0,0 +1,142 @@
+//! Grabación de la partida (`--record <fichero>`).
+//!
+//! Empieza al pasar del menú al juego (cuando se genera el terreno y se
+//! siembra el botín) y guarda un frame por cada frame EN JUEGO: con la pausa,
+//! el inventario o la consola abiertos no se juega, así que no se graba. Se
+//! escribe al volver al menú o al cerrar. Solo partidas sin red y de un
+//! jugador: lo que no pasa por las `PlayerActions` (comandos de la consola,
+//! ediciones del servidor) no se graba.
+//!
+//! Mientras se graba, la partida corre con [`DeterministicGame`]: el juego
+//! determinista de [`super::sim`] en lugar de los sistemas normales, el mismo
+//! que usa la repetición (chunks generados en el acto hasta
+//! `DETERMINISTIC_VIEW_RADIUS`, sin LOD ni descarga).
+//!
+//! [`DeterministicGame`]: crate::core::DeterministicGame
+
+use super::format::{diff_hash, sorted_diffs, Replay, ReplayFrame};
+use crate::core::{GameState, WorldSeed};
+use crate::player::{local_spawn_point, Player, PlayerActions, PrimaryPlayer};
+use crate::voxel::{teardown_world, VoxelDiffs};
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+/// Grabación en curso: a dónde se escribe y lo grabado hasta ahora.
+#[derive(Resource, Debug)]
+pub struct ReplayRecorder {
+    path: PathBuf,
+    /// `None` fuera de partida
+    replay: Option<Replay>,
+}
+
+impl ReplayRecorder {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            replay: None,
+        }
+    }
+
+    /// Cierra la grabación con la huella de los diffs finales y la escribe.
+    fn finish(&mut self, voxel_diffs: &VoxelDiffs) {
+        let Some(mut replay) = self.replay.take() else {
+            return;
+        };
+        replay.diff_hash = diff_hash(voxel_diffs);
+        match replay.save(&self.path) {
+            Ok(()) => info!(
+                "Repetición guardada en {:?} ({} frames)",
+                self.path,
+                replay.frames.len()
+            ),
+            Err(e) => error!("No se pudo guardar la repetición {:?}: {}", self.path, e),
+        }
+    }
+}
+
+/// Graba la partida si existe un [`ReplayRecorder`] (solo con `--record`).
+pub struct ReplayRecordPlugin;
+
+impl Plugin for ReplayRecordPlugin {
+    fn build(&self, app: &mut App) {
+        let recording = resource_exists::<ReplayRecorder>;
+        app.add_systems(
+            OnTransition {
+                exited: GameState::MainMenu,
+                entered: GameState::InGame,
+            },
+            start_recording.run_if(recording),
+        )
+        // Antes de que `teardown_world` borre los diffs
+        .add_systems(
+            OnEnter(GameState::MainMenu),
+            finish_recording.before(teardown_world).run_if(recording),
+        )
+        // Tras la física del frame: la posición ya es la final
+        .add_systems(
+            Last,
+            (
+                record_replay_frame.run_if(in_state(GameState::InGame)),
+                finish_recording_on_exit,
+            )
+                .chain()
+                .run_if(recording),
+        );
+    }
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Empieza a grabar con el mundo tal como lo dejó la pantalla de selección.
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    world_seed: Res<WorldSeed>,
+    voxel_diffs: Res<VoxelDiffs>,
+) {
+    // Mismo punto y giro que `spawn_player` le da al jugador principal
+    let player = Player::default();
+    recorder.replay = Some(Replay {
+        seed: world_seed.0,
+        initial_diffs: sorted_diffs(&voxel_diffs),
+        spawn: local_spawn_point(0),
+        yaw: player.yaw,
+        pitch: player.pitch,
+        ..default()
+    });
+    info!("Grabando la partida en {:?}", recorder.path);
+}
+
+/// Guarda el frame del jugador principal: su `dt`, sus acciones y dónde acabó.
+fn record_replay_frame(
+    time: Res<Time>,
+    mut recorder: ResMut<ReplayRecorder>,
+    player: Query<(&PlayerActions, &Transform), With<PrimaryPlayer>>,
+) {
+    let (Some(replay), Ok((actions, transform))) = (recorder.replay.as_mut(), player.single())
+    else {
+        return;
+    };
+    replay.frames.push(ReplayFrame {
+        dt: time.delta_secs(),
+        actions: actions.clone(),
+        position: transform.translation,
+    });
+}
+
+/// Escribe la grabación al volver al menú.
+fn finish_recording(mut recorder: ResMut<ReplayRecorder>, voxel_diffs: Res<VoxelDiffs>) {
+    recorder.finish(&voxel_diffs);
+}
+
+/// Escribe la grabación al cerrar la ventana.
+fn finish_recording_on_exit(
+    mut exit: MessageReader<AppExit>,
+    mut recorder: ResMut<ReplayRecorder>,
+    voxel_diffs: Res<VoxelDiffs>,
+) {
+    if exit.read().next().is_some() {
+        recorder.finish(&voxel_diffs);
+    }
+}
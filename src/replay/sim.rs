@@ -0,0 +1,142 @@
+//! El juego determinista que comparten la grabación y la repetición.
+//!
+//! Jugador (cámara, movimiento, barra rápida, vida), edición del mundo
+//! (romper, colocar, tirar), lo que el mundo hace solo (lava, mechas y
+//! explosiones, derrumbes, caída de arena) y los drops, en un único orden y
+//! sin nada que dependa del reloj real: los chunks se generan en el acto
+//! alrededor del jugador (sin hilos ni LOD), y remallado y lava tienen un
+//! presupuesto fijo por frame en lugar de uno de tiempo. Con
+//! [`DeterministicGame`] lo corre la partida que se graba, en lugar de sus
+//! sistemas normales, y la repetición corre exactamente lo mismo.
+
+use crate::core::{DeterministicGame, GameState, WorldSeed};
+use crate::physics::{
+    attract_rapier_drops_system, collect_rapier_drops_system,
+    freeze_drops_on_unloaded_chunks_system, merge_rapier_drops_system,
+    update_rapier_drops_system, DropSettings,
+};
+use crate::player::camera::player_look;
+use crate::player::input::{select_hotbar_slot, sync_held_tool, throw_held_item};
+use crate::player::movement::player_movement;
+use crate::player::{burning_system, lava_contact_system, respawn_on_death, PrimaryPlayer};
+use crate::server::{chunks_around, insert_server_chunk};
+use crate::voxel::{
+    apply_pending_voxel_edits, build_chunk_collider, burn_drops_in_lava,
+    greedy_mesh_basechunk_simple, place_voxel_system, remesh_dirty_chunks_system,
+    start_voxel_breaking_system, update_voxel_breaking_system, world_pos_to_chunk_pos,
+    world_simulation_systems, BaseChunk, ChunkLOD, ChunkMap, ChunkMaterials, Explosion,
+    FallingSim, Fuses, LavaSim, LootRng, PendingVoxelEdits, SpatialHashGrid, StructuralChecks,
+    VoxelDiffs, VoxelEdited,
+};
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::*;
+
+/// Radio (chunks) que se mantiene generado alrededor del jugador principal.
+pub const DETERMINISTIC_VIEW_RADIUS: i32 = 2;
+
+/// Corre el juego determinista durante la partida si existe
+/// [`DeterministicGame`]. Inicia el estado de las simulaciones; `ChunkMap`,
+/// los assets de mesh y material y los de drops y escombros los pone la app.
+pub struct DeterministicGamePlugin;
+
+impl Plugin for DeterministicGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VoxelDiffs>()
+            .init_resource::<SpatialHashGrid>()
+            .init_resource::<PendingVoxelEdits>()
+            .init_resource::<LootRng>()
+            .init_resource::<LavaSim>()
+            .init_resource::<StructuralChecks>()
+            .init_resource::<FallingSim>()
+            .init_resource::<Fuses>()
+            .init_resource::<DropSettings>()
+            .add_message::<VoxelEdited>()
+            .add_message::<Explosion>()
+            .add_systems(
+                Update,
+                deterministic_game_systems()
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(resource_exists::<DeterministicGame>),
+            );
+    }
+}
+
+/// Los sistemas de juego que corren con [`DeterministicGame`], en su orden.
+pub fn deterministic_game_systems() -> ScheduleConfigs<ScheduleSystem> {
+    (
+        load_chunks_around_player,
+        // Ediciones encoladas para los chunks que acaban de llegar
+        apply_pending_voxel_edits,
+        player_look,
+        player_movement,
+        (select_hotbar_slot, throw_held_item, sync_held_tool).chain(),
+        start_voxel_breaking_system,
+        update_voxel_breaking_system,
+        place_voxel_system,
+        world_simulation_systems(),
+        // Presupuesto fijo: los primeros chunks editados en orden de posición
+        remesh_dirty_chunks_system,
+        (lava_contact_system, burning_system, respawn_on_death).chain(),
+        burn_drops_in_lava,
+        (
+            freeze_drops_on_unloaded_chunks_system,
+            update_rapier_drops_system,
+            merge_rapier_drops_system,
+            attract_rapier_drops_system,
+            collect_rapier_drops_system,
+        )
+            .chain(),
+    )
+        .chain()
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Genera en el acto los chunks que faltan alrededor del jugador principal:
+/// con la carga asíncrona, cuándo llega cada uno dependería del reloj real. Su
+/// collider sale de [`build_chunk_collider`], como en el servidor; si hay
+/// `ChunkMaterials` (en el juego) llevan además mesh para dibujarse. No se
+/// descargan.
+#[allow(clippy::too_many_arguments)]
+pub fn load_chunks_around_player(
+    mut commands: Commands,
+    world_seed: Res<WorldSeed>,
+    voxel_diffs: Res<VoxelDiffs>,
+    mut chunk_map: ResMut<ChunkMap>,
+    mut spatial_hash: ResMut<SpatialHashGrid>,
+    player: Query<&Transform, With<PrimaryPlayer>>,
+    mut meshes: Option<ResMut<Assets<Mesh>>>,
+    chunk_materials: Option<Res<ChunkMaterials>>,
+) {
+    let Ok(transform) = player.single() else {
+        return;
+    };
+    let center = world_pos_to_chunk_pos(transform.translation);
+    for chunk_pos in chunks_around(center, DETERMINISTIC_VIEW_RADIUS) {
+        if chunk_map.chunks.contains_key(&chunk_pos) {
+            continue;
+        }
+        let mut base_chunk = BaseChunk::new(chunk_pos, world_seed.0);
+        if let Some(diffs) = voxel_diffs.chunks.get(&chunk_pos) {
+            base_chunk.apply_diffs(diffs);
+        }
+        let collider = build_chunk_collider(&base_chunk);
+
+        let mut chunk = commands.spawn_empty();
+        if let (Some(meshes), Some(chunk_materials)) =
+            (meshes.as_deref_mut(), chunk_materials.as_deref())
+        {
+            chunk.insert((
+                Mesh3d(meshes.add(greedy_mesh_basechunk_simple(&base_chunk))),
+                MeshMaterial3d(chunk_materials.real_handle(ChunkLOD::Ultra)),
+                ChunkLOD::Ultra,
+            ));
+        }
+        insert_server_chunk(&mut chunk, base_chunk, collider);
+        chunk_map.chunks.insert(chunk_pos, chunk.id());
+        spatial_hash.insert(chunk_pos);
+    }
+}
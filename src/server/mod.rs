@@ -0,0 +1,386 @@
+//! Servidor headless (`--server`): el mundo sin ventana, sin GPU y sin UI.
+//!
+//! Corre lo que hace al mundo autoritativo y nada de lo que solo sirve para
+//! verlo: generación del terreno, `VoxelDiffs` (y su guardado en disco), física
+//! de Rapier, lava, explosiones, escombros y caída (`world_simulation_systems`,
+//! sin drops ni meshes) y carga de chunks alrededor de VARIOS jugadores a la
+//! vez. No hay `ChunkMaterials`, meshes, LOD, frustum culling ni menús; el
+//! bucle lo lleva `ScheduleRunnerPlugin` de `MinimalPlugins` a un ritmo fijo.
+//! Los jugadores llegan por la red (`src/net/`, `--connect` en el cliente) o
+//! son cuerpos simulados por el propio servidor (`--viewer`).
+//!
+//! ## Estructura
+//! - [`ServerConfig`]: argumentos de la línea de comandos
+//! - [`ServerPlugin`]: recursos y sistemas del mundo sin render
+//! - [`streaming`]: carga y descarga de chunks por jugador ([`ChunkViewer`])
+//! - [`network`]: clientes conectados, diffs y ediciones en lotes
+//!
+//! ```text
+//! cargo run -- --server --world mi_mundo --listen 0.0.0.0:7777
+//! cargo run -- --connect 127.0.0.1:7777
+//! ```
+
+pub mod network;
+pub mod streaming;
+
+pub use network::*;
+pub use streaming::*;
+
+use crate::core::WorldSeed;
+use crate::crafting::RecipeBook;
+use crate::net::DEFAULT_PORT;
+use crate::physics::{Collider, RigidBody};
+use crate::player::{PLAYER_HALF_HEIGHT, PLAYER_RADIUS};
+use crate::voxel::{
+    apply_pending_voxel_edits, autosave_world_system, create_world, flush_world_on_exit,
+    list_worlds, open_world, seed_loot_rng, unload_chunks_system, world_pos_to_chunk_pos,
+    world_simulation_systems, ChunkLoadQueue, ChunkMap, Explosion, FallingSim, Fuses, LavaSim,
+    LootRng, PendingVoxelEdits, SpatialHashGrid, StructuralChecks, VoxelDiffs, VoxelEdited,
+    WorldSaveState,
+};
+use bevy::app::{ScheduleRunnerPlugin, TerminalCtrlCHandlerPlugin};
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    AdditionalMassProperties, LockedAxes, NoUserData, RapierPhysicsPlugin, Velocity,
+};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+/// Radio de carga por jugador (chunks) si no se pasa `--view-radius`.
+pub const SERVER_VIEW_RADIUS: i32 = 6;
+
+/// Ticks por segundo del bucle del servidor.
+pub const SERVER_TICK_RATE: f64 = 30.0;
+
+/// Radio (chunks) que se genera de golpe bajo cada jugador al arrancar, para
+/// que tenga suelo antes de que lleguen los chunks async.
+const INITIAL_RADIUS: i32 = 1;
+
+// ============================================================================
+// CONFIGURACIÓN
+// ============================================================================
+
+/// Configuración del servidor (de los argumentos de `--server`).
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    /// Mundo guardado a abrir (o crear); `None` = no se guarda nada.
+    pub world: Option<String>,
+    /// Seed de un mundo nuevo (uno existente usa la suya); `None` = aleatoria.
+    pub seed: Option<i32>,
+    /// Radio de carga por jugador, en chunks.
+    pub view_radius: i32,
+    /// Jugadores simulados por el servidor (sin cliente): su posición inicial.
+    pub viewers: Vec<Vec3>,
+    /// Dirección donde se aceptan clientes; `None` = sin red.
+    pub listen: Option<SocketAddr>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            world: Some("server".to_string()),
+            seed: None,
+            view_radius: SERVER_VIEW_RADIUS,
+            viewers: Vec::new(),
+            listen: Some(SocketAddr::from((Ipv4Addr::UNSPECIFIED, DEFAULT_PORT))),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Lee la configuración de los argumentos (sin el nombre del programa):
+    /// `--world <nombre>`, `--no-save`, `--seed <n>`, `--view-radius <n>`,
+    /// `--viewer x,y,z` (repetible), `--listen <ip:puerto>` y `--offline`.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or(format!("falta el valor de {name}"));
+            match arg.as_str() {
+                "--server" => {}
+                "--no-save" => config.world = None,
+                "--world" => config.world = Some(value("--world")?),
+                "--seed" => {
+                    let seed = value("--seed")?;
+                    config.seed = Some(seed.parse().map_err(|_| format!("seed inválida: {seed}"))?);
+                }
+                "--view-radius" => {
+                    let radius = value("--view-radius")?;
+                    config.view_radius = radius
+                        .parse()
+                        .ok()
+                        .filter(|r| *r > 0)
+                        .ok_or(format!("radio inválido: {radius}"))?;
+                }
+                "--viewer" => config.viewers.push(parse_position(&value("--viewer")?)?),
+                "--offline" => config.listen = None,
+                "--listen" => {
+                    let addr = value("--listen")?;
+                    config.listen = Some(addr.parse().map_err(|_| format!("dirección inválida: {addr}"))?);
+                }
+                other => return Err(format!("argumento desconocido: {other}")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// `x,y,z` en metros.
+fn parse_position(text: &str) -> Result<Vec3, String> {
+    let coords: Vec<f32> = text
+        .split(',')
+        .map(|c| c.trim().parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("posición inválida: {text}"))?;
+    match coords[..] {
+        [x, y, z] => Ok(Vec3::new(x, y, z)),
+        _ => Err(format!("posición inválida (se espera x,y,z): {text}")),
+    }
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+/// El mundo del servidor: los mismos recursos de mundo que el cliente (menos
+/// los de render) y la carga de chunks por jugador. Necesita `ServerConfig`.
+pub struct ServerPlugin;
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+            .insert_resource(WorldSeed(0)) // La fija open_server_world
+            .insert_resource(ChunkMap {
+                chunks: HashMap::new(),
+            })
+            .init_resource::<ChunkLoadQueue>()
+            .init_resource::<SpatialHashGrid>()
+            .init_resource::<VoxelDiffs>()
+            .init_resource::<PendingVoxelEdits>()
+            .init_resource::<WorldSaveState>()
+            .init_resource::<ServerStreaming>()
+            .init_resource::<LavaSim>()
+            .init_resource::<LootRng>()
+            .init_resource::<Fuses>()
+            .init_resource::<StructuralChecks>()
+            .init_resource::<FallingSim>()
+            // Las recetas que fabrican los clientes (assets/data/recipes.ron)
+            .insert_resource(RecipeBook::load())
+            .add_message::<VoxelEdited>()
+            .add_message::<Explosion>()
+            .add_systems(
+                Startup,
+                (open_server_world, seed_loot_rng, spawn_viewers, open_listener).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    // Clientes nuevos, posiciones y peticiones de edición
+                    (accept_clients, receive_client_messages)
+                        .chain()
+                        .run_if(resource_exists::<NetServer>),
+                    update_viewer_chunk_queue,
+                    load_viewer_chunks,
+                    complete_viewer_chunks,
+                    unload_chunks_system,
+                    apply_pending_voxel_edits,
+                    world_simulation_systems(),
+                    rebuild_dirty_colliders,
+                    // Lo que cambió en el tick, a los clientes
+                    (
+                        broadcast_voxel_edits,
+                        stream_chunk_diffs,
+                        broadcast_player_states,
+                        flush_clients,
+                    )
+                        .chain()
+                        .run_if(resource_exists::<NetServer>),
+                    autosave_world_system,
+                )
+                    .chain(),
+            )
+            // Al parar (Ctrl+C), esperar a que lo pendiente llegue a disco
+            .add_systems(Last, flush_world_on_exit);
+    }
+}
+
+/// Arranca el servidor y bloquea hasta que termina.
+pub fn run_server(config: ServerConfig) -> AppExit {
+    App::new()
+        .add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(
+            Duration::from_secs_f64(1.0 / SERVER_TICK_RATE),
+        )))
+        .add_plugins((
+            LogPlugin::default(),
+            TransformPlugin,
+            TerminalCtrlCHandlerPlugin,
+        ))
+        .insert_resource(config)
+        .add_plugins(ServerPlugin)
+        .run()
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Abre (o crea) el mundo de `--world` como hace la pantalla de selección; con
+/// `--no-save`, solo fija la seed.
+fn open_server_world(
+    mut commands: Commands,
+    config: Res<ServerConfig>,
+    mut world_seed: ResMut<WorldSeed>,
+    mut voxel_diffs: ResMut<VoxelDiffs>,
+) {
+    let seed = config.seed.unwrap_or_else(|| WorldSeed::random().0);
+    let Some(name) = &config.world else {
+        world_seed.0 = seed;
+        info!("Servidor sin guardado, seed {}", seed);
+        return;
+    };
+
+    let saved = match list_worlds().into_iter().find(|w| &w.meta.name == name) {
+        Some(saved) => Ok(saved),
+        None => create_world(name, seed),
+    };
+    match saved.and_then(|saved| open_world(&saved, &mut world_seed, &mut voxel_diffs)) {
+        Ok(active_world) => commands.insert_resource(active_world),
+        Err(e) => {
+            // Se sigue sin guardar antes que tumbar el servidor
+            error!("No se pudo abrir el mundo '{}': {}", name, e);
+            world_seed.0 = seed;
+        }
+    }
+}
+
+/// Genera el suelo bajo cada jugador y crea su cuerpo (la cápsula del jugador
+/// del cliente, sin cámara ni input).
+fn spawn_viewers(
+    mut commands: Commands,
+    config: Res<ServerConfig>,
+    world_seed: Res<WorldSeed>,
+    voxel_diffs: Res<VoxelDiffs>,
+    mut chunk_map: ResMut<ChunkMap>,
+    mut spatial_hash: ResMut<SpatialHashGrid>,
+) {
+    for (id, &position) in config.viewers.iter().enumerate() {
+        for chunk_pos in chunks_around(world_pos_to_chunk_pos(position), INITIAL_RADIUS) {
+            if chunk_map.chunks.contains_key(&chunk_pos) {
+                continue;
+            }
+            generate_server_chunk(
+                &mut commands,
+                chunk_pos,
+                world_seed.0,
+                &voxel_diffs,
+                &mut chunk_map,
+                &mut spatial_hash,
+            );
+        }
+
+        commands.spawn((
+            ChunkViewer,
+            NetPlayer { id: id as u32 },
+            Transform::from_translation(position),
+            RigidBody::Dynamic,
+            Collider::capsule_y(PLAYER_HALF_HEIGHT, PLAYER_RADIUS),
+            Velocity::zero(),
+            LockedAxes::ROTATION_LOCKED,
+            AdditionalMassProperties::Mass(70.0),
+        ));
+    }
+    info!(
+        "Servidor: {} jugadores, {} chunks iniciales",
+        config.viewers.len(),
+        chunk_map.chunks.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{BaseChunk, ChunkGenerationTask};
+    use std::time::Instant;
+
+    fn args(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split_whitespace().map(str::to_string)
+    }
+
+    #[test]
+    fn parses_server_arguments() {
+        assert_eq!(ServerConfig::from_args(args("--server")), Ok(ServerConfig::default()));
+        let config = ServerConfig::from_args(args(
+            "--server --world pruebas --seed -3 --view-radius 4 --viewer 0,20,0 --viewer 64,20.5,-8 \
+             --listen 127.0.0.1:9000",
+        ))
+        .unwrap();
+        assert_eq!(config.world.as_deref(), Some("pruebas"));
+        assert_eq!(config.seed, Some(-3));
+        assert_eq!(config.view_radius, 4);
+        assert_eq!(config.viewers, vec![Vec3::new(0.0, 20.0, 0.0), Vec3::new(64.0, 20.5, -8.0)]);
+        assert_eq!(config.listen, Some("127.0.0.1:9000".parse().unwrap()));
+        assert_eq!(ServerConfig::from_args(args("--no-save")).unwrap().world, None);
+        assert_eq!(ServerConfig::from_args(args("--offline")).unwrap().listen, None);
+
+        assert!(ServerConfig::from_args(args("--seed")).is_err());
+        assert!(ServerConfig::from_args(args("--viewer 1,2")).is_err());
+        assert!(ServerConfig::from_args(args("--view-radius 0")).is_err());
+        assert!(ServerConfig::from_args(args("--listen 7777")).is_err());
+        assert!(ServerConfig::from_args(args("--fullscreen")).is_err());
+    }
+
+    #[test]
+    fn headless_server_streams_chunks_for_every_viewer() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin))
+            .insert_resource(ServerConfig {
+                world: None,
+                seed: Some(7),
+                view_radius: 2,
+                viewers: vec![Vec3::new(0.0, 20.0, 0.0), Vec3::new(64.0, 20.0, 0.0)],
+                listen: None,
+            })
+            .add_plugins(ServerPlugin);
+        app.finish();
+        app.cleanup();
+
+        // Hasta que no queda nada en cola ni generándose (y un poco de física)
+        let start = Instant::now();
+        loop {
+            app.update();
+            std::thread::sleep(Duration::from_millis(5));
+            let world = app.world_mut();
+            let pending = world
+                .query::<&ChunkGenerationTask>()
+                .iter(world)
+                .count();
+            let done = pending == 0 && world.resource::<ChunkLoadQueue>().to_load.is_empty();
+            if done && start.elapsed() > Duration::from_millis(500) {
+                break;
+            }
+            assert!(start.elapsed() < Duration::from_secs(60), "la carga no termina");
+        }
+
+        let world = app.world_mut();
+        let chunk_map = world.resource::<ChunkMap>();
+        // El borde de cada jugador (radio 2), bajo tierra: terreno con collider
+        let edges = [IVec3::new(2, -1, 0), IVec3::new(22, -1, 0)];
+        let edges: Vec<Entity> = edges.iter().map(|pos| chunk_map.chunks[pos]).collect();
+        assert!(!chunk_map.chunks.contains_key(&IVec3::new(11, -1, 0)));
+        for entity in edges {
+            let chunk = world.entity(entity);
+            assert!(chunk.contains::<BaseChunk>() && chunk.contains::<Collider>());
+        }
+
+        // Nada de render
+        assert_eq!(world.query::<&Mesh3d>().iter(world).count(), 0);
+        assert!(!world.contains_resource::<crate::voxel::ChunkMaterials>());
+
+        // Rapier simula: los jugadores caen hacia el suelo
+        let mut viewers = world.query_filtered::<&Transform, With<ChunkViewer>>();
+        assert_eq!(viewers.iter(world).count(), 2);
+        assert!(viewers.iter(world).all(|t| t.translation.y < 20.0));
+    }
+}
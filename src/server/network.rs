@@ -0,0 +1,713 @@
+//! Lado de red del servidor: acepta clientes y les sirve el mundo.
+//!
+//! Cada cliente que saluda pasa a ser un [`ChunkViewer`] más (el servidor le
+//! carga los chunks alrededor) con un [`NetPlayer`]. Por cliente se lleva qué
+//! chunks con diffs ya conoce: al entrar uno en su radio se le mandan TODOS sus
+//! diffs (`ChunkDiffs`) y desde entonces solo las ediciones, que salen a todos
+//! juntas en un `VoxelDelta` por tick, leídas de `VoxelEdited`.
+//!
+//! El servidor es la autoridad del mundo y de los inventarios: cada
+//! `NetPlayer` de un cliente lleva su [`Inventory`], y los clientes solo mandan
+//! intenciones. Romper usa la herramienta del slot que dice el cliente y
+//! resuelve el cráter, el botín (al inventario; lo que no cabe se pierde), las
+//! mechas y el desgaste con `break_with_tool`, como la partida local; colocar
+//! gasta el material del slot si `placement_allowed` lo deja. Lo que está fuera
+//! del alcance de quien lo pide o en chunks sin cargar se descarta. Tras cada
+//! intención que cambia su inventario se le manda entero (`Inventory`).
+//!
+//! La posición de cada cliente la propone el cliente y el servidor la sigue
+//! como mucho a [`MAX_PLAYER_SPEED`] (con un margen acumulado para los
+//! tirones de la red): un cliente no se teletransporta para romper lejos. La
+//! del servidor es la que se reparte a todos en `PlayerStates`. El chat se reenvía a todos, incluido quien lo
+//! escribió.
+
+use super::streaming::UNLOAD_MARGIN;
+use super::{chunks_around, ChunkViewer, ServerConfig};
+use crate::core::constants::VOXEL_SIZE;
+use crate::core::{Inventory, WorldSeed, HOTBAR_SLOTS, INVENTORY_SLOTS};
+use crate::crafting::RecipeBook;
+use crate::net::{
+    ClientMessage, Connection, PlayerSnapshot, ServerMessage, MAX_CHAT_CHARS, PROTOCOL_VERSION,
+};
+use crate::player::PLAYER_SPAWN;
+use crate::voxel::{
+    break_with_tool, nearest_horizontal_distance_sq, placement_allowed, world_pos_to_chunk_pos,
+    BreakHit, Fuses, LootRng, Tool, VoxelDiffs, VoxelEdited, VoxelType, VoxelWorld,
+};
+use bevy::prelude::*;
+use std::collections::HashSet;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+
+/// Distancia máxima (metros) entre un jugador y el voxel que rompe o coloca:
+/// el alcance del raycast (5 m) con margen para la posición que aún no llegó.
+const EDIT_REACH: f32 = 8.0;
+
+/// Velocidad máxima (m/s) a la que el servidor sigue la posición que manda un
+/// cliente: andar son 5 m/s; el resto es para caídas y respawns.
+const MAX_PLAYER_SPEED: f32 = 30.0;
+
+/// Segundos de movimiento que un cliente puede acumular (mensajes que llegan
+/// tarde y juntos).
+const MAX_MOVE_BURST_SECS: f32 = 1.0;
+
+/// Las normales que puede traer un `Break`. Se comparan tal cual, sin
+/// aritmética sobre lo que manda el cliente (`i32::MIN` desbordaría).
+const FACE_NORMALS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+// ============================================================================
+// RESOURCES / COMPONENTS
+// ============================================================================
+
+/// Jugador con id de red (los clientes y los `--viewer` del servidor).
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetPlayer {
+    pub id: u32,
+}
+
+/// Un cliente conectado.
+struct RemoteClient {
+    connection: Connection,
+    /// Id y entidad del jugador, tras el saludo.
+    player: Option<(u32, Entity)>,
+    /// Chunks cuyos diffs ya tiene.
+    known_chunks: HashSet<IVec3>,
+    /// Metros que aún puede moverse (ver [`MAX_PLAYER_SPEED`]).
+    move_budget: f32,
+    /// Mensajes llegados antes de que su jugador exista (en el mismo lote que
+    /// el saludo): se atienden el tick siguiente, en orden.
+    deferred: Vec<ClientMessage>,
+}
+
+/// Socket de escucha y clientes conectados.
+#[derive(Resource)]
+pub struct NetServer {
+    listener: TcpListener,
+    clients: Vec<RemoteClient>,
+    next_player_id: u32,
+}
+
+impl NetServer {
+    /// Escucha en `addr`; los ids de jugador empiezan en `first_player_id`.
+    pub fn bind(addr: SocketAddr, first_player_id: u32) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: Vec::new(),
+            next_player_id: first_player_id,
+        })
+    }
+
+    /// Dirección real de escucha (con el puerto elegido si se pidió el 0).
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Manda un mensaje a todos los clientes que ya saludaron.
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for client in self.clients.iter_mut().filter(|c| c.player.is_some()) {
+            client.connection.send(message);
+        }
+    }
+
+    /// Quita los clientes de `dropped` (índices), su jugador y avisa al resto.
+    fn drop_clients(&mut self, commands: &mut Commands, mut dropped: Vec<usize>) {
+        dropped.sort_unstable();
+        dropped.dedup();
+        for index in dropped.into_iter().rev() {
+            let client = self.clients.swap_remove(index);
+            if let Some((player_id, entity)) = client.player {
+                commands.entity(entity).despawn();
+                self.broadcast(&ServerMessage::PlayerLeft { player_id });
+                info!("Jugador {} desconectado", player_id);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Abre el socket de `--listen`. Si no se puede, el servidor sigue sin red.
+pub fn open_listener(mut commands: Commands, config: Res<ServerConfig>) {
+    let Some(addr) = config.listen else {
+        return;
+    };
+    match NetServer::bind(addr, config.viewers.len() as u32) {
+        Ok(server) => {
+            info!("Escuchando en {:?}", server.local_addr());
+            commands.insert_resource(server);
+        }
+        Err(e) => error!("No se pudo escuchar en {}: {}", addr, e),
+    }
+}
+
+/// Acepta conexiones nuevas (sin jugador hasta que saludan).
+pub fn accept_clients(mut server: ResMut<NetServer>) {
+    loop {
+        match server.listener.accept() {
+            Ok((stream, addr)) => match Connection::new(stream) {
+                Ok(connection) => {
+                    info!("Conexión de {}", addr);
+                    server.clients.push(RemoteClient {
+                        connection,
+                        player: None,
+                        known_chunks: HashSet::new(),
+                        move_budget: 0.0,
+                        deferred: Vec::new(),
+                    });
+                }
+                Err(e) => warn!("Conexión de {} rechazada: {}", addr, e),
+            },
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+            Err(e) => {
+                warn!("Error aceptando conexiones: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Atiende los mensajes de los clientes: saludo, posición, intenciones
+/// (romper, colocar, fabricar, mover stacks) y chat.
+#[allow(clippy::too_many_arguments)]
+pub fn receive_client_messages(
+    mut commands: Commands,
+    mut server: ResMut<NetServer>,
+    time: Res<Time>,
+    world_seed: Res<WorldSeed>,
+    mut world: VoxelWorld,
+    mut players: Query<(&mut Transform, &mut Inventory), With<NetPlayer>>,
+    mut loot_rng: ResMut<LootRng>,
+    mut fuses: ResMut<Fuses>,
+    recipes: Res<RecipeBook>,
+) {
+    let server = &mut *server;
+    let mut dropped = Vec::new();
+    let mut chat = Vec::new();
+    for (index, client) in server.clients.iter_mut().enumerate() {
+        let received = match client.connection.receive::<ClientMessage>() {
+            Ok(messages) => messages,
+            Err(e) => {
+                info!("Cliente perdido: {}", e);
+                dropped.push(index);
+                continue;
+            }
+        };
+        let mut messages = std::mem::take(&mut client.deferred);
+        messages.extend(received);
+        let refill = MAX_PLAYER_SPEED * time.delta_secs();
+        client.move_budget =
+            (client.move_budget + refill).min(MAX_PLAYER_SPEED * MAX_MOVE_BURST_SECS);
+        let mut inventory_changed = false;
+        for message in messages {
+            let Some((player_id, entity)) = client.player else {
+                // Antes del saludo solo vale el saludo
+                let ClientMessage::Hello { version } = message else {
+                    continue;
+                };
+                if version != PROTOCOL_VERSION {
+                    warn!("Cliente con protocolo {} (el nuestro es {})", version, PROTOCOL_VERSION);
+                    dropped.push(index);
+                    break;
+                }
+                let player_id = server.next_player_id;
+                server.next_player_id += 1;
+                let entity = commands
+                    .spawn((
+                        ChunkViewer,
+                        NetPlayer { id: player_id },
+                        Transform::from_translation(PLAYER_SPAWN),
+                        Inventory::default(),
+                    ))
+                    .id();
+                client.player = Some((player_id, entity));
+                client.connection.send(&ServerMessage::Welcome {
+                    player_id,
+                    seed: world_seed.0,
+                    spawn: PLAYER_SPAWN,
+                });
+                info!("Jugador {} conectado", player_id);
+                continue;
+            };
+
+            // Cápsulas de todos: nadie construye dentro de otro
+            let bodies: Vec<Vec3> = match message {
+                ClientMessage::Place { .. } => players.iter().map(|(t, _)| t.translation).collect(),
+                _ => Vec::new(),
+            };
+            // El jugador aún no está en el mundo (se acaba de crear)
+            let Ok((mut transform, mut inventory)) = players.get_mut(entity) else {
+                client.deferred.push(message);
+                continue;
+            };
+            let position = transform.translation;
+            let in_reach = |voxel: IVec3| {
+                let center = (voxel.as_vec3() + Vec3::splat(0.5)) * VOXEL_SIZE;
+                center.distance(position) <= EDIT_REACH
+            };
+            match message {
+                ClientMessage::Hello { .. } => {} // Un segundo saludo
+                ClientMessage::PlayerState { position, yaw } => {
+                    if !position.is_finite() || !yaw.is_finite() {
+                        continue;
+                    }
+                    // Hacia donde dice, sin pasarse de lo que pudo moverse
+                    let step = position - transform.translation;
+                    let step = step.clamp_length_max(client.move_budget);
+                    client.move_budget = (client.move_budget - step.length()).max(0.0);
+                    transform.translation += step;
+                    transform.rotation = Quat::from_rotation_y(yaw);
+                }
+                ClientMessage::Break {
+                    voxel,
+                    normal,
+                    direction,
+                    slot,
+                } => {
+                    let hit = world.get(voxel);
+                    if slot as usize >= HOTBAR_SLOTS
+                        || !in_reach(voxel)
+                        || !hit.is_some_and(|v| v.is_solid())
+                    {
+                        warn!("Jugador {}: no puede romper {:?}", player_id, voxel);
+                        continue;
+                    }
+                    // Solo una cara de verdad orienta la forma
+                    let normal = if FACE_NORMALS.contains(&normal) { normal } else { IVec3::ZERO };
+                    let direction = if direction.is_finite() { direction } else { Vec3::NEG_Y };
+                    inventory.selected = slot as usize;
+                    let tool = inventory
+                        .held()
+                        .and_then(|stack| stack.as_tool())
+                        .unwrap_or_else(Tool::hands);
+                    let target = BreakHit {
+                        voxel,
+                        normal,
+                        direction,
+                    };
+                    let (broken, loot) =
+                        break_with_tool(&mut world, target, &tool, &mut loot_rng, &mut fuses);
+                    if broken > 0 {
+                        for (id, &count) in loot.iter().enumerate().filter(|(_, c)| **c > 0) {
+                            inventory.add(VoxelType::from_u8(id as u8), count);
+                        }
+                        inventory.wear_held_tool(1);
+                        inventory_changed = true;
+                    }
+                }
+                ClientMessage::Place { voxel, slot } => {
+                    let material = inventory.slots.get(slot as usize).copied().flatten();
+                    let material = material.and_then(|stack| stack.as_voxel());
+                    let (Some(material), true) = (material, (slot as usize) < HOTBAR_SLOTS) else {
+                        warn!("Jugador {}: no tiene material en el slot {}", player_id, slot);
+                        continue;
+                    };
+                    if !in_reach(voxel) || !placement_allowed(voxel, |v| world.get(v), &bodies) {
+                        warn!("Jugador {}: no puede colocar en {:?}", player_id, voxel);
+                        continue;
+                    }
+                    inventory.selected = slot as usize;
+                    inventory.take_held();
+                    world.set(voxel, material);
+                    inventory_changed = true;
+                }
+                ClientMessage::Craft { recipe } => {
+                    if let Some(recipe) = recipes.0.get(recipe as usize)
+                        && recipe.craft(&mut inventory)
+                    {
+                        info!("Jugador {} fabrica {}", player_id, recipe.label());
+                        inventory_changed = true;
+                    }
+                }
+                ClientMessage::MoveStack { from, to } => {
+                    if (from as usize) < INVENTORY_SLOTS && (to as usize) < INVENTORY_SLOTS {
+                        inventory.move_stack(from as usize, to as usize);
+                        inventory_changed = true;
+                    }
+                }
+                ClientMessage::Chat { text } => {
+                    let text: String = text.trim().chars().take(MAX_CHAT_CHARS).collect();
+                    if !text.is_empty() {
+                        info!("<Jugador {}> {}", player_id, text);
+                        chat.push(ServerMessage::Chat { player_id, text });
+                    }
+                }
+            }
+        }
+        if inventory_changed
+            && let Some((_, entity)) = client.player
+            && let Ok((_, inventory)) = players.get(entity)
+        {
+            client.connection.send(&ServerMessage::Inventory {
+                slots: inventory.slots.to_vec(),
+            });
+        }
+    }
+    for message in &chat {
+        server.broadcast(message);
+    }
+    server.drop_clients(&mut commands, dropped);
+}
+
+/// Reparte a todos las ediciones del tick en un solo `VoxelDelta`.
+pub fn broadcast_voxel_edits(
+    mut server: ResMut<NetServer>,
+    mut edited: MessageReader<VoxelEdited>,
+) {
+    let edits: Vec<_> = edited.read().map(|e| (e.voxel, e.voxel_type)).collect();
+    if !edits.is_empty() {
+        server.broadcast(&ServerMessage::VoxelDelta { edits });
+    }
+}
+
+/// Manda a cada cliente los diffs de los chunks que entran en su radio y
+/// olvida los que quedan lejos (si vuelven, se mandan otra vez enteros).
+pub fn stream_chunk_diffs(
+    mut server: ResMut<NetServer>,
+    config: Res<ServerConfig>,
+    voxel_diffs: Res<VoxelDiffs>,
+    viewers: Query<&Transform, With<ChunkViewer>>,
+) {
+    let keep = config.view_radius + UNLOAD_MARGIN;
+    for client in &mut server.clients {
+        let Some((_, entity)) = client.player else {
+            continue;
+        };
+        let Ok(transform) = viewers.get(entity) else {
+            continue;
+        };
+        let center = world_pos_to_chunk_pos(transform.translation);
+        client
+            .known_chunks
+            .retain(|&pos| nearest_horizontal_distance_sq(pos, &[center]) <= keep * keep);
+        for chunk_pos in chunks_around(center, config.view_radius) {
+            let Some(voxels) = voxel_diffs.chunks.get(&chunk_pos) else {
+                continue;
+            };
+            if client.known_chunks.insert(chunk_pos) {
+                client.connection.send(&ServerMessage::ChunkDiffs {
+                    chunk_pos,
+                    voxels: voxels.iter().map(|(l, v)| (*l, *v)).collect(),
+                });
+            }
+        }
+    }
+}
+
+/// Reparte la posición de todos los jugadores.
+pub fn broadcast_player_states(
+    time: Res<Time>,
+    mut server: ResMut<NetServer>,
+    players: Query<(&NetPlayer, &Transform)>,
+) {
+    if server.clients.is_empty() {
+        return;
+    }
+    let players = players
+        .iter()
+        .map(|(player, transform)| PlayerSnapshot {
+            player_id: player.id,
+            position: transform.translation,
+            yaw: transform.rotation.to_euler(EulerRot::YXZ).0,
+        })
+        .collect();
+    server.broadcast(&ServerMessage::PlayerStates {
+        time: time.elapsed_secs_f64(),
+        players,
+    });
+}
+
+/// Escribe lo encolado del tick; quien no lo acepta se desconecta.
+pub fn flush_clients(mut commands: Commands, mut server: ResMut<NetServer>) {
+    let dropped: Vec<usize> = server
+        .clients
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(index, client)| client.connection.flush().err().map(|_| index))
+        .collect();
+    server.drop_clients(&mut commands, dropped);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ItemStack;
+    use std::time::{Duration, Instant};
+
+    /// Bajo tierra junto al spawn (chunk (0,-1,0), local (0,12,0)).
+    const NEAR: IVec3 = IVec3::new(0, -20, 0);
+    /// A 10 m: cargado pero fuera del alcance de quien está en el spawn.
+    const FAR: IVec3 = IVec3::new(100, -20, 0);
+
+    /// Corre el servidor hasta que `done` da `true` con lo recibido.
+    fn serve_until(
+        app: &mut App,
+        client: &mut Connection,
+        received: &mut Vec<ServerMessage>,
+        done: impl Fn(&[ServerMessage]) -> bool,
+    ) {
+        let start = Instant::now();
+        while !done(received) {
+            assert!(start.elapsed() < Duration::from_secs(60), "el servidor no responde");
+            app.update();
+            std::thread::sleep(Duration::from_millis(5));
+            received.extend(client.receive::<ServerMessage>().unwrap());
+        }
+    }
+
+    fn loaded(app: &mut App, voxel: IVec3) -> Option<VoxelType> {
+        app.world_mut()
+            .run_system_cached_with(
+                |In(voxel): In<IVec3>, world: VoxelWorld| world.get(voxel),
+                voxel,
+            )
+            .unwrap()
+    }
+
+    /// Servidor de pruebas (semilla 7) con un cliente ya saludado y los chunks
+    /// de [`NEAR`] y [`FAR`] cargados.
+    fn start_server() -> (App, Connection, Vec<ServerMessage>) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin))
+            .insert_resource(ServerConfig {
+                world: None,
+                seed: Some(7),
+                view_radius: 3,
+                viewers: vec![],
+                listen: Some("127.0.0.1:0".parse().unwrap()),
+            })
+            .add_plugins(super::super::ServerPlugin);
+        app.finish();
+        app.cleanup();
+        app.update();
+        let addr = app.world().resource::<NetServer>().local_addr().unwrap();
+
+        let mut client = Connection::connect(addr).unwrap();
+        client.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+        });
+        client.flush().unwrap();
+
+        let mut received = Vec::new();
+        let start = Instant::now();
+        while loaded(&mut app, NEAR).is_none() || loaded(&mut app, FAR).is_none() {
+            assert!(start.elapsed() < Duration::from_secs(60), "los chunks no cargan");
+            app.update();
+            std::thread::sleep(Duration::from_millis(5));
+            received.extend(client.receive::<ServerMessage>().unwrap());
+        }
+        assert!(loaded(&mut app, NEAR).is_some_and(|v| v.is_solid()));
+        (app, client, received)
+    }
+
+    fn delta_has(received: &[ServerMessage], edit: (IVec3, VoxelType)) -> bool {
+        received
+            .iter()
+            .any(|m| matches!(m, ServerMessage::VoxelDelta { edits } if edits.contains(&edit)))
+    }
+
+    fn player_id(received: &[ServerMessage]) -> Option<u32> {
+        received.iter().find_map(|m| match m {
+            ServerMessage::Welcome { player_id, .. } => Some(*player_id),
+            _ => None,
+        })
+    }
+
+    /// Última posición de `player_id` que repartió el servidor.
+    fn position_of(received: &[ServerMessage], player_id: u32) -> Option<Vec3> {
+        received.iter().rev().find_map(|m| match m {
+            ServerMessage::PlayerStates { players, .. } => players
+                .iter()
+                .find(|p| p.player_id == player_id)
+                .map(|p| p.position),
+            _ => None,
+        })
+    }
+
+    /// Manda la posición cada tick, como el cliente, hasta que el servidor
+    /// la reparte.
+    fn walk_to(
+        app: &mut App,
+        client: &mut Connection,
+        received: &mut Vec<ServerMessage>,
+        position: Vec3,
+    ) {
+        let player_id = player_id(received).unwrap();
+        let start = Instant::now();
+        while position_of(received, player_id) != Some(position) {
+            assert!(start.elapsed() < Duration::from_secs(60), "el jugador no llega");
+            client.send(&ClientMessage::PlayerState { position, yaw: 0.0 });
+            client.flush().unwrap();
+            app.update();
+            std::thread::sleep(Duration::from_millis(5));
+            received.extend(client.receive::<ServerMessage>().unwrap());
+        }
+    }
+
+    #[test]
+    fn client_intents_are_resolved_by_the_server() {
+        let (mut app, mut client, mut received) = start_server();
+        let crater_chunk = IVec3::new(0, -1, 0);
+
+        // Baja del spawn hasta encima del voxel, como haría su cliente
+        walk_to(&mut app, &mut client, &mut received, Vec3::new(0.0, 1.0, 0.0));
+
+        // Romper a mano: el servidor hace el cráter; lo lejano no se toca
+        for voxel in [NEAR, FAR] {
+            client.send(&ClientMessage::Break {
+                voxel,
+                normal: IVec3::Y,
+                direction: Vec3::NEG_Y,
+                slot: 0,
+            });
+        }
+        client.flush().unwrap();
+        serve_until(&mut app, &mut client, &mut received, |received| {
+            delta_has(received, (NEAR, VoxelType::Air))
+                && received.iter().any(|m| matches!(m, ServerMessage::Inventory { .. }))
+                && received.iter().any(|m| {
+                    matches!(m, ServerMessage::ChunkDiffs { chunk_pos, .. } if *chunk_pos == crater_chunk)
+                })
+                && received.iter().any(|m| matches!(m, ServerMessage::PlayerStates { .. }))
+        });
+        assert!(!app.world().resource::<VoxelDiffs>().chunks.contains_key(&IVec3::new(3, -1, 0)));
+
+        assert!(received.iter().any(|m| matches!(m, ServerMessage::Welcome { seed: 7, .. })));
+        // El chunk del cráter está en el radio: llegan sus diffs
+        let voxels = received.iter().find_map(|m| match m {
+            ServerMessage::ChunkDiffs { chunk_pos, voxels } if *chunk_pos == crater_chunk => Some(voxels),
+            _ => None,
+        });
+        let voxels = voxels.unwrap();
+        assert!(voxels.contains(&(IVec3::new(0, 12, 0), VoxelType::Air)));
+        // Y su posición, la última que mandó
+        let player_id = player_id(&received).unwrap();
+        assert_eq!(position_of(&received, player_id), Some(Vec3::new(0.0, 1.0, 0.0)));
+
+        // Colocar en el cráter, contra su borde: solo con material en el
+        // slot, según el inventario del servidor (el cliente no puede
+        // inventarse el metal)
+        let target = NEAR + IVec3::X;
+        received.clear();
+        client.send(&ClientMessage::Place { voxel: target, slot: 2 });
+        client.flush().unwrap();
+        for _ in 0..10 {
+            app.update();
+        }
+        received.extend(client.receive::<ServerMessage>().unwrap());
+        assert!(!delta_has(&received, (target, VoxelType::Metal)));
+
+        let world = app.world_mut();
+        let mut inventory = world.query_filtered::<&mut Inventory, With<NetPlayer>>();
+        let metal = Some(ItemStack::voxels(VoxelType::Metal, 5));
+        inventory.single_mut(world).unwrap().slots[2] = metal;
+        client.send(&ClientMessage::Place { voxel: target, slot: 2 });
+        client.flush().unwrap();
+        serve_until(&mut app, &mut client, &mut received, |received| {
+            delta_has(received, (target, VoxelType::Metal))
+                && received.iter().any(|m| matches!(m, ServerMessage::Inventory { .. }))
+        });
+        let slots = received
+            .iter()
+            .find_map(|m| match m {
+                ServerMessage::Inventory { slots } => Some(slots),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(slots[2], Some(ItemStack::voxels(VoxelType::Metal, 4)));
+    }
+
+    #[test]
+    fn malformed_break_normals_are_ignored() {
+        let (mut app, mut client, mut received) = start_server();
+
+        // Normales que no son una cara: se rompe igual, orientado por la
+        // dirección, y el servidor sigue en pie
+        walk_to(&mut app, &mut client, &mut received, Vec3::new(0.0, 1.0, 0.0));
+        let normals = [
+            IVec3::splat(i32::MIN),
+            IVec3::splat(i32::MAX),
+            IVec3::new(i32::MIN, 1, 0),
+        ];
+        for normal in normals {
+            client.send(&ClientMessage::Break {
+                voxel: NEAR,
+                normal,
+                direction: Vec3::NEG_Y,
+                slot: 0,
+            });
+        }
+        client.flush().unwrap();
+        serve_until(&mut app, &mut client, &mut received, |received| {
+            delta_has(received, (NEAR, VoxelType::Air))
+        });
+    }
+
+    #[test]
+    fn clients_cannot_teleport_to_edit() {
+        let (mut app, mut client, mut received) = start_server();
+        let standing = Vec3::new(0.0, 1.0, 0.0);
+        walk_to(&mut app, &mut client, &mut received, standing);
+        let player_id = player_id(&received).unwrap();
+
+        // Gasta lo que podía moverse y en el mismo lote salta junto al voxel
+        // lejano para romperlo: el servidor no lo ha seguido
+        client.send(&ClientMessage::PlayerState {
+            position: Vec3::new(0.0, 1.0, 1000.0),
+            yaw: 0.0,
+        });
+        client.send(&ClientMessage::PlayerState {
+            position: (FAR.as_vec3() + Vec3::Y * 5.0) * VOXEL_SIZE,
+            yaw: 0.0,
+        });
+        client.send(&ClientMessage::Break {
+            voxel: FAR,
+            normal: IVec3::Y,
+            direction: Vec3::NEG_Y,
+            slot: 0,
+        });
+        client.flush().unwrap();
+        received.clear();
+        for _ in 0..10 {
+            app.update();
+            std::thread::sleep(Duration::from_millis(5));
+            received.extend(client.receive::<ServerMessage>().unwrap());
+        }
+        assert!(!delta_has(&received, (FAR, VoxelType::Air)));
+        let position = position_of(&received, player_id).unwrap();
+        assert!(position.distance(standing) <= MAX_PLAYER_SPEED * MAX_MOVE_BURST_SECS + 1e-3);
+    }
+
+    #[test]
+    fn messages_batched_with_hello_are_kept() {
+        let (mut app, _, _) = start_server();
+        let addr = app.world().resource::<NetServer>().local_addr().unwrap();
+
+        // Saludo y chat en el mismo lote: el jugador aún no existe al leer el
+        // chat, que espera al tick siguiente en vez de perderse
+        let mut client = Connection::connect(addr).unwrap();
+        client.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+        });
+        client.send(&ClientMessage::Chat {
+            text: "hola".to_string(),
+        });
+        client.flush().unwrap();
+        let mut received = Vec::new();
+        serve_until(&mut app, &mut client, &mut received, |received| {
+            received
+                .iter()
+                .any(|m| matches!(m, ServerMessage::Chat { text, .. } if text == "hola"))
+        });
+    }
+}
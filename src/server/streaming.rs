@@ -0,0 +1,245 @@
+//! Carga de chunks del servidor: la de `chunk_loading` pero para VARIOS
+//! jugadores y sin render.
+//!
+//! Cada [`ChunkViewer`] pide los chunks reales a `view_radius` de él; se cargan
+//! los de todos y se descargan los que quedan lejos de todos. No hay LOD (solo
+//! sirven para dibujar lejos) ni meshes: el chunk llega del hilo de fondo con
+//! su `BaseChunk` y su collider, y tras una edición se rehace solo el collider.
+
+use super::ServerConfig;
+use crate::core::{WorldSeed, WORLD_CHUNK_RADIUS};
+use crate::physics::{Collider, RigidBody};
+use crate::voxel::{
+    build_chunk_collider, nearest_horizontal_distance_sq, player_chunks,
+    start_real_chunk_generation, BaseChunk,
+    ChunkGenerationTask, ChunkLoadQueue, ChunkMap, ChunkType, DirtyChunk, SpatialHashGrid,
+    TerrainGenerator, VoxelDiffs, CHUNK_COMPLETION_BUDGET_MS, MAX_CHUNKS_PER_FRAME,
+    MAX_CHUNK_COMPLETIONS_PER_FRAME,
+};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use futures_lite::future;
+use std::collections::{HashSet, VecDeque};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+/// Niveles de chunk en Y: el mismo rango vertical que el cliente
+/// (`update_chunk_load_queue`).
+pub const CHUNK_Y_RANGE: RangeInclusive<i32> = -1..=4;
+
+/// Margen (chunks) sobre `view_radius` antes de descargar: histéresis para que
+/// un jugador en el borde de un chunk no cargue y descargue sin parar.
+pub(super) const UNLOAD_MARGIN: i32 = 2;
+
+/// Presupuesto para rehacer colliders de chunks editados (ms/frame).
+const COLLIDER_REBUILD_BUDGET_MS: u64 = 4;
+
+/// Posición alrededor de la que el servidor mantiene chunks cargados (un
+/// jugador).
+#[derive(Component, Debug, Default)]
+pub struct ChunkViewer;
+
+/// Chunks de los jugadores en la última actualización de la cola: solo se
+/// recalcula cuando alguno cambia de chunk.
+#[derive(Resource, Debug, Default)]
+pub struct ServerStreaming {
+    last_centers: Vec<IVec3>,
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// Chunks reales a `radius` chunks (horizontales) de `center`, en todo
+/// [`CHUNK_Y_RANGE`] y dentro del mapa.
+pub fn chunks_around(center: IVec3, radius: i32) -> impl Iterator<Item = IVec3> {
+    (-radius..=radius)
+        .flat_map(move |dx| (-radius..=radius).map(move |dz| IVec2::new(dx, dz)))
+        .filter(move |offset| offset.length_squared() <= radius * radius)
+        .map(move |offset| IVec2::new(center.x + offset.x, center.z + offset.y))
+        .filter(|column| column.abs().max_element() <= WORLD_CHUNK_RADIUS)
+        .flat_map(|column| CHUNK_Y_RANGE.map(move |y| IVec3::new(column.x, y, column.y)))
+}
+
+/// Componentes de un chunk real en el servidor: su `BaseChunk` y, si tiene
+/// geometría colisionable, un collider fijo.
+pub fn insert_server_chunk(
+    chunk: &mut EntityCommands,
+    base_chunk: BaseChunk,
+    collider: Option<Collider>,
+) {
+    chunk.insert((Transform::default(), base_chunk));
+    if let Some(collider) = collider {
+        chunk.insert((RigidBody::Fixed, collider));
+    }
+}
+
+/// Genera un chunk real en el acto (sin hilo de fondo), con sus diffs y su
+/// collider, y lo registra en `ChunkMap` y `SpatialHashGrid`.
+pub fn generate_server_chunk(
+    commands: &mut Commands,
+    chunk_pos: IVec3,
+    seed: i32,
+    voxel_diffs: &VoxelDiffs,
+    chunk_map: &mut ChunkMap,
+    spatial_hash: &mut SpatialHashGrid,
+) {
+    let mut base_chunk = BaseChunk::new(chunk_pos, seed);
+    if let Some(diffs) = voxel_diffs.chunks.get(&chunk_pos) {
+        base_chunk.apply_diffs(diffs);
+    }
+    let collider = build_chunk_collider(&base_chunk);
+    let mut chunk = commands.spawn_empty();
+    insert_server_chunk(&mut chunk, base_chunk, collider);
+    chunk_map.chunks.insert(chunk_pos, chunk.id());
+    spatial_hash.insert(chunk_pos);
+}
+
+/// Rehace el collider de un chunk editado desde su `BaseChunk` (sin geometría
+/// colisionable, se lo quita) y le quita la marca `DirtyChunk`.
+pub fn rebuild_chunk_collider(chunk: &mut EntityCommands, base_chunk: Option<&BaseChunk>) {
+    match base_chunk.and_then(build_chunk_collider) {
+        Some(collider) => {
+            chunk.insert((RigidBody::Fixed, collider));
+        }
+        None => {
+            chunk.remove::<Collider>();
+        }
+    }
+    chunk.remove::<DirtyChunk>();
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Rehace la cola de carga cuando algún jugador cambia de chunk: carga lo que
+/// pide cualquiera (lo más cercano primero) y descarga lo que no pide nadie.
+pub fn update_viewer_chunk_queue(
+    config: Res<ServerConfig>,
+    viewers: Query<&Transform, With<ChunkViewer>>,
+    chunk_map: Res<ChunkMap>,
+    mut streaming: ResMut<ServerStreaming>,
+    mut load_queue: ResMut<ChunkLoadQueue>,
+) {
+    let centers = player_chunks(viewers.iter());
+    if centers == streaming.last_centers {
+        return;
+    }
+
+    let wanted: HashSet<IVec3> = centers
+        .iter()
+        .flat_map(|&center| chunks_around(center, config.view_radius))
+        .collect();
+    let nearest = |pos: IVec3| nearest_horizontal_distance_sq(pos, &centers);
+    let mut to_load: Vec<IVec3> = wanted
+        .into_iter()
+        .filter(|pos| !chunk_map.chunks.contains_key(pos))
+        .collect();
+    to_load.sort_by_key(|&pos| (nearest(pos), pos.y, pos.x, pos.z));
+    load_queue.to_load = to_load
+        .into_iter()
+        .map(|pos| (pos, ChunkType::Real))
+        .collect::<VecDeque<_>>();
+
+    let keep = config.view_radius + UNLOAD_MARGIN;
+    load_queue.to_unload = chunk_map
+        .chunks
+        .iter()
+        .filter(|&(&pos, _)| nearest(pos) > keep * keep)
+        .map(|(&pos, &entity)| (pos, entity))
+        .collect();
+
+    streaming.last_centers = centers;
+}
+
+/// Lanza la generación async de los chunks en cola (hasta
+/// `MAX_CHUNKS_PER_FRAME` por frame).
+pub fn load_viewer_chunks(
+    mut commands: Commands,
+    mut chunk_map: ResMut<ChunkMap>,
+    mut spatial_hash: ResMut<SpatialHashGrid>,
+    mut load_queue: ResMut<ChunkLoadQueue>,
+    world_seed: Res<WorldSeed>,
+    voxel_diffs: Res<VoxelDiffs>,
+) {
+    let mut terrain_gen = TerrainGenerator::new(world_seed.0);
+    for _ in 0..load_queue.to_load.len().min(MAX_CHUNKS_PER_FRAME) {
+        let Some((chunk_pos, _)) = load_queue.to_load.pop_front() else {
+            break;
+        };
+        if chunk_map.chunks.contains_key(&chunk_pos) {
+            continue;
+        }
+        let chunk_entity = commands.spawn_empty().id();
+        chunk_map.chunks.insert(chunk_pos, chunk_entity);
+        spatial_hash.insert(chunk_pos);
+        start_real_chunk_generation(
+            &mut commands,
+            chunk_entity,
+            chunk_pos,
+            world_seed.0,
+            &voxel_diffs,
+            &mut terrain_gen,
+        );
+    }
+}
+
+/// Integra los chunks cuya tarea terminó: `BaseChunk` + collider, sin mesh.
+pub fn complete_viewer_chunks(
+    mut commands: Commands,
+    mut load_queue: ResMut<ChunkLoadQueue>,
+    mut tasks: Query<(Entity, &mut ChunkGenerationTask)>,
+) {
+    let start = Instant::now();
+    let mut completed = 0;
+    for (entity, mut task) in &mut tasks {
+        if completed >= MAX_CHUNK_COMPLETIONS_PER_FRAME
+            || start.elapsed() >= Duration::from_millis(CHUNK_COMPLETION_BUDGET_MS)
+        {
+            break;
+        }
+        let Some((_, base_chunk, collider)) = future::block_on(future::poll_once(&mut task.task))
+        else {
+            continue;
+        };
+        let mut chunk = commands.entity(entity);
+        insert_server_chunk(&mut chunk, base_chunk, collider);
+        chunk.remove::<ChunkGenerationTask>();
+        load_queue.total_loaded += 1;
+        completed += 1;
+    }
+}
+
+/// Rehace el collider de los chunks editados (`DirtyChunk`), con presupuesto:
+/// el servidor no tiene mesh de render que remallar.
+pub fn rebuild_dirty_colliders(
+    mut commands: Commands,
+    dirty: Query<(Entity, Option<&BaseChunk>), With<DirtyChunk>>,
+) {
+    let start = Instant::now();
+    for (entity, chunk) in &dirty {
+        if start.elapsed() >= Duration::from_millis(COLLIDER_REBUILD_BUDGET_MS) {
+            break; // El resto sigue marcado para el frame siguiente
+        }
+        rebuild_chunk_collider(&mut commands.entity(entity), chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_around_cover_a_disc_of_columns() {
+        let levels = CHUNK_Y_RANGE.count();
+        assert_eq!(chunks_around(IVec3::new(3, 7, -2), 0).count(), levels);
+        // Radio 1: la columna central y sus 4 vecinas (las diagonales quedan fuera)
+        let ring: Vec<IVec3> = chunks_around(IVec3::ZERO, 1).collect();
+        assert_eq!(ring.len(), 5 * levels);
+        assert!(ring.contains(&IVec3::new(1, -1, 0)) && !ring.contains(&IVec3::new(1, 0, 1)));
+        // El borde del mapa corta
+        let edge = IVec3::new(WORLD_CHUNK_RADIUS, 0, 0);
+        assert!(chunks_around(edge, 1).all(|pos| pos.x <= WORLD_CHUNK_RADIUS));
+    }
+}
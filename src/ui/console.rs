@@ -0,0 +1,284 @@
+//! Chat y consola de comandos (overlay)
+//!
+//! - T abre la consola vacía y `/` con la barra ya escrita
+//!   (`GameState::Console`: el juego queda detrás, como con la fabricación).
+//!   Enter envía y cierra; ESC cierra sin enviar.
+//! - Flechas arriba/abajo recorren lo enviado; RePág/AvPág y la rueda del
+//!   ratón desplazan las líneas.
+//! - Con la consola cerrada, las últimas líneas se ven un rato sobre la barra
+//!   de vida y se desvanecen (así se lee el chat de los demás jugando).
+//!
+//! Lo enviado lo ejecuta `console::run_console_commands`: un comando del
+//! `CommandRegistry` o una línea de chat.
+
+use crate::console::{Console, LineKind};
+use crate::core::GameState;
+use crate::net::{NetClient, NetInbox, MAX_CHAT_CHARS};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::mouse::AccumulatedMouseScroll;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+/// Líneas a la vista con la consola abierta.
+const CONSOLE_LINES: usize = 14;
+/// Líneas del chat sobre el HUD.
+const FEED_LINES: usize = 6;
+/// Segundos que se ve una línea en el chat del HUD (el último, desvaneciéndose).
+const FEED_SECS: f64 = 8.0;
+const FEED_FADE_SECS: f64 = 1.0;
+/// Líneas por paso de la rueda del ratón.
+const SCROLL_STEP: i32 = 3;
+
+const CONSOLE_WIDTH: f32 = 620.0;
+const FONT_SIZE: f32 = 16.0;
+const CONSOLE_BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+/// Marcador del overlay de la consola (para limpieza).
+#[derive(Component)]
+pub struct ConsoleUI;
+
+/// Línea a la vista de la consola (0 = la de arriba).
+#[derive(Component)]
+pub struct ConsoleLineText(pub usize);
+
+/// Lo que se está escribiendo.
+#[derive(Component)]
+pub struct ConsoleInputText;
+
+/// Marcador del chat sobre el HUD (para limpieza).
+#[derive(Component)]
+pub struct ChatFeedUI;
+
+/// Línea del chat sobre el HUD (0 = la de arriba).
+#[derive(Component)]
+pub struct ChatFeedLine(pub usize);
+
+/// Color de cada tipo de línea.
+fn line_color(kind: LineKind) -> Color {
+    match kind {
+        LineKind::Chat => Color::WHITE,
+        LineKind::Command => Color::srgb(0.6, 0.6, 0.6),
+        LineKind::Reply => Color::srgb(0.5, 0.9, 1.0),
+        LineKind::Error => Color::srgb(1.0, 0.45, 0.4),
+    }
+}
+
+/// Texto de una línea de la consola.
+fn line_bundle<C: Component>(marker: C) -> impl Bundle {
+    (
+        Text::default(),
+        TextFont {
+            font_size: FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        marker,
+    )
+}
+
+/// Pone en el texto `slot` (de `slots`) su línea de `lines`: pegadas abajo, si
+/// hay menos líneas que textos los de arriba quedan vacíos.
+fn fill_line(
+    lines: &[(&str, Color)],
+    slots: usize,
+    slot: usize,
+    text: &mut Mut<Text>,
+    color: &mut Mut<TextColor>,
+) {
+    let empty_above = slots.saturating_sub(lines.len());
+    let (line, line_color) = slot
+        .checked_sub(empty_above)
+        .and_then(|i| lines.get(i).copied())
+        .unwrap_or(("", Color::NONE));
+    if text.0 != line {
+        text.0 = line.to_string();
+    }
+    color.set_if_neq(TextColor(line_color));
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Abre la consola con T (vacía) o `/` (con la barra escrita).
+pub fn toggle_console(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut console: ResMut<Console>,
+) {
+    if *state.get() != GameState::InGame {
+        return;
+    }
+    let prefix = if keys.just_pressed(KeyCode::KeyT) {
+        ""
+    } else if keys.just_pressed(KeyCode::Slash) {
+        "/"
+    } else {
+        return;
+    };
+    console.input = prefix.to_string();
+    console.scroll = 0;
+    next_state.set(GameState::Console);
+}
+
+/// Escribe en la consola. Corre en cualquier estado para consumir las teclas
+/// aunque esté cerrada: así la T que la abre no se escribe al abrirse.
+pub fn console_text_input(
+    mut keyboard: MessageReader<KeyboardInput>,
+    scroll: Res<AccumulatedMouseScroll>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut console: ResMut<Console>,
+) {
+    if *state.get() != GameState::Console {
+        keyboard.clear();
+        return;
+    }
+    if scroll.delta.y != 0.0 {
+        console.scroll_by(scroll.delta.y.signum() as i32 * SCROLL_STEP);
+    }
+
+    let page = CONSOLE_LINES as i32 / 2;
+    for event in keyboard.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                console.submit();
+                next_state.set(GameState::InGame);
+                break;
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::ArrowUp => console.browse_history(-1),
+            Key::ArrowDown => console.browse_history(1),
+            Key::PageUp => console.scroll_by(page),
+            Key::PageDown => console.scroll_by(-page),
+            Key::Space if console.input.chars().count() < MAX_CHAT_CHARS => {
+                console.input.push(' ')
+            }
+            Key::Character(text) => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if console.input.chars().count() < MAX_CHAT_CHARS {
+                        console.input.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Pasa a la consola el chat que llegó del servidor.
+pub fn receive_chat(
+    time: Res<Time<Real>>,
+    client: Res<NetClient>,
+    mut inbox: ResMut<NetInbox>,
+    mut console: ResMut<Console>,
+) {
+    for (player_id, text) in inbox.take_chat() {
+        let line = match client.player_id == Some(player_id) {
+            true => format!("<Tú> {}", text),
+            false => format!("<Jugador {}> {}", player_id, text),
+        };
+        console.push(LineKind::Chat, &line, time.elapsed_secs_f64());
+    }
+}
+
+/// Construye el overlay al entrar en `Console`: las líneas y, debajo, lo que
+/// se escribe.
+pub fn setup_console(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                width: Val::Px(CONSOLE_WIDTH),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(6.0)),
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            BackgroundColor(CONSOLE_BG),
+            ConsoleUI,
+        ))
+        .with_children(|parent| {
+            for i in 0..CONSOLE_LINES {
+                parent.spawn(line_bundle(ConsoleLineText(i)));
+            }
+            parent.spawn(line_bundle(ConsoleInputText));
+        });
+}
+
+/// Refleja la `Console` en el overlay (scroll incluido).
+pub fn update_console_ui(
+    console: Res<Console>,
+    mut lines: Query<(&ConsoleLineText, &mut Text, &mut TextColor)>,
+    mut input: Query<&mut Text, (With<ConsoleInputText>, Without<ConsoleLineText>)>,
+) {
+    let visible: Vec<_> = console
+        .visible(CONSOLE_LINES)
+        .map(|line| (line.text.as_str(), line_color(line.kind)))
+        .collect();
+    for (slot, mut text, mut color) in &mut lines {
+        fill_line(&visible, CONSOLE_LINES, slot.0, &mut text, &mut color);
+    }
+    for mut text in &mut input {
+        let shown = format!("> {}_", console.input);
+        if text.0 != shown {
+            text.0 = shown;
+        }
+    }
+}
+
+/// Construye el chat sobre el HUD al entrar en juego.
+pub fn setup_chat_feed(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                // Encima de la barra rápida y la de vida
+                bottom: Val::Px(100.0),
+                left: Val::Px(10.0),
+                width: Val::Px(CONSOLE_WIDTH),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            ChatFeedUI,
+        ))
+        .with_children(|parent| {
+            for i in 0..FEED_LINES {
+                parent.spawn(line_bundle(ChatFeedLine(i)));
+            }
+        });
+}
+
+/// Muestra las líneas recientes en el chat del HUD, desvaneciendo las que
+/// caducan.
+pub fn update_chat_feed(
+    time: Res<Time<Real>>,
+    console: Res<Console>,
+    mut lines: Query<(&ChatFeedLine, &mut Text, &mut TextColor)>,
+) {
+    let now = time.elapsed_secs_f64();
+    let mut recent: Vec<_> = console
+        .lines()
+        .iter()
+        .rev()
+        .take(FEED_LINES)
+        .take_while(|line| now - line.time < FEED_SECS)
+        .map(|line| {
+            let alpha = ((FEED_SECS - (now - line.time)) / FEED_FADE_SECS).clamp(0.0, 1.0);
+            (line.text.as_str(), line_color(line.kind).with_alpha(alpha as f32))
+        })
+        .collect();
+    recent.reverse();
+    for (slot, mut text, mut color) in &mut lines {
+        fill_line(&recent, FEED_LINES, slot.0, &mut text, &mut color);
+    }
+}
@@ -0,0 +1,259 @@
+//! Pantalla de fabricación (tecla C durante el juego)
+//!
+//! Una rejilla con todas las recetas de `RecipeBook`: icono, nombre y coste. Las
+//! que no alcanza el inventario (o sin slot libre) se ven apagadas; las que ya
+//! se tienen llevan una marca. Click en una receta = fabricarla. C o ESC vuelven al juego.
+//! Se fabrica con el inventario del `PrimaryPlayer`.
+
+use super::hud::{icon_path, tool_icon};
+use crate::core::{GameState, Inventory};
+use crate::crafting::RecipeBook;
+use crate::net::{ClientMessage, NetClient};
+use crate::player::PrimaryPlayer;
+use bevy::prelude::*;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER: Color = Color::srgb(0.3, 0.3, 0.3);
+const BUTTON_DISABLED: Color = Color::srgb(0.08, 0.08, 0.08);
+const TEXT_DISABLED: Color = Color::srgb(0.45, 0.45, 0.45);
+const ACCENT: Color = Color::srgb(0.0, 1.0, 0.8);
+
+/// Marcador de la pantalla de fabricación (para limpieza).
+#[derive(Component)]
+pub struct CraftingUI;
+
+/// Botón de una receta (índice en `RecipeBook`).
+#[derive(Component)]
+pub struct RecipeButton(pub usize);
+
+/// Textos de un botón de receta, para apagarlos si no alcanza.
+#[derive(Component)]
+pub struct RecipeText(pub usize);
+
+/// Marca "✓" de una receta cuya herramienta (o una mejor) ya se tiene.
+#[derive(Component)]
+pub struct RecipeOwned(pub usize);
+
+/// Abre/cierra la fabricación con C.
+pub fn toggle_crafting(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Crafting),
+        GameState::Crafting => next_state.set(GameState::InGame),
+        _ => {}
+    }
+}
+
+/// Construye la pantalla al entrar en `Crafting`.
+pub fn setup_crafting_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    recipes: Res<RecipeBook>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            // Fondo semitransparente sobre el juego
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            CraftingUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("CRAFTING"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(ACCENT),
+            ));
+
+            parent
+                .spawn(Node {
+                    display: Display::Grid,
+                    grid_template_columns: vec![RepeatedGridTrack::px(4, 220.0)],
+                    row_gap: Val::Px(10.0),
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|grid| {
+                    for (index, recipe) in recipes.0.iter().enumerate() {
+                        spawn_recipe_button(
+                            grid,
+                            &asset_server,
+                            index,
+                            &recipe.label(),
+                            &recipe.cost_label(),
+                            tool_icon(recipe.tool),
+                        );
+                    }
+                });
+
+            parent.spawn((
+                Text::new("C / ESC para volver"),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(TEXT_DISABLED),
+            ));
+        });
+}
+
+/// Un botón de receta: icono a la izquierda, nombre y coste a la derecha.
+fn spawn_recipe_button(
+    grid: &mut ChildSpawnerCommands<'_>,
+    asset_server: &AssetServer,
+    index: usize,
+    label: &str,
+    cost: &str,
+    icon: &str,
+) {
+    grid.spawn((
+        Button,
+        Node {
+            height: Val::Px(72.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            column_gap: Val::Px(8.0),
+            border: UiRect::all(Val::Px(2.0)),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderColor::all(Color::srgb(0.8, 0.8, 0.8)),
+        BackgroundColor(BUTTON_COLOR),
+        RecipeButton(index),
+    ))
+    .with_children(|button| {
+        button.spawn((
+            ImageNode::new(asset_server.load(icon_path(icon))),
+            Node {
+                width: Val::Px(48.0),
+                height: Val::Px(48.0),
+                flex_shrink: 0.0,
+                ..default()
+            },
+        ));
+        button
+            .spawn(Node {
+                flex_direction: FlexDirection::Column,
+                ..default()
+            })
+            .with_children(|texts| {
+                texts.spawn((
+                    Text::new(label),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    RecipeText(index),
+                ));
+                texts.spawn((
+                    Text::new(cost),
+                    TextFont {
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    RecipeText(index),
+                ));
+            });
+        button.spawn((
+            Text::new("✓"),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(ACCENT),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(2.0),
+                right: Val::Px(6.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            RecipeOwned(index),
+        ));
+    });
+}
+
+/// Fabrica la receta pulsada si alcanza el inventario. En red se le pide al
+/// servidor, dueño del inventario.
+pub fn crafting_button_system(
+    interaction_query: Query<(&Interaction, &RecipeButton), Changed<Interaction>>,
+    recipes: Res<RecipeBook>,
+    mut player: Query<&mut Inventory, With<PrimaryPlayer>>,
+    mut net: Option<ResMut<NetClient>>,
+) {
+    let Ok(mut inventory) = player.single_mut() else {
+        return;
+    };
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let recipe = &recipes.0[button.0];
+        if let Some(net) = net.as_mut() {
+            net.request(ClientMessage::Craft {
+                recipe: button.0 as u16,
+            });
+        } else if recipe.craft(&mut inventory) {
+            info!("Fabricado: {}", recipe.label());
+        }
+    }
+}
+
+/// Colorea cada receta según se pueda pagar (y el hover), y marca las que ya se
+/// tienen.
+pub fn update_recipe_buttons(
+    recipes: Res<RecipeBook>,
+    player: Query<&Inventory, With<PrimaryPlayer>>,
+    mut buttons: Query<(&Interaction, &RecipeButton, &mut BackgroundColor)>,
+    mut texts: Query<(&RecipeText, &mut TextColor)>,
+    mut owned: Query<(&RecipeOwned, &mut Visibility)>,
+) {
+    let Ok(inventory) = player.single() else {
+        return;
+    };
+    let affordable: Vec<bool> = recipes.0.iter().map(|r| r.can_craft(inventory)).collect();
+
+    for (interaction, button, mut color) in &mut buttons {
+        color.0 = match (affordable[button.0], interaction) {
+            (false, _) => BUTTON_DISABLED,
+            (true, Interaction::None) => BUTTON_COLOR,
+            (true, _) => BUTTON_HOVER,
+        };
+    }
+    for (text, mut color) in &mut texts {
+        color.0 = if affordable[text.0] {
+            Color::WHITE
+        } else {
+            TEXT_DISABLED
+        };
+    }
+    for (mark, mut visibility) in &mut owned {
+        let recipe = &recipes.0[mark.0];
+        let has = inventory
+            .best_tool(recipe.tool)
+            .is_some_and(|tier| tier >= recipe.tier);
+        visibility.set_if_neq(if has {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
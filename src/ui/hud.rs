@@ -1,75 +1,167 @@
 //! HUD del juego
 //!
-//! - Barra de herramientas fija abajo a la izquierda (4 slots: pico, hacha,
-//!   pala, azada). El slot equipado se resalta.
-//! - Inventario en rejilla que aparece mientras se mantiene `Tab`: un slot por
-//!   tipo de voxel recolectado, con su icono y la cantidad.
+//! - Barra rápida fija abajo a la izquierda: los primeros 9 slots del
+//!   `Inventory` (teclas 1–9 o rueda del ratón). El slot en la mano se resalta.
+//!   Cada slot muestra el icono del item, la cantidad y, en las herramientas,
+//!   una barra de durabilidad.
+//! - Barra de vida encima de la barra rápida.
+//! - Inventario completo con `Tab` (`GameState::Inventory`): la mochila y la
+//!   barra rápida en rejilla; los items se arrastran con el ratón entre slots
+//!   (mismo material = se juntan, si no = se intercambian).
 //!
-//! Todo visible solo en `GameState::InGame` (se limpia al salir a pausa/menú).
-//! Los iconos viven en `assets/icons/<nombre>.png`; los tipos sin icono propio
-//! usan `default.png`.
+//! Todo refleja al `PrimaryPlayer` (el jugador 0; en pantalla dividida la UI
+//! se dibuja en su vista). La barra rápida y la de vida solo existen en
+//! `GameState::InGame` (se limpian
+//! al salir a pausa/menú/inventario). Los iconos viven en
+//! `assets/icons/<nombre>.png`; los tipos sin icono propio usan `default.png`.
 
-use crate::core::Inventory;
-use crate::player::components::Player;
-use crate::voxel::{Tool, ToolType, VoxelType, VOXEL_TYPE_COUNT};
+use crate::core::{GameState, Inventory, ItemId, HOTBAR_SLOTS, INVENTORY_SLOTS};
+use crate::net::{ClientMessage, NetClient};
+use crate::player::{Health, PrimaryPlayer};
+use crate::voxel::{ToolTier, ToolType};
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 
-/// Herramientas mostradas en la barra, en el orden de las teclas 1–4.
-const TOOLBAR: [ToolType; 4] = [
-    ToolType::Pickaxe,
-    ToolType::Axe,
-    ToolType::Shovel,
-    ToolType::Hoe,
-];
-
-const SLOT_PX: f32 = 64.0;
+const SLOT_PX: f32 = 56.0;
+const SLOT_GAP: f32 = 6.0;
 const HIGHLIGHT: Color = Color::srgb(1.0, 0.9, 0.2);
 const SLOT_BORDER: Color = Color::srgb(0.4, 0.4, 0.4);
+const SLOT_BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.5);
+const SLOT_HOVER: Color = Color::srgba(0.3, 0.3, 0.3, 0.8);
+
+/// Marcador del contenedor de la barra rápida.
+#[derive(Component)]
+pub struct HotbarUI;
+
+/// Slot de la barra rápida (índice en el `Inventory`); se resalta el de la mano.
+#[derive(Component)]
+pub struct HotbarSlot(pub usize);
+
+/// Icono del item de un slot del inventario.
+#[derive(Component)]
+pub struct SlotIcon(pub usize);
+
+/// Cantidad del item de un slot (vacía si es 1 o no hay item).
+#[derive(Component)]
+pub struct SlotCount(pub usize);
+
+/// Barra de durabilidad de la herramienta de un slot.
+#[derive(Component)]
+pub struct SlotDurability(pub usize);
 
-/// Marcador del contenedor de la barra de herramientas.
+/// Marcador del contenedor de la barra de vida.
 #[derive(Component)]
-pub struct ToolbarUI;
+pub struct HealthBarUI;
 
-/// Slot de la barra; guarda qué herramienta representa para resaltarlo.
+/// Relleno de la barra de vida (su ancho es la vida restante).
 #[derive(Component)]
-pub struct ToolSlot(pub ToolType);
+pub struct HealthBarFill;
+
+const HEALTH_BAR_WIDTH: f32 =
+    HOTBAR_SLOTS as f32 * SLOT_PX + (HOTBAR_SLOTS - 1) as f32 * SLOT_GAP;
+const HEALTH_COLOR: Color = Color::srgb(0.85, 0.15, 0.15);
 
-/// Marcador del contenedor del inventario (rejilla temporal con `Tab`).
+/// Marcador de la pantalla de inventario (para limpieza).
 #[derive(Component)]
 pub struct InventoryUI;
 
+/// Slot arrastrable del panel de inventario (índice en el `Inventory`).
+#[derive(Component)]
+pub struct InventorySlot(pub usize);
+
+/// Icono que sigue al cursor mientras se arrastra un item.
+#[derive(Component)]
+pub struct DragGhost;
+
 /// Ruta del icono en `assets/`. `stem` vacío / desconocido cae en `default`.
-fn icon_path(stem: &str) -> String {
+pub(crate) fn icon_path(stem: &str) -> String {
     format!("icons/{}.png", stem)
 }
 
 /// Icono de cada herramienta.
-fn tool_icon(tool: ToolType) -> &'static str {
+pub(crate) fn tool_icon(tool: ToolType) -> &'static str {
     match tool {
         ToolType::Pickaxe => "pickaxe",
         ToolType::Axe => "axe",
         ToolType::Shovel => "shovel",
         ToolType::Hoe => "hoe",
+        ToolType::Shears => "shears",
         ToolType::None => "default",
     }
 }
 
-/// Icono de cada tipo de voxel; los que no tienen icono propio usan `default`.
-fn voxel_icon(voxel: VoxelType) -> &'static str {
-    match voxel {
-        VoxelType::Dirt => "dirt",
-        VoxelType::Grass => "grass",
-        VoxelType::Stone => "stone",
-        VoxelType::Metal => "metal",
-        VoxelType::Sand => "sand",
-        VoxelType::Wood | VoxelType::PineWood => "wood",
-        VoxelType::Leaves | VoxelType::PineNeedles | VoxelType::SmallLeaves => "leaves",
-        _ => "default",
+/// Icono de un item: el de su material (archivo de materiales) o su herramienta.
+fn item_icon(item: ItemId) -> &'static str {
+    match item {
+        ItemId::Voxel(voxel) => &voxel.properties().icon,
+        ItemId::Tool(tool, _) => tool_icon(tool),
     }
 }
 
-/// Crea la barra de herramientas al entrar a `InGame`.
-pub fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Tinte del icono: las herramientas comparten icono, el tier lo colorea.
+fn item_tint(item: ItemId) -> Color {
+    match item {
+        ItemId::Tool(_, ToolTier::Wood) => Color::srgb(0.85, 0.65, 0.45),
+        ItemId::Tool(_, ToolTier::Stone) => Color::srgb(0.7, 0.7, 0.75),
+        _ => Color::WHITE,
+    }
+}
+
+/// Contenido de un slot (icono, cantidad y durabilidad); lo rellena
+/// `update_item_slots` a partir del `Inventory`.
+fn spawn_slot_contents(slot: &mut ChildSpawnerCommands<'_>, index: usize) {
+    slot.spawn((
+        ImageNode::default(),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        SlotIcon(index),
+    ));
+    slot.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(2.0),
+            right: Val::Px(4.0),
+            ..default()
+        },
+        SlotCount(index),
+    ));
+    slot.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Px(5.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.1, 0.9, 0.1)),
+        Visibility::Hidden,
+        SlotDurability(index),
+    ));
+}
+
+/// Nodo de un slot del tamaño estándar, con borde.
+fn slot_node() -> Node {
+    Node {
+        width: Val::Px(SLOT_PX),
+        height: Val::Px(SLOT_PX),
+        border: UiRect::all(Val::Px(3.0)),
+        ..default()
+    }
+}
+
+/// Crea la barra rápida al entrar a `InGame`.
+pub fn setup_hotbar(mut commands: Commands) {
     commands
         .spawn((
             Node {
@@ -77,76 +169,163 @@ pub fn setup_toolbar(mut commands: Commands, asset_server: Res<AssetServer>) {
                 bottom: Val::Px(10.0),
                 left: Val::Px(10.0),
                 flex_direction: FlexDirection::Row,
-                column_gap: Val::Px(6.0),
+                column_gap: Val::Px(SLOT_GAP),
                 ..default()
             },
-            ToolbarUI,
+            HotbarUI,
         ))
         .with_children(|parent| {
-            for tool in TOOLBAR {
+            for index in 0..HOTBAR_SLOTS {
                 parent
                     .spawn((
-                        Node {
-                            width: Val::Px(SLOT_PX),
-                            height: Val::Px(SLOT_PX),
-                            border: UiRect::all(Val::Px(3.0)),
-                            ..default()
-                        },
+                        slot_node(),
                         BorderColor::all(SLOT_BORDER),
-                        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
-                        ToolSlot(tool),
+                        BackgroundColor(SLOT_BG),
+                        HotbarSlot(index),
                     ))
-                    .with_children(|slot| {
-                        slot.spawn((
-                            ImageNode::new(asset_server.load(icon_path(tool_icon(tool)))),
-                            Node {
-                                width: Val::Percent(100.0),
-                                height: Val::Percent(100.0),
-                                ..default()
-                            },
-                        ));
-                    });
+                    .with_children(|slot| spawn_slot_contents(slot, index));
             }
         });
 }
 
-/// Resalta el slot de la herramienta equipada.
-pub fn update_toolbar_highlight(
-    tool_query: Query<&Tool, With<Player>>,
-    mut slots: Query<(&ToolSlot, &mut BorderColor)>,
+/// Refleja el `Inventory` en todos los slots visibles (barra rápida y panel).
+pub fn update_item_slots(
+    asset_server: Res<AssetServer>,
+    player: Query<&Inventory, With<PrimaryPlayer>>,
+    mut icons: Query<(&SlotIcon, &mut ImageNode, &mut Visibility), Without<SlotDurability>>,
+    mut counts: Query<(&SlotCount, &mut Text)>,
+    mut bars: Query<(&SlotDurability, &mut Node, &mut BackgroundColor, &mut Visibility)>,
 ) {
-    let Ok(tool) = tool_query.single() else {
+    let Ok(inventory) = player.single() else {
+        return;
+    };
+    for (icon, mut image, mut visibility) in &mut icons {
+        let Some(stack) = inventory.slots[icon.0] else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        // `load` devuelve el handle cacheado: solo se reasigna si cambia
+        let handle = asset_server.load(icon_path(item_icon(stack.item)));
+        if image.image != handle {
+            image.image = handle;
+        }
+        let tint = item_tint(stack.item);
+        if image.color != tint {
+            image.color = tint;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+
+    for (count, mut text) in &mut counts {
+        let label = match inventory.slots[count.0] {
+            Some(stack) if stack.count > 1 => stack.count.to_string(),
+            _ => String::new(),
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+
+    for (bar, mut node, mut color, mut visibility) in &mut bars {
+        let Some(tool) = inventory.slots[bar.0].and_then(|stack| stack.as_tool()) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        // Verde con la herramienta nueva → rojo a punto de romperse
+        let fraction = tool.get_durability_percentage();
+        visibility.set_if_neq(Visibility::Inherited);
+        node.width = Val::Percent(fraction * 100.0);
+        color.0 = Color::srgb(1.0 - fraction, fraction, 0.1);
+    }
+}
+
+/// Resalta el slot de la barra rápida que está en la mano.
+pub fn update_hotbar_highlight(
+    player: Query<&Inventory, With<PrimaryPlayer>>,
+    mut slots: Query<(&HotbarSlot, &mut BorderColor)>,
+) {
+    let Ok(inventory) = player.single() else {
         return;
     };
     for (slot, mut border) in &mut slots {
-        *border = if slot.0 == tool.tool_type {
-            BorderColor::all(HIGHLIGHT)
+        let color = if slot.0 == inventory.selected {
+            HIGHLIGHT
         } else {
-            BorderColor::all(SLOT_BORDER)
+            SLOT_BORDER
         };
+        border.set_if_neq(BorderColor::all(color));
+    }
+}
+
+/// Crea la barra de vida (encima de la barra rápida) al entrar a `InGame`.
+pub fn setup_health_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(SLOT_PX + 20.0),
+                left: Val::Px(10.0),
+                width: Val::Px(HEALTH_BAR_WIDTH),
+                height: Val::Px(10.0),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor::all(SLOT_BORDER),
+            BackgroundColor(SLOT_BG),
+            HealthBarUI,
+        ))
+        .with_child((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(HEALTH_COLOR),
+            HealthBarFill,
+        ));
+}
+
+/// Ajusta el relleno de la barra a la vida del jugador.
+pub fn update_health_bar(
+    health_query: Query<&Health, With<PrimaryPlayer>>,
+    mut fill: Query<&mut Node, With<HealthBarFill>>,
+) {
+    let Ok(health) = health_query.single() else {
+        return;
+    };
+    for mut node in &mut fill {
+        node.width = Val::Percent(health.fraction() * 100.0);
     }
 }
 
-/// Muestra/oculta el inventario según se mantenga `Tab`.
+/// Abre/cierra el inventario con `Tab`.
 pub fn toggle_inventory(
-    mut commands: Commands,
     keys: Res<ButtonInput<KeyCode>>,
-    asset_server: Res<AssetServer>,
-    inventory: Res<Inventory>,
-    open: Query<Entity, With<InventoryUI>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if keys.just_pressed(KeyCode::Tab) {
-        spawn_inventory(&mut commands, &asset_server, &inventory);
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
     }
-    if keys.just_released(KeyCode::Tab) {
-        for entity in &open {
-            commands.entity(entity).despawn();
-        }
+
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Inventory),
+        GameState::Inventory => next_state.set(GameState::InGame),
+        _ => {}
     }
 }
 
-/// Construye la rejilla del inventario (un slot por tipo con cantidad > 0).
-fn spawn_inventory(commands: &mut Commands, asset_server: &AssetServer, inventory: &Inventory) {
+/// Construye el panel del inventario al entrar en `GameState::Inventory`: la
+/// mochila arriba y la barra rápida abajo, separadas.
+pub fn spawn_inventory(mut commands: Commands) {
+    let grid = || Node {
+        display: Display::Grid,
+        grid_template_columns: vec![RepeatedGridTrack::px(HOTBAR_SLOTS as u16, SLOT_PX)],
+        row_gap: Val::Px(SLOT_GAP),
+        column_gap: Val::Px(SLOT_GAP),
+        ..default()
+    };
+
     commands
         .spawn((
             // Capa a pantalla completa que centra el panel.
@@ -158,83 +337,151 @@ fn spawn_inventory(commands: &mut Commands, asset_server: &AssetServer, inventor
                 align_items: AlignItems::Center,
                 ..default()
             },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.4)),
             InventoryUI,
         ))
         .with_children(|parent| {
             parent
                 .spawn((
                     Node {
-                        display: Display::Grid,
-                        grid_template_columns: vec![RepeatedGridTrack::px(6, SLOT_PX)],
-                        row_gap: Val::Px(6.0),
-                        column_gap: Val::Px(6.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(16.0),
                         padding: UiRect::all(Val::Px(12.0)),
                         ..default()
                     },
                     BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
                 ))
-                .with_children(|grid| {
-                    for id in 0..VOXEL_TYPE_COUNT {
-                        let count = inventory.0[id];
-                        if count == 0 {
-                            continue;
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new("INVENTORY"),
+                        TextFont {
+                            font_size: 28.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.0, 1.0, 0.8)),
+                    ));
+                    panel.spawn(grid()).with_children(|backpack| {
+                        for index in HOTBAR_SLOTS..INVENTORY_SLOTS {
+                            spawn_inventory_slot(backpack, index);
+                        }
+                    });
+                    panel.spawn(grid()).with_children(|hotbar| {
+                        for index in 0..HOTBAR_SLOTS {
+                            spawn_inventory_slot(hotbar, index);
                         }
-                        spawn_inventory_slot(
-                            grid,
-                            asset_server,
-                            VoxelType::from_u8(id as u8),
-                            count,
-                        );
-                    }
+                    });
                 });
+
+            // Último hijo: se dibuja encima del panel
+            parent.spawn((
+                ImageNode::default(),
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(SLOT_PX),
+                    height: Val::Px(SLOT_PX),
+                    ..default()
+                },
+                Visibility::Hidden,
+                DragGhost,
+            ));
         });
 }
 
-/// Un slot: icono del voxel con la cantidad en la esquina inferior derecha.
-fn spawn_inventory_slot(
-    grid: &mut ChildSpawnerCommands<'_>,
-    asset_server: &AssetServer,
-    voxel: VoxelType,
-    count: u32,
+/// Un slot del panel: botón (para arrastrar) con el contenido del slot.
+fn spawn_inventory_slot(grid: &mut ChildSpawnerCommands<'_>, index: usize) {
+    let mut slot = grid.spawn((
+        Button,
+        slot_node(),
+        BorderColor::all(SLOT_BORDER),
+        BackgroundColor(SLOT_BG),
+        InventorySlot(index),
+    ));
+    // Los slots de la barra rápida también marcan el que está en la mano
+    if index < HOTBAR_SLOTS {
+        slot.insert(HotbarSlot(index));
+    }
+    slot.with_children(|slot| spawn_slot_contents(slot, index));
+}
+
+/// Arrastrar y soltar items entre slots del panel.
+///
+/// Se coge el stack del slot donde se pulsa el botón izquierdo y se suelta en
+/// el slot sobre el que se levanta (`Inventory::move_stack`; en red lo mueve el
+/// servidor). Mientras tanto su icono sigue al cursor.
+#[allow(clippy::too_many_arguments)]
+pub fn inventory_drag_system(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+    mut player: Query<&mut Inventory, With<PrimaryPlayer>>,
+    mut slots: Query<(&Interaction, &InventorySlot, &mut BackgroundColor)>,
+    mut ghost: Query<(&mut Node, &mut ImageNode, &mut Visibility), With<DragGhost>>,
+    mut dragging: Local<Option<usize>>,
+    mut net: Option<ResMut<NetClient>>,
 ) {
-    grid.spawn((
-        Node {
-            width: Val::Px(SLOT_PX),
-            height: Val::Px(SLOT_PX),
-            ..default()
-        },
-        BackgroundColor(Color::srgba(0.15, 0.15, 0.15, 1.0)),
-    ))
-    .with_children(|slot| {
-        slot.spawn((
-            ImageNode::new(asset_server.load(icon_path(voxel_icon(voxel)))),
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                ..default()
-            },
-        ));
-        slot.spawn((
-            Text::new(count.to_string()),
-            TextFont {
-                font_size: 16.0,
-                ..default()
-            },
-            TextColor(Color::WHITE),
-            Node {
-                position_type: PositionType::Absolute,
-                bottom: Val::Px(2.0),
-                right: Val::Px(4.0),
-                ..default()
-            },
-        ));
-    });
+    let Ok(mut inventory) = player.single_mut() else {
+        return;
+    };
+    if mouse.just_pressed(MouseButton::Left) {
+        *dragging = slots
+            .iter()
+            .find(|(interaction, ..)| **interaction == Interaction::Pressed)
+            .map(|(_, slot, _)| slot.0)
+            .filter(|&index| inventory.slots[index].is_some());
+    }
+    if mouse.just_released(MouseButton::Left)
+        && let Some(from) = dragging.take()
+    {
+        // Al soltar, el slot de destino es el que queda bajo el cursor
+        let target = slots
+            .iter()
+            .find(|(interaction, slot, _)| **interaction != Interaction::None && slot.0 != from)
+            .map(|(_, slot, _)| slot.0);
+        match (target, net.as_mut()) {
+            (Some(to), Some(net)) => net.request(ClientMessage::MoveStack {
+                from: from as u8,
+                to: to as u8,
+            }),
+            (Some(to), None) => inventory.move_stack(from, to),
+            (None, _) => {}
+        }
+    }
+
+    for (interaction, _, mut color) in &mut slots {
+        let background = if *interaction == Interaction::None {
+            SLOT_BG
+        } else {
+            SLOT_HOVER
+        };
+        color.set_if_neq(BackgroundColor(background));
+    }
+
+    let Ok((mut node, mut image, mut visibility)) = ghost.single_mut() else {
+        return;
+    };
+    let stack = dragging.and_then(|index| inventory.slots[index]);
+    let cursor = windows.single().ok().and_then(|window| window.cursor_position());
+    let (Some(stack), Some(cursor)) = (stack, cursor) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    let handle = asset_server.load(icon_path(item_icon(stack.item)));
+    if image.image != handle {
+        image.image = handle;
+    }
+    image.color = item_tint(stack.item).with_alpha(0.8);
+    node.left = Val::Px(cursor.x - SLOT_PX * 0.5);
+    node.top = Val::Px(cursor.y - SLOT_PX * 0.5);
+    visibility.set_if_neq(Visibility::Inherited);
 }
 
-/// Elimina barra e inventario al salir de `InGame`.
+/// Raíces del HUD (cada una arrastra a sus hijos al despawnearse).
+type HudRoot = Or<(With<HotbarUI>, With<HealthBarUI>)>;
+
+/// Elimina las barras al salir de `InGame`.
 pub fn cleanup_hud(
     mut commands: Commands,
-    hud_query: Query<Entity, Or<(With<ToolbarUI>, With<InventoryUI>)>>,
+    hud_query: Query<Entity, HudRoot>,
 ) {
     for entity in &hud_query {
         commands.entity(entity).despawn();
@@ -1,6 +1,6 @@
 //! Sistema de menu pricipal
 
-use crate::core::GameState;
+use crate::core::MenuScreen;
 use bevy::prelude::*;
 
 /// Componente marcador para entidades del menu pricipal
@@ -10,6 +10,11 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct MainMenuUI;
 
+/// Marcador de la pantalla de titulo (Play/Settings); se limpia al cambiar
+/// de pantalla dentro del menu
+#[derive(Component)]
+pub struct TitleScreenUI;
+
 /// Actions que pueden realizar los botones del menu
 // Credits/Quit aún no tienen botón que los emita; reservados para el menú completo.
 #[allow(dead_code)]
@@ -23,14 +28,18 @@ pub enum MenuAction {
 
 /// Sistema que se ejecuta al ENTRAR al estado MainMenu
 ///
+/// Cámara para renderizar la UI del menú (el jugador aún no existe, así que
+/// no hay Camera3d todavía). Se elimina al salir del menú via MainMenuUI.
+pub fn spawn_menu_camera(mut commands: Commands) {
+    commands.spawn((Camera2d, MainMenuUI));
+}
+
+/// Sistema que se ejecuta al ENTRAR a la pantalla de titulo
+///
 /// Crea todo los botones y elementos visuales del menu
 pub fn setup_main_menu(mut commands: Commands) {
     info!("Creando menú principal...");
 
-    // Cámara para renderizar la UI del menú (el jugador aún no existe, así que
-    // no hay Camera3d todavía). Se elimina al salir del menú via MainMenuUI.
-    commands.spawn((Camera2d, MainMenuUI));
-
     // Contenedor principal (toda la pantalla)
     commands
         .spawn((
@@ -42,7 +51,7 @@ pub fn setup_main_menu(mut commands: Commands) {
                 ..default()
             },
             BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
-            MainMenuUI,
+            TitleScreenUI,
         ))
         .with_children(|parent| {
             parent
@@ -128,15 +137,20 @@ fn create_menu_button(parent: &mut ChildSpawnerCommands<'_>, text: &str, action:
         });
 }
 
+/// Botones recién pulsados/sobrevolados con su color y su acción `A`
+type ButtonChanged<'w, 's, A> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static mut BackgroundColor, &'static A),
+    (Changed<Interaction>, With<Button>),
+>;
+
 /// Sistema que detecta interacciones con los btones del menu
 ///
 /// Cambia colores cuando el mouse esta encima y ejecuta acciones al hacer ckick
 pub fn menu_button_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &MenuAction),
-        (Changed<Interaction>, With<Button>),
-    >,
-    mut next_state: ResMut<NextState<GameState>>,
+    mut interaction_query: ButtonChanged<MenuAction>,
+    mut next_screen: ResMut<NextState<MenuScreen>>,
 ) {
     for (interaction, mut color, menu_action) in &mut interaction_query {
         match *interaction {
@@ -146,8 +160,8 @@ pub fn menu_button_system(
 
                 match menu_action {
                     MenuAction::Play => {
-                        info!("Iniciando juego ... ");
-                        next_state.set(GameState::InGame);
+                        // La partida arranca al elegir (o crear) un mundo
+                        next_screen.set(MenuScreen::WorldSelect);
                     }
                     MenuAction::Settings => {
                         info!("Abriendo configuracion .. (no implementado aun)");
@@ -2,13 +2,17 @@
 //!
 //! Maneja todos los menus, botones y elementos visiales de la ui
 
+pub mod console;
+pub mod crafting;
 pub mod hud;
 pub mod menu;
 pub mod pause;
+pub mod world_select;
 
 pub use menu::*;
 
 use crate::core::GameState::*;
+use crate::core::MenuScreen;
 use bevy::prelude::*;
 
 // Plugin que maneja toda la UI del jugador
@@ -19,26 +23,118 @@ impl Plugin for UIPlugin {
         app
             // REgistrar el sustema de estados
             .init_state::<crate::core::GameState>()
+            .add_sub_state::<MenuScreen>()
+            .init_resource::<world_select::WorldList>()
+            .init_resource::<world_select::NewWorldForm>()
+            .init_resource::<crate::console::Console>()
+            .init_resource::<crate::console::CommandRegistry>()
             // Sistema que corren al entrar al menu principal
-            .add_systems(OnEnter(MainMenu), setup_main_menu)
+            .add_systems(OnEnter(MainMenu), spawn_menu_camera)
+            .add_systems(OnEnter(MenuScreen::Title), setup_main_menu)
+            .add_systems(
+                OnExit(MenuScreen::Title),
+                world_select::despawn_screen::<TitleScreenUI>,
+            )
             // Sistema que corren MIENTRAS estamos en el menu
-            .add_systems(Update, menu_button_system.run_if(in_state(MainMenu)))
+            .add_systems(Update, menu_button_system.run_if(in_state(MenuScreen::Title)))
             // Sistemas que corren AL SALIR del menu
             .add_systems(OnExit(MainMenu), cleanup_main_menu)
+            // ----- Selección / creación de mundos -----
+            .add_systems(OnEnter(MenuScreen::WorldSelect), world_select::setup_world_select)
+            .add_systems(
+                OnExit(MenuScreen::WorldSelect),
+                world_select::despawn_screen::<world_select::WorldSelectUI>,
+            )
+            .add_systems(OnEnter(MenuScreen::NewWorld), world_select::setup_new_world)
+            .add_systems(
+                OnExit(MenuScreen::NewWorld),
+                world_select::despawn_screen::<world_select::NewWorldUI>,
+            )
+            .add_systems(Update, world_select::world_menu_hover_system.run_if(in_state(MainMenu)))
+            .add_systems(
+                Update,
+                (
+                    world_select::world_select_button_system,
+                    world_select::world_list_scroll_system,
+                )
+                    .run_if(in_state(MenuScreen::WorldSelect)),
+            )
+            .add_systems(
+                Update,
+                (
+                    world_select::new_world_text_input,
+                    world_select::new_world_button_system,
+                    world_select::update_new_world_form,
+                )
+                    .chain()
+                    .run_if(in_state(MenuScreen::NewWorld)),
+            )
             // ----- Menú de pausa -----
             // ESC alterna pausa (corre en cualquier estado, ignora MainMenu)
             .add_systems(Update, pause::toggle_pause)
             .add_systems(OnEnter(Paused), pause::setup_pause_menu)
             .add_systems(OnExit(Paused), pause::cleanup_pause_menu)
             .add_systems(Update, pause::pause_button_system.run_if(in_state(Paused)))
-            // ----- HUD (barra de herramientas + inventario) -----
+            // ----- Fabricación -----
+            // C alterna la pantalla (como ESC con la pausa); el juego queda detrás
+            .add_systems(Update, crafting::toggle_crafting)
+            .add_systems(OnEnter(Crafting), crafting::setup_crafting_screen)
+            .add_systems(OnExit(Crafting), world_select::despawn_screen::<crafting::CraftingUI>)
+            .add_systems(
+                Update,
+                (crafting::crafting_button_system, crafting::update_recipe_buttons)
+                    .chain()
+                    .run_if(in_state(Crafting)),
+            )
+            // ----- Chat y consola (T o /) -----
+            // La entrada corre siempre (consume las teclas con la consola
+            // cerrada); lo enviado se ejecuta justo después
+            .add_systems(
+                Update,
+                (
+                    console::toggle_console,
+                    console::console_text_input,
+                    crate::console::run_console_commands,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                console::receive_chat.run_if(resource_exists::<crate::net::NetClient>),
+            )
+            .add_systems(OnEnter(Console), console::setup_console)
+            .add_systems(OnExit(Console), world_select::despawn_screen::<console::ConsoleUI>)
+            .add_systems(
+                Update,
+                console::update_console_ui
+                    .after(crate::console::run_console_commands)
+                    .run_if(in_state(Console)),
+            )
+            .add_systems(OnEnter(InGame), console::setup_chat_feed)
+            .add_systems(OnExit(InGame), world_select::despawn_screen::<console::ChatFeedUI>)
+            .add_systems(
+                Update,
+                console::update_chat_feed
+                    .after(crate::console::run_console_commands)
+                    .run_if(in_state(InGame)),
+            )
+            // ----- HUD (barra rápida + vida) -----
             // Visible solo en juego: se crea al entrar y se elimina al salir
-            // (salir a pausa/menú también dispara OnExit(InGame)).
-            .add_systems(OnEnter(InGame), hud::setup_toolbar)
+            // (salir a pausa/menú/inventario también dispara OnExit(InGame)).
+            .add_systems(OnEnter(InGame), (hud::setup_hotbar, hud::setup_health_bar))
             .add_systems(OnExit(InGame), hud::cleanup_hud)
+            .add_systems(Update, hud::update_health_bar.run_if(in_state(InGame)))
+            // ----- Inventario (Tab) -----
+            .add_systems(Update, hud::toggle_inventory)
+            .add_systems(OnEnter(Inventory), hud::spawn_inventory)
+            .add_systems(OnExit(Inventory), world_select::despawn_screen::<hud::InventoryUI>)
+            .add_systems(Update, hud::inventory_drag_system.run_if(in_state(Inventory)))
+            // Slots de la barra rápida y del panel: reflejan el `Inventory`
             .add_systems(
                 Update,
-                (hud::update_toolbar_highlight, hud::toggle_inventory).run_if(in_state(InGame)),
+                (hud::update_item_slots, hud::update_hotbar_highlight)
+                    .after(hud::inventory_drag_system)
+                    .run_if(in_state(InGame).or(in_state(Inventory))),
             );
     }
 }
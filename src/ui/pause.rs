@@ -33,6 +33,10 @@ pub fn toggle_pause(
     match state.get() {
         GameState::InGame => next_state.set(GameState::Paused),
         GameState::Paused => next_state.set(GameState::InGame),
+        // ESC cierra la fabricación / el inventario / la consola en lugar de pausar
+        GameState::Crafting | GameState::Inventory | GameState::Console => {
+            next_state.set(GameState::InGame)
+        }
         GameState::MainMenu => {}
     }
 }
@@ -107,12 +111,17 @@ fn create_pause_button(parent: &mut ChildSpawnerCommands<'_>, text: &str, action
         });
 }
 
+/// Botones recién pulsados/sobrevolados con su color y su acción `A`
+type ButtonChanged<'w, 's, A> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static mut BackgroundColor, &'static A),
+    (Changed<Interaction>, With<Button>),
+>;
+
 /// Maneja clicks y hover de los botones del menú de pausa.
 pub fn pause_button_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &PauseAction),
-        (Changed<Interaction>, With<Button>),
-    >,
+    mut interaction_query: ButtonChanged<PauseAction>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     for (interaction, mut color, action) in &mut interaction_query {
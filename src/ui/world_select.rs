@@ -0,0 +1,621 @@
+//! Pantallas de selección y creación de mundos (dentro del menú principal)
+//!
+//! - `WorldSelect`: lista de mundos guardados con Play / Copy / Delete
+//! - `NewWorld`: formulario con nombre y seed (escrita o aleatoria)
+//!
+//! Elegir un mundo fija `WorldSeed` y `VoxelDiffs` y SOLO ENTONCES pide el paso
+//! a `InGame`, así `setup` genera el terreno con la seed correcta.
+
+use crate::core::{GameState, MenuScreen, WorldSeed};
+use crate::voxel::{
+    SavedWorld, VoxelDiffs, create_world, delete_world, duplicate_world, list_worlds, now_secs,
+    open_world,
+};
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+        mouse::{MouseScrollUnit, MouseWheel},
+    },
+    prelude::*,
+};
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER: Color = Color::srgb(0.3, 0.3, 0.3);
+const ACCENT: Color = Color::srgb(0.0, 1.0, 0.8);
+
+/// Largo máximo del nombre de un mundo
+const MAX_NAME_LEN: usize = 32;
+
+/// Botones recién pulsados/sobrevolados con su acción `A`
+type ButtonChanged<'w, 's, A> =
+    Query<'w, 's, (&'static Interaction, &'static A), (Changed<Interaction>, With<Button>)>;
+
+/// Botones de estas pantallas (para el hover común)
+type WorldMenuButton = (
+    Changed<Interaction>,
+    With<Button>,
+    Or<(With<WorldSelectAction>, With<NewWorldAction>, With<FormField>)>,
+);
+
+// ============================================================================
+// COMPONENTES Y RECURSOS
+// ============================================================================
+
+/// Marcador de la pantalla de lista de mundos
+#[derive(Component)]
+pub struct WorldSelectUI;
+
+/// Marcador de la pantalla de mundo nuevo
+#[derive(Component)]
+pub struct NewWorldUI;
+
+/// Contenedor con scroll de la lista de mundos
+#[derive(Component)]
+pub struct WorldListScroll;
+
+/// Mundos en disco, tal como se muestran (índices = los de `WorldSelectAction`)
+#[derive(Resource, Default)]
+pub struct WorldList(pub Vec<SavedWorld>);
+
+/// Acciones de la pantalla de selección
+#[derive(Component, Clone, Copy, Debug)]
+pub enum WorldSelectAction {
+    Play(usize),
+    Duplicate(usize),
+    Delete(usize),
+    CreateNew,
+    Back,
+}
+
+/// Campos editables del formulario de mundo nuevo
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FormField {
+    #[default]
+    Name,
+    Seed,
+}
+
+/// Texto que refleja el contenido de un campo del formulario
+#[derive(Component)]
+pub struct FormFieldText(pub FormField);
+
+/// Acciones del formulario de mundo nuevo
+#[derive(Component, Clone, Copy, Debug)]
+pub enum NewWorldAction {
+    RandomSeed,
+    Create,
+    Back,
+}
+
+/// Estado del formulario de mundo nuevo
+#[derive(Resource, Default)]
+pub struct NewWorldForm {
+    pub name: String,
+    /// Texto de la seed: número, texto libre (se hashea) o vacío (aleatoria)
+    pub seed: String,
+    pub focus: FormField,
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// Convierte el texto del campo seed en una seed.
+///
+/// Un número se usa tal cual; cualquier otro texto se hashea de forma estable
+/// (mismo texto → mismo mundo); vacío → aleatoria.
+pub fn parse_seed(text: &str) -> i32 {
+    let text = text.trim();
+    if text.is_empty() {
+        return WorldSeed::random().0;
+    }
+    text.parse().unwrap_or_else(|_| {
+        text.chars()
+            .fold(0i32, |h, c| h.wrapping_mul(31).wrapping_add(c as i32))
+    })
+}
+
+/// "1h 05m" / "12m"
+fn format_play_time(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Hace cuánto se jugó, en relativo ("5 min ago", "2 days ago")
+fn format_last_played(timestamp: u64) -> String {
+    let ago = now_secs().saturating_sub(timestamp);
+    match ago {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", ago / 60),
+        3600..86400 => format!("{} h ago", ago / 3600),
+        _ => format!("{} days ago", ago / 86400),
+    }
+}
+
+fn spawn_button<A: Component>(
+    parent: &mut ChildSpawnerCommands<'_>,
+    text: &str,
+    width: Val,
+    action: A,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width,
+                height: Val::Px(48.0),
+                border: UiRect::all(Val::Px(3.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor::all(Color::srgb(0.8, 0.8, 0.8)),
+            BackgroundColor(BUTTON_COLOR),
+            action,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(text),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn spawn_title(parent: &mut ChildSpawnerCommands<'_>, text: &str) {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font_size: 56.0,
+            ..default()
+        },
+        TextColor(ACCENT),
+    ));
+}
+
+/// Contenedor de pantalla completa común a ambas pantallas
+fn screen_root() -> (Node, BackgroundColor) {
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(16.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+    )
+}
+
+/// Elimina todas las entidades con el marcador `T` (limpieza de pantallas)
+pub fn despawn_screen<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Hover de todos los botones de estas pantallas
+pub fn world_menu_hover_system(
+    mut query: Query<(&Interaction, &mut BackgroundColor), WorldMenuButton>,
+) {
+    for (interaction, mut color) in &mut query {
+        match *interaction {
+            Interaction::Hovered => *color = BUTTON_HOVER.into(),
+            Interaction::None => *color = BUTTON_COLOR.into(),
+            Interaction::Pressed => {}
+        }
+    }
+}
+
+/// Abre un mundo y arranca la partida. Si falla la lectura se queda en el menú.
+fn start_world(
+    commands: &mut Commands,
+    world: &SavedWorld,
+    world_seed: &mut WorldSeed,
+    voxel_diffs: &mut VoxelDiffs,
+    next_state: &mut NextState<GameState>,
+) {
+    match open_world(world, world_seed, voxel_diffs) {
+        Ok(active_world) => {
+            commands.insert_resource(active_world);
+            next_state.set(GameState::InGame);
+        }
+        Err(e) => error!("No se pudo abrir el mundo {:?}: {}", world.dir, e),
+    }
+}
+
+// ============================================================================
+// PANTALLA: LISTA DE MUNDOS
+// ============================================================================
+
+fn spawn_world_select(commands: &mut Commands, worlds: &[SavedWorld]) {
+    commands
+        .spawn((screen_root(), WorldSelectUI))
+        .with_children(|parent| {
+            spawn_title(parent, "SELECT WORLD");
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(760.0),
+                        max_height: Val::Percent(60.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    ScrollPosition::default(),
+                    WorldListScroll,
+                ))
+                .with_children(|list| {
+                    if worlds.is_empty() {
+                        list.spawn((
+                            Text::new("No saved worlds yet"),
+                            TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                        ));
+                    }
+                    for (i, world) in worlds.iter().enumerate() {
+                        spawn_world_row(list, i, world);
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(row, "NEW WORLD", Val::Px(240.0), WorldSelectAction::CreateNew);
+                    spawn_button(row, "BACK", Val::Px(240.0), WorldSelectAction::Back);
+                });
+        });
+}
+
+/// Fila de la lista: nombre + detalles a la izquierda, acciones a la derecha
+fn spawn_world_row(parent: &mut ChildSpawnerCommands<'_>, index: usize, world: &SavedWorld) {
+    let meta = &world.meta;
+    parent
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                flex_shrink: 0.0,
+                ..default()
+            },
+            BorderColor::all(Color::srgb(0.4, 0.4, 0.4)),
+        ))
+        .with_children(|row| {
+            row.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                ..default()
+            })
+            .with_children(|info| {
+                info.spawn((
+                    Text::new(meta.name.clone()),
+                    TextFont {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+                info.spawn((
+                    Text::new(format!(
+                        "seed {}  ·  {}  ·  played {}",
+                        meta.seed,
+                        format_last_played(meta.last_played),
+                        format_play_time(meta.play_time_secs)
+                    )),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                ));
+            });
+
+            row.spawn(Node {
+                column_gap: Val::Px(8.0),
+                ..default()
+            })
+            .with_children(|actions| {
+                spawn_button(actions, "PLAY", Val::Px(100.0), WorldSelectAction::Play(index));
+                spawn_button(actions, "COPY", Val::Px(100.0), WorldSelectAction::Duplicate(index));
+                spawn_button(actions, "DELETE", Val::Px(110.0), WorldSelectAction::Delete(index));
+            });
+        });
+}
+
+/// Al entrar en `WorldSelect`: relee `saves/` y construye la lista
+pub fn setup_world_select(mut commands: Commands, mut world_list: ResMut<WorldList>) {
+    world_list.0 = list_worlds();
+    spawn_world_select(&mut commands, &world_list.0);
+}
+
+/// Clicks de la pantalla de selección
+#[allow(clippy::too_many_arguments)]
+pub fn world_select_button_system(
+    mut commands: Commands,
+    interaction_query: ButtonChanged<WorldSelectAction>,
+    screen_query: Query<Entity, With<WorldSelectUI>>,
+    mut world_list: ResMut<WorldList>,
+    mut next_screen: ResMut<NextState<MenuScreen>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut world_seed: ResMut<WorldSeed>,
+    mut voxel_diffs: ResMut<VoxelDiffs>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        info!("Botón presionado: {:?}", action);
+
+        let result = match *action {
+            WorldSelectAction::Play(i) => {
+                if let Some(world) = world_list.0.get(i) {
+                    start_world(
+                        &mut commands,
+                        world,
+                        &mut world_seed,
+                        &mut voxel_diffs,
+                        &mut next_state,
+                    );
+                }
+                continue;
+            }
+            WorldSelectAction::CreateNew => {
+                next_screen.set(MenuScreen::NewWorld);
+                continue;
+            }
+            WorldSelectAction::Back => {
+                next_screen.set(MenuScreen::Title);
+                continue;
+            }
+            WorldSelectAction::Duplicate(i) => world_list.0.get(i).map(|w| duplicate_world(w).map(|_| ())),
+            WorldSelectAction::Delete(i) => world_list.0.get(i).map(delete_world),
+        };
+
+        if let Some(Err(e)) = result {
+            error!("Operación sobre el mundo fallida: {}", e);
+        }
+
+        // La lista cambió en disco: reconstruir la pantalla
+        for entity in &screen_query {
+            commands.entity(entity).despawn();
+        }
+        world_list.0 = list_worlds();
+        spawn_world_select(&mut commands, &world_list.0);
+        // Los índices de los botones ya no son válidos: ignorar el resto del frame
+        break;
+    }
+}
+
+/// Scroll con la rueda del ratón sobre la lista de mundos
+pub fn world_list_scroll_system(
+    mut wheel: MessageReader<MouseWheel>,
+    mut query: Query<&mut ScrollPosition, With<WorldListScroll>>,
+) {
+    for event in wheel.read() {
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * 40.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in &mut query {
+            scroll.y = (scroll.y - dy).max(0.0);
+        }
+    }
+}
+
+// ============================================================================
+// PANTALLA: MUNDO NUEVO
+// ============================================================================
+
+/// Al entrar en `NewWorld`: formulario limpio con el foco en el nombre
+pub fn setup_new_world(mut commands: Commands, mut form: ResMut<NewWorldForm>) {
+    *form = NewWorldForm {
+        name: "New World".to_string(),
+        ..default()
+    };
+
+    commands
+        .spawn((screen_root(), NewWorldUI))
+        .with_children(|parent| {
+            spawn_title(parent, "NEW WORLD");
+
+            spawn_form_field(parent, "World name", FormField::Name);
+            spawn_form_field(parent, "Seed (number or text, empty = random)", FormField::Seed);
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(row, "RANDOM SEED", Val::Px(240.0), NewWorldAction::RandomSeed);
+                    spawn_button(row, "CREATE", Val::Px(240.0), NewWorldAction::Create);
+                    spawn_button(row, "BACK", Val::Px(240.0), NewWorldAction::Back);
+                });
+        });
+}
+
+/// Etiqueta + caja clickable (el click le da el foco)
+fn spawn_form_field(parent: &mut ChildSpawnerCommands<'_>, label: &str, field: FormField) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|column| {
+            column.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+            column
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(520.0),
+                        height: Val::Px(48.0),
+                        border: UiRect::all(Val::Px(3.0)),
+                        padding: UiRect::horizontal(Val::Px(10.0)),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor::all(Color::srgb(0.5, 0.5, 0.5)),
+                    BackgroundColor(BUTTON_COLOR),
+                    field,
+                ))
+                .with_children(|input| {
+                    input.spawn((
+                        Text::default(),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        FormFieldText(field),
+                    ));
+                });
+        });
+}
+
+/// Escritura en el campo con foco: caracteres, Backspace, Tab cambia de campo
+pub fn new_world_text_input(
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut form: ResMut<NewWorldForm>,
+) {
+    for event in keyboard.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Tab => {
+                form.focus = match form.focus {
+                    FormField::Name => FormField::Seed,
+                    FormField::Seed => FormField::Name,
+                };
+            }
+            Key::Backspace => {
+                match form.focus {
+                    FormField::Name => form.name.pop(),
+                    FormField::Seed => form.seed.pop(),
+                };
+            }
+            Key::Character(text) => {
+                let chars = text.chars().filter(|c| !c.is_control());
+                match form.focus {
+                    FormField::Name => {
+                        for c in chars {
+                            if form.name.chars().count() < MAX_NAME_LEN {
+                                form.name.push(c);
+                            }
+                        }
+                    }
+                    FormField::Seed => form.seed.extend(chars),
+                }
+            }
+            Key::Space => match form.focus {
+                FormField::Name if form.name.chars().count() < MAX_NAME_LEN => form.name.push(' '),
+                FormField::Seed => form.seed.push(' '),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Refleja el formulario en los textos y bordes (cursor `_` en el campo activo)
+pub fn update_new_world_form(
+    form: Res<NewWorldForm>,
+    mut texts: Query<(&FormFieldText, &mut Text)>,
+    mut boxes: Query<(&FormField, &mut BorderColor)>,
+) {
+    if !form.is_changed() {
+        return;
+    }
+    for (field, mut text) in &mut texts {
+        let value = match field.0 {
+            FormField::Name => &form.name,
+            FormField::Seed => &form.seed,
+        };
+        let cursor = if field.0 == form.focus { "_" } else { "" };
+        text.0 = format!("{}{}", value, cursor);
+    }
+    for (field, mut border) in &mut boxes {
+        *border = if *field == form.focus {
+            BorderColor::all(ACCENT)
+        } else {
+            BorderColor::all(Color::srgb(0.5, 0.5, 0.5))
+        };
+    }
+}
+
+/// Clicks del formulario (y Enter = crear)
+#[allow(clippy::too_many_arguments)]
+pub fn new_world_button_system(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    action_query: ButtonChanged<NewWorldAction>,
+    field_query: ButtonChanged<FormField>,
+    mut form: ResMut<NewWorldForm>,
+    mut next_screen: ResMut<NextState<MenuScreen>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut world_seed: ResMut<WorldSeed>,
+    mut voxel_diffs: ResMut<VoxelDiffs>,
+) {
+    for (interaction, field) in &field_query {
+        if *interaction == Interaction::Pressed {
+            form.focus = *field;
+        }
+    }
+
+    let mut create = keys.just_pressed(KeyCode::Enter);
+    for (interaction, action) in &action_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            NewWorldAction::RandomSeed => form.seed = WorldSeed::random().0.to_string(),
+            NewWorldAction::Create => create = true,
+            NewWorldAction::Back => next_screen.set(MenuScreen::WorldSelect),
+        }
+    }
+
+    if !create {
+        return;
+    }
+
+    let name = form.name.trim();
+    let name = if name.is_empty() { "New World" } else { name };
+    let seed = parse_seed(&form.seed);
+    match create_world(name, seed) {
+        Ok(world) => {
+            info!("Mundo '{}' creado (seed {})", world.meta.name, seed);
+            start_world(
+                &mut commands,
+                &world,
+                &mut world_seed,
+                &mut voxel_diffs,
+                &mut next_state,
+            );
+        }
+        Err(e) => error!("No se pudo crear el mundo: {}", e),
+    }
+}
@@ -1,5 +1,6 @@
 //! Pasto: tufos cortos de follaje ATRAVESABLE (`VoxelType::Foliage`), densos,
-//! sobre las columnas de pasto del chunk.
+//! sobre las columnas de pasto del chunk. Cuántas columnas lo llevan depende del
+//! bioma (`Biome::grass_density`).
 //!
 //! A diferencia de los árboles (rejilla dispersa de celdas), el pasto es denso:
 //! se decide por COLUMNA con un hash del mundo. Determinista → cada chunk
@@ -8,9 +9,6 @@
 use crate::core::constants::BASE_CHUNK_SIZE;
 use crate::voxel::{BaseChunk, VoxelType};
 
-/// Fracción de columnas de pasto que reciben un tufo.
-const GRASS_DENSITY: f32 = 0.35;
-
 /// Hash determinista por columna mundial + seed.
 fn column_hash(wx: i32, wz: i32, seed: i32) -> u32 {
     let mut h = (wx as u32).wrapping_mul(0x9e37_79b9);
@@ -23,7 +21,10 @@ fn column_hash(wx: i32, wz: i32, seed: i32) -> u32 {
 /// Estampa tufos de pasto sobre las columnas cuyo voxel de superficie (el sólido
 /// más alto DENTRO de este chunk) es pasto. Se ejecuta después de los árboles, así
 /// no crece pasto encima de troncos/copas.
-pub fn place_grass(chunk: &mut BaseChunk, seed: i32) {
+///
+/// `density[lx + lz * BASE_CHUNK_SIZE]` es la fracción (0..1) de columnas de
+/// pasto que reciben un tufo en esa columna (la del bioma).
+pub fn place_grass(chunk: &mut BaseChunk, density: &[f32], seed: i32) {
     let n = BASE_CHUNK_SIZE;
     let origin_x = chunk.position.x * n as i32;
     let origin_z = chunk.position.z * n as i32;
@@ -47,7 +48,7 @@ pub fn place_grass(chunk: &mut BaseChunk, seed: i32) {
 
             // Decisión determinista por columna.
             let h = column_hash(origin_x + lx as i32, origin_z + lz as i32, seed);
-            if (h & 0xff) as f32 / 255.0 > GRASS_DENSITY {
+            if (h & 0xff) as f32 / 255.0 > density[lx + lz * n] {
                 continue;
             }
 
@@ -3,7 +3,8 @@
 //! altura llega un árbol (para no marcar como "aire" los chunks que atraviesa).
 //!
 //! Todo es función pura de `(celda, seed)`, así cualquier chunk reconstruye los
-//! mismos árboles → consistentes a través de bordes y regeneraciones.
+//! mismos árboles → consistentes a través de bordes y regeneraciones. El bioma
+//! (también función de la seed) escala la probabilidad de árbol de cada celda.
 
 use super::bush::bush_template;
 use super::oak::oak_template;
@@ -16,7 +17,8 @@ use bevy::prelude::*;
 /// Tamaño de la celda de espaciado (en voxels). Como máximo un árbol por celda.
 pub const TREE_CELL_SIZE: i32 = 12;
 
-/// Probabilidad (0..1) de que una celda contenga un árbol.
+/// Probabilidad (0..1) de que una celda contenga un árbol, con densidad de
+/// bioma 1.0 (ver `Biome::tree_density`).
 const TREE_PROBABILITY: f32 = 0.45;
 
 /// Alcance horizontal máximo de un árbol (voxels): debe ser >= lo que sobresale
@@ -68,10 +70,17 @@ fn hash_cell(cell_x: i32, cell_z: i32, seed: i32) -> u32 {
 /// Devuelve `Some(TreeInstance)` (posición con jitter dentro de la celda + forma)
 /// o `None`. Función PURA de `(cell, seed)`: cualquier chunk que pregunte por la
 /// misma celda obtiene el mismo resultado → árboles consistentes en los bordes.
-pub fn tree_in_cell(cell_x: i32, cell_z: i32, seed: i32) -> Option<TreeInstance> {
+/// La probabilidad se escala con la densidad del bioma en la columna del árbol.
+pub fn tree_in_cell(
+    cell_x: i32,
+    cell_z: i32,
+    biome: &BiomeGenerator,
+    seed: i32,
+) -> Option<TreeInstance> {
     let h = hash_cell(cell_x, cell_z, seed);
 
-    // Bits 0–11 → probabilidad. Si no pasa, no hay árbol en esta celda.
+    // Bits 0–11 → probabilidad. Si no pasa ni con la densidad máxima, no hay
+    // árbol en esta celda (y nos ahorramos consultar el bioma).
     let p = (h & 0xfff) as f32 / 0xfff as f32;
     if p > TREE_PROBABILITY {
         return None;
@@ -82,10 +91,17 @@ pub fn tree_in_cell(cell_x: i32, cell_z: i32, seed: i32) -> Option<TreeInstance>
     let world_x = cell_x * TREE_CELL_SIZE + jitter_x;
     let world_z = cell_z * TREE_CELL_SIZE + jitter_z;
 
+    let density = biome
+        .biome_at(world_x as f32 * VOXEL_SIZE, world_z as f32 * VOXEL_SIZE)
+        .tree_density();
+    if p > TREE_PROBABILITY * density {
+        return None;
+    }
+
     // Segundo hash con "sal" → bits independientes para tipo y forma del pino.
     let h2 = hash_cell(cell_x, cell_z, seed ^ 0x5f37_59df);
 
-    if h2 % 6 == 0 {
+    if h2.is_multiple_of(6) {
         // pino (igual que antes)
         if !crate::vegetation::config::ENABLE_TREES {
             return None;
@@ -149,10 +165,10 @@ pub fn tree_in_cell(cell_x: i32, cell_z: i32, seed: i32) -> Option<TreeInstance>
 ///
 /// Recorre las celdas de espaciado que se solapan con el chunk (expandido por el
 /// alcance de copa), calcula cada árbol con `tree_in_cell`, obtiene la altura de
-/// su columna con `biome` (función pura → vale también para columnas fuera del
-/// chunk) y escribe los voxels de la plantilla que caen DENTRO de este chunk.
+/// su columna con `biome.surface_voxel_y` (función pura → vale también para
+/// columnas fuera del chunk) y escribe los voxels de la plantilla que caen DENTRO de este chunk.
 /// Solo escribe sobre aire, para no perforar el terreno.
-pub fn place_trees(chunk: &mut BaseChunk, biome: &mut BiomeGenerator, seed: i32) {
+pub fn place_trees(chunk: &mut BaseChunk, biome: &BiomeGenerator, seed: i32) {
     let n = BASE_CHUNK_SIZE as i32;
 
     // Origen del chunk en voxels de mundo.
@@ -167,15 +183,13 @@ pub fn place_trees(chunk: &mut BaseChunk, biome: &mut BiomeGenerator, seed: i32)
 
     for cell_x in cell_x_min..=cell_x_max {
         for cell_z in cell_z_min..=cell_z_max {
-            let Some(tree) = tree_in_cell(cell_x, cell_z, seed) else {
+            let Some(tree) = tree_in_cell(cell_x, cell_z, biome, seed) else {
                 continue;
             };
 
-            // Altura de la superficie en la columna del árbol (metros → voxels).
-            let world_x_m = tree.world_x as f32 * VOXEL_SIZE;
-            let world_z_m = tree.world_z as f32 * VOXEL_SIZE;
-            let surface_m = biome.generate_height(world_x_m, world_z_m);
-            let surface_voxel_y = (surface_m / VOXEL_SIZE).floor() as i32;
+            // Voxel sólido más alto de la columna del árbol (con salientes no
+            // coincide con el heightmap).
+            let surface_voxel_y = biome.surface_voxel_y(tree.world_x, tree.world_z);
 
             // Base = el voxel justo SOBRE el suelo (donde arranca el tronco).
             let base = IVec3::new(tree.world_x, surface_voxel_y + 1, tree.world_z);
@@ -217,7 +231,7 @@ pub fn place_trees(chunk: &mut BaseChunk, biome: &mut BiomeGenerator, seed: i32)
 /// El loader lo consulta para NO marcar como "aire" un chunk que un árbol cruza.
 pub fn tree_ceiling_for_chunk(
     chunk_pos: IVec3,
-    biome: &mut BiomeGenerator,
+    biome: &BiomeGenerator,
     seed: i32,
 ) -> Option<i32> {
     let n = BASE_CHUNK_SIZE as i32;
@@ -231,12 +245,10 @@ pub fn tree_ceiling_for_chunk(
     let mut ceiling: Option<i32> = None;
     for cell_x in cell_x_min..=cell_x_max {
         for cell_z in cell_z_min..=cell_z_max {
-            let Some(tree) = tree_in_cell(cell_x, cell_z, seed) else {
+            let Some(tree) = tree_in_cell(cell_x, cell_z, biome, seed) else {
                 continue;
             };
-            let wx_m = tree.world_x as f32 * VOXEL_SIZE;
-            let wz_m = tree.world_z as f32 * VOXEL_SIZE;
-            let surface_voxel_y = (biome.generate_height(wx_m, wz_m) / VOXEL_SIZE).floor() as i32;
+            let surface_voxel_y = biome.surface_voxel_y(tree.world_x, tree.world_z);
             let top = surface_voxel_y + 1 + tree.height();
             ceiling = Some(ceiling.map_or(top, |c| c.max(top)));
         }
@@ -1,6 +1,23 @@
 //! Sistema de biomas para generación de terreno variado
 //! Incluye montañas, llanuras, valles, colinas, etc.
+//!
+//! El terreno es un campo de DENSIDAD 3D (`> 0` = sólido): el heightmap da la
+//! forma base (`altura(x,z) - y`) y encima se suman ruidos 3D para salientes en
+//! acantilados y cuevas (cheese + spaghetti). Todo es función pura de la seed y
+//! de la posición, así que cualquier chunk (o hilo de rayon) obtiene lo mismo.
+//!
+//! El relieve es continuo, pero cada columna pertenece además a un [`Biome`]
+//! discreto (continentalidad + temperatura + humedad) que decide el material de
+//! superficie y la densidad de vegetación.
+//!
+//! Todo el aire de superficie por debajo de [`SEA_LEVEL`] se llena de agua
+//! (mares y lagos en las hondonadas) y una cresta de ruido excava ríos hasta
+//! por debajo de ese nivel. En el fondo de las cuevas, bajo [`LAVA_LEVEL`], el
+//! aire es lava.
 
+use crate::core::VOXEL_SIZE;
+use crate::voxel::VoxelType;
+use bevy::math::IVec3;
 use fastnoise_lite::{FastNoiseLite, FractalType, NoiseType};
 
 // ============================================================================
@@ -22,6 +39,85 @@ const MAX_AMPLITUDE: f32 = 4.0;
 /// Intensidad del detalle extra de montaña, en metros.
 const MOUNTAIN_DETAIL: f32 = 1.5;
 
+// ============================================================================
+// AGUA
+// ============================================================================
+
+/// Nivel del mar (metros): el aire de superficie por debajo se llena de agua.
+pub const SEA_LEVEL: f32 = -0.6;
+/// Frecuencia del ruido de ríos: el cauce sigue sus ceros (una "cresta" de
+/// `1 - |ruido|`), así que los ríos son líneas largas y sinuosas.
+const RIVER_FREQUENCY: f32 = 0.0035;
+/// Semiancho del cauce en unidades de ruido (fondo plano a `RIVER_BED`).
+const RIVER_WIDTH: f32 = 0.02;
+/// Ancho extra de las orillas (transición suave del cauce al terreno).
+const RIVER_BANK: f32 = 0.06;
+/// Profundidad del fondo del río bajo el nivel del mar (metros).
+const RIVER_DEPTH: f32 = 0.5;
+/// Y mundial (metros) bajo la cual el aire de las cuevas es lava fuente
+/// (lagos de lava en el fondo de las cavernas más hondas).
+pub const LAVA_LEVEL: f32 = -2.0;
+
+// ============================================================================
+// PARÁMETROS DE DENSIDAD 3D
+// ============================================================================
+// Unidades en metros (la densidad base es `altura - y`, también en metros).
+
+/// Máximo que el ruido de salientes desplaza la superficie (arriba o abajo).
+/// Cota superior garantizada: no hay sólido por encima de `altura + OVERHANG_MAX`.
+pub const OVERHANG_MAX: f32 = 1.2;
+/// Peso del ruido de salientes en terreno llano (en acantilados es 1.0).
+const OVERHANG_FLAT_WEIGHT: f32 = 0.15;
+/// Pendiente (m/m) donde empiezan y se completan los salientes de acantilado.
+const CLIFF_SLOPE_START: f32 = 0.3;
+const CLIFF_SLOPE_FULL: f32 = 0.9;
+/// Frecuencia del ruido de salientes (rasgos de ~3 m).
+const OVERHANG_FREQUENCY: f32 = 0.35;
+
+/// Profundidad bajo el heightmap a la que empiezan las cuevas (deja techo).
+const CAVE_MIN_DEPTH: f32 = 0.8;
+/// Tramo (en profundidad y en altura) en el que las cuevas crecen hasta su tamaño pleno.
+const CAVE_FADE: f32 = 1.5;
+/// Y mundial bajo la cual no se excava: el mundo acaba en el chunk y = -1
+/// (-3.2 m) y una cueva ahí dejaría ver el vacío.
+const CAVE_MIN_Y: f32 = -2.5;
+/// Umbral del ruido "cheese": por encima hay caverna.
+const CHEESE_THRESHOLD: f32 = 0.55;
+/// Aplastamiento vertical de las cavernas (más anchas que altas).
+const CHEESE_Y_SQUASH: f32 = 1.8;
+/// Semiancho (en unidades de ruido) de los túneles "spaghetti".
+const SPAGHETTI_RADIUS: f32 = 0.15;
+
+// ============================================================================
+// PARÁMETROS DE BIOMAS
+// ============================================================================
+// Temperatura y humedad son ruidos 2D en [-1, 1], más lentos que la
+// continentalidad para que un bioma ocupe cientos de metros.
+
+/// Frecuencia de los ruidos de clima.
+const TEMPERATURE_FREQUENCY: f32 = 0.0018;
+const HUMIDITY_FREQUENCY: f32 = 0.0022;
+/// Enfriamiento por metro de altura (la temperatura baja al subir).
+const ALTITUDE_CHILL: f32 = 0.06;
+/// Sobre esta altura (metros) hay nieve siempre.
+const SNOW_LINE: f32 = 8.0;
+/// Temperatura bajo la cual las tierras altas ya son cumbres nevadas.
+const SNOW_TEMPERATURE: f32 = -0.5;
+/// Hasta esta altura (metros) sobre el nivel del mar, la orilla es playa.
+const BEACH_BAND: f32 = 0.3;
+/// Umbrales de continentalidad (0..1) para colinas y montañas.
+const HILLS_CONTINENTALNESS: f32 = 0.55;
+const MOUNTAIN_CONTINENTALNESS: f32 = 0.72;
+/// Bajo este umbral de continentalidad, las tierras bajas son valle.
+const VALLEY_CONTINENTALNESS: f32 = 0.3;
+
+/// Pendientes (m/m) a partir de las cuales la superficie es grava o roca
+/// desnuda, en cualquier bioma.
+const GRAVEL_SLOPE: f32 = 0.5;
+const STONE_SLOPE: f32 = 0.7;
+/// Grosor (metros) de la capa de grava en laderas.
+const GRAVEL_DEPTH: f32 = 0.3;
+
 /// Interpolación lineal.
 fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t
@@ -33,6 +129,101 @@ fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
     t * t * (3.0 - 2.0 * t)
 }
 
+// ============================================================================
+// BIOMAS
+// ============================================================================
+
+/// Bioma discreto de una columna. Lo decide [`BiomeGenerator::biome_at`] a partir
+/// de continentalidad, temperatura y humedad; no cambia la forma del terreno
+/// (que es continua), solo su superficie y su vegetación.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Biome {
+    /// Terreno bajo el nivel del mar: fondo de arena bajo el agua
+    Ocean,
+    /// Cauce excavado por la cresta de ríos: fondo de grava
+    River,
+    /// Orillas justo sobre el nivel del mar: arena
+    Beach,
+    /// Llanura templada
+    Plains,
+    /// Llanura húmeda, muchos árboles
+    Forest,
+    /// Tierras bajas del fondo de los valles
+    Valley,
+    /// Caluroso y seco: arena y casi sin vegetación
+    Desert,
+    /// Frío: pinares con poco pasto
+    Taiga,
+    /// Tierras medias onduladas
+    Hills,
+    /// Tierras medias secas
+    Plateau,
+    /// Tierras altas bajo la línea de nieve
+    Mountains,
+    /// Cumbres nevadas
+    SnowyPeaks,
+}
+
+impl Biome {
+    /// Multiplicador (0..1) de la probabilidad de árbol por celda.
+    pub fn tree_density(self) -> f32 {
+        match self {
+            Biome::Forest | Biome::Taiga => 1.0,
+            Biome::Valley => 0.6,
+            Biome::Hills => 0.5,
+            Biome::Plains => 0.3,
+            Biome::Mountains => 0.25,
+            Biome::Plateau => 0.15,
+            Biome::Ocean | Biome::River | Biome::Beach | Biome::Desert | Biome::SnowyPeaks => 0.0,
+        }
+    }
+
+    /// Fracción (0..1) de columnas de pasto que reciben un tufo.
+    pub fn grass_density(self) -> f32 {
+        match self {
+            Biome::Valley => 0.5,
+            Biome::Plains => 0.45,
+            Biome::Forest | Biome::Hills => 0.35,
+            Biome::Taiga => 0.2,
+            Biome::Plateau | Biome::Mountains => 0.15,
+            Biome::Ocean | Biome::River | Biome::Beach | Biome::Desert | Biome::SnowyPeaks => 0.0,
+        }
+    }
+
+    /// Material de un voxel según el bioma, su profundidad bajo la superficie y
+    /// la pendiente de la columna. Generaliza [`VoxelType::from_depth`]: capa
+    /// superior + relleno del bioma y piedra en profundidad; en laderas la
+    /// superficie pasa a grava y, si es muy empinada, a roca desnuda.
+    ///
+    /// # Parámetros
+    /// - `density`: densidad del voxel (`<= 0` = aire)
+    /// - `depth_below_surface`: metros bajo la superficie del terreno
+    /// - `slope`: pendiente de la columna (m/m), ver [`TerrainColumn::slope`]
+    pub fn surface_material(self, density: f32, depth_below_surface: f32, slope: f32) -> VoxelType {
+        if density <= 0.0 {
+            return VoxelType::Air;
+        }
+        if depth_below_surface >= 0.5 || slope >= STONE_SLOPE {
+            return VoxelType::Stone;
+        }
+        if slope >= GRAVEL_SLOPE {
+            return if depth_below_surface < GRAVEL_DEPTH {
+                VoxelType::Gravel
+            } else {
+                VoxelType::Stone
+            };
+        }
+        let (top, filler) = match self {
+            Biome::Ocean | Biome::Beach | Biome::Desert => (VoxelType::Sand, VoxelType::Sand),
+            Biome::River => (VoxelType::Gravel, VoxelType::Dirt),
+            Biome::SnowyPeaks => (VoxelType::Snow, VoxelType::Stone),
+            // Pasto sobre tierra: las capas por defecto
+            _ => return VoxelType::from_depth(density, depth_below_surface),
+        };
+        if depth_below_surface < 0.1 { top } else { filler }
+    }
+}
+
 /// Generador de biomas
 pub struct BiomeGenerator {
     /// Continentalidad: campo suave que controla el relieve (valle ↔ montaña)
@@ -41,6 +232,29 @@ pub struct BiomeGenerator {
     terrain_noise: FastNoiseLite,
     /// Detalle adicional para montañas (entra gradualmente con la altura)
     mountain_detail_noise: FastNoiseLite,
+    /// Ruido 3D que deforma la superficie (salientes en acantilados)
+    overhang_noise: FastNoiseLite,
+    /// Ruido 3D de cavernas grandes ("cheese")
+    cheese_noise: FastNoiseLite,
+    /// Par de ruidos 3D cuyo cruce de ceros forma túneles ("spaghetti")
+    spaghetti_a: FastNoiseLite,
+    spaghetti_b: FastNoiseLite,
+    /// Clima 2D que, junto con la continentalidad, decide el [`Biome`]
+    temperature_noise: FastNoiseLite,
+    humidity_noise: FastNoiseLite,
+    /// Ruido 2D cuyos ceros trazan los ríos
+    river_noise: FastNoiseLite,
+}
+
+/// Datos 2D de una columna XZ que necesita la densidad 3D.
+#[derive(Clone, Copy, Debug)]
+pub struct TerrainColumn {
+    /// Altura del heightmap (metros)
+    pub height: f32,
+    /// Pendiente del heightmap (m/m)
+    pub slope: f32,
+    /// 0 = llano, 1 = acantilado (según la pendiente del heightmap)
+    pub cliff: f32,
 }
 
 impl BiomeGenerator {
@@ -66,10 +280,25 @@ impl BiomeGenerator {
         mountain_detail_noise.set_frequency(Some(0.08));
         mountain_detail_noise.set_seed(Some(seed.wrapping_add(54321)));
 
+        let noise = |frequency: f32, offset: i32| {
+            let mut noise = FastNoiseLite::new();
+            noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+            noise.set_frequency(Some(frequency));
+            noise.set_seed(Some(seed.wrapping_add(offset)));
+            noise
+        };
+
         Self {
             biome_noise,
             terrain_noise,
             mountain_detail_noise,
+            overhang_noise: noise(OVERHANG_FREQUENCY, 7001),
+            cheese_noise: noise(0.09, 7002),
+            spaghetti_a: noise(0.045, 7003),
+            spaghetti_b: noise(0.045, 7004),
+            temperature_noise: noise(TEMPERATURE_FREQUENCY, 8001),
+            humidity_noise: noise(HUMIDITY_FREQUENCY, 8002),
+            river_noise: noise(RIVER_FREQUENCY, 8003),
         }
     }
 
@@ -78,9 +307,8 @@ impl BiomeGenerator {
     /// La continentalidad (`biome_noise`) es un campo suave en [-1, 1]; de él
     /// derivamos base y amplitud interpoladas, por lo que el terreno pasa de
     /// llano a montañoso gradualmente y nunca de golpe.
-    pub fn generate_height(&mut self, world_x: f32, world_z: f32) -> f32 {
-        let continent = self.biome_noise.get_noise_2d(world_x, world_z);
-        let t = ((continent + 1.0) * 0.5).clamp(0.0, 1.0); // [0, 1]
+    pub fn generate_height(&self, world_x: f32, world_z: f32) -> f32 {
+        let t = self.continentalness(world_x, world_z);
         let s = t * t * (3.0 - 2.0 * t); // smoothstep para suavizar aún más
 
         let base = lerp(VALLEY_BASE, MOUNTAIN_BASE, s);
@@ -95,8 +323,179 @@ impl BiomeGenerator {
             * MOUNTAIN_DETAIL
             * mountain_weight;
 
+        // Ríos: cerca de la cresta el terreno baja hasta el fondo del cauce (solo
+        // baja, nunca sube). No cruzan montañas: se desvanecen con la continentalidad.
+        let carve = self.river_carve(world_x, world_z)
+            * (1.0 - smoothstep(HILLS_CONTINENTALNESS, MOUNTAIN_CONTINENTALNESS, t));
+        if carve > 0.0 {
+            height = lerp(height, height.min(SEA_LEVEL - RIVER_DEPTH), carve);
+        }
+
         height
     }
+
+    /// Peso del cauce en (x, z): 1 en el centro del río, 0 fuera de sus orillas.
+    fn river_carve(&self, world_x: f32, world_z: f32) -> f32 {
+        let ridge = self.river_noise.get_noise_2d(world_x, world_z).abs();
+        1.0 - smoothstep(RIVER_WIDTH, RIVER_WIDTH + RIVER_BANK, ridge)
+    }
+
+    /// ¿Se llena de agua el aire en `world_y` (fondo del voxel, metros) de esta
+    /// columna? Solo bajo el nivel del mar y cerca de la superficie: las cuevas
+    /// (que empiezan a `CAVE_MIN_DEPTH`) quedan secas.
+    pub fn is_flooded(world_y: f32, column: &TerrainColumn) -> bool {
+        world_y < SEA_LEVEL && column.height - world_y < CAVE_MIN_DEPTH
+    }
+
+    /// ¿Es lava el aire en `world_y` de esta columna? Solo en cuevas (bajo
+    /// `CAVE_MIN_DEPTH`) y por debajo de `LAVA_LEVEL`: nunca toca el agua.
+    pub fn is_lava_pool(world_y: f32, column: &TerrainColumn) -> bool {
+        world_y < LAVA_LEVEL && column.height - world_y >= CAVE_MIN_DEPTH
+    }
+
+    /// Continentalidad normalizada a [0, 1] (0 = valle, 1 = montaña).
+    fn continentalness(&self, world_x: f32, world_z: f32) -> f32 {
+        let continent = self.biome_noise.get_noise_2d(world_x, world_z);
+        ((continent + 1.0) * 0.5).clamp(0.0, 1.0)
+    }
+
+    /// Bioma en la posición `(world_x, world_z)` (metros). Consultable en
+    /// cualquier punto del mundo: función pura de la seed.
+    pub fn biome_at(&self, world_x: f32, world_z: f32) -> Biome {
+        self.biome_with_height(world_x, world_z, self.generate_height(world_x, world_z))
+    }
+
+    /// Como [`Self::biome_at`], reutilizando una altura ya calculada (la
+    /// generación de chunks la tiene cacheada en su heightmap).
+    pub fn biome_with_height(&self, world_x: f32, world_z: f32, height: f32) -> Biome {
+        let continent = self.continentalness(world_x, world_z);
+        let temperature =
+            self.temperature_noise.get_noise_2d(world_x, world_z) - height.max(0.0) * ALTITUDE_CHILL;
+        let humidity = self.humidity_noise.get_noise_2d(world_x, world_z);
+
+        // Primero el agua, luego la altitud (nieve, montaña), la orilla y, en
+        // tierras bajas y medias, el clima.
+        if height < SEA_LEVEL {
+            if self.river_carve(world_x, world_z) > 0.0 {
+                Biome::River
+            } else {
+                Biome::Ocean
+            }
+        } else if height >= SNOW_LINE || (continent > MOUNTAIN_CONTINENTALNESS && temperature < SNOW_TEMPERATURE) {
+            Biome::SnowyPeaks
+        } else if continent > MOUNTAIN_CONTINENTALNESS {
+            Biome::Mountains
+        } else if height < SEA_LEVEL + BEACH_BAND {
+            Biome::Beach
+        } else if continent > HILLS_CONTINENTALNESS {
+            if humidity < -0.25 { Biome::Plateau } else { Biome::Hills }
+        } else if temperature > 0.3 && humidity < -0.1 {
+            Biome::Desert
+        } else if temperature < -0.35 {
+            Biome::Taiga
+        } else if humidity > 0.2 {
+            Biome::Forest
+        } else if continent < VALLEY_CONTINENTALNESS {
+            Biome::Valley
+        } else {
+            Biome::Plains
+        }
+    }
+
+    /// Cota superior conservadora de la altura del terreno sólido en (x, z):
+    /// por encima de esto la densidad es siempre negativa (aire).
+    pub fn max_solid_height(&self, world_x: f32, world_z: f32) -> f32 {
+        self.generate_height(world_x, world_z) + OVERHANG_MAX
+    }
+
+    /// Altura y "acantilado" de una columna en coordenadas de voxel de mundo.
+    ///
+    /// La pendiente sale de diferencias centrales con las columnas vecinas;
+    /// `generate_terrain` calcula lo mismo desde su heightmap cacheado.
+    pub fn terrain_column(&self, voxel_x: i32, voxel_z: i32) -> TerrainColumn {
+        let h = |vx: i32, vz: i32| self.generate_height(vx as f32 * VOXEL_SIZE, vz as f32 * VOXEL_SIZE);
+        Self::column_from_heights(
+            h(voxel_x, voxel_z),
+            [
+                h(voxel_x - 1, voxel_z),
+                h(voxel_x + 1, voxel_z),
+                h(voxel_x, voxel_z - 1),
+                h(voxel_x, voxel_z + 1),
+            ],
+        )
+    }
+
+    /// Construye la columna a partir de su altura y la de sus 4 vecinas
+    /// (-x, +x, -z, +z).
+    pub fn column_from_heights(height: f32, neighbors: [f32; 4]) -> TerrainColumn {
+        let dx = (neighbors[1] - neighbors[0]) / (2.0 * VOXEL_SIZE);
+        let dz = (neighbors[3] - neighbors[2]) / (2.0 * VOXEL_SIZE);
+        let slope = (dx * dx + dz * dz).sqrt();
+        TerrainColumn {
+            height,
+            slope,
+            cliff: smoothstep(CLIFF_SLOPE_START, CLIFF_SLOPE_FULL, slope),
+        }
+    }
+
+    /// Densidad 3D en un voxel de mundo (`> 0` = sólido), en metros.
+    ///
+    /// `altura - y` + salientes (solo cerca de la superficie) y luego se restan
+    /// las cuevas (solo por debajo de `CAVE_MIN_DEPTH`). Toma `&self`: se evalúa
+    /// en paralelo desde rayon.
+    pub fn density(&self, voxel: IVec3, column: &TerrainColumn) -> f32 {
+        let x = voxel.x as f32 * VOXEL_SIZE;
+        let y = voxel.y as f32 * VOXEL_SIZE;
+        let z = voxel.z as f32 * VOXEL_SIZE;
+        let depth = column.height - y;
+
+        // Por encima de la cota no hay nada que evaluar: aire seguro.
+        if depth <= -OVERHANG_MAX {
+            return depth;
+        }
+
+        let mut density = depth;
+
+        // Salientes: desplazan la superficie ±OVERHANG_MAX, con peso pleno en
+        // acantilados y atenuados a cero al alejarse de ella (sin escalones).
+        if depth < OVERHANG_MAX {
+            let falloff = 1.0 - smoothstep(OVERHANG_MAX * 0.5, OVERHANG_MAX, depth.abs());
+            let weight = lerp(OVERHANG_FLAT_WEIGHT, 1.0, column.cliff);
+            density += self.overhang_noise.get_noise_3d(x, y, z) * OVERHANG_MAX * weight * falloff;
+        }
+
+        // Cuevas: crecen con la profundidad y desaparecen cerca del fondo del mundo.
+        let fade = smoothstep(CAVE_MIN_DEPTH, CAVE_MIN_DEPTH + CAVE_FADE, depth)
+            * smoothstep(CAVE_MIN_Y, CAVE_MIN_Y + CAVE_FADE, y);
+        if fade > 0.0 {
+            // Ambos campos son < 0 DENTRO de la cueva
+            let cheese = CHEESE_THRESHOLD - self.cheese_noise.get_noise_3d(x, y * CHEESE_Y_SQUASH, z);
+            let spaghetti = self
+                .spaghetti_a
+                .get_noise_3d(x, y, z)
+                .abs()
+                .max(self.spaghetti_b.get_noise_3d(x, y, z).abs())
+                - SPAGHETTI_RADIUS;
+            // Con fade < 1 solo excava lo más interior de la cueva
+            density = density.min(cheese.min(spaghetti) + (1.0 - fade));
+        }
+
+        density
+    }
+
+    /// Y del voxel sólido más alto de la columna (el suelo real, que con
+    /// salientes ya no es `altura` a secas). Busca de la cota superior hacia abajo;
+    /// si la columna cae en una boca de cueva, devuelve el fondo de la zona de
+    /// salientes.
+    pub fn surface_voxel_y(&self, voxel_x: i32, voxel_z: i32) -> i32 {
+        let column = self.terrain_column(voxel_x, voxel_z);
+        let top = ((column.height + OVERHANG_MAX) / VOXEL_SIZE).ceil() as i32;
+        let bottom = ((column.height - OVERHANG_MAX) / VOXEL_SIZE).floor() as i32;
+        (bottom..=top)
+            .rev()
+            .find(|&vy| self.density(IVec3::new(voxel_x, vy, voxel_z), &column) > 0.0)
+            .unwrap_or(bottom)
+    }
 }
 
 /// Generador de terreno con múltiples capas de ruido
@@ -111,3 +510,113 @@ impl TerrainGenerator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn density_never_solid_above_upper_bound() {
+        let biome = BiomeGenerator::new(1234);
+        for vx in (-400..400).step_by(37) {
+            for vz in (-400..400).step_by(41) {
+                let column = biome.terrain_column(vx, vz);
+                let bound = biome.max_solid_height(vx as f32 * VOXEL_SIZE, vz as f32 * VOXEL_SIZE);
+                let first_above = (bound / VOXEL_SIZE).floor() as i32 + 1;
+                for vy in first_above..first_above + 20 {
+                    assert!(biome.density(IVec3::new(vx, vy, vz), &column) <= 0.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn density_is_deterministic_per_seed() {
+        let a = BiomeGenerator::new(77);
+        let b = BiomeGenerator::new(77);
+        let voxel = IVec3::new(123, -15, -456);
+        let column = a.terrain_column(voxel.x, voxel.z);
+        assert_eq!(a.density(voxel, &column), b.density(voxel, &b.terrain_column(voxel.x, voxel.z)));
+    }
+
+    #[test]
+    fn caves_carve_air_below_the_surface() {
+        // En una región amplia tiene que haber aire bajo el heightmap (cuevas)
+        let biome = BiomeGenerator::new(42);
+        let mut carved = 0;
+        for vx in (0..600).step_by(6) {
+            for vz in (0..600).step_by(6) {
+                let column = biome.terrain_column(vx, vz);
+                let vy = ((column.height - 2.5) / VOXEL_SIZE) as i32;
+                if biome.density(IVec3::new(vx, vy, vz), &column) <= 0.0 {
+                    carved += 1;
+                }
+            }
+        }
+        assert!(carved > 0);
+    }
+
+    #[test]
+    fn biome_at_is_deterministic_and_varied() {
+        let a = BiomeGenerator::new(9);
+        let b = BiomeGenerator::new(9);
+        let mut seen = std::collections::HashSet::new();
+        for x in (-3000..3000).step_by(97) {
+            for z in (-3000..3000).step_by(89) {
+                let (wx, wz) = (x as f32, z as f32);
+                assert_eq!(a.biome_at(wx, wz), b.biome_at(wx, wz));
+                seen.insert(format!("{:?}", a.biome_at(wx, wz)));
+            }
+        }
+        assert!(seen.len() >= 5, "solo {seen:?}");
+    }
+
+    #[test]
+    fn surface_rules_follow_biome_and_slope() {
+        assert_eq!(Biome::Desert.surface_material(1.0, 0.0, 0.0), VoxelType::Sand);
+        assert_eq!(Biome::Beach.surface_material(1.0, 0.3, 0.0), VoxelType::Sand);
+        assert_eq!(Biome::SnowyPeaks.surface_material(1.0, 0.0, 0.0), VoxelType::Snow);
+        assert_eq!(Biome::Plains.surface_material(1.0, 0.0, 0.0), VoxelType::Grass);
+        assert_eq!(Biome::Plains.surface_material(1.0, 0.0, GRAVEL_SLOPE), VoxelType::Gravel);
+        assert_eq!(Biome::Plains.surface_material(1.0, 0.0, STONE_SLOPE), VoxelType::Stone);
+        assert_eq!(Biome::Desert.surface_material(1.0, 1.0, 0.0), VoxelType::Stone);
+        assert_eq!(Biome::Forest.surface_material(-0.1, 0.0, 0.0), VoxelType::Air);
+    }
+
+    #[test]
+    fn river_beds_lie_below_sea_level() {
+        let biome = BiomeGenerator::new(5);
+        let mut rivers = 0;
+        for x in (-4000..4000).step_by(7) {
+            for z in (-4000..4000).step_by(53) {
+                let (wx, wz) = (x as f32 * 0.5, z as f32 * 0.5);
+                let lowland = biome.continentalness(wx, wz) < HILLS_CONTINENTALNESS;
+                if lowland && biome.river_carve(wx, wz) >= 1.0 {
+                    rivers += 1;
+                    assert!(biome.generate_height(wx, wz) < SEA_LEVEL);
+                    assert_eq!(biome.biome_at(wx, wz), Biome::River);
+                }
+            }
+        }
+        assert!(rivers > 0);
+    }
+
+    #[test]
+    fn only_surface_air_below_sea_level_floods() {
+        let column = TerrainColumn { height: -1.5, slope: 0.0, cliff: 0.0 };
+        assert!(BiomeGenerator::is_flooded(SEA_LEVEL - 0.1, &column));
+        assert!(!BiomeGenerator::is_flooded(SEA_LEVEL, &column));
+        // Una cueva bajo el fondo del mar queda seca
+        assert!(!BiomeGenerator::is_flooded(column.height - CAVE_MIN_DEPTH - 0.1, &column));
+    }
+
+    #[test]
+    fn lava_pools_only_in_deep_caves() {
+        let column = TerrainColumn { height: 1.0, slope: 0.0, cliff: 0.0 };
+        assert!(BiomeGenerator::is_lava_pool(LAVA_LEVEL - 0.1, &column));
+        assert!(!BiomeGenerator::is_lava_pool(LAVA_LEVEL + 0.1, &column));
+        // Terreno hundido: el aire bajo LAVA_LEVEL es superficie, no cueva
+        let sunken = TerrainColumn { height: LAVA_LEVEL, ..column };
+        assert!(!BiomeGenerator::is_lava_pool(LAVA_LEVEL - 0.1, &sunken));
+    }
+}
@@ -9,11 +9,12 @@ use crate::{
     player::Player,
     voxel::{
         BaseChunk, ChunkLOD, ChunkMap, ChunkMaterial, LodChunk, LodLevel, PaletteExtension,
-        SpatialHashGrid, TerrainGenerator, VoxelDiffs, mesh_lod_chunk,
+        SEA_LEVEL, SpatialHashGrid, TerrainGenerator, VoxelDiffs, mesh_lod_chunk,
     },
 };
 use bevy::{
     prelude::*,
+    render::storage::ShaderStorageBuffer,
     tasks::{AsyncComputeTaskPool, Task},
 };
 use futures_lite::future;
@@ -83,7 +84,7 @@ impl ChunkType {
 
 /// Materiales compartidos por todos los chunks.
 ///
-/// Un puñado de handles fijos (uno por color de debug) en lugar de un
+/// Un puñado de handles fijos (uno por nivel de detalle) en lugar de un
 /// `StandardMaterial` nuevo por chunk: miles de materiales idénticos rompen
 /// el batching del renderer y multiplican los draw calls. Además NO desactivan
 /// `cull_mode`, así el GPU descarta las caras traseras (≈mitad de fragmentos).
@@ -91,8 +92,6 @@ impl ChunkType {
 pub struct ChunkMaterials {
     /// Por nivel de `ChunkLOD` (chunks reales): Ultra, High, Medium, Low, Minimal
     real: [Handle<ChunkMaterial>; 5],
-    /// Por nivel de `LodLevel` (chunks LOD): Medium, Low, Minimal
-    lod: [Handle<StandardMaterial>; 3],
 }
 
 impl FromWorld for ChunkMaterials {
@@ -102,6 +101,9 @@ impl FromWorld for ChunkMaterials {
         // iguales para que `real_handle` siga indexando por ChunkLOD aunque
         // `update_chunk_lod_system` los intercambie.
         let real = {
+            let extension = PaletteExtension::from_registry(
+                &mut world.resource_mut::<Assets<ShaderStorageBuffer>>(),
+            );
             let mut materials = world.resource_mut::<Assets<ChunkMaterial>>();
             [(); 5].map(|_| {
                 materials.add(ChunkMaterial {
@@ -109,28 +111,12 @@ impl FromWorld for ChunkMaterials {
                         base_color: Color::WHITE,
                         ..default()
                     },
-                    extension: PaletteExtension::default(),
+                    extension: extension.clone(),
                 })
             })
         };
 
-        // Colores de debug por nivel LOD (naranja → rojo según distancia)
-        let lod = {
-            let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
-            [
-                Color::srgb(1.0, 0.6, 0.0), // Medium (32-64 chunks)
-                Color::srgb(1.0, 0.3, 0.0), // Low (64-128)
-                Color::srgb(0.8, 0.0, 0.0), // Minimal (128+)
-            ]
-            .map(|color| {
-                materials.add(StandardMaterial {
-                    base_color: color,
-                    ..default()
-                })
-            })
-        };
-
-        Self { real, lod }
+        Self { real }
     }
 }
 
@@ -158,7 +144,8 @@ pub struct ChunkLoadQueue {
     pub to_convert_to_real: Vec<Entity>, // LOD → Real
     pub to_convert_to_lod: Vec<Entity>,  // Real → LOD
 
-    pub last_player_chunk: IVec3,
+    /// Chunk de cada jugador en la última actualización de la cola
+    pub last_player_chunks: Vec<IVec3>,
     pub total_loaded: usize,
     pub last_log_time: f32,
 }
@@ -181,6 +168,14 @@ pub struct ChunkGenerationTask {
     pub chunk_pos: IVec3,
 }
 
+/// Cualquier chunk: real, LOD, generándose o vacío.
+type AnyChunk = Or<(
+    With<BaseChunk>,
+    With<LodChunk>,
+    With<ChunkGenerationTask>,
+    With<EmptyChunk>,
+)>;
+
 /// Destruye el mundo y reinicia los recursos de chunks.
 ///
 /// Se ejecuta al volver al menú principal (desde InGame o Paused) para que una
@@ -190,15 +185,7 @@ pub fn teardown_world(
     mut chunk_map: ResMut<ChunkMap>,
     mut spatial_hash: ResMut<SpatialHashGrid>,
     mut load_queue: ResMut<ChunkLoadQueue>,
-    chunks: Query<
-        Entity,
-        Or<(
-            With<BaseChunk>,
-            With<LodChunk>,
-            With<ChunkGenerationTask>,
-            With<EmptyChunk>,
-        )>,
-    >,
+    chunks: Query<Entity, AnyChunk>,
     lights: Query<Entity, With<DirectionalLight>>,
     mut voxel_diffs: ResMut<VoxelDiffs>,
 ) {
@@ -212,31 +199,52 @@ pub fn teardown_world(
     }
 
     chunk_map.chunks.clear();
-    voxel_diffs.chunks.clear();
+    voxel_diffs.clear();
     spatial_hash.clear();
     *load_queue = ChunkLoadQueue::default();
 }
 
-/// Sistema que detecta cuando el jugador se mueve y actualiza la cola de carga
+/// Chunk en el que está cada jugador.
+pub fn player_chunks<'a>(players: impl Iterator<Item = &'a Transform>) -> Vec<IVec3> {
+    players
+        .map(|transform| world_pos_to_chunk_pos(transform.translation))
+        .collect()
+}
+
+/// Distancia horizontal al cuadrado (en chunks) de `pos` al más cercano de
+/// `centers`; `i32::MAX` si no hay ninguno.
+pub fn nearest_horizontal_distance_sq(pos: IVec3, centers: &[IVec3]) -> i32 {
+    centers
+        .iter()
+        .map(|center| {
+            let delta = pos - *center;
+            delta.x * delta.x + delta.z * delta.z
+        })
+        .min()
+        .unwrap_or(i32::MAX)
+}
+
+/// Sistema que detecta cuando algún jugador se mueve y actualiza la cola de
+/// carga: se carga la unión de los radios de todos los jugadores y cada chunk
+/// toma su tipo (Real/LOD) del jugador más cercano.
 pub fn update_chunk_load_queue(
     player_query: Query<&Transform, With<Player>>,
     chunk_map: Res<ChunkMap>,
     spatial_hash: Res<SpatialHashGrid>,
     mut load_queue: ResMut<ChunkLoadQueue>,
 ) {
-    let Ok(player_transform) = player_query.single() else {
+    // Convertir posición de los jugadores a coordenadas de chunk
+    let centers = player_chunks(player_query.iter());
+    if centers.is_empty() {
         return;
-    };
-
-    // Convertir posición del jugador a coordenadas de chunk
-    let player_chunk = world_pos_to_chunk_pos(player_transform.translation);
+    }
 
-    // Solo actualizar si el jugador cambió de chunk
-    if player_chunk == load_queue.last_player_chunk {
+    // Solo actualizar si algún jugador cambió de chunk
+    if centers == load_queue.last_player_chunks {
         return;
     }
 
-    load_queue.last_player_chunk = player_chunk;
+    load_queue.last_player_chunks = centers.clone();
 
     // Rango vertical reducido: desde -1 hasta +3 chunks (mejor rendimiento)
     let y_min = -1;
@@ -246,59 +254,67 @@ pub fn update_chunk_load_queue(
     // El triple bucle visita cada (cx,cy,cz) exactamente una vez, así que no hay
     // duplicados que deduplicar: el viejo HashSet de ~64k entradas era puro coste.
     // Comprobamos chunk_map en el momento y empujamos solo lo que falta cargar.
+    // Con varios jugadores, un chunk que ya cubre el círculo de un jugador
+    // anterior se salta: cada posición se visita una sola vez.
     let radius_sq = CHUNK_LOAD_RADIUS * CHUNK_LOAD_RADIUS;
     let mut to_load_vec: Vec<(IVec3, ChunkType)> = Vec::new();
 
-    for cy in y_min..=y_max {
-        // Usar simetría del círculo para reducir cálculos
-        for cx in -CHUNK_LOAD_RADIUS..=CHUNK_LOAD_RADIUS {
-            // Calcular el rango Z válido para este X (usando la ecuación del círculo)
-            let x_sq = cx * cx;
-            if x_sq > radius_sq {
-                continue; // Este X está fuera del círculo
-            }
-
-            // Calcular el máximo Z para este X: z² <= r² - x²
-            let max_z_sq = radius_sq - x_sq;
-            let max_z = (max_z_sq as f32).sqrt() as i32;
-
-            // Solo iterar en el rango válido de Z
-            for cz in -max_z..=max_z {
-                let chunk_pos = IVec3::new(player_chunk.x + cx, cy, player_chunk.z + cz);
-                // Mapa finito: no generar nada fuera del límite del mundo
-                if chunk_pos.x.abs() > WORLD_CHUNK_RADIUS || chunk_pos.z.abs() > WORLD_CHUNK_RADIUS
-                {
-                    continue;
+    for (i, &player_chunk) in centers.iter().enumerate() {
+        for cy in y_min..=y_max {
+            // Usar simetría del círculo para reducir cálculos
+            for cx in -CHUNK_LOAD_RADIUS..=CHUNK_LOAD_RADIUS {
+                // Calcular el rango Z válido para este X (usando la ecuación del círculo)
+                let x_sq = cx * cx;
+                if x_sq > radius_sq {
+                    continue; // Este X está fuera del círculo
                 }
 
-                // Solo encolar lo que aún no está cargado
-                if chunk_map.chunks.contains_key(&chunk_pos) {
-                    continue;
-                }
+                // Calcular el máximo Z para este X: z² <= r² - x²
+                let max_z_sq = radius_sq - x_sq;
+                let max_z = (max_z_sq as f32).sqrt() as i32;
 
-                // Distancia horizontal al jugador = (cx, cz) directamente
-                let distance_chunks = ((x_sq + cz * cz) as f32).sqrt() as i32;
-                let chunk_type = ChunkType::from_distance(distance_chunks);
+                // Solo iterar en el rango válido de Z
+                for cz in -max_z..=max_z {
+                    let chunk_pos = IVec3::new(player_chunk.x + cx, cy, player_chunk.z + cz);
+                    // Mapa finito: no generar nada fuera del límite del mundo
+                    if chunk_pos.x.abs() > WORLD_CHUNK_RADIUS || chunk_pos.z.abs() > WORLD_CHUNK_RADIUS
+                    {
+                        continue;
+                    }
 
-                // Los LOD son heightmaps con alturas ABSOLUTAS (ignoran position.y):
-                // un solo chunk en y=0 representa la columna entera. Cargar los
-                // demás niveles Y produciría 5 meshes idénticos apilados.
-                if chunk_type == ChunkType::Lod && chunk_pos.y != 0 {
-                    continue;
-                }
+                    // Solo encolar lo que aún no está cargado (ni encolado ya por
+                    // el círculo de otro jugador)
+                    if chunk_map.chunks.contains_key(&chunk_pos)
+                        || nearest_horizontal_distance_sq(chunk_pos, &centers[..i]) <= radius_sq
+                    {
+                        continue;
+                    }
 
-                to_load_vec.push((chunk_pos, chunk_type));
+                    // Distancia horizontal al jugador más cercano
+                    let distance_chunks =
+                        (nearest_horizontal_distance_sq(chunk_pos, &centers) as f32).sqrt() as i32;
+                    let chunk_type = ChunkType::from_distance(distance_chunks);
+
+                    // Los LOD son heightmaps con alturas ABSOLUTAS (ignoran position.y):
+                    // un solo chunk en y=0 representa la columna entera. Cargar los
+                    // demás niveles Y produciría 5 meshes idénticos apilados.
+                    if chunk_type == ChunkType::Lod && chunk_pos.y != 0 {
+                        continue;
+                    }
+
+                    to_load_vec.push((chunk_pos, chunk_type));
+                }
             }
         }
     }
 
-    // Ordenar por distancia al jugador (cargar los más cercanos primero)
-    let player_pos = player_chunk;
+    // Ordenar por distancia al jugador más cercano (cargar los más cercanos primero)
     to_load_vec.sort_by_key(|(pos, _chunk_type)| {
-        let dx = pos.x - player_pos.x;
-        let dy = pos.y - player_pos.y;
-        let dz = pos.z - player_pos.z;
-        dx * dx + dy * dy + dz * dz
+        centers
+            .iter()
+            .map(|center| (*pos - *center).length_squared())
+            .min()
+            .unwrap_or(i32::MAX)
     });
 
     load_queue.to_load = VecDeque::from(to_load_vec);
@@ -307,12 +323,12 @@ pub fn update_chunk_load_queue(
     // OPTIMIZACIÓN: Usar Spatial Hash Grid con distancia HORIZONTAL (2D)
     load_queue.to_unload.clear();
 
-    // Usar spatial hash para encontrar chunks DENTRO del radio horizontal
-    let chunks_to_keep = spatial_hash.query_radius_horizontal(player_chunk, CHUNK_UNLOAD_RADIUS);
-
-    // Filtrar por rango vertical y convertir a HashSet para búsqueda O(1)
-    let keep_set: HashSet<IVec3> = chunks_to_keep
-        .into_iter()
+    // Usar spatial hash para encontrar chunks DENTRO del radio horizontal de
+    // algún jugador, filtrar por rango vertical y juntarlos en un HashSet para
+    // búsqueda O(1)
+    let keep_set: HashSet<IVec3> = centers
+        .iter()
+        .flat_map(|&center| spatial_hash.query_radius_horizontal(center, CHUNK_UNLOAD_RADIUS))
         .filter(|pos| pos.y >= y_min && pos.y <= y_max)
         .collect();
 
@@ -325,6 +341,7 @@ pub fn update_chunk_load_queue(
 }
 
 /// Sistema que inicia la generación asíncrona de chunks con caché
+#[allow(clippy::too_many_arguments)]
 pub fn load_chunks_system(
     mut commands: Commands,
     mut chunk_map: ResMut<ChunkMap>,
@@ -335,7 +352,6 @@ pub fn load_chunks_system(
     world_seed: Res<WorldSeed>,
     voxel_diffs: Res<VoxelDiffs>,
 ) {
-    let thread_pool = AsyncComputeTaskPool::get();
     let seed = world_seed.0;
 
     // Generador reutilizado para sondear la altura del terreno (saltar chunks de aire)
@@ -360,44 +376,20 @@ pub fn load_chunks_system(
 
             // Genera chunk segun tipo
             match chunk_type {
-                ChunkType::Real => {
-                    // Saltar chunks enteramente por encima del terreno: son puro
-                    // aire, sin geometría ni colisión. Se marcan con EmptyChunk
-                    // (siguen en ChunkMap, así no se vuelven a evaluar). NO se
-                    // saltan si el jugador los modificó (tienen diffs).
-                    if !voxel_diffs.chunks.contains_key(&chunk_pos)
-                        && chunk_is_above_terrain(chunk_pos, &mut terrain_gen, seed)
-                    {
-                        commands.entity(chunk_entity).insert(EmptyChunk);
-                        continue;
-                    }
-
-                    // Copia los diffs de ESTE chunk antes de lanzar la tarea
-                    let chunk_diffs = voxel_diffs.chunks.get(&chunk_pos).cloned();
-
-                    let task = thread_pool.spawn(async move {
-                        // El mallado de RENDER (con vecinos) se hace en
-                        // complete_chunk_generation_system. Aquí, en el hilo de fondo,
-                        // construimos el COLLIDER con un mesh simple solo-colisionable
-                        // (sin vecinos) → saca el trabajo caro del hilo principal.
-                        let mut base_chunk = BaseChunk::new(chunk_pos, seed);
-
-                        if let Some(diffs) = &chunk_diffs {
-                            base_chunk.apply_diffs(diffs);
-                        }
-                        let collider = build_chunk_collider(&base_chunk);
-                        (chunk_pos, base_chunk, collider)
-                    });
-
-                    commands
-                        .entity(chunk_entity)
-                        .insert(ChunkGenerationTask { task, chunk_pos });
-                }
+                ChunkType::Real => start_real_chunk_generation(
+                    &mut commands,
+                    chunk_entity,
+                    chunk_pos,
+                    seed,
+                    &voxel_diffs,
+                    &mut terrain_gen,
+                ),
 
                 ChunkType::Lod => {
                     // chunk Lod solo superficie (generacion sincrona por ahora)
-                    let delta = chunk_pos - load_queue.last_player_chunk;
-                    let distance_chunks = ((delta.x.pow(2) + delta.z.pow(2)) as f32).sqrt() as i32;
+                    let distance_sq =
+                        nearest_horizontal_distance_sq(chunk_pos, &load_queue.last_player_chunks);
+                    let distance_chunks = (distance_sq as f32).sqrt() as i32;
                     let lod_level = LodLevel::from_distance(distance_chunks);
 
                     let mut lod_chunk = LodChunk::new(chunk_pos, lod_level);
@@ -430,7 +422,52 @@ pub fn load_chunks_system(
     }
 }
 
+/// Lanza en el hilo de fondo la generación de un chunk real (terreno + diffs
+/// del jugador + collider) sobre su entidad placeholder, o la marca
+/// `EmptyChunk` si es puro aire. Lo comparten el cliente y el servidor.
+pub fn start_real_chunk_generation(
+    commands: &mut Commands,
+    chunk_entity: Entity,
+    chunk_pos: IVec3,
+    seed: i32,
+    voxel_diffs: &VoxelDiffs,
+    terrain_gen: &mut TerrainGenerator,
+) {
+    // Saltar chunks enteramente por encima del terreno: son puro aire, sin
+    // geometría ni colisión. Se marcan con EmptyChunk (siguen en ChunkMap, así
+    // no se vuelven a evaluar). NO se saltan si el jugador los modificó (tienen
+    // diffs).
+    if !voxel_diffs.chunks.contains_key(&chunk_pos)
+        && chunk_is_above_terrain(chunk_pos, terrain_gen, seed)
+    {
+        commands.entity(chunk_entity).insert(EmptyChunk);
+        return;
+    }
+
+    // Copia los diffs de ESTE chunk antes de lanzar la tarea
+    let chunk_diffs = voxel_diffs.chunks.get(&chunk_pos).cloned();
+
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        // El mallado de RENDER (con vecinos) se hace en
+        // complete_chunk_generation_system. Aquí, en el hilo de fondo,
+        // construimos el COLLIDER con un mesh simple solo-colisionable (sin
+        // vecinos) → saca el trabajo caro del hilo principal.
+        let mut base_chunk = BaseChunk::new(chunk_pos, seed);
+
+        if let Some(diffs) = &chunk_diffs {
+            base_chunk.apply_diffs(diffs);
+        }
+        let collider = build_chunk_collider(&base_chunk);
+        (chunk_pos, base_chunk, collider)
+    });
+
+    commands
+        .entity(chunk_entity)
+        .insert(ChunkGenerationTask { task, chunk_pos });
+}
+
 /// Sistema que completa la generación de chunks cuando las tareas terminan
+#[allow(clippy::too_many_arguments)]
 pub fn complete_chunk_generation_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -444,22 +481,16 @@ pub fn complete_chunk_generation_system(
 ) {
     use crate::voxel::greedy_mesh_basechunk;
 
-    // Posición del jugador en chunks: para integrar primero los huecos cercanos.
-    let player_chunk = player_query
-        .single()
-        .map(|t| world_pos_to_chunk_pos(t.translation))
-        .unwrap_or(IVec3::ZERO);
+    // Posición de los jugadores en chunks: para integrar primero los huecos cercanos.
+    let centers = player_chunks(player_query.iter());
 
-    // Ordenar las tareas por cercanía HORIZONTAL al jugador. El orden de
-    // iter() del ECS es arbitrario; sin esto, dentro del presupuesto de tiempo
-    // se podían integrar chunks lejanos antes que los huecos junto al jugador.
+    // Ordenar las tareas por cercanía HORIZONTAL al jugador más cercano. El
+    // orden de iter() del ECS es arbitrario; sin esto, dentro del presupuesto
+    // de tiempo se podían integrar chunks lejanos antes que los huecos junto
+    // a un jugador.
     let mut pending: Vec<(Entity, IVec3)> =
         task_query.iter().map(|(e, t)| (e, t.chunk_pos)).collect();
-    pending.sort_by_key(|(_, pos)| {
-        let dx = pos.x - player_chunk.x;
-        let dz = pos.z - player_chunk.z;
-        dx * dx + dz * dz
-    });
+    pending.sort_by_key(|(_, pos)| nearest_horizontal_distance_sq(*pos, &centers));
 
     let mut completed_this_frame = 0;
     let start = std::time::Instant::now();
@@ -541,18 +572,19 @@ pub fn unload_chunks_system(
 /// Construye el collider de un chunk a partir de un mesh simple SOLO-COLISIONABLE
 /// (sin vecinos, ignora el follaje). Pensado para correr en el hilo de fondo.
 /// `None` si el chunk no tiene geometría colisionable.
-fn build_chunk_collider(chunk: &BaseChunk) -> Option<Collider> {
+pub fn build_chunk_collider(chunk: &BaseChunk) -> Option<Collider> {
     let mesh = crate::voxel::greedy_mesh_basechunk_collider_simple(chunk);
     (mesh.count_vertices() > 0).then(|| create_terrain_collider(&mesh))
 }
 
 /// ¿El chunk está enteramente por ENCIMA del terreno (puro aire)?
 ///
-/// Como la densidad es `altura(x,z) - y` (monótona en Y), un chunk es todo aire
-/// si la altura MÁXIMA del terreno sobre su huella XZ queda por debajo del fondo
-/// del chunk. Muestrea una rejilla 5×5 (la frecuencia del ruido hace innecesario
-/// muestrear más fino sobre 3.2 m) y añade un margen para no saltar un chunk que
-/// apenas roce el terreno.
+/// La densidad 3D no es monótona en Y (salientes), pero nunca hay sólido por
+/// encima de `max_solid_height` (altura + `OVERHANG_MAX`): un chunk es todo aire
+/// si esa cota MÁXIMA sobre su huella XZ queda por debajo del fondo del chunk.
+/// Muestrea una rejilla 5×5 (la frecuencia del ruido hace innecesario muestrear
+/// más fino sobre 3.2 m) y añade un margen para no saltar un chunk que apenas
+/// roce el terreno.
 fn chunk_is_above_terrain(chunk_pos: IVec3, terrain_gen: &mut TerrainGenerator, seed: i32) -> bool {
     // Y mundial del fondo del chunk (metros)
     let chunk_bottom_y = chunk_pos.y as f32 * BASE_CHUNK_SIZE as f32 * VOXEL_SIZE;
@@ -568,7 +600,7 @@ fn chunk_is_above_terrain(chunk_pos: IVec3, terrain_gen: &mut TerrainGenerator,
         while sz <= BASE_CHUNK_SIZE {
             let world_x = (chunk_pos.x * BASE_CHUNK_SIZE as i32 + sx as i32) as f32 * VOXEL_SIZE;
             let world_z = (chunk_pos.z * BASE_CHUNK_SIZE as i32 + sz as i32) as f32 * VOXEL_SIZE;
-            let h = terrain_gen.biome_gen.generate_height(world_x, world_z);
+            let h = terrain_gen.biome_gen.max_solid_height(world_x, world_z);
             if h > max_height {
                 max_height = h;
             }
@@ -582,19 +614,19 @@ fn chunk_is_above_terrain(chunk_pos: IVec3, terrain_gen: &mut TerrainGenerator,
     let chunk_bottom_voxel = chunk_pos.y * BASE_CHUNK_SIZE as i32;
     if let Some(ceiling) = crate::vegetation::trees::tree_ceiling_for_chunk(
         chunk_pos,
-        &mut terrain_gen.biome_gen,
+        &terrain_gen.biome_gen,
         seed,
-    ) {
-        if ceiling >= chunk_bottom_voxel {
-            return false;
-        }
+    ) && ceiling >= chunk_bottom_voxel
+    {
+        return false;
     }
 
-    max_height + margin < chunk_bottom_y
+    // Bajo el nivel del mar el "aire" es agua: el chunk no está vacío.
+    max_height + margin < chunk_bottom_y && chunk_bottom_y >= SEA_LEVEL
 }
 
 /// Convierte posición mundial a posición de chunk
-fn world_pos_to_chunk_pos(world_pos: Vec3) -> IVec3 {
+pub fn world_pos_to_chunk_pos(world_pos: Vec3) -> IVec3 {
     let chunk_size_meters = BASE_CHUNK_SIZE as f32 * 0.1; // VOXEL_SIZE = 0.1
 
     IVec3::new(
@@ -611,20 +643,19 @@ pub fn update_chunk_transitions_system(
     base_chunk_query: Query<&BaseChunk>,
     lod_chunk_query: Query<&LodChunk>,
     mut load_queue: ResMut<ChunkLoadQueue>,
-    mut last_chunk: Local<IVec3>,
+    mut last_chunks: Local<Vec<IVec3>>,
 ) {
-    let Ok(player_transform) = player_query.single() else {
+    let centers = player_chunks(player_query.iter());
+    if centers.is_empty() {
         return;
-    };
-
-    let player_chunk = world_pos_to_chunk_pos(player_transform.translation);
+    }
 
-    // Las conversiones solo cambian cuando el jujgador cambia de chunk
-    // Estando quieto, lsos sitemas de conversion siguien drenando las colas.
-    if player_chunk == *last_chunk {
+    // Las conversiones solo cambian cuando algún jujgador cambia de chunk
+    // Estando quietos, lsos sitemas de conversion siguien drenando las colas.
+    if centers == *last_chunks {
         return;
     }
-    *last_chunk = player_chunk;
+    *last_chunks = centers.clone();
 
     // Limpiar colas de conversión
     load_queue.to_convert_to_real.clear();
@@ -632,19 +663,18 @@ pub fn update_chunk_transitions_system(
 
     // Revisar todos los chunks cargados
     for (chunk_pos, &entity) in &chunk_map.chunks {
-        // Calcular distancia horizontal al jugador (ignorar Y)
-        let dx = chunk_pos.x - player_chunk.x;
-        let dz = chunk_pos.z - player_chunk.z;
-        let distance_sq = dx * dx + dz * dz;
+        // Calcular distancia horizontal al jugador más cercano (ignorar Y):
+        // un chunk es Real si está cerca de cualquiera
+        let distance_sq = nearest_horizontal_distance_sq(*chunk_pos, &centers);
 
         if base_chunk_query.get(entity).is_ok() {
             if distance_sq > REAL_TO_LOD_DISTANCE * REAL_TO_LOD_DISTANCE {
                 load_queue.to_convert_to_lod.push(entity);
             }
-        } else if lod_chunk_query.get(entity).is_ok() {
-            if distance_sq < LOD_TO_REAL_DISTANCE * LOD_TO_REAL_DISTANCE {
-                load_queue.to_convert_to_real.push(entity);
-            }
+        } else if lod_chunk_query.get(entity).is_ok()
+            && distance_sq < LOD_TO_REAL_DISTANCE * LOD_TO_REAL_DISTANCE
+        {
+            load_queue.to_convert_to_real.push(entity);
         }
     }
 }
@@ -668,37 +698,38 @@ pub fn convert_lod_to_real_system(
         .min(MAX_CHUNK_TRANSITIONS_PER_FRAME);
 
     for _ in 0..conversions_to_do {
-        if let Some(entity) = load_queue.to_convert_to_real.pop() {
-            if let Ok(lod_chunk) = lod_query.get(entity) {
-                let chunk_pos = lod_chunk.position;
-                let chunk_diffs = voxel_diffs.chunks.get(&chunk_pos).cloned();
-
-                // Generar BaseChunk asíncronamente
-                let task = thread_pool.spawn(async move {
-                    let mut base_chunk = BaseChunk::new(chunk_pos, seed);
-                    if let Some(diffs) = chunk_diffs {
-                        base_chunk.apply_diffs(&diffs);
-                    }
-                    let collider = build_chunk_collider(&base_chunk);
-                    (chunk_pos, base_chunk, collider)
-                });
+        if let Some(entity) = load_queue.to_convert_to_real.pop()
+            && let Ok(lod_chunk) = lod_query.get(entity)
+        {
+            let chunk_pos = lod_chunk.position;
+            let chunk_diffs = voxel_diffs.chunks.get(&chunk_pos).cloned();
+
+            // Generar BaseChunk asíncronamente
+            let task = thread_pool.spawn(async move {
+                let mut base_chunk = BaseChunk::new(chunk_pos, seed);
+                if let Some(diffs) = chunk_diffs {
+                    base_chunk.apply_diffs(&diffs);
+                }
+                let collider = build_chunk_collider(&base_chunk);
+                (chunk_pos, base_chunk, collider)
+            });
 
-                // Despawnear el LOD chunk y crear tarea de generación
-                commands.entity(entity).despawn();
+            // Despawnear el LOD chunk y crear tarea de generación
+            commands.entity(entity).despawn();
 
-                // Crear nueva entidad con la tarea
-                let new_entity = commands.spawn(ChunkGenerationTask { task, chunk_pos }).id();
+            // Crear nueva entidad con la tarea
+            let new_entity = commands.spawn(ChunkGenerationTask { task, chunk_pos }).id();
 
-                // Actualizar ChunkMap para que apunte a la nueva entidad
-                chunk_map.chunks.insert(chunk_pos, new_entity);
+            // Actualizar ChunkMap para que apunte a la nueva entidad
+            chunk_map.chunks.insert(chunk_pos, new_entity);
 
-                info!("Converting LOD → Real at {:?}", chunk_pos);
-            }
+            info!("Converting LOD → Real at {:?}", chunk_pos);
         }
     }
 }
 
 /// Sistema que ejecuta las conversiones Real → LOD
+#[allow(clippy::too_many_arguments)]
 pub fn convert_real_to_lod_system(
     mut commands: Commands,
     mut load_queue: ResMut<ChunkLoadQueue>,
@@ -716,58 +747,58 @@ pub fn convert_real_to_lod_system(
         .min(MAX_CHUNK_TRANSITIONS_PER_FRAME);
 
     for _ in 0..conversions_to_do {
-        if let Some(entity) = load_queue.to_convert_to_lod.pop() {
-            if let Ok(base_chunk) = base_query.get(entity) {
-                let chunk_pos = base_chunk.position;
-
-                // Solo la columna y=0 se convierte en LOD (heightmap con
-                // alturas absolutas que representa la columna entera); los
-                // demás niveles Y simplemente se descartan.
-                if chunk_pos.y != 0 {
-                    commands.entity(entity).despawn();
-                    chunk_map.chunks.remove(&chunk_pos);
-                    spatial_hash.remove(chunk_pos);
-                    continue;
-                }
+        if let Some(entity) = load_queue.to_convert_to_lod.pop()
+            && let Ok(base_chunk) = base_query.get(entity)
+        {
+            let chunk_pos = base_chunk.position;
 
-                // Calcular distancia al jugador para determinar nivel LOD
-                let delta = chunk_pos - load_queue.last_player_chunk;
-                let distance_chunks =
-                    ((delta.x * delta.x + delta.z * delta.z) as f32).sqrt() as i32;
-                let lod_level = LodLevel::from_distance(distance_chunks);
-
-                // Regenerar la superficie desde el noise: el volumen del chunk
-                // y=0 no contiene las montañas de los niveles superiores, así
-                // que extraerla de ahí aplanaría el terreno alto.
-                let mut lod_chunk = LodChunk::new(chunk_pos, lod_level);
-                let mut terrain_gen = TerrainGenerator::new(world_seed.0);
-                lod_chunk.generate_surface(&mut terrain_gen);
-                let mesh = mesh_lod_chunk(&lod_chunk, world_seed.0);
-
-                // Solo crear si el mesh tiene vértices
-                if mesh.count_vertices() > 0 {
-                    // Despawnear el BaseChunk
-                    commands.entity(entity).despawn();
-
-                    // Crear nuevo LOD chunk
-                    let new_entity = commands
-                        .spawn((
-                            Mesh3d(meshes.add(mesh)),
-                            MeshMaterial3d(chunk_materials.real_handle(ChunkLOD::Ultra)),
-                            Transform::default(),
-                            lod_chunk,
-                        ))
-                        .id();
+            // Solo la columna y=0 se convierte en LOD (heightmap con
+            // alturas absolutas que representa la columna entera); los
+            // demás niveles Y simplemente se descartan.
+            if chunk_pos.y != 0 {
+                commands.entity(entity).despawn();
+                chunk_map.chunks.remove(&chunk_pos);
+                spatial_hash.remove(chunk_pos);
+                continue;
+            }
+
+            // Calcular distancia al jugador más cercano para determinar nivel LOD
+            let distance_sq =
+                nearest_horizontal_distance_sq(chunk_pos, &load_queue.last_player_chunks);
+            let distance_chunks = (distance_sq as f32).sqrt() as i32;
+            let lod_level = LodLevel::from_distance(distance_chunks);
+
+            // Regenerar la superficie desde el noise: el volumen del chunk
+            // y=0 no contiene las montañas de los niveles superiores, así
+            // que extraerla de ahí aplanaría el terreno alto.
+            let mut lod_chunk = LodChunk::new(chunk_pos, lod_level);
+            let mut terrain_gen = TerrainGenerator::new(world_seed.0);
+            lod_chunk.generate_surface(&mut terrain_gen);
+            let mesh = mesh_lod_chunk(&lod_chunk, world_seed.0);
+
+            // Solo crear si el mesh tiene vértices
+            if mesh.count_vertices() > 0 {
+                // Despawnear el BaseChunk
+                commands.entity(entity).despawn();
 
-                    // Actualizar ChunkMap
-                    chunk_map.chunks.insert(chunk_pos, new_entity);
+                // Crear nuevo LOD chunk
+                let new_entity = commands
+                    .spawn((
+                        Mesh3d(meshes.add(mesh)),
+                        MeshMaterial3d(chunk_materials.real_handle(ChunkLOD::Ultra)),
+                        Transform::default(),
+                        lod_chunk,
+                    ))
+                    .id();
+
+                // Actualizar ChunkMap
+                chunk_map.chunks.insert(chunk_pos, new_entity);
 
-                    info!("Converting Real → LOD at {:?}", chunk_pos);
-                } else {
-                    // Mesh vacío, solo despawnear
-                    commands.entity(entity).despawn();
-                    chunk_map.chunks.remove(&chunk_pos);
-                }
+                info!("Converting Real → LOD at {:?}", chunk_pos);
+            } else {
+                // Mesh vacío, solo despawnear
+                commands.entity(entity).despawn();
+                chunk_map.chunks.remove(&chunk_pos);
             }
         }
     }
@@ -4,29 +4,34 @@
 
 use super::{
     greedy_meshing::greedy_mesh_basechunk,
-    tools::{Tool, ToolType},
-    BaseChunk, VoxelType, VOXEL_TYPE_COUNT,
+    tools::{fell_tree, DestructionShape, Tool, ToolTier, ToolType},
+    BaseChunk, Fuses, LootRng, VoxelReader, VoxelType, VoxelWorld, FUSE_SECS,
 };
 use crate::core::constants::{BASE_CHUNK_SIZE, VOXEL_SIZE};
+use crate::core::{DeterministicGame, Inventory};
+use crate::net::{ClientMessage, NetClient};
 use crate::{
     physics::{create_terrain_collider, spawn_rapier_voxel_drop, Collider, DropAssets},
-    player::components::Player,
+    player::{components::Player, PlayerActions},
 };
 use bevy::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // ============================================================================
 // COMPONENTS
 // ============================================================================
 
-/// Component que rastrea el progreso de destruccion de un voxel.
+/// Component que rastrea el progreso de destruccion de un voxel. Va en la
+/// entidad del jugador que lo rompe: cada jugador rompe lo suyo.
 #[derive(Component, Debug)]
 pub struct VoxelBreaking {
-    // Posicion del chunk que contiene el voxel.
-    pub chunk_pos: IVec3,
+    // Coordenada de voxel mundial del voxel apuntado.
+    pub voxel: IVec3,
 
-    // Posicion local del voxel dnetro del chunk (0-31).]
-    pub local_pos: IVec3,
+    // Cara golpeada y dirección de la cámara al empezar: orientan la forma de
+    // la herramienta (disco de la pala, túnel del pico).
+    pub normal: IVec3,
+    pub direction: Vec3,
 
     // Preogreso de destruccion (0.0 = intacto - 1.0 = roto).
     pub progress: f32,
@@ -54,6 +59,39 @@ pub struct DirtyChunk;
 pub struct VoxelDiffs {
     /// chunk_pos -> (local_pos -> voxel_type)
     pub chunks: HashMap<IVec3, HashMap<IVec3, VoxelType>>,
+
+    /// Chunks con diffs aún no guardados en disco (ver `world_save`).
+    dirty: HashSet<IVec3>,
+}
+
+impl VoxelDiffs {
+    /// Registra un voxel modificado y marca su chunk como pendiente de guardar.
+    pub fn record(&mut self, chunk_pos: IVec3, local_pos: IVec3, voxel_type: VoxelType) {
+        self.chunks
+            .entry(chunk_pos)
+            .or_default()
+            .insert(local_pos, voxel_type);
+        self.dirty.insert(chunk_pos);
+    }
+
+    /// Devuelve (y vacía) el conjunto de chunks modificados desde el último guardado.
+    pub fn take_dirty(&mut self) -> HashSet<IVec3> {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Olvida los diffs de un chunk (vuelve a ser terreno procedural) y lo
+    /// marca para guardar. Devuelve cuántos voxels tenía modificados.
+    pub fn discard_chunk(&mut self, chunk_pos: IVec3) -> usize {
+        let removed = self.chunks.remove(&chunk_pos).map_or(0, |voxels| voxels.len());
+        self.dirty.insert(chunk_pos);
+        removed
+    }
+
+    /// Borra todos los diffs (al destruir el mundo).
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.dirty.clear();
+    }
 }
 
 // ============================================================================
@@ -65,10 +103,11 @@ pub struct VoxelDiffs {
 /// # Parametros
 /// voxel_type: Tipo de voxel a romper
 /// tool_type: Heramienta siendo usada
+/// tier: Material de la herramienta (su velocidad)
 ///
 /// # Retorna
 /// Tiempo en segundos para romper el voxel.
-pub fn calculate_break_time(voxel_type: VoxelType, tool_type: ToolType) -> f32 {
+pub fn calculate_break_time(voxel_type: VoxelType, tool_type: ToolType, tier: ToolTier) -> f32 {
     // Ontener dureza del voxel
     let hardness = voxel_type.properties().hardness;
 
@@ -76,7 +115,7 @@ pub fn calculate_break_time(voxel_type: VoxelType, tool_type: ToolType) -> f32 {
     let effectiveness = tool_type.effectiveness_against(voxel_type);
 
     // Obtener multiplicador de velocidad de la herramienta
-    let speed = tool_type.properties().speed_multiplier;
+    let speed = tool_type.properties(tier).speed_multiplier;
 
     // Formula: tiempo_base * hardness / (effectiveness * speed)
     // Tiempo base 1 segundo
@@ -92,28 +131,56 @@ pub fn calculate_break_time(voxel_type: VoxelType, tool_type: ToolType) -> f32 {
 /// Convierte una posicion mundial a la posicion de chunk y posicion local.
 ///
 /// # Retorna
-/// (chunk_pos, local_pos, voxel_pos_in_chunk)
+/// (chunk_pos, local_pos, voxel_pos) — `voxel_pos` es la coordenada de voxel mundial
 pub fn world_to_voxel(world_pos: Vec3) -> (IVec3, IVec3, IVec3) {
     // Convertir a coordenadas de voxel
-    let voxel_x = (world_pos.x / VOXEL_SIZE).floor() as i32;
-    let voxel_y = (world_pos.y / VOXEL_SIZE).floor() as i32;
-    let voxel_z = (world_pos.z / VOXEL_SIZE).floor() as i32;
-
-    // Calcular la posicion del chunk
-    let chunk_x = voxel_x.div_euclid(BASE_CHUNK_SIZE as i32);
-    let chunk_y = voxel_y.div_euclid(BASE_CHUNK_SIZE as i32);
-    let chunk_z = voxel_z.div_euclid(BASE_CHUNK_SIZE as i32);
-
-    // Calcular la posicion local del voxel dentro del chunk
-    let local_x = voxel_x.rem_euclid(BASE_CHUNK_SIZE as i32);
-    let local_y = voxel_y.rem_euclid(BASE_CHUNK_SIZE as i32);
-    let local_z = voxel_z.rem_euclid(BASE_CHUNK_SIZE as i32);
-
-    (
-        IVec3::new(chunk_x, chunk_y, chunk_z),
-        IVec3::new(local_x, local_y, local_z),
-        IVec3::new(voxel_x, voxel_y, voxel_z),
-    )
+    let voxel = (world_pos / VOXEL_SIZE).floor().as_ivec3();
+
+    // Chunk y posicion local dentro del chunk
+    let (chunk_pos, local_pos) = voxel_to_chunk(voxel);
+
+    (chunk_pos, local_pos, voxel)
+}
+
+/// Separa una coordenada de voxel mundial en (chunk_pos, local_pos): la parte
+/// entera de `world_to_voxel`, para ediciones que ya trabajan en voxels.
+pub fn voxel_to_chunk(voxel: IVec3) -> (IVec3, IVec3) {
+    let size = BASE_CHUNK_SIZE as i32;
+    (voxel.div_euclid(IVec3::splat(size)), voxel.rem_euclid(IVec3::splat(size)))
+}
+
+/// Chunks cuyo mesh depende de un voxel: el suyo y, si el voxel está en un
+/// borde, el vecino del otro lado de cada cara tocada (su costura cambia).
+pub fn chunks_affected_by(chunk_pos: IVec3, local_pos: IVec3) -> Vec<IVec3> {
+    let last = BASE_CHUNK_SIZE as i32 - 1;
+    let mut affected = vec![chunk_pos];
+    for axis in 0..3 {
+        let mut offset = IVec3::ZERO;
+        if local_pos[axis] == 0 {
+            offset[axis] = -1;
+        } else if local_pos[axis] == last {
+            offset[axis] = 1;
+        } else {
+            continue;
+        }
+        affected.push(chunk_pos + offset);
+    }
+    affected
+}
+
+/// Marca como `DirtyChunk` los chunks dados que estén cargados. Para ediciones
+/// masivas: se acumulan los `chunks_affected_by` de cada voxel en un conjunto y
+/// se marca cada chunk una sola vez.
+pub fn mark_chunks_dirty(
+    commands: &mut Commands,
+    chunk_map: &ChunkMap,
+    chunk_positions: impl IntoIterator<Item = IVec3>,
+) {
+    for pos in chunk_positions {
+        if let Some(&entity) = chunk_map.chunks.get(&pos) {
+            commands.entity(entity).try_insert(DirtyChunk);
+        }
+    }
 }
 
 /// Realiza un raycast usando algoritmo DDA para detectar el voxel mas cercano.
@@ -125,19 +192,22 @@ pub fn world_to_voxel(world_pos: Vec3) -> (IVec3, IVec3, IVec3) {
 /// - origin: Punto de inicio del rayo (posicion de la camara)
 /// - direction: Direccion del rayo (direccion de la camara)  
 /// - max_distance: Distancia maxima del raycast (en metros)
-/// - chunk_map: Mapa de chunks disponibles
-/// - chunks: Query de todos los chunks en el mundo
+/// - get: Lectura del mundo por coordenada de voxel mundial (`None` = sin
+///   cargar); ver `VoxelReader::raycast`
 ///
 /// # Retorna
-/// Some((chunk_entity, chunk_pos, local_pos, voxel_type)) si encuentra un voxel solido
+/// Some((voxel, voxel_type, normal)) si encuentra un voxel solido, con `voxel`
+/// en coordenadas de voxel mundiales.
+/// `normal` es la cara por la que entró el rayo (el eje del último paso, hacia
+/// el origen): la celda `voxel + normal` es donde se coloca un bloque. Es
+/// `IVec3::ZERO` si el origen ya estaba dentro del voxel.
 /// None si no encuentra nada
 pub fn raycast_voxel(
     origin: Vec3,
     direction: Vec3,
     max_distance: f32,
-    chunk_map: &ChunkMap,
-    chunks: &Query<&BaseChunk>,
-) -> Option<(Entity, IVec3, IVec3, VoxelType)> {
+    get: impl Fn(IVec3) -> Option<VoxelType>,
+) -> Option<(IVec3, VoxelType, IVec3)> {
     let dir = direction.normalize();
 
     // Convertir origen a coordenadas de voxel
@@ -209,34 +279,16 @@ pub fn raycast_voxel(
 
     let max_steps = (max_distance / VOXEL_SIZE) as i32 + 1;
 
+    // Cara de entrada al voxel actual (opuesta al último paso)
+    let mut normal = IVec3::ZERO;
+
     // Algoritmo DDA principal
     for _ in 0..max_steps {
-        // Convertir posicion de voxel a chunk y posicion local
-        let (chunk_pos, local_pos, _) = world_to_voxel(Vec3::new(
-            voxel_pos.x as f32 * VOXEL_SIZE + VOXEL_SIZE * 0.5,
-            voxel_pos.y as f32 * VOXEL_SIZE + VOXEL_SIZE * 0.5,
-            voxel_pos.z as f32 * VOXEL_SIZE + VOXEL_SIZE * 0.5,
-        ));
-
-        // Verificar si tenemos este chunk
-        if let Some(&chunk_entity) = chunk_map.chunks.get(&chunk_pos) {
-            if let Ok(chunk) = chunks.get(chunk_entity) {
-                // Verificar limites del chunk
-                if local_pos.x >= 0
-                    && local_pos.x < BASE_CHUNK_SIZE as i32
-                    && local_pos.y >= 0
-                    && local_pos.y < BASE_CHUNK_SIZE as i32
-                    && local_pos.z >= 0
-                    && local_pos.z < BASE_CHUNK_SIZE as i32
-                {
-                    let voxel_type = chunk.voxel_types[local_pos.x as usize][local_pos.y as usize]
-                        [local_pos.z as usize];
-
-                    if voxel_type.is_solid() {
-                        return Some((chunk_entity, chunk_pos, local_pos, voxel_type));
-                    }
-                }
-            }
+        // Verificar si el voxel está cargado y es sólido
+        if let Some(voxel_type) = get(voxel_pos)
+            && voxel_type.is_solid()
+        {
+            return Some((voxel_pos, voxel_type, normal));
         }
 
         // Avanzar al siguiente voxel usando DDA
@@ -244,14 +296,17 @@ pub fn raycast_voxel(
             // Avanzar en X
             voxel_pos.x += step.x;
             t_max.x += t_delta.x;
+            normal = IVec3::new(-step.x, 0, 0);
         } else if t_max.y < t_max.z {
             // Avanzar en Y
             voxel_pos.y += step.y;
             t_max.y += t_delta.y;
+            normal = IVec3::new(0, -step.y, 0);
         } else {
             // Avanzar en Z
             voxel_pos.z += step.z;
             t_max.z += t_delta.z;
+            normal = IVec3::new(0, 0, -step.z);
         }
 
         // Verificar si hemos excedido la distancia maxima
@@ -270,81 +325,151 @@ pub fn raycast_voxel(
     None
 }
 
+/// Golpe que rompe: el voxel, la cara golpeada y la dirección de la mirada
+/// (orientan la forma de la herramienta, como en [`VoxelBreaking`]).
+#[derive(Debug, Clone, Copy)]
+pub struct BreakHit {
+    pub voxel: IVec3,
+    pub normal: IVec3,
+    pub direction: Vec3,
+}
+
+/// Rompe con `tool` el voxel golpeado: lo que cubre la forma de la
+/// herramienta, la madera conectada con el hacha o la tierra labrada con la
+/// azada. Lo explosivo enciende su mecha; el resto tira su tabla de botín si la herramienta lo
+/// puede cosechar. Devuelve los voxels cambiados y el botín sumado por tipo
+/// (indexado por `VoxelType::id`); soltarlo y desgastar la herramienta queda
+/// para quien llama (la partida local o el servidor).
+pub fn break_with_tool(
+    world: &mut VoxelWorld,
+    BreakHit {
+        voxel: center,
+        normal,
+        direction,
+    }: BreakHit,
+    tool: &Tool,
+    loot_rng: &mut LootRng,
+    fuses: &mut Fuses,
+) -> (usize, Vec<u32>) {
+    let (tool_type, tier) = (tool.tool_type, tool.tier);
+    let shape = tool_type.destruction_shape(tier);
+
+    // Voxels que cubre la forma, en coordenadas de voxel MUNDIALES:
+    // `VoxelWorld` resuelve cada uno en su chunk (el cráter no se corta en los
+    // bordes), lo registra y remalla cada chunk tocado una vez.
+    let hit = world.get(center);
+    let voxels: Vec<IVec3> = match shape {
+        // El hacha tala el árbol: la madera del mismo tipo que la golpeada
+        DestructionShape::Fell { max_voxels } if hit.is_some_and(|v| v.is_wood()) => fell_tree(
+            center,
+            max_voxels,
+            |v| world.get(v) == hit,
+            |v| world.get(v).is_some_and(|t| t.is_leaves()),
+        ),
+        // Fuera de su material, hacha y azada rompen solo el voxel
+        DestructionShape::Fell { .. } => vec![center],
+        DestructionShape::Till { .. } if !hit.is_some_and(|v| v.is_tillable()) => vec![center],
+        _ => shape
+            .offsets(direction, normal)
+            .into_iter()
+            .map(|offset| center + offset)
+            .collect(),
+    };
+
+    // La azada labra en lugar de romper: sin drops.
+    let tilling =
+        matches!(shape, DestructionShape::Till { .. }) && hit.is_some_and(|v| v.is_tillable());
+    // Cada voxel roto tira su tabla de botín: sumamos por tipo y se suelta un
+    // único drop por tipo con la cantidad total (evita cientos de cuerpos
+    // físicos cuando la forma es grande).
+    let mut drop_counts = vec![0u32; VoxelType::count()];
+    let broken = world.modify(voxels, |voxel, voxel_type| {
+        if tilling {
+            return voxel_type.is_tillable().then_some(VoxelType::Farmland);
+        }
+        // Solo destruir si es sólido
+        if !voxel_type.is_solid() {
+            return None;
+        }
+        // Lo explosivo no suelta nada: enciende su mecha
+        if let Some(blast) = voxel_type.explosive() {
+            fuses.light(voxel, blast, FUSE_SECS);
+            return Some(VoxelType::Air);
+        }
+        // Suelta lo que diga su botín si la herramienta lo puede cosechar.
+        if tool_type.can_harvest(tier, voxel_type)
+            && let Some((item, count)) = voxel_type.properties().loot.roll(tool_type, &mut loot_rng.0)
+        {
+            drop_counts[item.id() as usize] += count;
+        }
+        // Convertir a aire
+        Some(VoxelType::Air)
+    });
+    (broken, drop_counts)
+}
+
 // ============================================================================
 // BEVY SYSTEMS
 // ============================================================================
 
-/// Sistema que detecta cuando el jugador intenta romper un voxel.
+/// Lo que el inicio de la destrucción mira de cada jugador: su cámara (va en
+/// su entidad), herramienta, acciones y lo que ya esté rompiendo.
+type BreakerQuery<'a> = (
+    Entity,
+    &'a Transform,
+    &'a Tool,
+    &'a PlayerActions,
+    Option<&'a VoxelBreaking>,
+);
+
+/// Sistema que detecta cuando un jugador intenta romper un voxel.
 ///
-/// Solo se ejecuta cuando el jugador presiona el boton de romper.
+/// Solo empieza mientras el jugador mantiene el boton de romper (click
+/// izquierdo o gatillo derecho).
 pub fn start_voxel_breaking_system(
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    camera_query: Query<&Transform, With<Camera>>,
-    chunk_map: Res<ChunkMap>,
-    chunks: Query<&BaseChunk>,
-    player_query: Query<&Tool, With<Player>>,
+    world: VoxelReader,
+    player_query: Query<BreakerQuery, With<Player>>,
     mut commands: Commands,
-    mut breaking_query: Query<(Entity, &mut VoxelBreaking)>,
 ) {
-    // Solo ejecuta si preional el boton izquierdo
-    if !mouse_input.pressed(MouseButton::Left) {
+    for (player, camera_transform, tool, actions, breaking) in &player_query {
         // Si suelta el boton, cancelar destruccion en progreso
-        for (entity, _) in breaking_query.iter() {
-            commands.entity(entity).despawn();
+        if !actions.breaking {
+            if breaking.is_some() {
+                commands.entity(player).remove::<VoxelBreaking>();
+            }
+            continue;
         }
-        return;
-    }
 
-    // Obtener la camara (posicion y direccion)
-    let Ok(camera_transform) = camera_query.single() else {
-        return; // No hay camara.
-    };
+        // La camara (posicion y direccion) va en la entidad del jugador
+        let ray_origin = camera_transform.translation;
+        let ray_direction = camera_transform.forward().as_vec3();
+
+        // Hacer raycast para encontrar voxel
+        let Some((voxel, voxel_type, normal)) = world.raycast(
+            ray_origin,
+            ray_direction,
+            5.0, // Maximo 5 metros de distancia
+        ) else {
+            // No encontro nada, cnacelar destruccion
+            if breaking.is_some() {
+                commands.entity(player).remove::<VoxelBreaking>();
+            }
+            continue;
+        };
 
-    let ray_origin = camera_transform.translation;
-    let ray_direction = camera_transform.forward().as_vec3();
-
-    // Hacer raycast para encontrar voxel
-    let Some((_chunk_entity, chunk_pos, local_pos, voxel_type)) = raycast_voxel(
-        ray_origin,
-        ray_direction,
-        5.0, // Maximo 5 metros de distancia
-        &chunk_map,
-        &chunks,
-    ) else {
-        // No encontro nada, cnacelar destruccion
-        for (entity, _) in breaking_query.iter() {
-            commands.entity(entity).despawn();
+        // Si ya estamos rompiendo este voxel, no hacer nada; si miramos otro,
+        // el nuevo reemplaza al anterior
+        if breaking.is_some_and(|breaking| breaking.voxel == voxel) {
+            continue;
         }
-        return;
-    };
 
-    // Obtener herramienta del jugador
-    let tool_type = player_query
-        .single()
-        .map(|tool| tool.tool_type)
-        .unwrap_or(ToolType::None);
-
-    // Calcular tiempo de destruccion
-    let break_time = calculate_break_time(voxel_type, tool_type);
-
-    // Verificar si ya estamos rompiendo este voxel
-    let mut found_existing = false;
-    for (entity, breaking) in breaking_query.iter_mut() {
-        if breaking.chunk_pos == chunk_pos && breaking.local_pos == local_pos {
-            // Ya estamos rompiendo este voxel, no hacer nada
-            found_existing = true;
-            break;
-        } else {
-            // Estamos mirandop otro voxel, cancelar el anterior
-            commands.entity(entity).despawn();
-        }
-    }
+        // Calcular tiempo de destruccion con la herramienta del jugador
+        let break_time = calculate_break_time(voxel_type, tool.tool_type, tool.tier);
 
-    // Si no existe crear nuevo componente de destruccion
-    if !found_existing {
-        commands.spawn(VoxelBreaking {
-            chunk_pos,
-            local_pos,
+        commands.entity(player).insert(VoxelBreaking {
+            voxel,
+            normal,
+            direction: ray_direction,
             progress: 0.0,
             break_time,
         });
@@ -353,135 +478,80 @@ pub fn start_voxel_breaking_system(
 
 /// Sistema que actualiza el progreso de destruccion de voxels.
 ///
-/// Se ejecuta cada frame para actualizar el progreso.
+/// Se ejecuta cada frame para actualizar el progreso de cada jugador; lo roto
+/// gasta la herramienta de quien lo rompe. En red solo se avisa al servidor de
+/// qué se rompió y con qué slot: él decide el cráter, el botín y el desgaste, y
+/// el resultado vuelve en un `VoxelDelta` y en el inventario.
+#[allow(clippy::too_many_arguments)]
 pub fn update_voxel_breaking_system(
     time: Res<Time>,
-    mut breaking_query: Query<(Entity, &mut VoxelBreaking)>,
-    mut chunks: Query<&mut BaseChunk>,
-    chunk_map: Res<ChunkMap>,
+    mut breaking_query: Query<(Entity, &mut VoxelBreaking, &Tool, &mut Inventory), With<Player>>,
+    mut world: VoxelWorld,
     mut commands: Commands,
-    mut player_query: Query<&mut Tool, With<Player>>,
-    mut voxel_diffs: ResMut<VoxelDiffs>,
+    // Drops: assets compartidos y azar del botín; mechas de la TNT rota
     drop_assets: Res<DropAssets>,
+    mut loot_rng: ResMut<LootRng>,
+    mut fuses: ResMut<Fuses>,
+    mut net: Option<ResMut<NetClient>>,
 ) {
-    for (entity, mut breaking) in breaking_query.iter_mut() {
+    for (player, mut breaking, tool, mut inventory) in breaking_query.iter_mut() {
         // Actualizar preogreso basado en tiempo
         breaking.progress += time.delta_secs() / breaking.break_time;
+        if breaking.progress < 1.0 {
+            continue;
+        }
+        // Llegó a 100%: romper y eliminar el componente de destruccion
+        commands.entity(player).remove::<VoxelBreaking>();
+
+        if let Some(net) = net.as_mut() {
+            net.request(ClientMessage::Break {
+                voxel: breaking.voxel,
+                normal: breaking.normal,
+                direction: breaking.direction,
+                slot: inventory.selected as u8,
+            });
+            continue;
+        }
 
-        // Si llego a 100%, romper el voxel
-        if breaking.progress >= 1.0 {
-            // Obtener el chunk
-            if let Some(&chunk_entity) = chunk_map.chunks.get(&breaking.chunk_pos) {
-                // Primero modificar el chunk
-                let broken_voxel_type = if let Ok(mut chunk) = chunks.get_mut(chunk_entity) {
-                    // Obtener herramienta para el patron de destruccion
-                    let tool_type = player_query
-                        .single()
-                        .map(|tool| tool.tool_type)
-                        .unwrap_or(ToolType::None);
-                    let destruction_pattern = tool_type.get_destruction_pattern();
-
-                    // Destruir multiples voxels segun el patron. Un voxel roto =
-                    // un item: contamos por tipo y soltamos un único drop por
-                    // tipo con la cantidad exacta (evita cientos de cuerpos
-                    // físicos cuando el patrón es grande).
-                    let mut drop_counts = [0u32; VOXEL_TYPE_COUNT];
-                    for offset in destruction_pattern {
-                        let target_x = (breaking.local_pos.x + offset.x) as usize;
-                        let target_y = (breaking.local_pos.y + offset.y) as usize;
-                        let target_z = (breaking.local_pos.z + offset.z) as usize;
-
-                        // Verificar limites del chunk
-                        if target_x < BASE_CHUNK_SIZE
-                            && target_y < BASE_CHUNK_SIZE
-                            && target_z < BASE_CHUNK_SIZE
-                        {
-                            let voxel_type = chunk.voxel_types[target_x][target_y][target_z];
-
-                            // Solo destruir si es sólido
-                            if voxel_type.is_solid() {
-                                // Convertir a aire
-                                chunk.voxel_types[target_x][target_y][target_z] = VoxelType::Air;
-
-                                voxel_diffs
-                                    .chunks
-                                    .entry(breaking.chunk_pos)
-                                    .or_default()
-                                    .insert(
-                                        IVec3::new(
-                                            target_x as i32,
-                                            target_y as i32,
-                                            target_z as i32,
-                                        ),
-                                        VoxelType::Air,
-                                    );
-
-                                // Follaje/arbustos no sueltan nada.
-                                if !matches!(voxel_type, VoxelType::Foliage | VoxelType::Bush) {
-                                    drop_counts[voxel_type as usize] += 1;
-                                }
-                            }
-                        }
-                    }
-
-                    // Un drop por tipo, en el centro del cráter, con la cantidad
-                    // exacta de voxels rotos de ese tipo.
-                    let center = Vec3::new(
-                        (breaking.chunk_pos.x * BASE_CHUNK_SIZE as i32 + breaking.local_pos.x)
-                            as f32
-                            * VOXEL_SIZE,
-                        (breaking.chunk_pos.y * BASE_CHUNK_SIZE as i32 + breaking.local_pos.y)
-                            as f32
-                            * VOXEL_SIZE,
-                        (breaking.chunk_pos.z * BASE_CHUNK_SIZE as i32 + breaking.local_pos.z)
-                            as f32
-                            * VOXEL_SIZE,
-                    );
-                    let mut total_drops = 0;
-                    for (id, &count) in drop_counts.iter().enumerate() {
-                        if count == 0 {
-                            continue;
-                        }
-                        total_drops += count;
-                        spawn_rapier_voxel_drop(
-                            &mut commands,
-                            &drop_assets,
-                            VoxelType::from_u8(id as u8),
-                            count,
-                            center,
-                            time.elapsed_secs(),
-                        );
-                    }
-                    info!("Roto cráter: {} voxels", total_drops);
-                    Some(VoxelType::Air) // Retorna algo para que compile
-                } else {
-                    None
-                };
-
-                // Marcar el chunk como sucio (tras liberar el borrow mutable).
-                if let Some(_) = broken_voxel_type {
-                    // `remesh_dirty_chunks_system` lo remalla con presupuesto de
-                    // tiempo: coalesce varias roturas y saca el remallado +
-                    // collider (caro) del sistema de input.
-                    commands.entity(chunk_entity).insert(DirtyChunk);
-
-                    // Danar herramienta del jugador
-                    if let Ok(mut tool) = player_query.single_mut() {
-                        let broke = tool.damage(1); // 1 punto de durabilidad
-                        if broke {
-                            info!("Herramienta rota");
-                            // TODO: Cambiar a manos (ToolType::None) Tambien hacer que desaparesca la heramienta
-                        }
-                    }
-
-                    if let Some(voxel_type) = broken_voxel_type {
-                        info!("voxel roto {:?} en {:?}", voxel_type, breaking.local_pos);
-                    }
-                }
-            }
+        let center_voxel = breaking.voxel;
+        let target = BreakHit {
+            voxel: center_voxel,
+            normal: breaking.normal,
+            direction: breaking.direction,
+        };
+        let (broken, drop_counts) =
+            break_with_tool(&mut world, target, tool, &mut loot_rng, &mut fuses);
+        if broken == 0 {
+            continue;
+        }
 
-            // Eliminar el componente de destruccion
-            commands.entity(entity).despawn();
+        // Un drop por tipo, en el centro del cráter, con la cantidad que sumó
+        // el botín de ese tipo.
+        let center = center_voxel.as_vec3() * VOXEL_SIZE;
+        let mut total_drops = 0;
+        for (id, &count) in drop_counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            total_drops += count;
+            spawn_rapier_voxel_drop(
+                &mut commands,
+                &drop_assets,
+                VoxelType::from_u8(id as u8),
+                count,
+                center,
+                None,
+            );
+        }
+        info!(
+            "Roto cráter: {} voxels ({} items) en {:?}",
+            broken, total_drops, center_voxel
+        );
+
+        // Dañar la herramienta en la mano (1 punto de durabilidad); la que se
+        // rompe desaparece del inventario y queda a mano
+        if inventory.wear_held_tool(1) {
+            info!("Herramienta rota: {}", tool.tool_type.properties(tool.tier).name);
         }
     }
 }
@@ -489,11 +559,17 @@ pub fn update_voxel_breaking_system(
 /// Presupuesto de remallado por destrucción (ms/frame).
 const DIRTY_REMESH_BUDGET_MS: u64 = 4;
 
+/// Chunks remallados por frame con [`DeterministicGame`], donde el presupuesto
+/// no puede depender del reloj real.
+pub const DETERMINISTIC_REMESH_CHUNKS: usize = 8;
+
 /// Remalla los chunks marcados como `DirtyChunk` tras romper voxels.
 ///
 /// Saca el remallado + collider (caro) del sistema de input y lo acota por
 /// tiempo: varias roturas en el mismo chunk/frame se coalescen en un remallado,
 /// y las ráfagas (herramientas rápidas, patrones) no causan un pico de frame.
+/// Con [`DeterministicGame`] el presupuesto es fijo: los
+/// [`DETERMINISTIC_REMESH_CHUNKS`] primeros en orden de posición.
 pub fn remesh_dirty_chunks_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -501,12 +577,26 @@ pub fn remesh_dirty_chunks_system(
     chunks: Query<&BaseChunk>,
     chunk_map: Res<ChunkMap>,
     mut mesh_query: Query<&mut Mesh3d>,
+    deterministic: Option<Res<DeterministicGame>>,
 ) {
     let start = std::time::Instant::now();
 
-    for entity in dirty.iter() {
-        if start.elapsed() >= std::time::Duration::from_millis(DIRTY_REMESH_BUDGET_MS) {
-            break; // El resto conserva DirtyChunk y se remalla en frames siguientes
+    // En orden de posición: el de la query sale del orden en que se marcaron
+    // (un `HashSet`), y Rapier recibiría los colliders cada vez en otro orden
+    let mut dirty: Vec<(Entity, Option<[i32; 3]>)> = dirty
+        .iter()
+        .map(|entity| (entity, chunks.get(entity).ok().map(|c| c.position.to_array())))
+        .collect();
+    dirty.sort_by_key(|&(_, position)| position);
+
+    for (done, (entity, _)) in dirty.into_iter().enumerate() {
+        // El resto conserva DirtyChunk y se remalla en frames siguientes
+        let over_budget = match deterministic {
+            Some(_) => done >= DETERMINISTIC_REMESH_CHUNKS,
+            None => start.elapsed() >= std::time::Duration::from_millis(DIRTY_REMESH_BUDGET_MS),
+        };
+        if over_budget {
+            break;
         }
 
         // Si ya no es un BaseChunk (despawneado / convertido a LOD), quitar marca
@@ -526,10 +616,63 @@ pub fn remesh_dirty_chunks_system(
             commands.entity(entity).remove::<Collider>();
         }
 
+        // Un chunk vacío materializado por `VoxelWorld` aún no tiene mesh
+        let new_mesh = Mesh3d(meshes.add(new_mesh));
         if let Ok(mut mesh3d) = mesh_query.get_mut(entity) {
-            *mesh3d = Mesh3d(meshes.add(new_mesh));
+            *mesh3d = new_mesh;
+        } else {
+            commands.entity(entity).insert(new_mesh);
         }
 
         commands.entity(entity).remove::<DirtyChunk>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voxels_resolve_to_their_own_chunk() {
+        let n = BASE_CHUNK_SIZE as i32;
+        assert_eq!(voxel_to_chunk(IVec3::new(0, 0, 0)), (IVec3::ZERO, IVec3::ZERO));
+        assert_eq!(
+            voxel_to_chunk(IVec3::new(-1, n, 2 * n + 3)),
+            (IVec3::new(-1, 1, 2), IVec3::new(n - 1, 0, 3))
+        );
+        // world_to_voxel usa la misma resolución (centro del voxel -1 en X)
+        let (chunk_pos, local_pos, voxel) = world_to_voxel(Vec3::new(-0.05, 0.05, 0.05));
+        assert_eq!(voxel, IVec3::new(-1, 0, 0));
+        assert_eq!((chunk_pos, local_pos), voxel_to_chunk(voxel));
+    }
+
+    #[test]
+    fn border_voxels_dirty_their_neighbors() {
+        let last = BASE_CHUNK_SIZE as i32 - 1;
+        let chunk = IVec3::new(2, 0, -1);
+        assert_eq!(chunks_affected_by(chunk, IVec3::new(5, 5, 5)), vec![chunk]);
+        let corner = chunks_affected_by(chunk, IVec3::new(0, last, 0));
+        assert_eq!(
+            corner,
+            vec![chunk, chunk + IVec3::NEG_X, chunk + IVec3::Y, chunk + IVec3::NEG_Z]
+        );
+    }
+
+    #[test]
+    fn raycast_reports_hit_and_entry_face() {
+        // Suelo de piedra en voxel y = 0, todo lo demás aire
+        let get = |v: IVec3| Some(if v.y == 0 { VoxelType::Stone } else { VoxelType::Air });
+        let origin = Vec3::new(0.05, 0.55, 0.05);
+
+        let (voxel, voxel_type, normal) = raycast_voxel(origin, Vec3::NEG_Y, 5.0, get).unwrap();
+        assert_eq!((voxel, voxel_type, normal), (IVec3::ZERO, VoxelType::Stone, IVec3::Y));
+
+        // Hacia arriba no hay nada
+        assert!(raycast_voxel(origin, Vec3::Y, 5.0, get).is_none());
+
+        // Pared en x = 3: se entra por su cara -X
+        let wall = |v: IVec3| Some(if v.x == 3 { VoxelType::Dirt } else { VoxelType::Air });
+        let hit = raycast_voxel(origin, Vec3::X, 5.0, wall).unwrap();
+        assert_eq!((hit.0.x, hit.2), (3, IVec3::NEG_X));
+    }
+}
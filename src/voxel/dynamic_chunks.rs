@@ -1,14 +1,22 @@
 //! Sistema de chunks dinamicos
 //! Chunks base de 32³ con generacion de terreno optimizada
-//! Incluye sistema de biomas con montañas, valles, llanuras, etc.
+//! Incluye sistema de biomas con montañas, valles, llanuras, desiertos, etc.
 
 use crate::core::{BASE_CHUNK_SIZE, VOXEL_SIZE};
 use crate::vegetation::trees::place_trees;
-use crate::voxel::{TerrainGenerator, VoxelType};
+use crate::voxel::{Biome, BiomeGenerator, TerrainColumn, TerrainGenerator, VoxelType};
 use bevy::prelude::*;
 use rayon::prelude::*;
 use std::collections::HashMap;
 
+/// Voxels extra muestreados sobre el chunk para clasificar su superficie
+/// (pasto/tierra dependen de cuánto sólido hay encima).
+const SURFACE_SCAN: usize = 6;
+
+/// Bajo esta profundidad (metros bajo el heightmap) el material ya no depende
+/// de lo que haya encima: todo es piedra, también alrededor de las cuevas.
+const SURFACE_LAYERS_DEPTH: f32 = 0.8;
+
 /// Chunk base de 32³ (usa heap para evitar stack overflow)
 #[derive(Component)]
 pub struct BaseChunk {
@@ -31,6 +39,17 @@ impl BaseChunk {
         }
     }
 
+    /// Chunk todo aire, sin generar terreno: para materializar un `EmptyChunk`
+    /// al construir dentro de él.
+    pub fn empty(position: IVec3) -> Self {
+        Self {
+            voxel_types: Box::new(
+                [[[VoxelType::Air; BASE_CHUNK_SIZE]; BASE_CHUNK_SIZE]; BASE_CHUNK_SIZE],
+            ),
+            position,
+        }
+    }
+
     pub fn new(position: IVec3, seed: i32) -> Self {
         let mut chunk = Self {
             voxel_types: Box::new(
@@ -44,69 +63,115 @@ impl BaseChunk {
         chunk
     }
 
-    /// Un voxel es sólido si no es aire. Reemplaza a `get_density() <= 0.0`:
+    /// Un voxel es sólido si no es aire ni líquido. Reemplaza a `get_density() <= 0.0`:
     /// `voxel_types` ya contiene exactamente esa información.
     pub fn is_solid(&self, x: usize, y: usize, z: usize) -> bool {
-        self.voxel_types[x][y][z] != VoxelType::Air
+        self.voxel_types[x][y][z].is_solid()
     }
 
     /// Generación de terreno con biomas
     /// Combina FastNoiseLite + Rayon + Sistema de Biomas
-    /// Optimizado: calcula heightmap una vez por columna XZ (33x33 = 1,089 evaluaciones)
-    /// en lugar de por cada voxel (33³ = 35,937 evaluaciones)
+    /// Optimizado: el heightmap se calcula una vez por columna XZ y la densidad
+    /// 3D (salientes/cuevas) solo evalúa ruido 3D cerca de la superficie o bajo ella
     pub fn generate_terrain(&mut self, seed: i32) {
-        let chunk_pos = self.position;
+        let n = BASE_CHUNK_SIZE;
+        let origin = self.position * n as i32;
 
         // Crear generador de terreno UNA VEZ para todo el chunk
-        let mut terrain_gen = TerrainGenerator::new(seed);
+        let terrain_gen = TerrainGenerator::new(seed);
+        let biome_gen = &terrain_gen.biome_gen;
 
-        // Paso 1: Calcular heightmap 2D (solo XZ, una vez por columna)
-        let grid = BASE_CHUNK_SIZE + 1;
+        // Paso 1: heightmap 2D con un borde de 1 columna (-1..=n) para la
+        // pendiente por diferencias centrales → columna (altura + acantilado).
+        let grid = n + 2;
         let mut heightmap = vec![0.0f32; grid * grid];
-
-        for z in 0..grid {
-            for x in 0..grid {
-                let world_x = (chunk_pos.x * BASE_CHUNK_SIZE as i32 + x as i32) as f32 * VOXEL_SIZE;
-                let world_z = (chunk_pos.z * BASE_CHUNK_SIZE as i32 + z as i32) as f32 * VOXEL_SIZE;
-                heightmap[x + z * grid] = terrain_gen.biome_gen.generate_height(world_x, world_z);
+        for gz in 0..grid {
+            for gx in 0..grid {
+                let world_x = (origin.x + gx as i32 - 1) as f32 * VOXEL_SIZE;
+                let world_z = (origin.z + gz as i32 - 1) as f32 * VOXEL_SIZE;
+                heightmap[gx + gz * grid] = biome_gen.generate_height(world_x, world_z);
             }
         }
+        let columns: Vec<TerrainColumn> = (0..n * n)
+            .map(|i| {
+                let (gx, gz) = (i % n + 1, i / n + 1);
+                let h = |x: usize, z: usize| heightmap[x + z * grid];
+                BiomeGenerator::column_from_heights(
+                    h(gx, gz),
+                    [h(gx - 1, gz), h(gx + 1, gz), h(gx, gz - 1), h(gx, gz + 1)],
+                )
+            })
+            .collect();
+        // Bioma de cada columna (superficie + densidad de pasto).
+        let biomes: Vec<Biome> = (0..n * n)
+            .map(|i| {
+                let world_x = (origin.x + (i % n) as i32) as f32 * VOXEL_SIZE;
+                let world_z = (origin.z + (i / n) as i32) as f32 * VOXEL_SIZE;
+                biome_gen.biome_with_height(world_x, world_z, columns[i].height)
+            })
+            .collect();
 
-        // Paso 2: Calcular tipos de voxel en paralelo a partir de la profundidad.
-        // Se clasifican por PROFUNDIDAD bajo la superficie (pasto/tierra/piedra),
-        // no por altura absoluta, para que la superficie siempre sea excavable.
-        let heightmap_ref = &heightmap;
-        let voxel_types_flat: Vec<VoxelType> = (0..BASE_CHUNK_SIZE.pow(3))
+        // Paso 2: densidad 3D en paralelo. Se calculan SURFACE_SCAN voxels extra
+        // por encima del chunk para saber cuánta tierra hay sobre cada voxel.
+        let height = n + SURFACE_SCAN;
+        let columns_ref = &columns;
+        let density: Vec<f32> = (0..n * height * n)
             .into_par_iter()
             .map(|idx| {
-                let x = idx % BASE_CHUNK_SIZE;
-                let y = (idx / BASE_CHUNK_SIZE) % BASE_CHUNK_SIZE;
-                let z = idx / (BASE_CHUNK_SIZE * BASE_CHUNK_SIZE);
-
-                let world_y = (chunk_pos.y * BASE_CHUNK_SIZE as i32 + y as i32) as f32 * VOXEL_SIZE;
-                let terrain_height = heightmap_ref[x + z * grid];
-                let depth = terrain_height - world_y;
-
-                VoxelType::from_depth(depth, depth)
+                let x = idx % n;
+                let y = (idx / n) % height;
+                let z = idx / (n * height);
+                let voxel = origin + IVec3::new(x as i32, y as i32, z as i32);
+                biome_gen.density(voxel, &columns_ref[x + z * n])
             })
             .collect();
 
-        // Copiar tipos de voxel
-        for (idx, voxel_type) in voxel_types_flat.iter().enumerate() {
-            let x = idx % BASE_CHUNK_SIZE;
-            let y = (idx / BASE_CHUNK_SIZE) % BASE_CHUNK_SIZE;
-            let z = idx / (BASE_CHUNK_SIZE * BASE_CHUNK_SIZE);
-            self.voxel_types[x][y][z] = *voxel_type;
+        // Paso 3: material por columna, de arriba abajo. Cerca de la superficie
+        // manda la tierra que hay ENCIMA (un saliente lleva pasto arriba y
+        // tierra/piedra debajo); en profundidad, la distancia al heightmap
+        // (techos y suelos de cuevas son de piedra, no de pasto). Qué capas hay
+        // (pasto, arena, nieve, grava...) lo decide el bioma de la columna; el
+        // aire de superficie bajo el nivel del mar es agua y el de las cuevas
+        // hondas, lava.
+        for z in 0..n {
+            for x in 0..n {
+                let column = &columns[x + z * n];
+                let biome = biomes[x + z * n];
+                let mut solid_above = 0usize;
+                for y in (0..height).rev() {
+                    let d = density[x + y * n + z * n * height];
+                    if y < n {
+                        let world_y = (origin.y + y as i32) as f32 * VOXEL_SIZE;
+                        let hm_depth = column.height - world_y;
+                        let depth = if hm_depth > SURFACE_LAYERS_DEPTH {
+                            hm_depth
+                        } else {
+                            solid_above as f32 * VOXEL_SIZE
+                        };
+                        self.voxel_types[x][y][z] =
+                            if d <= 0.0 && BiomeGenerator::is_flooded(world_y, column) {
+                                VoxelType::Water
+                            } else if d <= 0.0 && BiomeGenerator::is_lava_pool(world_y, column) {
+                                VoxelType::Lava
+                            } else {
+                                biome.surface_material(d, depth, column.slope)
+                            };
+                    }
+                    solid_above = if d > 0.0 { solid_above + 1 } else { 0 };
+                }
+            }
         }
 
-        // Paso 3: decoración determinista — estampar árboles/arbustos.
+        // Paso 4: decoración determinista — estampar árboles/arbustos.
         if crate::vegetation::config::ENABLE_TREES || crate::vegetation::config::ENABLE_BUSHES {
-            place_trees(self, &mut terrain_gen.biome_gen, seed);
+            place_trees(self, biome_gen, seed);
         }
 
-        // Paso 4: pasto denso (follaje atravesable) sobre las columnas de pasto.
+        // Paso 5: pasto denso (follaje atravesable) sobre las columnas de pasto,
+        // con la densidad del bioma de cada columna.
         if crate::vegetation::config::ENABLE_GRASS {
-            crate::vegetation::grass::place_grass(self, seed);
+            let grass_density: Vec<f32> = biomes.iter().map(|b| b.grass_density()).collect();
+            crate::vegetation::grass::place_grass(self, &grass_density, seed);
         }
     }
 }
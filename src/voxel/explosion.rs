@@ -0,0 +1,269 @@
+//! Explosiones: daño radial al terreno y empuje a lo que hay alrededor.
+//!
+//! Cualquier sistema pide una explosión escribiendo un [`Explosion`]. Arranca
+//! los voxels sólidos cuya dureza no aguanta la fuerza que les llega (máxima en
+//! el centro, nula en el borde del radio), todos en UN lote de `VoxelWorld`: se
+//! guardan en `VoxelDiffs` y cada chunk tocado se remalla una sola vez. Una
+//! fracción de lo arrancado suelta su botín como drops, y los drops y el
+//! jugador dentro del radio salen despedidos.
+//!
+//! Los materiales con `explosive` en el archivo de materiales (la TNT) encienden
+//! una mecha ([`Fuses`]) al romperse, al arder en la lava o al alcanzarlos otra
+//! explosión (en cadena, con un pequeño retardo para que se vea la onda).
+
+use super::{sphere_voxels, world_to_voxel, LootRng, ToolType, VoxelType, VoxelWorld};
+use crate::core::constants::VOXEL_SIZE;
+use crate::physics::rapier_integration::RapierVoxelDrop;
+use crate::physics::{spawn_rapier_voxel_drop, DropAssets};
+use crate::player::components::Player;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{RigidBodyDisabled, Velocity};
+use rand::Rng;
+use serde::Deserialize;
+
+// ============================================================================
+// PARÁMETROS
+// ============================================================================
+
+/// Fracción de los voxels arrancados que suelta su botín.
+pub const EXPLOSION_DROP_FRACTION: f64 = 0.1;
+
+/// Velocidad (m/s) que da cada punto de `power` a un cuerpo en el centro; se
+/// reduce linealmente hasta 0 en el borde del radio.
+const KNOCKBACK_PER_POWER: f32 = 1.5;
+
+/// Velocidad con la que salen los drops de una explosión (m/s).
+const DROP_SPEED: f32 = 4.0;
+
+/// Mecha de la TNT rota o encendida por la lava (segundos).
+pub const FUSE_SECS: f32 = 0.5;
+
+/// Retardo entre una explosión y la TNT que alcanza (segundos).
+const CHAIN_FUSE_SECS: f32 = 0.15;
+
+/// Cuerpos que empuja una explosión: drops sueltos y el jugador.
+type Pushable = (
+    Or<(With<RapierVoxelDrop>, With<Player>)>,
+    Without<RigidBodyDisabled>,
+);
+
+// ============================================================================
+// MESSAGES / RESOURCES
+// ============================================================================
+
+/// Una explosión: arranca terreno y empuja cuerpos en `radius` metros
+/// alrededor de `center` (posición mundial). `power` se compara con la dureza
+/// de cada voxel (ver [`blasted_voxels`]).
+#[derive(Message, Debug, Clone, Copy, PartialEq)]
+pub struct Explosion {
+    pub center: Vec3,
+    pub radius: f32,
+    pub power: f32,
+}
+
+/// Carga de un material explosivo (`explosive` en el archivo de materiales).
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Blast {
+    /// Radio en metros.
+    pub radius: f32,
+    /// Fuerza en el centro (en unidades de dureza).
+    pub power: f32,
+}
+
+impl Explosion {
+    /// La explosión de un voxel explosivo, centrada en él.
+    pub fn at_voxel(voxel: IVec3, blast: Blast) -> Self {
+        Self {
+            center: (voxel.as_vec3() + Vec3::splat(0.5)) * VOXEL_SIZE,
+            radius: blast.radius,
+            power: blast.power,
+        }
+    }
+}
+
+/// Mechas encendidas: explosiones que saltan cuando se les acaba el tiempo.
+#[derive(Resource, Debug, Default)]
+pub struct Fuses {
+    lit: Vec<(f32, Explosion)>,
+}
+
+impl Fuses {
+    /// Enciende la carga del voxel `voxel`: explota dentro de `secs` segundos.
+    pub fn light(&mut self, voxel: IVec3, blast: Blast, secs: f32) {
+        self.lit.push((secs, Explosion::at_voxel(voxel, blast)));
+    }
+
+    /// Apaga todas las mechas (al destruir el mundo).
+    pub fn clear(&mut self) {
+        self.lit.clear();
+    }
+}
+
+// ============================================================================
+// REGLAS (funciones puras)
+// ============================================================================
+
+/// Voxels que arranca una explosión, con su tipo. Un voxel sólido a `d` metros
+/// del centro recibe `power * (1 - d / radius)` y cae si eso alcanza su
+/// dureza: la tierra se va lejos, la piedra solo cerca y el metal casi nunca.
+/// Los líquidos y lo que no está cargado se quedan.
+pub fn blasted_voxels(
+    explosion: &Explosion,
+    get: impl Fn(IVec3) -> Option<VoxelType>,
+) -> Vec<(IVec3, VoxelType)> {
+    let (_, _, center) = world_to_voxel(explosion.center);
+    sphere_voxels(center, explosion.radius / VOXEL_SIZE)
+        .filter_map(|voxel| {
+            let voxel_type = get(voxel).filter(|v| v.is_solid())?;
+            let distance = ((voxel.as_vec3() + Vec3::splat(0.5)) * VOXEL_SIZE)
+                .distance(explosion.center);
+            let strength = explosion.power * (1.0 - distance / explosion.radius);
+            (strength >= voxel_type.properties().hardness).then_some((voxel, voxel_type))
+        })
+        .collect()
+}
+
+/// Velocidad que una explosión añade a un cuerpo en `position`: hacia fuera
+/// del centro (hacia arriba si está justo encima), nula fuera del radio.
+pub fn knockback(explosion: &Explosion, position: Vec3) -> Vec3 {
+    let offset = position - explosion.center;
+    let falloff = 1.0 - offset.length() / explosion.radius;
+    if falloff <= 0.0 {
+        return Vec3::ZERO;
+    }
+    offset.normalize_or(Vec3::Y) * explosion.power * KNOCKBACK_PER_POWER * falloff
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Consume las mechas y escribe la explosión de las que se acaban.
+pub fn burn_fuses_system(
+    time: Res<Time>,
+    mut fuses: ResMut<Fuses>,
+    mut explosions: MessageWriter<Explosion>,
+) {
+    let delta = time.delta_secs();
+    fuses.lit.retain_mut(|(secs, explosion)| {
+        *secs -= delta;
+        if *secs > 0.0 {
+            return true;
+        }
+        explosions.write(*explosion);
+        false
+    });
+}
+
+/// Aplica las explosiones: arranca el terreno en un lote, suelta parte del
+/// botín, empuja drops y jugador y enciende la TNT alcanzada.
+///
+/// Sin `DropAssets` (el servidor headless) el botín se tira igual, para que el
+/// `LootRng` avance como en el juego, pero no se suelta.
+pub fn explosion_system(
+    mut commands: Commands,
+    mut explosions: MessageReader<Explosion>,
+    mut world: VoxelWorld,
+    mut fuses: ResMut<Fuses>,
+    drop_assets: Option<Res<DropAssets>>,
+    mut loot_rng: ResMut<LootRng>,
+    mut bodies: Query<(&Transform, &mut Velocity), Pushable>,
+) {
+    for explosion in explosions.read() {
+        let blasted = blasted_voxels(explosion, |v| world.get(v));
+        let mut drop_counts = vec![0u32; VoxelType::count()];
+        for &(voxel, voxel_type) in &blasted {
+            if let Some(blast) = voxel_type.explosive() {
+                fuses.light(voxel, blast, CHAIN_FUSE_SECS);
+            } else if loot_rng.0.gen_bool(EXPLOSION_DROP_FRACTION)
+                && let Some((item, count)) =
+                    voxel_type.properties().loot.roll(ToolType::None, &mut loot_rng.0)
+            {
+                drop_counts[item.id() as usize] += count;
+            }
+        }
+        world.set_batch(blasted.iter().map(|&(voxel, _)| (voxel, VoxelType::Air)));
+
+        // Un drop por tipo, lanzado hacia fuera y hacia arriba
+        for (id, &count) in drop_counts.iter().enumerate() {
+            let Some(drop_assets) = drop_assets.as_deref().filter(|_| count > 0) else {
+                continue;
+            };
+            let direction = Vec3::new(
+                loot_rng.0.gen_range(-1.0..1.0),
+                1.0,
+                loot_rng.0.gen_range(-1.0..1.0),
+            )
+            .normalize();
+            spawn_rapier_voxel_drop(
+                &mut commands,
+                drop_assets,
+                VoxelType::from_u8(id as u8),
+                count,
+                explosion.center,
+                Some(direction * DROP_SPEED),
+            );
+        }
+
+        for (transform, mut velocity) in &mut bodies {
+            velocity.linvel += knockback(explosion, transform.translation);
+        }
+        info!(
+            "Explosión en {:?}: {} voxels arrancados",
+            explosion.center,
+            blasted.len()
+        );
+    }
+}
+
+/// Apaga las mechas al destruir el mundo.
+pub fn reset_fuses(mut fuses: ResMut<Fuses>) {
+    fuses.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blast_strength_is_weighed_against_hardness() {
+        // Todo cargado: tierra arriba (y >= 0), piedra abajo, y un metal y un
+        // agua junto al centro
+        let get = |v: IVec3| {
+            Some(match v {
+                IVec3 { x: 1, y: 0, z: 0 } => VoxelType::Metal,
+                IVec3 { x: -1, y: 0, z: 0 } => VoxelType::Water,
+                v if v.y >= 0 => VoxelType::Dirt,
+                _ => VoxelType::Stone,
+            })
+        };
+        let explosion = Explosion::at_voxel(IVec3::ZERO, Blast { radius: 1.0, power: 8.0 });
+        let blasted = blasted_voxels(&explosion, get);
+        let hit = |v: IVec3| blasted.iter().any(|&(voxel, _)| voxel == v);
+
+        // Tierra (dureza 1) hasta ~0.87 m; piedra (5) solo hasta ~0.37 m
+        assert!(hit(IVec3::new(0, 8, 0)));
+        assert!(!hit(IVec3::new(0, -6, 0)));
+        assert!(hit(IVec3::new(0, -3, 0)));
+        // El metal (10) aguanta y el agua no se arranca
+        assert!(!hit(IVec3::new(1, 0, 0)));
+        assert!(!hit(IVec3::new(-1, 0, 0)));
+        // Nada fuera del radio
+        assert!(blasted.iter().all(|&(v, _)| v.as_vec3().length() <= 10.0));
+    }
+
+    #[test]
+    fn knockback_pushes_outward_and_fades_with_distance() {
+        let explosion = Explosion {
+            center: Vec3::ZERO,
+            radius: 2.0,
+            power: 4.0,
+        };
+        let near = knockback(&explosion, Vec3::new(0.5, 0.0, 0.0));
+        let far = knockback(&explosion, Vec3::new(1.5, 0.0, 0.0));
+        assert!(near.x > far.x && far.x > 0.0);
+        assert_eq!(knockback(&explosion, Vec3::new(0.0, 0.0, -3.0)), Vec3::ZERO);
+        // En el mismo centro, hacia arriba
+        assert!(knockback(&explosion, Vec3::ZERO).y > 0.0);
+    }
+}
@@ -0,0 +1,251 @@
+//! Materiales que caen: arena, grava y todo lo que tenga `falls` en el archivo
+//! de materiales.
+//!
+//! Como la lava, es un autómata celular sobre coordenadas de voxel MUNDIALES
+//! con celdas "activas": solo se miran las que cambiaron y la de encima (lo
+//! único que sostiene a un voxel que cae es lo que tiene debajo). Cada tick un
+//! voxel que cae sin nada sólido debajo baja una celda, intercambiándose con el
+//! aire o el líquido que había ([`fall_step`]). Lo despierta cualquier edición
+//! del mundo vía `VoxelEdited` (romper, colocar, la lava, un escombro que
+//! vuelve al mundo); el terreno recién generado NO se despierta, así los
+//! salientes de arena del mundo se quedan como están hasta que alguien los toca.
+//!
+//! Un derrumbe grande (muchas columnas vecinas cayendo a la vez) no baja celda a
+//! celda: se saca del mundo y cae como escombro de `structural` (cuerpo rígido
+//! que al asentarse vuelve a ser voxels). Todo pasa por `VoxelWorld`, así que el
+//! resultado queda en `VoxelDiffs` y se guarda con el mundo.
+
+use super::{spawn_debris, DebrisAssets, VoxelEdited, VoxelType, VoxelWorld, MAX_ISLAND_VOXELS};
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+// ============================================================================
+// PARÁMETROS
+// ============================================================================
+
+/// Segundos entre ticks (un voxel baja una celda por tick: 10 cm cada 50 ms).
+pub const FALL_TICK_SECS: f32 = 0.05;
+
+/// Celdas evaluadas como mucho por tick; el resto espera al siguiente.
+const MAX_FALLING_CELLS_PER_TICK: usize = 4096;
+
+/// Un derrumbe de al menos tantos voxels cae como escombro (cuerpo rígido) en
+/// lugar de celda a celda.
+pub const LARGE_COLLAPSE_VOXELS: usize = 256;
+
+/// Vecinas horizontales: las columnas que caen juntas forman un derrumbe.
+const SIDES: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+// ============================================================================
+// RESOURCES
+// ============================================================================
+
+/// Celdas activas de la simulación (coordenadas de voxel mundiales).
+#[derive(Resource, Debug, Default)]
+pub struct FallingSim {
+    /// Celdas a evaluar en el próximo tick.
+    next: HashSet<IVec3>,
+    /// Tiempo acumulado desde el último tick.
+    elapsed: f32,
+}
+
+impl FallingSim {
+    /// Despierta una celda y la de encima: si `voxel` se vació, lo que tenga
+    /// encima puede caer; si se llenó, puede que sea lo que cae.
+    pub fn wake(&mut self, voxel: IVec3) {
+        self.next.insert(voxel);
+        self.next.insert(voxel + IVec3::Y);
+    }
+
+    /// Olvida todas las celdas activas (al destruir el mundo).
+    pub fn clear(&mut self) {
+        self.next.clear();
+        self.elapsed = 0.0;
+    }
+}
+
+// ============================================================================
+// REGLAS (funciones puras)
+// ============================================================================
+
+/// Escrituras de una celda que cae, o `None` si no cae (no es material que
+/// cae, tiene apoyo o lo de debajo no está cargado). Cae intercambiándose con
+/// el aire o el líquido de debajo: la arena se hunde en el agua.
+pub fn fall_step(
+    cell: IVec3,
+    get: impl Fn(IVec3) -> Option<VoxelType>,
+) -> Option<[(IVec3, VoxelType); 2]> {
+    let voxel_type = get(cell).filter(|v| v.falls())?;
+    let below = get(cell - IVec3::Y).filter(|v| !v.is_solid())?;
+    Some([(cell - IVec3::Y, voxel_type), (cell, below)])
+}
+
+/// Derrumbes: las celdas sin apoyo (`bottoms`) agrupadas por vecindad
+/// horizontal, cada una con la columna de material que cae que lleva encima.
+/// Devuelve los voxels de cada grupo (hasta `cap` por grupo), fondos incluidos.
+pub fn collapse_groups(
+    bottoms: &[IVec3],
+    get: impl Fn(IVec3) -> Option<VoxelType>,
+    cap: usize,
+) -> Vec<Vec<IVec3>> {
+    let pending: HashSet<IVec3> = bottoms.iter().copied().collect();
+    let mut visited: HashSet<IVec3> = HashSet::new();
+    let mut groups = Vec::new();
+
+    for &start in bottoms {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut stack = vec![start];
+        let mut voxels = Vec::new();
+        while let Some(bottom) = stack.pop() {
+            let mut cell = bottom;
+            while voxels.len() < cap && get(cell).is_some_and(|v| v.falls()) {
+                voxels.push(cell);
+                cell += IVec3::Y;
+            }
+            for offset in SIDES {
+                let next = bottom + offset;
+                if pending.contains(&next) && visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        groups.push(voxels);
+    }
+    groups
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Despierta las celdas de cada edición del mundo (de quien sea).
+pub fn wake_falling_on_edits(mut sim: ResMut<FallingSim>, mut edited: MessageReader<VoxelEdited>) {
+    for edit in edited.read() {
+        sim.wake(edit.voxel);
+    }
+}
+
+/// Avanza la caída un tick cuando toca: los derrumbes grandes pasan a
+/// escombros, el resto baja una celda.
+pub fn falling_voxels_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sim: ResMut<FallingSim>,
+    mut world: VoxelWorld,
+    mut meshes: Option<ResMut<Assets<Mesh>>>,
+    debris_assets: Option<Res<DebrisAssets>>,
+) {
+    sim.elapsed += time.delta_secs();
+    if sim.elapsed < FALL_TICK_SECS || sim.next.is_empty() {
+        return;
+    }
+    sim.elapsed = 0.0;
+
+    // De abajo arriba: una columna apoyada sobre otra que cae espera su turno.
+    // Orden total (no solo por altura): el del `HashSet` cambia de una
+    // ejecución a otra, y con él qué celdas entran en el tope del tick
+    let mut cells: Vec<IVec3> = sim.next.drain().collect();
+    cells.sort_by_key(|cell| (cell.y, cell.x, cell.z));
+    if cells.len() > MAX_FALLING_CELLS_PER_TICK {
+        let rest = cells.split_off(MAX_FALLING_CELLS_PER_TICK);
+        sim.next.extend(rest);
+    }
+    let bottoms: Vec<IVec3> = cells
+        .into_iter()
+        .filter(|&cell| fall_step(cell, |v| world.get(v)).is_some())
+        .collect();
+
+    let mut collapsed: HashSet<IVec3> = HashSet::new();
+    for group in collapse_groups(&bottoms, |v| world.get(v), MAX_ISLAND_VOXELS) {
+        if group.len() < LARGE_COLLAPSE_VOXELS {
+            continue;
+        }
+        let voxels: Vec<(IVec3, VoxelType)> = group
+            .iter()
+            .filter_map(|&voxel| Some((voxel, world.get(voxel)?)))
+            .collect();
+        world.set_batch(group.iter().map(|&voxel| (voxel, VoxelType::Air)));
+        info!("Derrumbe de {} voxels", voxels.len());
+        let render = meshes.as_deref_mut().zip(debris_assets.as_deref());
+        spawn_debris(&mut commands, render, voxels);
+        collapsed.extend(group);
+    }
+
+    for bottom in bottoms {
+        if collapsed.contains(&bottom) {
+            continue;
+        }
+        if let Some(writes) = fall_step(bottom, |v| world.get(v)) {
+            world.set_batch(writes);
+            // Sigue cayendo el tick siguiente; lo de encima, detrás
+            sim.wake(bottom - IVec3::Y);
+            sim.wake(bottom);
+        }
+    }
+}
+
+/// Vacía la simulación al destruir el mundo.
+pub fn reset_falling_sim(mut sim: ResMut<FallingSim>) {
+    sim.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn getter(world: &HashMap<IVec3, VoxelType>) -> impl Fn(IVec3) -> Option<VoxelType> + '_ {
+        // Todo cargado dentro de |x|,|y|,|z| <= 8; lo que no está es aire
+        |v: IVec3| (v.abs().max_element() <= 8).then(|| *world.get(&v).unwrap_or(&VoxelType::Air))
+    }
+
+    #[test]
+    fn sand_falls_through_air_and_water_but_not_stone() {
+        let mut world = HashMap::new();
+        world.insert(IVec3::new(0, 2, 0), VoxelType::Sand);
+        world.insert(IVec3::new(1, 2, 0), VoxelType::Sand);
+        world.insert(IVec3::new(1, 1, 0), VoxelType::Water);
+        world.insert(IVec3::new(2, 2, 0), VoxelType::Gravel);
+        world.insert(IVec3::new(2, 1, 0), VoxelType::Stone);
+        world.insert(IVec3::new(3, 2, 0), VoxelType::Dirt);
+        let get = getter(&world);
+
+        assert_eq!(
+            fall_step(IVec3::new(0, 2, 0), &get),
+            Some([(IVec3::new(0, 1, 0), VoxelType::Sand), (IVec3::new(0, 2, 0), VoxelType::Air)])
+        );
+        // En el agua se hunde: el agua sube a su sitio
+        assert_eq!(
+            fall_step(IVec3::new(1, 2, 0), &get).unwrap()[1],
+            (IVec3::new(1, 2, 0), VoxelType::Water)
+        );
+        assert!(fall_step(IVec3::new(2, 2, 0), &get).is_none());
+        assert!(fall_step(IVec3::new(3, 2, 0), &get).is_none());
+        // Con lo de debajo sin cargar, espera
+        assert!(fall_step(IVec3::new(0, -8, 0), &get).is_none());
+    }
+
+    #[test]
+    fn neighbouring_columns_collapse_together() {
+        // Dos columnas de arena de 3 juntas y una suelta de 1, todas sin apoyo
+        let mut world = HashMap::new();
+        for y in 0..3 {
+            world.insert(IVec3::new(0, y, 0), VoxelType::Sand);
+            world.insert(IVec3::new(1, y, 0), VoxelType::Sand);
+        }
+        world.insert(IVec3::new(5, 0, 5), VoxelType::Sand);
+        world.insert(IVec3::new(5, 1, 5), VoxelType::Stone);
+        let bottoms = [IVec3::new(0, 0, 0), IVec3::new(1, 0, 0), IVec3::new(5, 0, 5)];
+
+        let mut sizes: Vec<usize> = collapse_groups(&bottoms, getter(&world), 64)
+            .iter()
+            .map(Vec::len)
+            .collect();
+        sizes.sort();
+        // La piedra de encima no cae con la arena
+        assert_eq!(sizes, vec![1, 6]);
+        assert_eq!(collapse_groups(&bottoms[..2], getter(&world), 4)[0].len(), 4);
+    }
+}
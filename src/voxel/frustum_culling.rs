@@ -4,6 +4,7 @@
 //! NOTA MULTIPLAYER: Este sistema es CLIENT-SIDE ONLY
 //! Cada cliente decide qué chunks renderizar basado en su propia cámara
 //! El servidor NO usa frustum culling - siempre simula todos los chunks activos
+//! (el servidor headless `--server`, en src/server/, ni lo registra)
 
 use bevy::prelude::*;
 use crate::{
@@ -13,16 +14,20 @@ use crate::{
 
 /// Sistema simplificado de frustum culling usando distancia y ángulo
 /// Más robusto que calcular planos del frustum
+///
+/// La visibilidad es de la entidad, no de la cámara: con pantalla dividida un
+/// chunk se dibuja si lo ve CUALQUIERA de las cámaras de los jugadores.
 pub fn update_frustum_culling(
-    camera_query: Query<&Transform, With<Camera>>,
+    camera_query: Query<&Transform, With<Camera3d>>,
     mut chunk_query: Query<(&BaseChunk, &mut Visibility)>,
 ) {
-    let Ok(camera_transform) = camera_query.single() else {
+    let cameras: Vec<(Vec3, Vec3)> = camera_query
+        .iter()
+        .map(|transform| (transform.translation, *transform.forward()))
+        .collect();
+    if cameras.is_empty() {
         return;
-    };
-    
-    let camera_pos = camera_transform.translation;
-    let camera_forward = *camera_transform.forward();
+    }
     
     // Tamaño de chunk en metros
     let chunk_size = BASE_CHUNK_SIZE as f32 * 0.1; // VOXEL_SIZE = 0.1
@@ -32,41 +37,38 @@ pub fn update_frustum_culling(
     let fov_angle = 110.0_f32.to_radians(); // Campo de visión amplio (110 grados)
     let cos_half_fov = (fov_angle * 0.5).cos();
     
-    // Actualizar visibilidad de cada chunk
-    for (base_chunk, mut visibility) in chunk_query.iter_mut() {
-        // Calcular centro del chunk en mundo
-        let chunk_center = Vec3::new(
-            (base_chunk.position.x as f32 + 0.5) * chunk_size,
-            (base_chunk.position.y as f32 + 0.5) * chunk_size,
-            (base_chunk.position.z as f32 + 0.5) * chunk_size,
-        );
-        
+    // Visible desde una cámara: cerca, o lejos pero dentro de su FOV
+    let sees = |&(camera_pos, camera_forward): &(Vec3, Vec3), chunk_center: Vec3| {
         // Vector desde cámara al chunk
         let to_chunk = chunk_center - camera_pos;
         let distance = to_chunk.length();
         
         // Culling por distancia
         if distance > max_distance {
-            *visibility = Visibility::Hidden;
-            continue;
+            return false;
         }
         
         // Culling por ángulo (FOV)
         // Solo aplicar FOV culling a chunks que están lejos
         // Chunks cercanos siempre visibles para evitar pop-in
-        if distance > chunk_size * 3.0 {
-            let to_chunk_normalized = to_chunk / distance;
-            let dot = camera_forward.dot(to_chunk_normalized);
-            
-            // Si el ángulo es mayor que FOV/2, está fuera de vista
-            if dot < cos_half_fov {
-                *visibility = Visibility::Hidden;
-                continue;
-            }
-        }
+        // Si el ángulo es mayor que FOV/2, está fuera de vista
+        distance <= chunk_size * 3.0 || camera_forward.dot(to_chunk / distance) >= cos_half_fov
+    };
+    
+    // Actualizar visibilidad de cada chunk
+    for (base_chunk, mut visibility) in chunk_query.iter_mut() {
+        // Calcular centro del chunk en mundo
+        let chunk_center = Vec3::new(
+            (base_chunk.position.x as f32 + 0.5) * chunk_size,
+            (base_chunk.position.y as f32 + 0.5) * chunk_size,
+            (base_chunk.position.z as f32 + 0.5) * chunk_size,
+        );
         
-        // Chunk visible
-        *visibility = Visibility::Visible;
+        *visibility = if cameras.iter().any(|camera| sees(camera, chunk_center)) {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
     }
 }
 
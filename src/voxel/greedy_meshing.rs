@@ -8,6 +8,10 @@
 //! 2. En cada slice, crear una máscara de caras visibles EN AMBAS DIRECCIONES
 //! 3. Usar greedy algorithm para encontrar rectángulos máximos
 //! 4. Generar quads en lugar de caras individuales
+//!
+//! Hay tres pasadas sobre el mismo algoritmo ([`MeshPass`]): el mesh opaco de
+//! render, el de colisión (sin follaje) y el translúcido del agua, que va en una
+//! entidad aparte con material con transparencia.
 
 use crate::core::constants::{BASE_CHUNK_SIZE, VOXEL_SIZE};
 use crate::voxel::{voxel_color, BaseChunk, ChunkMap, VoxelType};
@@ -57,10 +61,46 @@ fn slope_at(column_top: &[i32], x: usize, z: usize) -> f32 {
     ((dx * dx + dz * dz).sqrt() / SLOPE_REF).clamp(0.0, 1.0)
 }
 
+/// Qué voxels entran en un mesh y cuáles tapan sus caras.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum MeshPass {
+    /// Render opaco: todo lo sólido (incluido el follaje) y la lava, que es
+    /// líquida pero opaca. El agua no, así que el fondo y las orillas bajo ella
+    /// sí se mallan.
+    Render,
+    /// Colisión: solo lo colisionable (sin follaje ni agua).
+    Collider,
+    /// Agua translúcida: solo caras de agua que dan al aire.
+    Water,
+}
+
+impl MeshPass {
+    /// ¿Este voxel genera caras en esta pasada?
+    #[inline]
+    fn includes(self, vt: VoxelType) -> bool {
+        match self {
+            MeshPass::Render => vt.is_solid() || vt.is_lava(),
+            MeshPass::Collider => vt.is_collidable(),
+            MeshPass::Water => vt == VoxelType::Water,
+        }
+    }
+
+    /// ¿Este vecino oculta la cara? En render y colisión, lo mismo que lo
+    /// incluido. El agua solo muestra su superficie contra el aire: ni contra
+    /// otra agua ni contra el terreno (que ya se ve a través de ella).
+    #[inline]
+    fn occludes(self, vt: VoxelType) -> bool {
+        match self {
+            MeshPass::Water => vt != VoxelType::Air,
+            _ => self.includes(vt),
+        }
+    }
+}
+
 /// Mesh simple (sin vecinos), para RENDER inicial. Usado al arrancar cuando no
 /// todos los chunks están cargados.
 pub fn greedy_mesh_basechunk_simple(chunk: &BaseChunk) -> Mesh {
-    mesh_simple_inner(chunk, false)
+    mesh_simple_inner(chunk, MeshPass::Render)
 }
 
 /// Mesh simple SOLO-COLISIONABLE (sin vecinos, ignora el follaje). Se usa para
@@ -68,10 +108,10 @@ pub fn greedy_mesh_basechunk_simple(chunk: &BaseChunk) -> Mesh {
 /// puede correr dentro de la tarea async de generación. Las caras extra en los
 /// bordes del chunk son inofensivas para la colisión.
 pub fn greedy_mesh_basechunk_collider_simple(chunk: &BaseChunk) -> Mesh {
-    mesh_simple_inner(chunk, true)
+    mesh_simple_inner(chunk, MeshPass::Collider)
 }
 
-fn mesh_simple_inner(chunk: &BaseChunk, collidable_only: bool) -> Mesh {
+fn mesh_simple_inner(chunk: &BaseChunk, pass: MeshPass) -> Mesh {
     let mut positions: Vec<[f32; 3]> = Vec::new();
     let mut normals: Vec<[f32; 3]> = Vec::new();
     let mut indices: Vec<u32> = Vec::new();
@@ -83,7 +123,7 @@ fn mesh_simple_inner(chunk: &BaseChunk, collidable_only: bool) -> Mesh {
     for axis in 0..3 {
         for d in 0..BASE_CHUNK_SIZE {
             // Dirección positiva
-            let mask_pos = generate_slice_mask_simple(chunk, axis, d, 1, collidable_only);
+            let mask_pos = generate_slice_mask_simple(chunk, axis, d, 1, pass);
             greedy_mesh_slice(
                 &mask_pos,
                 chunk,
@@ -98,7 +138,7 @@ fn mesh_simple_inner(chunk: &BaseChunk, collidable_only: bool) -> Mesh {
             );
 
             // Dirección negativa
-            let mask_neg = generate_slice_mask_simple(chunk, axis, d, -1, collidable_only);
+            let mask_neg = generate_slice_mask_simple(chunk, axis, d, -1, pass);
             greedy_mesh_slice(
                 &mask_neg,
                 chunk,
@@ -129,7 +169,7 @@ fn generate_slice_mask_simple(
     axis: usize,
     d: usize,
     direction: i32, // +1 o -1
-    collidable_only: bool,
+    pass: MeshPass,
 ) -> Vec<Option<VoxelType>> {
     let u = (axis + 1) % 3;
     let v = (axis + 2) % 3;
@@ -148,7 +188,7 @@ fn generate_slice_mask_simple(
             let z = pos[2];
 
             // ¿Presente para esta malla? (render = sólido; collider = colisionable)
-            if !voxel_present(chunk.voxel_types[x][y][z], collidable_only) {
+            if !pass.includes(chunk.voxel_types[x][y][z]) {
                 continue;
             }
 
@@ -166,10 +206,9 @@ fn generate_slice_mask_simple(
             {
                 true // Borde del chunk
             } else {
-                !voxel_present(
+                !pass.occludes(
                     chunk.voxel_types[neighbor_x as usize][neighbor_y as usize]
                         [neighbor_z as usize],
-                    collidable_only,
                 )
             };
 
@@ -182,34 +221,52 @@ fn generate_slice_mask_simple(
     mask
 }
 
-/// ¿Cuenta este voxel como "presente" para esta malla? Para el render, presente
-/// = sólido (se ve). Para el colisionador, presente = colisionable: el follaje
-/// (pasto/arbustos) se ignora, de modo que se puede atravesar.
-#[inline]
-fn voxel_present(vt: VoxelType, collidable_only: bool) -> bool {
-    if collidable_only {
-        vt.is_collidable()
-    } else {
-        vt.is_solid()
-    }
-}
-
 /// Mesh para RENDER (todos los voxeles sólidos, incluido el follaje).
 pub fn greedy_mesh_basechunk(
     chunk: &BaseChunk,
     chunk_map: &ChunkMap,
     chunks: &Query<&BaseChunk>,
 ) -> Mesh {
-    mesh_basechunk_inner(chunk, chunk_map, chunks, false)
+    mesh_basechunk_inner(chunk, chunk_map, chunks, MeshPass::Render)
+}
+
+/// Mesh TRANSLÚCIDO del agua del chunk (con vecinos), o `None` si no tiene agua
+/// visible. El vertex alpha es 1: la transparencia la pone el material del agua.
+pub fn greedy_mesh_basechunk_water(
+    chunk: &BaseChunk,
+    chunk_map: &ChunkMap,
+    chunks: &Query<&BaseChunk>,
+) -> Option<Mesh> {
+    let has_water = chunk
+        .voxel_types
+        .iter()
+        .flatten()
+        .flatten()
+        .any(|&vt| vt == VoxelType::Water);
+    if !has_water {
+        return None;
+    }
+    let mut mesh = mesh_basechunk_inner(chunk, chunk_map, chunks, MeshPass::Water);
+    if mesh.count_vertices() == 0 {
+        return None;
+    }
+    if let Some(bevy::mesh::VertexAttributeValues::Float32x4(colors)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR)
+    {
+        for color in colors {
+            color[3] = 1.0;
+        }
+    }
+    Some(mesh)
 }
 
-/// Greedy meshing con verificación de vecinos. `collidable_only` decide si el
-/// follaje cuenta (render) o se ignora (collider).
+/// Greedy meshing con verificación de vecinos. `pass` decide qué voxels
+/// cuentan (render, collider o agua).
 fn mesh_basechunk_inner(
     chunk: &BaseChunk,
     chunk_map: &ChunkMap,
     chunks: &Query<&BaseChunk>,
-    collidable_only: bool,
+    pass: MeshPass,
 ) -> Mesh {
     let mut positions: Vec<[f32; 3]> = Vec::new();
     let mut normals: Vec<[f32; 3]> = Vec::new();
@@ -223,7 +280,7 @@ fn mesh_basechunk_inner(
         for d in 0..BASE_CHUNK_SIZE {
             // Dirección positiva
             let mask_pos =
-                generate_slice_mask(chunk, chunk_map, chunks, axis, d, 1, collidable_only);
+                generate_slice_mask(chunk, chunk_map, chunks, axis, d, 1, pass);
             greedy_mesh_slice(
                 &mask_pos,
                 chunk,
@@ -239,7 +296,7 @@ fn mesh_basechunk_inner(
 
             // Dirección negativa
             let mask_neg =
-                generate_slice_mask(chunk, chunk_map, chunks, axis, d, -1, collidable_only);
+                generate_slice_mask(chunk, chunk_map, chunks, axis, d, -1, pass);
             greedy_mesh_slice(
                 &mask_neg,
                 chunk,
@@ -272,7 +329,7 @@ fn generate_slice_mask(
     axis: usize,
     d: usize,
     direction: i32,
-    collidable_only: bool,
+    pass: MeshPass,
 ) -> Vec<Option<VoxelType>> {
     let u = (axis + 1) % 3;
     let v = (axis + 2) % 3;
@@ -290,7 +347,7 @@ fn generate_slice_mask(
             let y = pos[1];
             let z = pos[2];
 
-            if !voxel_present(chunk.voxel_types[x][y][z], collidable_only) {
+            if !pass.includes(chunk.voxel_types[x][y][z]) {
                 continue;
             }
 
@@ -316,14 +373,13 @@ fn generate_slice_mask(
                     z,
                     axis,
                     direction,
-                    collidable_only,
+                    pass,
                 )
             } else {
                 // Dentro del chunk
-                !voxel_present(
+                !pass.occludes(
                     chunk.voxel_types[neighbor_x as usize][neighbor_y as usize]
                         [neighbor_z as usize],
-                    collidable_only,
                 )
             };
 
@@ -337,6 +393,7 @@ fn generate_slice_mask(
 }
 
 /// Aplica greedy meshing a un slice usando la máscara
+#[allow(clippy::too_many_arguments)]
 fn greedy_mesh_slice(
     mask: &[Option<VoxelType>],
     chunk: &BaseChunk,
@@ -438,6 +495,7 @@ fn find_max_rect(
 }
 
 /// Agrega un quad optimizado al mesh
+#[allow(clippy::too_many_arguments)]
 fn add_greedy_quad(
     chunk: &BaseChunk,
     axis: usize,
@@ -459,12 +517,9 @@ fn add_greedy_quad(
 
     // Posición base en coordenadas mundiales
     let mut base_pos = [0.0; 3];
-    base_pos[axis] =
-        (chunk.position[axis as usize] * BASE_CHUNK_SIZE as i32 + d as i32) as f32 * VOXEL_SIZE;
-    base_pos[u] =
-        (chunk.position[u as usize] * BASE_CHUNK_SIZE as i32 + i as i32) as f32 * VOXEL_SIZE;
-    base_pos[v] =
-        (chunk.position[v as usize] * BASE_CHUNK_SIZE as i32 + j as i32) as f32 * VOXEL_SIZE;
+    base_pos[axis] = (chunk.position[axis] * BASE_CHUNK_SIZE as i32 + d as i32) as f32 * VOXEL_SIZE;
+    base_pos[u] = (chunk.position[u] * BASE_CHUNK_SIZE as i32 + i as i32) as f32 * VOXEL_SIZE;
+    base_pos[v] = (chunk.position[v] * BASE_CHUNK_SIZE as i32 + j as i32) as f32 * VOXEL_SIZE;
 
     // Dimensiones del quad
     let mut size = [0.0; 3];
@@ -542,6 +597,7 @@ fn add_greedy_quad(
 }
 
 /// Verifica si una cara es visible en chunk vecino
+#[allow(clippy::too_many_arguments)]
 fn is_face_visible_cross_chunk(
     chunk: &BaseChunk,
     chunk_map: &ChunkMap,
@@ -551,44 +607,45 @@ fn is_face_visible_cross_chunk(
     z: usize,
     axis: usize,
     direction: i32,
-    collidable_only: bool,
+    pass: MeshPass,
 ) -> bool {
     let mut neighbor_chunk_offset = IVec3::ZERO;
-    neighbor_chunk_offset[axis as usize] = direction;
+    neighbor_chunk_offset[axis] = direction;
 
     let neighbor_chunk_pos = chunk.position + neighbor_chunk_offset;
 
-    if let Some(&neighbor_entity) = chunk_map.chunks.get(&neighbor_chunk_pos) {
-        if let Ok(neighbor_chunk) = chunks.get(neighbor_entity) {
-            // Posición local en chunk vecino
-            let local_x = if axis == 0 && direction < 0 {
-                BASE_CHUNK_SIZE - 1
-            } else if axis == 0 && direction > 0 {
-                0
-            } else {
-                x
-            };
-            let local_y = if axis == 1 && direction < 0 {
-                BASE_CHUNK_SIZE - 1
-            } else if axis == 1 && direction > 0 {
-                0
-            } else {
-                y
-            };
-            let local_z = if axis == 2 && direction < 0 {
-                BASE_CHUNK_SIZE - 1
-            } else if axis == 2 && direction > 0 {
-                0
-            } else {
-                z
-            };
+    if let Some(&neighbor_entity) = chunk_map.chunks.get(&neighbor_chunk_pos)
+        && let Ok(neighbor_chunk) = chunks.get(neighbor_entity)
+    {
+        // Posición local en chunk vecino
+        let local_x = if axis == 0 && direction < 0 {
+            BASE_CHUNK_SIZE - 1
+        } else if axis == 0 && direction > 0 {
+            0
+        } else {
+            x
+        };
+        let local_y = if axis == 1 && direction < 0 {
+            BASE_CHUNK_SIZE - 1
+        } else if axis == 1 && direction > 0 {
+            0
+        } else {
+            y
+        };
+        let local_z = if axis == 2 && direction < 0 {
+            BASE_CHUNK_SIZE - 1
+        } else if axis == 2 && direction > 0 {
+            0
+        } else {
+            z
+        };
 
-            return !voxel_present(
-                neighbor_chunk.voxel_types[local_x][local_y][local_z],
-                collidable_only,
-            );
-        }
+        return !pass.occludes(neighbor_chunk.voxel_types[local_x][local_y][local_z]);
     }
 
-    true // Sin chunk vecino, renderizar cara
+    // Sin chunk vecino, renderizar cara. El agua es la excepción: una pared
+    // translúcida en el borde se vería a través de la superficie, así que sin
+    // vecino solo se dibuja la cara superior (el vecino de arriba suele ser aire
+    // sin BaseChunk).
+    pass != MeshPass::Water || (axis == 1 && direction > 0)
 }
@@ -0,0 +1,523 @@
+//! Lava: simulación de la lava que fluye, su luz y lo que quema al tocarlo.
+//!
+//! Cada voxel de lava lleva su nivel en el propio `VoxelType` (`Lava` = fuente,
+//! nivel 0; `Lava1..Lava7` = fluyendo, a esa distancia de la fuente). La colada
+//! es un autómata celular sobre coordenadas de voxel MUNDIALES: las reglas
+//! ([`lava_step`]) no saben de chunks, así que cruzan bordes sin casos
+//! especiales. Una celda cuyo chunk no está cargado cuenta como roca: la lava no
+//! fluye hacia allí hasta que se carga (y entonces se despiertan sus bordes).
+//!
+//! Solo se evalúan las celdas "activas" (las que cambiaron o tienen una vecina
+//! que cambió), en ticks de [`LAVA_TICK_SECS`] acotados por
+//! [`LAVA_TICK_BUDGET_MS`] por frame, como el remallado de `DirtyChunk`. Los
+//! cambios pasan por `VoxelWorld` (persisten en `VoxelDiffs` y remallan), y
+//! cualquier edición del mundo —de la lava, del jugador o de otro sistema—
+//! despierta las celdas de alrededor vía `VoxelEdited`.
+
+use super::{
+    BaseChunk, ChunkMap, Fuses, VoxelEdited, VoxelReader, VoxelType, VoxelWorld, FUSE_SECS,
+    LAVA_MAX_LEVEL,
+};
+use crate::core::constants::{BASE_CHUNK_SIZE, VOXEL_SIZE};
+use crate::core::DeterministicGame;
+use crate::physics::rapier_integration::RapierVoxelDrop;
+use crate::player::components::Player;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+// ============================================================================
+// PARÁMETROS
+// ============================================================================
+
+/// Segundos entre ticks de la simulación (la lava avanza un voxel por tick).
+pub const LAVA_TICK_SECS: f32 = 0.25;
+/// Presupuesto de simulación por frame (ms). Lo que no quepa sigue el frame
+/// siguiente antes de empezar otro tick.
+pub const LAVA_TICK_BUDGET_MS: u64 = 2;
+/// Celdas evaluadas por frame con [`DeterministicGame`], en lugar de
+/// [`LAVA_TICK_BUDGET_MS`] (no puede depender del reloj real).
+pub const DETERMINISTIC_LAVA_CELLS: usize = 512;
+
+/// Color de la luz que emite la lava.
+const LAVA_LIGHT_COLOR: Color = Color::srgb(1.0, 0.45, 0.1);
+/// Intensidad (lúmenes) por voxel de lava de un chunk, hasta `LAVA_LIGHT_MAX`.
+const LAVA_LIGHT_PER_VOXEL: f32 = 2_000.0;
+const LAVA_LIGHT_MAX: f32 = 400_000.0;
+/// Alcance de la luz de la lava (metros).
+const LAVA_LIGHT_RANGE: f32 = 8.0;
+/// Máximo de luces de lava encendidas a la vez (las más cercanas al jugador).
+const LAVA_MAX_LIGHTS: usize = 16;
+
+/// Las 6 vecinas de una celda.
+const NEIGHBORS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+/// Vecinas horizontales (hacia donde se extiende la lava sobre suelo firme).
+const SIDES: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+// ============================================================================
+// RESOURCES / COMPONENTS
+// ============================================================================
+
+/// Estado de la simulación: celdas activas (coordenadas de voxel mundiales).
+#[derive(Resource, Debug, Default)]
+pub struct LavaSim {
+    /// Celdas a evaluar en el próximo tick.
+    next: HashSet<IVec3>,
+    /// Celdas del tick en curso aún sin evaluar.
+    pending: Vec<IVec3>,
+    /// Tiempo acumulado desde el último tick.
+    elapsed: f32,
+}
+
+impl LavaSim {
+    /// Despierta una celda y sus 6 vecinas: algo cambió en `voxel` y la lava de
+    /// alrededor puede fluir hacia allí (o dejar de estar alimentada).
+    pub fn wake(&mut self, voxel: IVec3) {
+        self.next.insert(voxel);
+        for offset in NEIGHBORS {
+            self.next.insert(voxel + offset);
+        }
+    }
+
+    /// Olvida todas las celdas activas (al destruir el mundo).
+    pub fn clear(&mut self) {
+        self.next.clear();
+        self.pending.clear();
+        self.elapsed = 0.0;
+    }
+}
+
+/// Marcador de la luz (hija del chunk) que emite la lava de un chunk.
+#[derive(Component)]
+pub struct LavaLight;
+
+// ============================================================================
+// REGLAS (funciones puras)
+// ============================================================================
+
+/// Lo que le corresponde a una celda de lava que FLUYE según lo que la
+/// alimenta: desde arriba, nivel 1; si no, la vecina horizontal de menor nivel
+/// más uno. Sin alimentación, o más allá de `LAVA_MAX_LEVEL`, se seca (aire).
+pub fn fed_lava(above: VoxelType, sides: [VoxelType; 4]) -> VoxelType {
+    if above.is_lava() {
+        return VoxelType::lava(1);
+    }
+    sides
+        .iter()
+        .filter_map(|v| v.lava_level())
+        .min()
+        .map_or(VoxelType::Air, |level| VoxelType::lava(level + 1))
+}
+
+/// En qué se convierte lo que toca la lava: lo inflamable arde y el pasto se
+/// chamusca. `None` = no le afecta.
+pub fn burnt(voxel: VoxelType) -> Option<VoxelType> {
+    match voxel {
+        v if v.is_flammable() => Some(VoxelType::Air),
+        VoxelType::Grass => Some(VoxelType::Dirt),
+        _ => None,
+    }
+}
+
+/// ¿Puede entrar lava de nivel `level` en esta celda? En el aire, o sobre
+/// lava más débil (de mayor nivel).
+fn accepts(target: VoxelType, level: u8) -> bool {
+    target == VoxelType::Air || target.lava_level().is_some_and(|l| l > level)
+}
+
+/// Un paso de la celda `pos`: devuelve las escrituras (posición → tipo) que
+/// produce. `get` lee el mundo; `None` = chunk no cargado (cuenta como roca).
+///
+/// Orden de las reglas:
+/// 1. El agua en contacto enfría la lava a piedra.
+/// 2. La lava que fluye recalcula su nivel (o se seca) según lo que la alimenta.
+/// 3. Lo inflamable de alrededor arde.
+/// 4. Cae si abajo cabe; si no, y tiene suelo firme, se extiende a los lados.
+pub fn lava_step(pos: IVec3, get: impl Fn(IVec3) -> Option<VoxelType>) -> Vec<(IVec3, VoxelType)> {
+    let mut writes = Vec::new();
+    let Some(cell) = get(pos) else {
+        return writes;
+    };
+    let Some(mut level) = cell.lava_level() else {
+        return writes;
+    };
+    let at = |offset: IVec3| get(pos + offset).unwrap_or(VoxelType::Stone);
+
+    if NEIGHBORS.iter().any(|&offset| at(offset) == VoxelType::Water) {
+        writes.push((pos, VoxelType::Stone));
+        return writes;
+    }
+
+    if level > 0 {
+        let fed = fed_lava(at(IVec3::Y), SIDES.map(at));
+        if fed != cell {
+            writes.push((pos, fed));
+        }
+        match fed.lava_level() {
+            Some(new_level) => level = new_level,
+            None => return writes,
+        }
+    }
+
+    for offset in NEIGHBORS {
+        if let Some(result) = burnt(at(offset)) {
+            writes.push((pos + offset, result));
+        }
+    }
+
+    let below = at(IVec3::NEG_Y);
+    if accepts(below, 1) {
+        writes.push((pos + IVec3::NEG_Y, VoxelType::lava(1)));
+    } else if below.is_solid() && burnt(below).is_none() && level < LAVA_MAX_LEVEL {
+        for offset in SIDES {
+            if accepts(at(offset), level + 1) {
+                writes.push((pos + offset, VoxelType::lava(level + 1)));
+            }
+        }
+    }
+
+    writes
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// Centro (metros) de una coordenada de voxel mundial.
+fn voxel_center(voxel: IVec3) -> Vec3 {
+    (voxel.as_vec3() + Vec3::splat(0.5)) * VOXEL_SIZE
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Avanza la simulación de la lava con presupuesto de tiempo.
+///
+/// Cada `LAVA_TICK_SECS` las celdas activas pasan a `pending` (ordenadas, para
+/// que el resultado no dependa del orden del `HashSet`) y se evalúan hasta
+/// agotar `LAVA_TICK_BUDGET_MS`; el resto sigue en el frame siguiente (con
+/// [`DeterministicGame`], hasta `DETERMINISTIC_LAVA_CELLS` por frame). Las escrituras van por
+/// `VoxelWorld`; sus `VoxelEdited` despiertan a las vecinas para el próximo
+/// tick (`wake_lava_on_edits`).
+pub fn lava_flow_system(
+    time: Res<Time>,
+    mut lava: ResMut<LavaSim>,
+    mut world: VoxelWorld,
+    mut fuses: ResMut<Fuses>,
+    deterministic: Option<Res<DeterministicGame>>,
+) {
+    lava.elapsed = (lava.elapsed + time.delta_secs()).min(LAVA_TICK_SECS);
+    if lava.pending.is_empty() {
+        if lava.elapsed < LAVA_TICK_SECS || lava.next.is_empty() {
+            return;
+        }
+        lava.elapsed = 0.0;
+        let mut cells: Vec<IVec3> = lava.next.drain().collect();
+        // Orden inverso: `pop` saca de abajo arriba.
+        cells.sort_unstable_by_key(|v| std::cmp::Reverse((v.y, v.x, v.z)));
+        lava.pending = cells;
+    }
+
+    let start = std::time::Instant::now();
+    let budget = std::time::Duration::from_millis(LAVA_TICK_BUDGET_MS);
+    let mut done = 0;
+    while let Some(pos) = lava.pending.pop() {
+        let writes = lava_step(pos, |v| world.get(v));
+        // Lo explosivo que arde enciende su mecha
+        for &(voxel, _) in &writes {
+            if let Some(blast) = world.get(voxel).and_then(|v| v.explosive()) {
+                fuses.light(voxel, blast, FUSE_SECS);
+            }
+        }
+        world.set_batch(writes);
+
+        done += 1;
+        let over_budget = match deterministic {
+            Some(_) => done >= DETERMINISTIC_LAVA_CELLS,
+            None => start.elapsed() >= budget,
+        };
+        if over_budget {
+            break;
+        }
+    }
+}
+
+/// Despierta la lava alrededor de cada voxel editado (por la propia lava, al
+/// romper o construir, o por cualquier otro usuario de `VoxelWorld`).
+pub fn wake_lava_on_edits(mut lava: ResMut<LavaSim>, mut edited: MessageReader<VoxelEdited>) {
+    for edit in edited.read() {
+        lava.wake(edit.voxel);
+    }
+}
+
+/// Despierta la lava de los chunks recién cargados.
+///
+/// La lava generada está en reposo (lagos llenos), así que solo se activan las
+/// celdas que podrían moverse: las que tienen aire abajo o a los lados, y las
+/// de los bordes del chunk y de las caras vecinas que lo tocan (al otro lado
+/// puede haber un hueco o una colada que antes estaba "tapada" por un chunk sin
+/// cargar).
+pub fn wake_lava_on_chunk_load(
+    mut lava: ResMut<LavaSim>,
+    added: Query<&BaseChunk, Added<BaseChunk>>,
+    chunks: Query<&BaseChunk>,
+    chunk_map: Res<ChunkMap>,
+) {
+    let n = BASE_CHUNK_SIZE;
+    let last = n - 1;
+    for chunk in &added {
+        let origin = chunk.position * n as i32;
+        for x in 0..n {
+            for y in 0..n {
+                for z in 0..n {
+                    if !chunk.voxel_types[x][y][z].is_lava() {
+                        continue;
+                    }
+                    let on_border = [x, y, z].iter().any(|&c| c == 0 || c == last);
+                    let open = on_border
+                        || chunk.voxel_types[x][y - 1][z] == VoxelType::Air
+                        || chunk.voxel_types[x - 1][y][z] == VoxelType::Air
+                        || chunk.voxel_types[x + 1][y][z] == VoxelType::Air
+                        || chunk.voxel_types[x][y][z - 1] == VoxelType::Air
+                        || chunk.voxel_types[x][y][z + 1] == VoxelType::Air;
+                    if open {
+                        lava.next.insert(origin + IVec3::new(x as i32, y as i32, z as i32));
+                    }
+                }
+            }
+        }
+
+        // Cara de cada vecino que toca al chunk nuevo.
+        for offset in NEIGHBORS {
+            let Some(neighbor) = chunk_map
+                .chunks
+                .get(&(chunk.position + offset))
+                .and_then(|&e| chunks.get(e).ok())
+            else {
+                continue;
+            };
+            let axis = if offset.x != 0 { 0 } else if offset.y != 0 { 1 } else { 2 };
+            let layer = if offset[axis] > 0 { 0 } else { last };
+            let neighbor_origin = neighbor.position * n as i32;
+            for i in 0..n {
+                for j in 0..n {
+                    let mut local = [0usize; 3];
+                    local[axis] = layer;
+                    local[(axis + 1) % 3] = i;
+                    local[(axis + 2) % 3] = j;
+                    if neighbor.voxel_types[local[0]][local[1]][local[2]].is_lava() {
+                        let local = IVec3::new(local[0] as i32, local[1] as i32, local[2] as i32);
+                        lava.next.insert(neighbor_origin + local);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Vacía la simulación al destruir el mundo.
+pub fn reset_lava_sim(mut lava: ResMut<LavaSim>) {
+    lava.clear();
+}
+
+/// Rehace la luz de lava de los chunks cuyo mesh acaba de cambiar (mismo
+/// enganche que `update_water_meshes`): una `PointLight` hija en el centroide de
+/// su lava, más intensa cuanta más lava haya.
+pub fn update_lava_lights(
+    mut commands: Commands,
+    changed: Query<(Entity, &BaseChunk, Option<&Children>), Changed<Mesh3d>>,
+    lava_lights: Query<(), With<LavaLight>>,
+) {
+    for (entity, chunk, children) in &changed {
+        for child in children.into_iter().flatten() {
+            if lava_lights.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+
+        let origin = chunk.position * BASE_CHUNK_SIZE as i32;
+        let mut sum = Vec3::ZERO;
+        let mut count = 0u32;
+        for x in 0..BASE_CHUNK_SIZE {
+            for y in 0..BASE_CHUNK_SIZE {
+                for z in 0..BASE_CHUNK_SIZE {
+                    if chunk.voxel_types[x][y][z].is_lava() {
+                        sum += voxel_center(origin + IVec3::new(x as i32, y as i32, z as i32));
+                        count += 1;
+                    }
+                }
+            }
+        }
+        if count == 0 {
+            continue;
+        }
+
+        commands.entity(entity).with_child((
+            LavaLight,
+            PointLight {
+                color: LAVA_LIGHT_COLOR,
+                intensity: (count as f32 * LAVA_LIGHT_PER_VOXEL).min(LAVA_LIGHT_MAX),
+                range: LAVA_LIGHT_RANGE,
+                shadows_enabled: false,
+                ..default()
+            },
+            // El chunk está en el origen (su mesh va en coordenadas mundiales)
+            Transform::from_translation(sum / count as f32),
+        ));
+    }
+}
+
+/// Enciende solo las `LAVA_MAX_LIGHTS` luces de lava más cercanas a algún
+/// jugador: un mundo con muchas cuevas de lava tendría cientos.
+pub fn cull_lava_lights(
+    player_query: Query<&Transform, With<Player>>,
+    mut lights: Query<(&GlobalTransform, &mut Visibility), With<LavaLight>>,
+) {
+    let players: Vec<Vec3> = player_query.iter().map(|t| t.translation).collect();
+    if players.is_empty() {
+        return;
+    }
+    let mut by_distance: Vec<(f32, Mut<Visibility>)> = lights
+        .iter_mut()
+        .map(|(transform, visibility)| {
+            let nearest = players
+                .iter()
+                .map(|player| transform.translation().distance_squared(*player))
+                .fold(f32::INFINITY, f32::min);
+            (nearest, visibility)
+        })
+        .collect();
+    by_distance.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+    for (i, (_, mut visibility)) in by_distance.into_iter().enumerate() {
+        let wanted = if i < LAVA_MAX_LIGHTS {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(wanted);
+    }
+}
+
+/// Los drops que caen en la lava se queman.
+pub fn burn_drops_in_lava(
+    mut commands: Commands,
+    drops: Query<(Entity, &Transform), With<RapierVoxelDrop>>,
+    world: VoxelReader,
+) {
+    for (entity, transform) in &drops {
+        if world.get_at(transform.translation).is_some_and(|v| v.is_lava()) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Ejecuta ticks sobre un mundo de prueba (lo que no está en el mapa es aire
+    /// dentro de `bounds`, y "sin cargar" fuera).
+    fn run(world: &mut HashMap<IVec3, VoxelType>, bounds: i32, ticks: usize) {
+        let get = |w: &HashMap<IVec3, VoxelType>, p: IVec3| {
+            (p.abs().max_element() <= bounds).then(|| *w.get(&p).unwrap_or(&VoxelType::Air))
+        };
+        for _ in 0..ticks {
+            let cells: Vec<IVec3> = world.keys().copied().collect();
+            let mut writes = Vec::new();
+            for pos in cells {
+                writes.extend(lava_step(pos, |p| get(world, p)));
+            }
+            for (pos, voxel_type) in writes {
+                world.insert(pos, voxel_type);
+            }
+        }
+    }
+
+    /// Suelo de piedra en y = -1 bajo todo el área de prueba.
+    fn floor(bounds: i32) -> HashMap<IVec3, VoxelType> {
+        let mut world = HashMap::new();
+        for x in -bounds..=bounds {
+            for z in -bounds..=bounds {
+                world.insert(IVec3::new(x, -1, z), VoxelType::Stone);
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn fed_lava_takes_the_strongest_neighbor() {
+        let air = VoxelType::Air;
+        assert_eq!(fed_lava(VoxelType::lava(5), [air; 4]), VoxelType::lava(1));
+        let sides = [VoxelType::lava(3), VoxelType::lava(1), air, VoxelType::Stone];
+        assert_eq!(fed_lava(air, sides), VoxelType::lava(2));
+        assert_eq!(fed_lava(air, [air; 4]), air);
+        assert_eq!(fed_lava(air, [VoxelType::lava(LAVA_MAX_LEVEL), air, air, air]), air);
+    }
+
+    #[test]
+    fn source_spreads_sideways_up_to_max_level() {
+        let mut world = floor(10);
+        world.insert(IVec3::ZERO, VoxelType::Lava);
+        run(&mut world, 10, 12);
+        for d in 1..=LAVA_MAX_LEVEL as i32 {
+            assert_eq!(world[&IVec3::new(d, 0, 0)], VoxelType::lava(d as u8));
+        }
+        assert!(!world.get(&IVec3::new(8, 0, 0)).is_some_and(|v| v.is_lava()));
+    }
+
+    #[test]
+    fn lava_falls_before_spreading() {
+        let mut world = floor(10);
+        world.insert(IVec3::new(0, 3, 0), VoxelType::Lava);
+        world.insert(IVec3::new(0, 2, 0), VoxelType::Stone);
+        world.insert(IVec3::new(1, 2, 0), VoxelType::Stone);
+        run(&mut world, 10, 6);
+        // Cae por el borde del bloque hasta el suelo
+        assert_eq!(world[&IVec3::new(1, 3, 0)], VoxelType::lava(1));
+        assert_eq!(world[&IVec3::new(2, 2, 0)], VoxelType::lava(1));
+        assert_eq!(world[&IVec3::new(2, 0, 0)], VoxelType::lava(1));
+    }
+
+    #[test]
+    fn flow_dries_up_without_its_source() {
+        let mut world = floor(10);
+        world.insert(IVec3::ZERO, VoxelType::Lava);
+        run(&mut world, 10, 10);
+        world.insert(IVec3::ZERO, VoxelType::Stone);
+        run(&mut world, 10, 20);
+        assert!(world.values().all(|v| !v.is_lava()));
+    }
+
+    #[test]
+    fn water_hardens_and_plants_burn() {
+        let mut world = floor(4);
+        world.insert(IVec3::ZERO, VoxelType::Lava);
+        world.insert(IVec3::new(0, 1, 0), VoxelType::Leaves);
+        world.insert(IVec3::new(0, 0, 1), VoxelType::Grass);
+        run(&mut world, 4, 1);
+        assert_eq!(world[&IVec3::new(0, 1, 0)], VoxelType::Air);
+        assert_eq!(world[&IVec3::new(0, 0, 1)], VoxelType::Dirt);
+
+        world.insert(IVec3::new(0, 1, 0), VoxelType::Water);
+        run(&mut world, 4, 1);
+        assert_eq!(world[&IVec3::ZERO], VoxelType::Stone);
+    }
+
+    #[test]
+    fn unloaded_neighbors_block_the_flow() {
+        let mut world = floor(1);
+        world.insert(IVec3::ZERO, VoxelType::Lava);
+        run(&mut world, 1, 5);
+        assert_eq!(world[&IVec3::X], VoxelType::lava(1));
+        // Fuera de `bounds` no se escribe nada
+        assert!(world.keys().all(|p| p.abs().max_element() <= 1));
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::{
     core::VOXEL_SIZE,
-    voxel::{voxel_color, TerrainGenerator, VoxelType},
+    voxel::{voxel_color, TerrainGenerator, VoxelType, SEA_LEVEL},
 };
 use bevy::{
     mesh::{Indices, PrimitiveTopology},
@@ -86,21 +86,35 @@ impl LodChunk {
                 let local_x = (x * step_size + step_size / 2) as i32;
                 let local_z = (z * step_size + step_size / 2) as i32;
 
-                let world_x = (self.position.x * 32 + local_x) as f32 * 0.1;
-                let world_z = (self.position.z * 32 + local_z) as f32 * 0.1;
+                let voxel_x = self.position.x * 32 + local_x;
+                let voxel_z = self.position.z * 32 + local_z;
 
-                // La densidad es `generate_height(x,z) - y` (monótona en Y),
-                // así que la superficie ES la altura del terreno directamente:
-                // una sola evaluación de noise en vez de binary search.
-                let surface_y = terrain_gen.biome_gen.generate_height(world_x, world_z);
+                // Con densidad 3D la superficie ya no es la altura del heightmap:
+                // se busca el voxel sólido más alto bajando desde la cota
+                // superior (`altura + OVERHANG_MAX`), ~25 evaluaciones como mucho.
+                // La cara superior del voxel queda un voxel más arriba.
+                let surface_voxel_y = terrain_gen.biome_gen.surface_voxel_y(voxel_x, voxel_z);
+                let surface_y = (surface_voxel_y + 1) as f32 * VOXEL_SIZE;
 
                 // Guardar la altura de la superficie
                 let index = x + z * grid_size;
                 self.surface_heights[index] = surface_y;
 
-                // Determinar el tipo de voxel en la superficie
-                // La superficie siempre es pasto (profundidad 0), igual que BaseChunk
-                self.surface_types[index] = VoxelType::from_depth(1.0, 0.0);
+                // Tipo de voxel en la superficie: la capa superior del bioma
+                // (profundidad 0), con las mismas reglas de pendiente que BaseChunk
+                let column = terrain_gen.biome_gen.terrain_column(voxel_x, voxel_z);
+                let biome = terrain_gen.biome_gen.biome_with_height(
+                    voxel_x as f32 * VOXEL_SIZE,
+                    voxel_z as f32 * VOXEL_SIZE,
+                    column.height,
+                );
+                self.surface_types[index] = biome.surface_material(1.0, 0.0, column.slope);
+
+                // Bajo el nivel del mar se ve la superficie del agua, no el fondo
+                if surface_y < SEA_LEVEL {
+                    self.surface_heights[index] = SEA_LEVEL;
+                    self.surface_types[index] = VoxelType::Water;
+                }
             }
         }
     }
@@ -276,6 +290,7 @@ pub fn mesh_lod_chunk(lod_chunk: &LodChunk, seed: i32) -> Mesh {
 }
 
 /// Agrega una cara superior (horizontal)
+#[allow(clippy::too_many_arguments)]
 fn add_top_face(
     positions: &mut Vec<[f32; 3]>,
     normals: &mut Vec<[f32; 3]>,
@@ -310,6 +325,7 @@ fn add_top_face(
     ]);
 }
 /// Agrega una cara lateral (vertical)
+#[allow(clippy::too_many_arguments)]
 fn add_side_face(
     positions: &mut Vec<[f32; 3]>,
     normals: &mut Vec<[f32; 3]>,
@@ -363,11 +379,11 @@ fn add_side_face(
 const IMPOSTOR_SIDES: usize = 8;
 
 /// Color RGBA lineal de un tipo de voxel (igual que la rama no-pasto de
-/// `voxel_color`): el alpha lleva el discriminante de `VoxelType` para que el
+/// `voxel_color`): el alpha lleva el id del material (`VoxelType::id`) para que el
 /// shader de paleta aplique la variación tonal (troncos/copas de los impostores).
 fn linear_rgba(voxel_type: VoxelType) -> [f32; 4] {
     let l = voxel_type.properties().color.to_linear();
-    [l.red, l.green, l.blue, voxel_type as u8 as f32 / 255.0]
+    [l.red, l.green, l.blue, voxel_type.id() as f32 / 255.0]
 }
 
 /// Punto `i` de un anillo horizontal de `sides` lados, radio `radius`, centrado en `center`.
@@ -383,6 +399,7 @@ fn ring_point(center: Vec3, radius: f32, i: usize, sides: usize) -> Vec3 {
 /// Añade un triángulo con normal plana, garantizando que su cara FRONTAL mire
 /// hacia afuera (lejos de `interior`). Así no importa el orden de los vértices:
 /// el winding se corrige para que el backface culling no lo oculte.
+#[allow(clippy::too_many_arguments)]
 fn add_tri_outward(
     a: Vec3,
     b: Vec3,
@@ -424,6 +441,7 @@ fn add_tri_outward(
 }
 
 /// Cono: abanico de triángulos desde un anillo de `IMPOSTOR_SIDES` lados hacia `apex`.
+#[allow(clippy::too_many_arguments)]
 fn add_cone(
     ring_center: Vec3,
     radius: f32,
@@ -588,6 +606,7 @@ fn add_oak_impostor(
 }
 
 /// Tronco: prisma cuadrado vertical (4 caras) desde `base`, radio `r`, alto `h`.
+#[allow(clippy::too_many_arguments)]
 fn add_trunk_prism(
     base: Vec3,
     r: f32,
@@ -633,11 +652,11 @@ fn add_tree_impostors(
     let cell_z_min = (origin.z - r).div_euclid(TREE_CELL_SIZE);
     let cell_z_max = (origin.z + n - 1 + r).div_euclid(TREE_CELL_SIZE);
 
-    let mut terrain_gen = TerrainGenerator::new(seed);
+    let terrain_gen = TerrainGenerator::new(seed);
 
     for cell_x in cell_x_min..=cell_x_max {
         for cell_z in cell_z_min..=cell_z_max {
-            let Some(tree) = tree_in_cell(cell_x, cell_z, seed) else {
+            let Some(tree) = tree_in_cell(cell_x, cell_z, &terrain_gen.biome_gen, seed) else {
                 continue;
             };
 
@@ -647,8 +666,7 @@ fn add_tree_impostors(
 
             let world_x_m = tree.world_x as f32 * VOXEL_SIZE;
             let world_z_m = tree.world_z as f32 * VOXEL_SIZE;
-            let surface_m = terrain_gen.biome_gen.generate_height(world_x_m, world_z_m);
-            let surface_voxel_y = (surface_m / VOXEL_SIZE).floor() as i32;
+            let surface_voxel_y = terrain_gen.biome_gen.surface_voxel_y(tree.world_x, tree.world_z);
 
             // Misma base que place_trees (surface_voxel_y + 1): el cono cae donde
             // arrancará el tronco real.
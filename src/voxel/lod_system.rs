@@ -31,7 +31,7 @@ impl ChunkLOD {
     }
 }
 
-/// Sistema que actualiza LOD, color basado en posicion del jugador
+/// Sistema que actualiza LOD, color basado en posicion del jugador mas cercano
 /// (Downsampling deshabilitado temporalmente para mejor rendimiento inicial)
 pub fn update_chunk_lod_system(
     player_query: Query<&Transform, With<Player>>,
@@ -42,9 +42,10 @@ pub fn update_chunk_lod_system(
     )>,
     chunk_materials: Res<crate::voxel::ChunkMaterials>,
 ) {
-    let Ok(player_transform) = player_query.single() else {
+    let players: Vec<Vec3> = player_query.iter().map(|t| t.translation).collect();
+    if players.is_empty() {
         return;
-    };
+    }
 
     for (base_chunk, mut chunk_lod, mut material_handle) in chunk_query.iter_mut() {
         // Calcular posición del chunk en el mundo desde su posición en la grilla
@@ -54,7 +55,11 @@ pub fn update_chunk_lod_system(
             base_chunk.position.z as f32 * BASE_CHUNK_SIZE as f32 * 0.1,
         );
 
-        let distance = player_transform.translation.distance(chunk_world_pos);
+        // Con pantalla dividida, el detalle lo manda el jugador mas cercano
+        let distance = players
+            .iter()
+            .map(|player| player.distance(chunk_world_pos))
+            .fold(f32::INFINITY, f32::min);
         let new_lod = ChunkLOD::from_distance(distance);
 
         if *chunk_lod != new_lod {
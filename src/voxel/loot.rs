@@ -0,0 +1,141 @@
+//! Tablas de botín: qué suelta un material al romperse.
+//!
+//! Cada material lleva su tabla en el archivo de materiales (`loot`). Al romper
+//! un voxel se elige UNA entrada al azar según su peso y se suelta una cantidad
+//! dentro de su rango; una entrada sin `item` es "nada". Las entradas con
+//! `tools` solo cuentan con una de esas herramientas en la mano y, si alguna
+//! aplica, sustituyen a las genéricas (las hojas cortadas con tijeras sueltan
+//! hojas en lugar de palos o brotes).
+//!
+//! El azar sale de [`LootRng`], sembrado con la seed del mundo.
+
+use crate::core::WorldSeed;
+use crate::voxel::{ToolType, VoxelType};
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Una salida posible de la tabla.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LootEntry {
+    /// Material que suelta (`None` = nada).
+    pub item: Option<VoxelType>,
+    /// Cantidad mínima y máxima (inclusive).
+    pub count: (u32, u32),
+    /// Peso relativo frente a las demás entradas que aplican.
+    pub weight: u32,
+    /// Solo con una de estas herramientas (vacío = con cualquiera).
+    pub tools: Vec<ToolType>,
+}
+
+/// Tabla de botín de un material. Vacía = no suelta nada.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LootTable {
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    /// Tira la tabla rompiendo con `tool`: material y cantidad, o `None` si
+    /// no suelta nada.
+    pub fn roll(&self, tool: ToolType, rng: &mut impl Rng) -> Option<(VoxelType, u32)> {
+        let with_tool: Vec<&LootEntry> =
+            self.entries.iter().filter(|e| e.tools.contains(&tool)).collect();
+        let candidates = if with_tool.is_empty() {
+            self.entries.iter().filter(|e| e.tools.is_empty()).collect()
+        } else {
+            with_tool
+        };
+
+        let total: u32 = candidates.iter().map(|e| e.weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.gen_range(0..total);
+        let entry = candidates.into_iter().find(|e| {
+            if pick < e.weight {
+                return true;
+            }
+            pick -= e.weight;
+            false
+        })?;
+
+        let (min, max) = entry.count;
+        let count = rng.gen_range(min..=max);
+        entry.item.filter(|_| count > 0).map(|item| (item, count))
+    }
+}
+
+/// Generador del botín. Sembrado con la seed del mundo al empezar partida: la
+/// misma secuencia de roturas suelta lo mismo.
+#[derive(Resource)]
+pub struct LootRng(pub StdRng);
+
+impl Default for LootRng {
+    fn default() -> Self {
+        Self(StdRng::seed_from_u64(0))
+    }
+}
+
+/// Re-siembra el botín con la seed del mundo elegido.
+pub fn seed_loot_rng(world_seed: Res<WorldSeed>, mut rng: ResMut<LootRng>) {
+    rng.0 = StdRng::seed_from_u64(world_seed.0 as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(item: Option<VoxelType>, count: (u32, u32), weight: u32) -> LootEntry {
+        LootEntry {
+            item,
+            count,
+            weight,
+            tools: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tool_entries_replace_the_generic_ones() {
+        let table = LootTable {
+            entries: vec![
+                LootEntry {
+                    tools: vec![ToolType::Shears],
+                    ..entry(Some(VoxelType::Leaves), (1, 1), 1)
+                },
+                entry(None, (1, 1), 1),
+                entry(Some(VoxelType::Wood), (1, 3), 1),
+            ],
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            assert_eq!(
+                table.roll(ToolType::Shears, &mut rng),
+                Some((VoxelType::Leaves, 1))
+            );
+            match table.roll(ToolType::None, &mut rng) {
+                None => {}
+                Some((VoxelType::Wood, n)) => assert!((1..=3).contains(&n)),
+                other => panic!("botín inesperado: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_rolls_the_same_loot() {
+        let table = LootTable {
+            entries: vec![
+                entry(Some(VoxelType::Stone), (1, 4), 3),
+                entry(Some(VoxelType::Dirt), (2, 2), 1),
+                entry(None, (1, 1), 2),
+            ],
+        };
+        let rolls = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..32)
+                .map(|_| table.roll(ToolType::Pickaxe, &mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rolls(42), rolls(42));
+        assert!(LootTable::default().roll(ToolType::None, &mut StdRng::seed_from_u64(1)).is_none());
+    }
+}
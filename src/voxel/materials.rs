@@ -0,0 +1,287 @@
+//! Registro de materiales: los datos de cada `VoxelType`.
+//!
+//! Color, dureza, colisión, paleta, icono, botín y herramientas de cada
+//! material son DATOS: se leen de [`MATERIALS_PATH`] la primera vez que se
+//! consultan, así que se pueden ajustar o añadir materiales sin recompilar. Si
+//! el archivo falta o no es válido, se usa la copia embebida en el binario (el
+//! mismo archivo, con `include_str!`) y se avisa en el log.
+//!
+//! El id de un material es su posición en la lista (los mundos guardados los
+//! traducen por nombre al abrirse, ver `world_save`). Es un registro global (no
+//! un `Resource`) porque `VoxelType::properties()` se consulta desde el meshing
+//! en hilos de rayon y tareas async, sin acceso al `World`.
+
+use crate::voxel::explosion::Blast;
+use crate::voxel::loot::{LootEntry, LootTable};
+use crate::voxel::palette::Palette;
+use crate::voxel::voxel_types::{VoxelProperties, VoxelType, BUILTIN_NAMES};
+use crate::voxel::{ToolTier, ToolType};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+/// Archivo de materiales (relativo al directorio de trabajo, como los mundos).
+pub const MATERIALS_PATH: &str = "assets/data/materials.ron";
+
+/// Copia embebida de los materiales, por si el archivo no está.
+const DEFAULT_MATERIALS: &str = include_str!("../../assets/data/materials.ron");
+
+/// Máximo de materiales: el id es un `u8` (y viaja en el vertex alpha como
+/// `id / 255`).
+pub const MAX_MATERIALS: usize = 256;
+
+static REGISTRY: LazyLock<MaterialRegistry> = LazyLock::new(MaterialRegistry::load);
+
+/// El registro global (se carga en la primera consulta).
+pub fn materials() -> &'static MaterialRegistry {
+    &REGISTRY
+}
+
+// ============================================================================
+// FORMATO DEL ARCHIVO
+// ============================================================================
+
+/// Una entrada de `materials.ron` (ver el comentario de cabecera del archivo).
+#[derive(Deserialize)]
+struct MaterialDef {
+    name: String,
+    color: (f32, f32, f32),
+    hardness: f32,
+    density: f32,
+    #[serde(default = "yes")]
+    collidable: bool,
+    #[serde(default)]
+    flammable: bool,
+    #[serde(default)]
+    falls: bool,
+    #[serde(default)]
+    explosive: Option<Blast>,
+    #[serde(default)]
+    palette: Option<SpreadDef>,
+    #[serde(default)]
+    glow: f32,
+    #[serde(default = "default_icon")]
+    icon: String,
+    #[serde(default)]
+    loot: Vec<LootDef>,
+    #[serde(default)]
+    tools: Vec<ToolType>,
+    #[serde(default)]
+    harvest: Option<ToolTier>,
+}
+
+/// Rango tonal de la paleta de un material.
+#[derive(Deserialize)]
+struct SpreadDef {
+    dark: f32,
+    light: f32,
+    steps: u8,
+}
+
+/// Una entrada de la tabla de botín (ver `loot`).
+#[derive(Deserialize)]
+struct LootDef {
+    /// Por nombre: se resuelve cuando ya se conocen todos los materiales.
+    #[serde(default)]
+    item: Option<String>,
+    #[serde(default = "one_each")]
+    count: (u32, u32),
+    #[serde(default = "one")]
+    weight: u32,
+    #[serde(default)]
+    tools: Vec<ToolType>,
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn one_each() -> (u32, u32) {
+    (1, 1)
+}
+
+fn default_icon() -> String {
+    "default".to_string()
+}
+
+// ============================================================================
+// REGISTRO
+// ============================================================================
+
+/// Todos los materiales, indexados por id.
+pub struct MaterialRegistry {
+    materials: Vec<VoxelProperties>,
+}
+
+impl MaterialRegistry {
+    /// Lee [`MATERIALS_PATH`]; si falta o no es válido, la copia embebida.
+    fn load() -> Self {
+        let text = std::fs::read_to_string(MATERIALS_PATH);
+        match text.map_err(|e| e.to_string()).and_then(|t| Self::parse(&t)) {
+            Ok(registry) => registry,
+            Err(error) => {
+                warn!("{}: {} (se usan los materiales embebidos)", MATERIALS_PATH, error);
+                Self::parse(DEFAULT_MATERIALS).expect("materiales embebidos válidos")
+            }
+        }
+    }
+
+    /// Parsea y valida una lista de materiales en RON.
+    ///
+    /// Los materiales que nombra el código deben abrir la lista en su orden
+    /// (sus ids están fijados en el código), los nombres no se pueden repetir
+    /// ni llevar comas (separan la tabla de `world.meta`) y lo que suelta el
+    /// botín debe existir.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let defs: Vec<MaterialDef> = ron::from_str(text).map_err(|e| e.to_string())?;
+        if defs.len() > MAX_MATERIALS {
+            return Err(format!("{} materiales (máximo {})", defs.len(), MAX_MATERIALS));
+        }
+        for (id, builtin) in BUILTIN_NAMES.iter().enumerate() {
+            match defs.get(id) {
+                Some(def) if def.name == *builtin => {}
+                found => {
+                    return Err(format!(
+                        "el material {} debe ser {} (hay {:?})",
+                        id,
+                        builtin,
+                        found.map(|def| def.name.as_str())
+                    ));
+                }
+            }
+        }
+
+        let id_of = |name: &str| defs.iter().position(|def| def.name == name);
+        let mut materials = Vec::with_capacity(defs.len());
+        for (id, def) in defs.iter().enumerate() {
+            if id_of(&def.name) != Some(id) {
+                return Err(format!("material repetido: {}", def.name));
+            }
+            if def.name.is_empty() || def.name.contains([',', '\n']) {
+                return Err(format!("nombre de material no válido: {:?}", def.name));
+            }
+            let mut loot = LootTable::default();
+            for entry in &def.loot {
+                let item = match &entry.item {
+                    None => None,
+                    Some(name) => match id_of(name) {
+                        Some(drop) => Some(VoxelType::from_id(drop as u8)),
+                        None => return Err(format!("{} suelta {}, que no existe", def.name, name)),
+                    },
+                };
+                if entry.count.0 > entry.count.1 {
+                    return Err(format!("{}: cantidad {:?} al revés", def.name, entry.count));
+                }
+                loot.entries.push(LootEntry {
+                    item,
+                    count: entry.count,
+                    weight: entry.weight,
+                    tools: entry.tools.clone(),
+                });
+            }
+            let (r, g, b) = def.color;
+            materials.push(VoxelProperties {
+                name: def.name.clone(),
+                color: Color::srgb(r, g, b),
+                hardness: def.hardness,
+                density: def.density,
+                collidable: def.collidable,
+                flammable: def.flammable,
+                falls: def.falls,
+                explosive: def.explosive,
+                palette: def.palette.as_ref().map(|spread| Palette {
+                    base: [r, g, b],
+                    dark_mul: spread.dark,
+                    light_mul: spread.light,
+                    steps: spread.steps,
+                }),
+                glow: def.glow,
+                icon: def.icon.clone(),
+                loot,
+                tools: def.tools.clone(),
+                harvest: def.harvest,
+            });
+        }
+        Ok(Self { materials })
+    }
+
+    /// Número de materiales (ids `0..count`).
+    pub fn count(&self) -> usize {
+        self.materials.len()
+    }
+
+    /// Propiedades de un material; un id desconocido se trata como aire.
+    pub fn get(&self, voxel_type: VoxelType) -> &VoxelProperties {
+        self.materials
+            .get(voxel_type.id() as usize)
+            .unwrap_or(&self.materials[0])
+    }
+
+    /// Material por nombre (`None` si no existe).
+    pub fn by_name(&self, name: &str) -> Option<VoxelType> {
+        self.materials
+            .iter()
+            .position(|material| material.name == name)
+            .map(|id| VoxelType::from_id(id as u8))
+    }
+
+    /// Todos los materiales con su tipo, en orden de id.
+    pub fn iter(&self) -> impl Iterator<Item = (VoxelType, &VoxelProperties)> {
+        self.materials
+            .iter()
+            .enumerate()
+            .map(|(id, material)| (VoxelType::from_id(id as u8), material))
+    }
+
+    /// Datos por material para el shader de paleta, indexados por id:
+    /// `(dark_mul, light_mul, steps, glow)`; `steps = 0` = color plano.
+    pub fn shader_spreads(&self) -> Vec<Vec4> {
+        self.materials
+            .iter()
+            .map(|material| match material.palette {
+                Some(p) => Vec4::new(p.dark_mul, p.light_mul, p.steps as f32, material.glow),
+                None => Vec4::new(0.0, 0.0, 0.0, material.glow),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_materials_match_the_named_types() {
+        let registry = MaterialRegistry::parse(DEFAULT_MATERIALS).unwrap();
+        assert!(registry.count() >= BUILTIN_NAMES.len());
+        assert_eq!(registry.by_name("Stone"), Some(VoxelType::Stone));
+        assert_eq!(registry.get(VoxelType::Stone).harvest, Some(ToolTier::Wood));
+        assert!(registry.get(VoxelType::Water).loot.entries.is_empty());
+        let grass = &registry.get(VoxelType::Grass).loot.entries;
+        assert_eq!(grass[0].item, Some(VoxelType::Dirt));
+        assert!(!registry.get(VoxelType::Foliage).collidable);
+        assert!(registry.get(VoxelType::Sand).falls && !registry.get(VoxelType::Stone).falls);
+        let tnt = registry.by_name("Tnt").map(|v| registry.get(v));
+        assert!(tnt.is_some_and(|tnt| tnt.explosive.is_some() && tnt.loot.entries.is_empty()));
+
+        // Shader: la lava brilla, el pasto (color baked) es plano
+        let spreads = registry.shader_spreads();
+        assert_eq!(spreads.len(), registry.count());
+        assert!(spreads[VoxelType::Lava3.id() as usize].w > 0.0);
+        assert_eq!(spreads[VoxelType::Grass.id() as usize].z, 0.0);
+    }
+
+    #[test]
+    fn invalid_material_lists_are_rejected() {
+        // Los materiales con nombre en el código no pueden cambiar de sitio
+        let swapped = DEFAULT_MATERIALS.replacen("\"Dirt\"", "\"Mud\"", 1);
+        assert!(MaterialRegistry::parse(&swapped).is_err());
+        // Lo que suelta tiene que existir
+        let bad_drop = DEFAULT_MATERIALS.replacen("item: \"Sand\"", "item: \"Glass\"", 1);
+        assert!(MaterialRegistry::parse(&bad_drop).is_err());
+    }
+}
@@ -5,40 +5,97 @@
 //! ## Estructura
 //! - 'greedy_meshing': Algoritmo de meshing optimizado que reduce triangulos 70-95%
 //! - 'voxel_types': Define los tipos de materiales y sus propiedades
+//! - 'materials': Registro de materiales leído de `assets/data/materials.ron`
+//! - 'loot': Tablas de botín de los materiales (qué suelta cada uno al romperse)
 //! - 'tools': Herramientas para interactuar con voxels
 //! - 'destruction': Sistema de destruccion de voxels
+//! - 'placement': Colocación de voxels del inventario (click derecho)
 //! - 'lod_system': Sistema de nivel de detalle (LOD) para chunks
 //! - 'dynamic_chunks': Chunks base de 32³ con generacion de terreno
+//! - 'world_save': Persistencia de seed + diffs en region files
+//! - 'water': Mesh translúcido del agua
+//! - 'lava': Simulación de la lava que fluye, su luz y lo que quema
+//! - 'structural': Integridad estructural (lo que queda sin apoyo cae como escombro)
+//! - 'falling': Arena y grava que caen cuando les quitan el apoyo
+//! - 'explosion': Explosiones (daño radial al terreno, empuje) y la TNT
+//! - 'voxel_world': `VoxelWorld`/`VoxelReader`, la API de lectura y edición del mundo
+//!
+//! [`world_simulation_systems`] junta lo que el mundo hace solo (lava,
+//! explosiones, derrumbes y caída): corre donde el mundo es autoritativo, en
+//! la partida local y en el servidor, nunca en un cliente de red.
 
 pub mod biomes;
 pub mod chunk_loading;
 pub mod destruction;
 pub mod dynamic_chunks;
+pub mod explosion;
+pub mod falling;
 pub mod frustum_culling;
 pub mod greedy_meshing;
+pub mod lava;
 pub mod lod_chunks;
 pub mod lod_system;
-// Espejo en Rust de la tabla `SPREADS` del shader de paleta (los valores por
-// material). Documentación/spec testeada; el render usa el WGSL, no esto — de ahí
-// el allow.
+pub mod loot;
+pub mod materials;
+// `step_multiplier` es la spec testeada de la fórmula del shader; el render usa
+// el WGSL, no esto — de ahí el allow.
 #[allow(dead_code)]
 pub mod palette;
 pub mod palette_material;
+pub mod placement;
 pub mod spatial_hash;
+pub mod structural;
 pub mod tools;
 pub mod voxel_color;
 pub mod voxel_types;
+pub mod voxel_world;
+pub mod water;
+pub mod world_save;
 
 pub use biomes::*;
 pub use chunk_loading::*;
 pub use destruction::*;
 pub use dynamic_chunks::BaseChunk;
+pub use explosion::*;
+pub use falling::*;
 pub use frustum_culling::*;
 pub use greedy_meshing::*;
+pub use lava::*;
 pub use lod_chunks::*;
 pub use lod_system::*;
+pub use loot::*;
+pub use materials::*;
 pub use palette_material::*;
+pub use placement::*;
 pub use spatial_hash::*;
+pub use structural::*;
 pub use tools::*;
 pub use voxel_color::*;
 pub use voxel_types::*;
+pub use voxel_world::*;
+pub use water::*;
+pub use world_save::*;
+
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::*;
+
+/// Lo que el mundo hace solo, en orden: la lava (despertada por los chunks
+/// nuevos y las ediciones), las mechas y explosiones, y lo que queda sin apoyo
+/// (escombros y materiales que caen, que al asentarse vuelven al mundo o se
+/// rompen en drops). Va justo después de `apply_pending_voxel_edits`.
+pub fn world_simulation_systems() -> ScheduleConfigs<ScheduleSystem> {
+    (
+        (wake_lava_on_chunk_load, wake_lava_on_edits, lava_flow_system).chain(),
+        (burn_fuses_system, explosion_system).chain(),
+        (
+            queue_structural_checks,
+            collapse_unsupported_voxels,
+            wake_falling_on_edits,
+            falling_voxels_system,
+            settle_debris_system,
+        )
+            .chain(),
+    )
+        .chain()
+}
@@ -1,11 +1,10 @@
-//! Registro de paletas tonales por material.
+//! Paletas tonales por material.
 //!
-//! Fuente única de verdad: cada `VoxelType` con variación de color declara aquí
-//! su paleta (color base + rango de brillo + nº de tonos). El pintado por CPU
-//! (`voxel_color`) la consume hoy; el shader de paleta (fase 4b) subirá estos
-//! mismos datos a la GPU. Materiales sin entrada usan su color plano.
+//! Cada material con variación de color declara su paleta (rango de brillo +
+//! nº de tonos) en el archivo de materiales; el color base es el del material.
+//! El registro sube esos datos a la GPU y el shader de paleta los aplica por
+//! fragmento. Materiales sin paleta usan su color plano.
 
-use crate::vegetation::config;
 use crate::voxel::VoxelType;
 
 /// Parámetros de una paleta tonal. Se generan `steps` tonos escalando el brillo
@@ -26,30 +25,11 @@ pub struct Palette {
 /// Paleta de un material, o `None` si usa un color plano (sin variación tonal).
 ///
 /// El rango tonal (`dark_mul`/`light_mul`/`steps`) es POR MATERIAL. Estos valores
-/// se suben a la GPU (`ChunkMaterials` → uniform `spreads`, indexado por el
-/// discriminante de `VoxelType`) y el shader los aplica por fragmento.
+/// se suben a la GPU (`MaterialRegistry::shader_spreads` → storage buffer
+/// `spreads`, indexado por el id del material) y el shader los aplica por
+/// fragmento.
 pub fn palette_of(voxel_type: VoxelType) -> Option<Palette> {
-    // `(dark_mul, light_mul, steps)` por material: rocas y madera varían más;
-    // hojas y suelo, más sutil. `base` debe coincidir con el color plano del
-    // material en `VoxelType::properties`.
-    let (base, dark_mul, light_mul, steps) = match voxel_type {
-        VoxelType::Wood => (config::WOOD_COLOR, 0.70, 1.25, 5),
-        VoxelType::PineWood => (config::PINE_WOOD_COLOR, 0.70, 1.25, 5),
-        VoxelType::Stone => ([0.5, 0.5, 0.5], 0.60, 1.35, 6),
-        VoxelType::Leaves => (config::LEAVES_COLOR, 0.80, 1.15, 4),
-        VoxelType::PineNeedles => (config::PINE_COLOR, 0.80, 1.15, 4),
-        VoxelType::SmallLeaves => (config::SMALL_LEAVES_COLOR, 0.80, 1.15, 4),
-        VoxelType::Bush => (config::BUSH_COLOR, 0.80, 1.20, 4),
-        VoxelType::Dirt => ([0.55, 0.35, 0.2], 0.80, 1.20, 4),
-        VoxelType::Sand => ([0.9, 0.85, 0.6], 0.88, 1.10, 4),
-        _ => return None,
-    };
-    Some(Palette {
-        base,
-        dark_mul,
-        light_mul,
-        steps,
-    })
+    voxel_type.properties().palette
 }
 
 /// Multiplicador de brillo del tono `i` de una paleta de `steps` tonos.
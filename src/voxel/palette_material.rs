@@ -4,9 +4,11 @@
 //! el greedy meshing vuelve a fusionar por `VoxelType` y el color se calcula por
 //! fragmento en la GPU.
 
+use crate::voxel::materials;
 use bevy::pbr::{ExtendedMaterial, MaterialExtension};
 use bevy::prelude::*;
 use bevy::render::render_resource::AsBindGroup;
+use bevy::render::storage::ShaderStorageBuffer;
 use bevy::shader::ShaderRef;
 
 /// Ruta del shader de la extensión (relativa a `assets/`).
@@ -15,13 +17,30 @@ const SHADER_PATH: &str = "shaders/palette_extension.wgsl";
 /// Material de los chunks reales.
 pub type ChunkMaterial = ExtendedMaterial<StandardMaterial, PaletteExtension>;
 
-/// Extensión de paleta. **Sin bindings**: solo intercambia el fragment shader.
-/// El rango tonal por material vive en el WGSL (`SPREADS`), no en un uniform,
-/// porque el `StandardMaterial` bindless de Bevy 0.17 descarta los bindings de
-/// extensión en el grupo 2. El vertex alpha lleva el discriminante de `VoxelType`
-/// y el shader indexa `SPREADS` con él.
-#[derive(Asset, TypePath, AsBindGroup, Clone, Default)]
-pub struct PaletteExtension {}
+/// Extensión de paleta: intercambia el fragment shader y le da, en un storage
+/// buffer, el rango tonal y el brillo de cada material (ver
+/// [`MaterialRegistry::shader_spreads`](crate::voxel::MaterialRegistry::shader_spreads)).
+/// El vertex alpha lleva el id del material y el shader indexa `spreads` con él.
+///
+/// Sin `#[bindless]`: la extensión no lo soporta, así que el `ChunkMaterial`
+/// entero usa bind groups normales (el binding 100 convive con los del
+/// `StandardMaterial`).
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct PaletteExtension {
+    /// `(dark_mul, light_mul, steps, glow)` por id de material.
+    #[storage(100, read_only)]
+    pub spreads: Handle<ShaderStorageBuffer>,
+}
+
+impl PaletteExtension {
+    /// Sube los datos del registro de materiales a un storage buffer.
+    pub fn from_registry(buffers: &mut Assets<ShaderStorageBuffer>) -> Self {
+        let spreads = ShaderStorageBuffer::from(materials().shader_spreads());
+        Self {
+            spreads: buffers.add(spreads),
+        }
+    }
+}
 
 impl MaterialExtension for PaletteExtension {
     fn fragment_shader() -> ShaderRef {
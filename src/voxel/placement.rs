@@ -0,0 +1,153 @@
+//! Sistema de colocación de voxels (construir)
+//!
+//! Con el click derecho (gatillo izquierdo en el mando) cada jugador coloca el
+//! material en su mano (slot elegido de la barra rápida de su `Inventory`) en la
+//! celda pegada a la cara que apunta el jugador: `VoxelWorld::raycast` devuelve
+//! el voxel golpeado y la normal de esa cara. La celda se resuelve en
+//! coordenadas de voxel mundiales, así que puede caer en el chunk vecino.
+//!
+//! En red el cliente solo pide colocar el slot en la celda
+//! (`ClientMessage::Place`); el servidor comprueba lo mismo con
+//! [`placement_allowed`] sobre su mundo y gasta el material de su inventario.
+
+use super::{VoxelType, VoxelWorld};
+use crate::core::{constants::VOXEL_SIZE, Inventory};
+use crate::net::{ClientMessage, NetClient};
+use crate::player::components::{Player, PLAYER_HALF_HEIGHT, PLAYER_RADIUS};
+use crate::player::PlayerActions;
+use bevy::prelude::*;
+
+/// Alcance de la colocación (metros), el mismo que el de la destrucción.
+const PLACE_REACH: f32 = 5.0;
+
+/// Vecinos por las 6 caras.
+const NEIGHBORS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+/// ¿Se solapa el voxel (coordenada mundial) con una cápsula vertical?
+///
+/// Distancia del voxel (AABB) al segmento de la cápsula: el punto del segmento
+/// más cercano a la caja tiene la Y del centro de la caja acotada al segmento.
+pub fn voxel_overlaps_capsule(voxel: IVec3, center: Vec3, half_height: f32, radius: f32) -> bool {
+    let min = voxel.as_vec3() * VOXEL_SIZE;
+    let max = min + Vec3::splat(VOXEL_SIZE);
+    let y = ((min.y + max.y) * 0.5).clamp(center.y - half_height, center.y + half_height);
+    let axis_point = Vec3::new(center.x, y, center.z);
+    axis_point.clamp(min, max).distance_squared(axis_point) < radius * radius
+}
+
+/// ¿Se puede construir en `target`? Su chunk está cargado, no hay nada sólido
+/// en él, toca por alguna cara algo sólido (se construye contra una cara, no en
+/// el aire) y no se solapa con ningún jugador de `bodies` (centros de cápsula).
+pub fn placement_allowed(
+    target: IVec3,
+    get: impl Fn(IVec3) -> Option<VoxelType>,
+    bodies: &[Vec3],
+) -> bool {
+    get(target).is_some_and(|current| !current.is_solid())
+        && NEIGHBORS
+            .iter()
+            .any(|&offset| get(target + offset).is_some_and(|v| v.is_solid()))
+        && !bodies
+            .iter()
+            .any(|&body| voxel_overlaps_capsule(target, body, PLAYER_HALF_HEIGHT, PLAYER_RADIUS))
+}
+
+/// Coloca un voxel del material en la mano de cada jugador que pulsa colocar.
+///
+/// Se niega si la celda destino está ocupada por algo sólido, si su chunk no
+/// está cargado, si se solapa con algún jugador o si en la mano no hay un
+/// material. Al colocar gasta uno del stack en la mano; `VoxelWorld` se encarga
+/// del diff, del remallado y de avisar a la lava. En red se pide al servidor,
+/// que es quien gasta el material.
+pub fn place_voxel_system(
+    mut player_query: Query<(&Transform, &PlayerActions, &mut Inventory), With<Player>>,
+    mut world: VoxelWorld,
+    mut net: Option<ResMut<NetClient>>,
+) {
+    // Cápsulas de todos los jugadores: nadie construye dentro de otro
+    let bodies: Vec<Vec3> = player_query
+        .iter()
+        .map(|(transform, ..)| transform.translation)
+        .collect();
+
+    for (transform, actions, mut inventory) in &mut player_query {
+        if !actions.place {
+            continue;
+        }
+        let Some(voxel_type) = inventory.held().and_then(|stack| stack.as_voxel()) else {
+            continue;
+        };
+
+        // La cámara va en la entidad del jugador
+        let Some((hit, _hit_type, normal)) = world.raycast(
+            transform.translation,
+            transform.forward().as_vec3(),
+            PLACE_REACH,
+        ) else {
+            continue;
+        };
+        if normal == IVec3::ZERO {
+            continue; // La cámara está dentro del voxel: no hay cara
+        }
+
+        // Solo se construye sobre aire o líquido, en un chunk cargado
+        let target = hit + normal;
+        if !placement_allowed(target, |v| world.get(v), &bodies) {
+            continue;
+        }
+        match net.as_mut() {
+            Some(net) => net.request(ClientMessage::Place {
+                voxel: target,
+                slot: inventory.selected as u8,
+            }),
+            None => {
+                inventory.take_held();
+                world.set(target, voxel_type);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capsule_blocks_cells_it_touches() {
+        let center = Vec3::new(0.05, 1.0, 0.05);
+        // Voxel en el eje de la cápsula, a la altura del centro
+        assert!(voxel_overlaps_capsule(IVec3::new(0, 10, 0), center, 0.9, 0.3));
+        // La punta de la semiesfera inferior (y = 1.0 - 0.9 - 0.3 = -0.2) toca
+        // el voxel y = -2; más abajo queda libre
+        assert!(voxel_overlaps_capsule(IVec3::new(0, -2, 0), center, 0.9, 0.3));
+        assert!(!voxel_overlaps_capsule(IVec3::new(0, -4, 0), center, 0.9, 0.3));
+        // A un lado: dentro y fuera del radio
+        assert!(!voxel_overlaps_capsule(IVec3::new(4, 10, 0), center, 0.9, 0.3));
+        assert!(voxel_overlaps_capsule(IVec3::new(3, 10, 0), center, 0.9, 0.3));
+    }
+
+    #[test]
+    fn placing_needs_a_free_loaded_cell_against_a_solid_face() {
+        // Suelo de piedra en y < 0, aire encima y nada cargado en x > 10
+        let get = |v: IVec3| match v {
+            _ if v.x > 10 => None,
+            _ if v.y < 0 => Some(VoxelType::Stone),
+            _ => Some(VoxelType::Air),
+        };
+        assert!(placement_allowed(IVec3::new(0, 0, 0), get, &[]));
+        // Ocupada, en el aire o sin cargar
+        assert!(!placement_allowed(IVec3::new(0, -1, 0), get, &[]));
+        assert!(!placement_allowed(IVec3::new(0, 3, 0), get, &[]));
+        assert!(!placement_allowed(IVec3::new(11, 0, 0), get, &[]));
+        // Dentro de un jugador
+        let body = Vec3::new(0.05, 1.0, 0.05);
+        assert!(!placement_allowed(IVec3::new(0, 0, 0), get, &[body]));
+    }
+}
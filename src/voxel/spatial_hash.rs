@@ -58,7 +58,7 @@ impl SpatialHashGrid {
     /// Inserta un chunk en el grid - O(1)
     pub fn insert(&mut self, chunk_pos: IVec3) {
         let cell = self.chunk_to_cell(chunk_pos);
-        let chunks = self.cells.entry(cell).or_insert_with(Vec::new);
+        let chunks = self.cells.entry(cell).or_default();
 
         // Evitar duplicados
         if !chunks.contains(&chunk_pos) {
@@ -71,18 +71,18 @@ impl SpatialHashGrid {
     pub fn remove(&mut self, chunk_pos: IVec3) -> bool {
         let cell = self.chunk_to_cell(chunk_pos);
 
-        if let Some(chunks) = self.cells.get_mut(&cell) {
-            if let Some(index) = chunks.iter().position(|&pos| pos == chunk_pos) {
-                chunks.swap_remove(index);
-                self.total_chunks -= 1;
+        if let Some(chunks) = self.cells.get_mut(&cell)
+            && let Some(index) = chunks.iter().position(|&pos| pos == chunk_pos)
+        {
+            chunks.swap_remove(index);
+            self.total_chunks -= 1;
 
-                // Limpiar celda vacía para ahorrar memoria
-                if chunks.is_empty() {
-                    self.cells.remove(&cell);
-                }
-
-                return true;
+            // Limpiar celda vacía para ahorrar memoria
+            if chunks.is_empty() {
+                self.cells.remove(&cell);
             }
+
+            return true;
         }
 
         false
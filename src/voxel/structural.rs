@@ -0,0 +1,503 @@
+//! Integridad estructural: lo que queda colgando se cae.
+//!
+//! Tras cada edición que deja un hueco (`VoxelEdited` con un tipo no sólido),
+//! se busca desde los sólidos vecinos del hueco si siguen "anclados". La
+//! búsqueda es un flood fill ACOTADO por las 6 caras ([`unsupported_islands`]):
+//! llegar a un chunk sin cargar o pasar de [`MAX_ISLAND_VOXELS`] cuenta como
+//! anclado (el terreno es enorme y está todo conectado). Lo que se agota antes
+//! es una isla suelta: un trozo de puente, una copa con el tronco cortado.
+//!
+//! Cada isla sale del mundo (pasa por `VoxelWorld`: diffs + remallado) y se
+//! convierte en un escombro ([`Debris`]): un cuerpo dinámico de Rapier con su
+//! propio greedy mesh y un collider convexo. Cuando se queda quieto:
+//! - Si cayó casi alineado con la rejilla, vuelve al mundo como voxels con la
+//!   orientación encajada (un árbol tumbado queda tumbado); lo que cae sobre
+//!   celdas ocupadas se rompe en drops.
+//! - Si quedó torcido, o no tiene dónde apoyarse, se rompe entero en drops
+//!   (su tabla de botín, como si se rompiera a mano).
+
+use super::{
+    greedy_mesh_basechunk_simple, voxel_to_chunk, world_to_voxel, BaseChunk, LootRng,
+    ToolType, VoxelEdited, VoxelType, VoxelWorld,
+};
+use crate::core::constants::VOXEL_SIZE;
+use crate::physics::{spawn_rapier_voxel_drop, Collider, DropAssets, RigidBody};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{ColliderMassProperties, Velocity};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// ============================================================================
+// PARÁMETROS
+// ============================================================================
+
+/// Tamaño máximo de una isla suelta (voxels). Una búsqueda que lo pasa se da
+/// por anclada: acota el coste de cada edición contra el terreno. Cabe un roble
+/// entero (tronco + copa).
+pub const MAX_ISLAND_VOXELS: usize = 16_384;
+
+/// Por debajo de estas velocidades (m/s y rad/s) un escombro cuenta como quieto.
+const SETTLE_SPEED: f32 = 0.05;
+const SETTLE_SPIN: f32 = 0.1;
+/// Segundos seguidos quieto antes de resolverse.
+const SETTLE_SECS: f32 = 0.5;
+/// Un escombro que no se asienta en este tiempo se resuelve igualmente.
+const MAX_DEBRIS_SECS: f32 = 15.0;
+/// Giro máximo (rad) respecto a la orientación de rejilla más cercana para
+/// volver al mundo como voxels.
+const MAX_SNAP_ANGLE: f32 = 0.35; // ~20°
+
+/// Voxels con su celda (o su desplazamiento dentro de un escombro).
+type VoxelCells = Vec<(IVec3, VoxelType)>;
+
+/// Las 6 vecinas de una celda.
+const NEIGHBORS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+// ============================================================================
+// RESOURCES / COMPONENTS
+// ============================================================================
+
+/// Un trozo de mundo desprendido, cayendo como cuerpo rígido.
+#[derive(Component, Debug)]
+pub struct Debris {
+    /// Voxels respecto al origen del cuerpo (su esquina mínima al desprenderse).
+    pub voxels: Vec<(IVec3, VoxelType)>,
+    /// Segundos seguidos quieto.
+    pub still_secs: f32,
+    /// Segundos desde que se desprendió.
+    pub age: f32,
+}
+
+/// Huecos recientes (coordenadas de voxel mundiales) cuyos sólidos vecinos hay
+/// que comprobar. Lo llena `queue_structural_checks` con los `VoxelEdited`.
+#[derive(Resource, Debug, Default)]
+pub struct StructuralChecks {
+    seeds: Vec<IVec3>,
+}
+
+/// Material de los escombros: blanco con vertex colors y SIN paleta (el shader
+/// de paleta hashea la posición mundial, que en un cuerpo en movimiento haría
+/// parpadear los tonos).
+#[derive(Resource)]
+pub struct DebrisAssets {
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for DebrisAssets {
+    fn from_world(world: &mut World) -> Self {
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.9,
+                ..default()
+            });
+        Self { material }
+    }
+}
+
+// ============================================================================
+// REGLAS (funciones puras)
+// ============================================================================
+
+/// Islas sin anclaje alcanzables desde `seeds` (coordenadas de voxel mundiales).
+///
+/// `get` devuelve el voxel o `None` si su chunk no está cargado. Cada semilla
+/// sólida arranca un flood fill por las 6 caras sobre voxels sólidos; queda
+/// anclada si toca algo sin cargar, pasa de `max_island` voxels o llega a algo
+/// que otra búsqueda ya dio por anclado. Las semillas de una misma isla se
+/// recorren una sola vez.
+pub fn unsupported_islands(
+    seeds: impl IntoIterator<Item = IVec3>,
+    get: impl Fn(IVec3) -> Option<VoxelType>,
+    max_island: usize,
+) -> Vec<Vec<IVec3>> {
+    let mut visited: HashSet<IVec3> = HashSet::new();
+    let mut islands = Vec::new();
+
+    for seed in seeds {
+        if visited.contains(&seed) || !get(seed).is_some_and(|v| v.is_solid()) {
+            continue;
+        }
+        let mut seen = HashSet::from([seed]);
+        let mut queue = VecDeque::from([seed]);
+        let mut anchored = false;
+
+        'fill: while let Some(voxel) = queue.pop_front() {
+            for offset in NEIGHBORS {
+                let next = voxel + offset;
+                if seen.contains(&next) {
+                    continue;
+                }
+                match get(next) {
+                    None => anchored = true,
+                    Some(v) if v.is_solid() => {
+                        anchored = visited.contains(&next) || seen.len() >= max_island;
+                        seen.insert(next);
+                        queue.push_back(next);
+                    }
+                    Some(_) => {}
+                }
+                if anchored {
+                    break 'fill;
+                }
+            }
+        }
+
+        if !anchored {
+            // En orden fijo: con el del `HashSet`, la masa y el collider del
+            // escombro cambiarían en el último decimal de una ejecución a otra
+            let mut island: Vec<IVec3> = seen.iter().copied().collect();
+            island.sort_by_key(|v| (v.y, v.x, v.z));
+            islands.push(island);
+        }
+        visited.extend(seen);
+    }
+    islands
+}
+
+/// Orientación de rejilla más cercana a `rotation` (adónde van los ejes X, Y,
+/// Z), o `None` si se aparta más de [`MAX_SNAP_ANGLE`] de cualquiera.
+fn snap_rotation(rotation: Quat) -> Option<[IVec3; 3]> {
+    let axes = [Vec3::X, Vec3::Y, Vec3::Z].map(|axis| {
+        let v = rotation * axis;
+        let a = v.abs();
+        if a.x >= a.y && a.x >= a.z {
+            IVec3::X * v.x.signum() as i32
+        } else if a.y >= a.z {
+            IVec3::Y * v.y.signum() as i32
+        } else {
+            IVec3::Z * v.z.signum() as i32
+        }
+    });
+    let snapped = Mat3::from_cols(axes[0].as_vec3(), axes[1].as_vec3(), axes[2].as_vec3());
+    // Dos ejes al mismo sitio (o un espejo): no es una rotación
+    if (snapped.determinant() - 1.0).abs() > 0.5 {
+        return None;
+    }
+    (Quat::from_mat3(&snapped).angle_between(rotation) <= MAX_SNAP_ANGLE).then_some(axes)
+}
+
+/// Celdas del mundo que ocupan los voxels de un escombro con origen en
+/// `translation` y la orientación encajada `axes`.
+///
+/// Se trabaja en medios voxels: el centro del voxel `d` es `2d + 1`, que una
+/// rotación de rejilla lleva a otro centro. Así todas las celdas se desplazan
+/// igual y la forma no se deforma ni abre huecos.
+fn landed_cells(
+    translation: Vec3,
+    axes: [IVec3; 3],
+    voxels: &[(IVec3, VoxelType)],
+) -> VoxelCells {
+    let base = (translation / VOXEL_SIZE).round().as_ivec3();
+    voxels
+        .iter()
+        .map(|&(offset, voxel_type)| {
+            let doubled = offset * 2 + IVec3::ONE;
+            let rotated = axes[0] * doubled.x + axes[1] * doubled.y + axes[2] * doubled.z;
+            (base + rotated.div_euclid(IVec3::splat(2)), voxel_type)
+        })
+        .collect()
+}
+
+/// Intenta devolver al mundo las celdas de un escombro, bajándolas un voxel si
+/// quedan en el aire (el redondeo puede dejar un hueco con el suelo).
+///
+/// Devuelve lo que se coloca y los voxels que no caben (celda ocupada o sin
+/// cargar), o `None` si no hay forma de apoyarlo (volvería a caer).
+fn revoxelize(
+    cells: &[(IVec3, VoxelType)],
+    get: impl Fn(IVec3) -> Option<VoxelType>,
+) -> Option<(VoxelCells, Vec<VoxelType>)> {
+    for drop in 0..=1 {
+        let (placed, blocked): (Vec<_>, Vec<_>) = cells
+            .iter()
+            .map(|&(cell, voxel_type)| (cell - IVec3::Y * drop, voxel_type))
+            .partition(|&(cell, _)| get(cell).is_some_and(|v| !v.is_solid()));
+
+        let occupied: HashSet<IVec3> = placed.iter().map(|&(cell, _)| cell).collect();
+        let supported = occupied.iter().any(|&cell| {
+            NEIGHBORS.iter().any(|&offset| {
+                let next = cell + offset;
+                !occupied.contains(&next) && get(next).is_none_or(|v| v.is_solid())
+            })
+        });
+        if supported {
+            return Some((placed, blocked.into_iter().map(|(_, t)| t).collect()));
+        }
+    }
+    None
+}
+
+// ============================================================================
+// ESCOMBROS
+// ============================================================================
+
+/// Meshes de un escombro: sus voxels repartidos en bloques de chunk (un
+/// `BaseChunk` temporal por bloque) y mallados con el greedy simple. Las
+/// posiciones salen relativas al origen del cuerpo.
+fn debris_meshes(voxels: &[(IVec3, VoxelType)]) -> Vec<Mesh> {
+    let mut parts: HashMap<IVec3, BaseChunk> = HashMap::new();
+    for &(offset, voxel_type) in voxels {
+        let (part, local) = voxel_to_chunk(offset);
+        let chunk = parts.entry(part).or_insert_with(|| BaseChunk::empty(part));
+        chunk.voxel_types[local.x as usize][local.y as usize][local.z as usize] = voxel_type;
+    }
+    parts.values().map(greedy_mesh_basechunk_simple).collect()
+}
+
+/// Collider convexo con las esquinas de los voxels de la superficie; una caja
+/// si el casco sale degenerado.
+fn debris_collider(voxels: &[(IVec3, VoxelType)]) -> Collider {
+    let cells: HashSet<IVec3> = voxels.iter().map(|&(offset, _)| offset).collect();
+    let mut corners: HashSet<IVec3> = HashSet::new();
+    for &cell in &cells {
+        if NEIGHBORS.iter().all(|&offset| cells.contains(&(cell + offset))) {
+            continue; // interior: no aporta al casco
+        }
+        for corner in 0..8 {
+            corners.insert(cell + IVec3::new(corner & 1, (corner >> 1) & 1, corner >> 2));
+        }
+    }
+    let points: Vec<Vec3> = corners.iter().map(|c| c.as_vec3() * VOXEL_SIZE).collect();
+    Collider::convex_hull(&points).unwrap_or_else(|| {
+        let max = cells.iter().fold(IVec3::ZERO, |acc, &c| acc.max(c + IVec3::ONE));
+        let half = max.as_vec3() * VOXEL_SIZE * 0.5;
+        Collider::compound(vec![(half, Quat::IDENTITY, Collider::cuboid(half.x, half.y, half.z))])
+    })
+}
+
+/// Deja caer como escombro unos voxels que ya se quitaron del mundo.
+///
+/// Sin `render` (el servidor headless) el cuerpo cae igual, pero sin meshes.
+pub(crate) fn spawn_debris(
+    commands: &mut Commands,
+    render: Option<(&mut Assets<Mesh>, &DebrisAssets)>,
+    island: VoxelCells,
+) {
+    let origin = island
+        .iter()
+        .fold(IVec3::MAX, |acc, &(voxel, _)| acc.min(voxel));
+    let voxels: VoxelCells = island
+        .into_iter()
+        .map(|(voxel, voxel_type)| (voxel - origin, voxel_type))
+        .collect();
+    let mass: f32 = voxels.iter().map(|(_, t)| t.properties().density).sum();
+
+    let collider = debris_collider(&voxels);
+    let parts = render.is_some().then(|| debris_meshes(&voxels));
+    let mut body = commands
+        .spawn((
+            Transform::from_translation(origin.as_vec3() * VOXEL_SIZE),
+            Visibility::default(),
+            RigidBody::Dynamic,
+            collider,
+            ColliderMassProperties::Mass(mass.max(0.1)),
+            Velocity::zero(),
+            Debris {
+                voxels,
+                still_secs: 0.0,
+                age: 0.0,
+            },
+        ));
+    let (Some((meshes, debris_assets)), Some(parts)) = (render, parts) else {
+        return;
+    };
+    body.with_children(|body| {
+        for mesh in parts {
+            body.spawn((
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(debris_assets.material.clone()),
+                Transform::default(),
+            ));
+        }
+    });
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Apunta los vecinos de cada voxel que se quedó vacío (roto, quemado...).
+pub fn queue_structural_checks(
+    mut edited: MessageReader<VoxelEdited>,
+    mut checks: ResMut<StructuralChecks>,
+) {
+    for edit in edited.read() {
+        if !edit.voxel_type.is_solid() {
+            checks.seeds.extend(NEIGHBORS.map(|offset| edit.voxel + offset));
+        }
+    }
+}
+
+/// Busca islas sueltas alrededor de los huecos apuntados y las convierte en
+/// escombros.
+pub fn collapse_unsupported_voxels(
+    mut commands: Commands,
+    mut checks: ResMut<StructuralChecks>,
+    mut world: VoxelWorld,
+    mut meshes: Option<ResMut<Assets<Mesh>>>,
+    debris_assets: Option<Res<DebrisAssets>>,
+) {
+    if checks.seeds.is_empty() {
+        return;
+    }
+    let seeds = std::mem::take(&mut checks.seeds);
+
+    for island in unsupported_islands(seeds, |v| world.get(v), MAX_ISLAND_VOXELS) {
+        let voxels: VoxelCells = island
+            .iter()
+            .filter_map(|&voxel| Some((voxel, world.get(voxel)?)))
+            .collect();
+        world.set_batch(island.iter().map(|&voxel| (voxel, VoxelType::Air)));
+        info!("Se desprende una isla de {} voxels", voxels.len());
+        let render = meshes.as_deref_mut().zip(debris_assets.as_deref());
+        spawn_debris(&mut commands, render, voxels);
+    }
+}
+
+/// Resuelve los escombros que se asentaron (o que llevan demasiado cayendo o
+/// están sobre un chunk sin cargar): de vuelta al mundo o en drops (sin
+/// `DropAssets`, en el servidor, el botín se tira pero no se suelta).
+pub fn settle_debris_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut world: VoxelWorld,
+    mut debris_query: Query<(Entity, &Transform, &Velocity, &mut Debris)>,
+    drop_assets: Option<Res<DropAssets>>,
+    mut loot_rng: ResMut<LootRng>,
+) {
+    for (entity, transform, velocity, mut debris) in debris_query.iter_mut() {
+        debris.age += time.delta_secs();
+        let resting =
+            velocity.linvel.length() < SETTLE_SPEED && velocity.angvel.length() < SETTLE_SPIN;
+        debris.still_secs = if resting {
+            debris.still_secs + time.delta_secs()
+        } else {
+            0.0
+        };
+        let over_unloaded = world.get(world_to_voxel(transform.translation).2).is_none();
+        if debris.still_secs < SETTLE_SECS && debris.age < MAX_DEBRIS_SECS && !over_unloaded {
+            continue;
+        }
+        commands.entity(entity).despawn();
+
+        let landed = snap_rotation(transform.rotation)
+            .map(|axes| landed_cells(transform.translation, axes, &debris.voxels))
+            .and_then(|cells| revoxelize(&cells, |v| world.get(v)));
+        let broken = match landed {
+            Some((placed, blocked)) => {
+                world.set_batch(placed);
+                blocked
+            }
+            None => debris.voxels.iter().map(|&(_, t)| t).collect(),
+        };
+        if broken.is_empty() {
+            continue;
+        }
+
+        // Lo que no vuelve al mundo se rompe: su botín, un drop por tipo en el
+        // centro de masa del escombro
+        let mut drop_counts = vec![0u32; VoxelType::count()];
+        for voxel_type in &broken {
+            let loot = &voxel_type.properties().loot;
+            if let Some((item, count)) = loot.roll(ToolType::None, &mut loot_rng.0) {
+                drop_counts[item.id() as usize] += count;
+            }
+        }
+        let center = transform.transform_point(
+            debris
+                .voxels
+                .iter()
+                .fold(Vec3::ZERO, |acc, &(offset, _)| acc + offset.as_vec3() + 0.5)
+                * VOXEL_SIZE
+                / debris.voxels.len() as f32,
+        );
+        for (id, &count) in drop_counts.iter().enumerate() {
+            if let Some(drop_assets) = drop_assets.as_deref().filter(|_| count > 0) {
+                let item = VoxelType::from_u8(id as u8);
+                spawn_rapier_voxel_drop(&mut commands, drop_assets, item, count, center, None);
+            }
+        }
+        info!("Escombro roto: {} voxels sueltan drops", broken.len());
+    }
+}
+
+/// Quita los escombros en vuelo y las comprobaciones pendientes al volver al
+/// menú.
+pub fn teardown_debris(
+    mut commands: Commands,
+    debris: Query<Entity, With<Debris>>,
+    mut checks: ResMut<StructuralChecks>,
+) {
+    for entity in &debris {
+        commands.entity(entity).despawn();
+    }
+    checks.seeds.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_islands_without_anchor_are_unsupported() {
+        // Suelo de piedra 5×5 en y = 0; fuera de |x|,|z| <= 2 o por debajo,
+        // "sin cargar". Un pilar apoyado y un bloque de 2 voxels flotando
+        let mut world: HashMap<IVec3, VoxelType> = HashMap::new();
+        for x in -2..=2 {
+            for z in -2..=2 {
+                world.insert(IVec3::new(x, 0, z), VoxelType::Stone);
+            }
+        }
+        world.insert(IVec3::new(0, 1, 0), VoxelType::Wood);
+        world.insert(IVec3::new(0, 2, 0), VoxelType::Wood);
+        world.insert(IVec3::new(1, 3, 1), VoxelType::Leaves);
+        world.insert(IVec3::new(1, 4, 1), VoxelType::Leaves);
+        let get = |v: IVec3| {
+            (v.x.abs() <= 2 && v.z.abs() <= 2 && v.y >= 0)
+                .then(|| *world.get(&v).unwrap_or(&VoxelType::Air))
+        };
+
+        let seeds = [IVec3::new(0, 2, 0), IVec3::new(1, 3, 1), IVec3::new(1, 4, 1)];
+        let islands = unsupported_islands(seeds, get, 64);
+        assert_eq!(islands.len(), 1);
+        let mut island = islands[0].clone();
+        island.sort_by_key(|v| v.y);
+        assert_eq!(island, vec![IVec3::new(1, 3, 1), IVec3::new(1, 4, 1)]);
+
+        // Con un tope menor que la isla, la búsqueda se rinde y la da por anclada
+        assert!(unsupported_islands([IVec3::new(1, 3, 1)], get, 1).is_empty());
+    }
+
+    #[test]
+    fn debris_lands_back_on_the_grid() {
+        let voxels = [
+            (IVec3::new(0, 0, 0), VoxelType::Wood),
+            (IVec3::new(0, 1, 0), VoxelType::Wood),
+            (IVec3::new(0, 2, 0), VoxelType::Leaves),
+        ];
+
+        // Casi recto: se queda en su sitio
+        let axes = snap_rotation(Quat::from_rotation_x(0.1)).unwrap();
+        let cells = landed_cells(Vec3::new(1.0, 2.0, 0.0), axes, &voxels);
+        assert_eq!(cells[0].0, IVec3::new(10, 20, 0));
+        assert_eq!(cells[2], (IVec3::new(10, 22, 0), VoxelType::Leaves));
+
+        // Tumbado 90° sobre Z: la columna pasa a ser una fila hacia -X
+        let axes = snap_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2 + 0.05));
+        let cells = landed_cells(Vec3::new(1.0, 2.0, 0.0), axes.unwrap(), &voxels);
+        let positions: Vec<IVec3> = cells.iter().map(|&(cell, _)| cell).collect();
+        assert_eq!(positions[1] - positions[0], IVec3::NEG_X);
+        assert_eq!(positions[2] - positions[1], IVec3::NEG_X);
+
+        // A 45° no encaja: se rompe
+        assert!(snap_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_4)).is_none());
+    }
+}
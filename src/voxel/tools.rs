@@ -2,9 +2,15 @@
 //!
 //! Define las herramientas que el jugador puede usar para destruir voxels.
 //! Y sus efectividades contra diferentes materiales.
+//!
+//! Cada herramienta es un tipo (`ToolType`) de un material (`ToolTier`): el
+//! tier decide su velocidad, su durabilidad, el tamaño de su forma y qué
+//! materiales puede cosechar.
 
 use crate::voxel::voxel_types::VoxelType;
 use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::{HashSet, VecDeque};
 
 // ============================================================================
 // TOOL TYPE ENUM
@@ -13,15 +19,190 @@ use bevy::prelude::*;
 /// Tipo de herramienta que el jugador puede usar.
 ///
 /// Cada herramienta tienen efeciencia diferente contra diferentes materiales.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum ToolType {
     Pickaxe,
     Axe,
     Shovel,
     Hoe,
+    /// Tijeras: cortan hojas y follaje enteros (ver el botín de los materiales)
+    Shears,
     None,
 }
 
+/// Material de la herramienta. Escala el tamaño de su forma de destrucción
+/// (radio, profundidad, cuánta madera tala), su velocidad y su durabilidad, y
+/// decide qué puede cosechar. Ordenado de peor a mejor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord, Deserialize)]
+pub enum ToolTier {
+    #[default]
+    Wood,
+    Stone,
+    Metal,
+}
+
+impl ToolTier {
+    /// Nivel numérico del tier (1 = madera, 3 = metal).
+    pub fn level(&self) -> i32 {
+        match self {
+            ToolTier::Wood => 1,
+            ToolTier::Stone => 2,
+            ToolTier::Metal => 3,
+        }
+    }
+
+    /// Nombre del material, para la UI.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToolTier::Wood => "Wood",
+            ToolTier::Stone => "Stone",
+            ToolTier::Metal => "Metal",
+        }
+    }
+}
+
+// ============================================================================
+// DESTRUCTION SHAPES
+// ============================================================================
+
+/// Forma que rompe (o transforma) una herramienta al completar un golpe. Los
+/// tamaños van en voxels y ya vienen escalados por el tier.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DestructionShape {
+    /// Esfera centrada en el voxel golpeado (manos).
+    Sphere { radius: i32 },
+    /// Túnel del pico: cilindro de `radius` que avanza `depth` voxels en la
+    /// dirección de la cámara.
+    Tunnel { radius: i32, depth: i32 },
+    /// Disco plano de la pala: paralelo a la cara golpeada, `depth` capas hacia
+    /// dentro.
+    Disc { radius: i32, depth: i32 },
+    /// Tala del hacha: la madera conectada al voxel golpeado, hasta
+    /// `max_voxels`, si es un árbol natural (ver [`fell_tree`]).
+    Fell { max_voxels: usize },
+    /// Azada: convierte pasto/tierra en `Farmland` en un disco de `radius`
+    /// sobre la cara golpeada. No rompe nada.
+    Till { radius: i32 },
+}
+
+impl DestructionShape {
+    /// Offsets (relativos al voxel golpeado) que cubre la forma.
+    ///
+    /// - `direction`: dirección de la cámara al empezar a romper (túnel).
+    /// - `normal`: cara golpeada (disco/azada); `IVec3::ZERO` si la cámara
+    ///   estaba dentro del voxel: se usa el eje dominante de `-direction`.
+    ///
+    /// `Fell` solo devuelve el centro: su extensión depende del mundo (ver
+    /// [`connected_voxels`]).
+    pub fn offsets(&self, direction: Vec3, normal: IVec3) -> Vec<IVec3> {
+        let normal = if normal == IVec3::ZERO {
+            dominant_axis(-direction)
+        } else {
+            normal
+        };
+        match *self {
+            DestructionShape::Sphere { radius } => cube(radius)
+                .filter(|o| o.length_squared() <= radius * radius)
+                .collect(),
+            DestructionShape::Tunnel { radius, depth } => {
+                let dir = direction.normalize_or(-normal.as_vec3());
+                let r2 = (radius * radius) as f32;
+                cube(radius + depth)
+                    .filter(|o| {
+                        let o = o.as_vec3();
+                        let along = o.dot(dir);
+                        (-0.5..depth as f32 - 0.5).contains(&along)
+                            && (o - dir * along).length_squared() <= r2
+                    })
+                    .collect()
+            }
+            DestructionShape::Disc { radius, depth } => disc(normal, radius, depth),
+            DestructionShape::Till { radius } => disc(normal, radius, 1),
+            DestructionShape::Fell { .. } => vec![IVec3::ZERO],
+        }
+    }
+}
+
+/// Offsets del cubo `[-r, r]³`.
+fn cube(r: i32) -> impl Iterator<Item = IVec3> {
+    (-r..=r).flat_map(move |x| {
+        (-r..=r).flat_map(move |y| (-r..=r).map(move |z| IVec3::new(x, y, z)))
+    })
+}
+
+/// Disco perpendicular a `normal`: `depth` capas desde la cara hacia dentro
+/// (sentido `-normal`).
+fn disc(normal: IVec3, radius: i32, depth: i32) -> Vec<IVec3> {
+    cube(radius.max(depth))
+        .filter(|o| {
+            let into = -o.dot(normal);
+            let planar = *o + normal * into;
+            (0..depth).contains(&into) && planar.length_squared() <= radius * radius
+        })
+        .collect()
+}
+
+/// Eje (con signo) en el que más pesa un vector.
+fn dominant_axis(v: Vec3) -> IVec3 {
+    let a = v.abs();
+    if a.x >= a.y && a.x >= a.z {
+        IVec3::new(v.x.signum() as i32, 0, 0)
+    } else if a.y >= a.z {
+        IVec3::new(0, v.y.signum() as i32, 0)
+    } else {
+        IVec3::new(0, 0, v.z.signum() as i32)
+    }
+}
+
+/// Voxels conectados a `start` (26-vecindad, para seguir ramas en diagonal)
+/// que cumplen `matches`, en orden de cercanía (BFS) y como mucho `max`.
+/// `start` se incluye si cumple.
+pub fn connected_voxels(start: IVec3, max: usize, matches: impl Fn(IVec3) -> bool) -> Vec<IVec3> {
+    let mut found = Vec::new();
+    if !matches(start) {
+        return found;
+    }
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(voxel) = queue.pop_front() {
+        if found.len() >= max {
+            break;
+        }
+        found.push(voxel);
+        for offset in cube(1) {
+            let next = voxel + offset;
+            if seen.insert(next) && matches(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    found
+}
+
+/// Cuánta madera conectada se examina como mucho al talar. El roble más grande
+/// ronda los 2500 voxels de madera: lo que pase de aquí no es un árbol.
+pub const FELL_SEARCH_LIMIT: usize = 4096;
+
+/// Lo que tala el hacha: la madera conectada a `start` ([`connected_voxels`]),
+/// como mucho `max`, SOLO si es un árbol natural (toca copa según `is_leaves`
+/// y no pasa de [`FELL_SEARCH_LIMIT`]). Una casa o un tronco suelto solo
+/// pierden el voxel golpeado.
+pub fn fell_tree(
+    start: IVec3,
+    max: usize,
+    is_wood: impl Fn(IVec3) -> bool,
+    is_leaves: impl Fn(IVec3) -> bool,
+) -> Vec<IVec3> {
+    let mut wood = connected_voxels(start, FELL_SEARCH_LIMIT + 1, is_wood);
+    let natural = wood.len() <= FELL_SEARCH_LIMIT
+        && wood.iter().any(|&v| cube(1).any(|o| is_leaves(v + o)));
+    if !natural {
+        return vec![start];
+    }
+    wood.truncate(max);
+    wood
+}
+
 // ============================================================================
 // TOOL PROPERTIES
 // ============================================================================
@@ -29,8 +210,6 @@ pub enum ToolType {
 #[derive(Clone, Debug)]
 pub struct ToolProperties {
     /// Nombre de la herramienta.
-    // El HUD usa iconos, no el nombre; reservado para tooltips/menús.
-    #[allow(dead_code)]
     pub name: &'static str,
 
     /// Durabilidad maxima de la herramienta.
@@ -45,34 +224,47 @@ pub struct ToolProperties {
 // ============================================================================
 
 impl ToolType {
-    /// Obtiene las propiedades de esta herramienta.
-    pub fn properties(&self) -> ToolProperties {
-        match self {
-            ToolType::Pickaxe => ToolProperties {
-                name: "Pickaxe",
-                max_durability: 100,
-                speed_multiplier: 1.0,
-            },
-            ToolType::Axe => ToolProperties {
-                name: "Axe",
-                max_durability: 100,
-                speed_multiplier: 1.0,
-            },
-            ToolType::Shovel => ToolProperties {
-                name: "Shovel",
-                max_durability: 100,
-                speed_multiplier: 1.0,
-            },
-            ToolType::Hoe => ToolProperties {
-                name: "Hoe",
-                max_durability: 100,
-                speed_multiplier: 1.0,
-            },
-            ToolType::None => ToolProperties {
-                name: "Hands",
-                max_durability: 0,     // Infinito
-                speed_multiplier: 0.5, // Muy lento
-            },
+    /// Obtiene las propiedades de esta herramienta hecha de `tier`.
+    ///
+    /// La velocidad y la durabilidad las pone el material; las manos no tienen
+    /// tier.
+    pub fn properties(&self, tier: ToolTier) -> ToolProperties {
+        let (max_durability, speed_multiplier) = match tier {
+            ToolTier::Wood => (60, 1.0),
+            ToolTier::Stone => (150, 1.6),
+            ToolTier::Metal => (400, 2.4),
+        };
+        let name = match self {
+            ToolType::Pickaxe => "Pickaxe",
+            ToolType::Axe => "Axe",
+            ToolType::Shovel => "Shovel",
+            ToolType::Hoe => "Hoe",
+            ToolType::Shears => "Shears",
+            ToolType::None => {
+                return ToolProperties {
+                    name: "Hands",
+                    max_durability: 0,     // Infinito
+                    speed_multiplier: 0.5, // Muy lento
+                };
+            }
+        };
+        ToolProperties {
+            name,
+            max_durability,
+            speed_multiplier,
+        }
+    }
+
+    /// ¿Suelta algo `voxel_type` al romperlo con esta herramienta de `tier`?
+    ///
+    /// Los materiales con `harvest` (la piedra, el metal) piden una de sus
+    /// herramientas preferidas de ese tier o mejor; lo demás se cosecha incluso
+    /// a mano. Lo que no se puede cosechar se rompe igual, pero no suelta nada.
+    pub fn can_harvest(&self, tier: ToolTier, voxel_type: VoxelType) -> bool {
+        let material = voxel_type.properties();
+        match material.harvest {
+            None => true,
+            Some(min) => material.tools.contains(self) && tier >= min,
         }
     }
 
@@ -80,57 +272,45 @@ impl ToolType {
     ///
     /// REtorna un multiplicador:
     ///
-    /// - 1.0: La herramienta es eficiente
-    /// - 0.5: La herramienta es poco eficiente
-    /// - 0.0: La herramienta es ineficiente
-
+    /// - 1.5: Es una de las herramientas preferidas del material (`tools`)
+    /// - 0.3: Herramienta incorrecta o manos desnudas
     pub fn effectiveness_against(&self, voxel_type: VoxelType) -> f32 {
-        match (self, voxel_type) {
-            // Aire no necesita herramienta
-            (_, VoxelType::Air) => 1.0,
-
-            // Pico es bueno contra piedra y metal
-            (ToolType::Pickaxe, VoxelType::Stone) => 1.5,
-            (ToolType::Pickaxe, VoxelType::Metal) => 1.5,
-
-            // Hacha es buena contra madera
-            (ToolType::Axe, VoxelType::Wood | VoxelType::PineWood) => 1.5,
-
-            // Pala es buena contra tierra, pasto y arena
-            (ToolType::Shovel, VoxelType::Dirt) => 1.5,
-            (ToolType::Shovel, VoxelType::Grass) => 1.5,
-            (ToolType::Shovel, VoxelType::Sand) => 1.5,
-
-            // Manos desnudas son malas contra todo
-            (ToolType::None, _) => 0.3,
-
-            // Herramienta incorrecta
-            _ => 0.3,
+        // Aire no necesita herramienta
+        if voxel_type == VoxelType::Air {
+            return 1.0;
+        }
+        if voxel_type.properties().tools.contains(self) {
+            1.5
+        } else {
+            0.3
         }
     }
 
-    /// Obtiene el patrón de destrucción para esta herramienta
+    /// Forma de destrucción de esta herramienta para un tier dado.
     ///
-    /// Retorna una lista de posiciones relativas que se destruirán.
-    ///
-    /// Esfera de radio `BREAK_RADIUS` (igual para todas las herramientas): todos
-    /// los offsets cuya distancia al centro cabe en el radio. Sube el radio para
-    /// romper cráteres más grandes.
-    pub fn get_destruction_pattern(&self) -> Vec<IVec3> {
-        /// Radio de la esfera de destrucción, en voxels.
-        const BREAK_RADIUS: i32 = 2;
-
-        let mut pattern = Vec::new();
-        for x in -BREAK_RADIUS..=BREAK_RADIUS {
-            for y in -BREAK_RADIUS..=BREAK_RADIUS {
-                for z in -BREAK_RADIUS..=BREAK_RADIUS {
-                    if x * x + y * y + z * z <= BREAK_RADIUS * BREAK_RADIUS {
-                        pattern.push(IVec3::new(x, y, z));
-                    }
-                }
-            }
+    /// - Pico: túnel en la dirección de la cámara.
+    /// - Pala: disco plano alineado con la cara golpeada.
+    /// - Hacha: tala los árboles (la madera conectada que toca copa).
+    /// - Azada: labra el suelo (`Farmland`).
+    /// - Tijeras y manos: una esfera (la de las tijeras crece con el tier).
+    pub fn destruction_shape(&self, tier: ToolTier) -> DestructionShape {
+        let level = tier.level();
+        match self {
+            ToolType::Pickaxe => DestructionShape::Tunnel {
+                radius: level,
+                depth: 1 + 3 * level,
+            },
+            ToolType::Shovel => DestructionShape::Disc {
+                radius: 1 + level,
+                depth: level,
+            },
+            ToolType::Axe => DestructionShape::Fell {
+                max_voxels: 512 << level,
+            },
+            ToolType::Hoe => DestructionShape::Till { radius: level },
+            ToolType::Shears => DestructionShape::Sphere { radius: level },
+            ToolType::None => DestructionShape::Sphere { radius: 1 },
         }
-        pattern
     }
 }
 
@@ -141,25 +321,36 @@ impl ToolType {
 /// Componente que representa una herramienta equipada
 ///
 /// Se adjuna a una entidad (jugador) para indicar que herramienta esta usando.
-#[derive(Component, Debug)]
+/// Es una copia de la herramienta del slot en la mano del `Inventory`, donde
+/// vive su durabilidad.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub struct Tool {
     /// Tipo de herramienta equipada
     pub tool_type: ToolType,
 
+    /// Material de la herramienta (tamaño de su forma)
+    pub tier: ToolTier,
+
     /// Durabilidad actual (0 = rota    )
     pub current_durability: u32,
 }
 
 impl Tool {
     /// Crea una herramienta con durabilidad maxima.
-    pub fn new(tool_type: ToolType) -> Self {
-        let max_durability = tool_type.properties().max_durability;
+    pub fn new(tool_type: ToolType, tier: ToolTier) -> Self {
+        let max_durability = tool_type.properties(tier).max_durability;
         Self {
             tool_type,
+            tier,
             current_durability: max_durability,
         }
     }
 
+    /// Sin herramienta: a mano (no se gasta).
+    pub fn hands() -> Self {
+        Self::new(ToolType::None, ToolTier::Wood)
+    }
+
     /// Reduce la durabilidad de la herramienta.
     ///
     /// Retorna "true" si la herramienta se rompio.
@@ -170,24 +361,21 @@ impl Tool {
         }
 
         self.current_durability = self.current_durability.saturating_sub(amount);
-        self.current_durability == 0
+        self.is_broken()
     }
 
     /// Verifica si la herramienta esta rota.
-    // Consultas de durabilidad para el HUD; el sistema de durabilidad aún no está conectado.
-    #[allow(dead_code)]
     pub fn is_broken(&self) -> bool {
         self.tool_type != ToolType::None && self.current_durability == 0
     }
 
     /// Obtiene el porcentaje de durabilidad restante (0.0 - 1.0)
-    #[allow(dead_code)]
     pub fn get_durability_percentage(&self) -> f32 {
         if self.tool_type == ToolType::None {
             return 1.0; // Manos nunca se rompen
         }
 
-        let max = self.tool_type.properties().max_durability;
+        let max = self.tool_type.properties(self.tier).max_durability;
         if max == 0 {
             return 1.0; // Evita division por cero
         }
@@ -201,10 +389,81 @@ mod tests {
     use super::*;
 
     #[test]
-    fn destruction_pattern_is_a_sphere() {
-        let pattern = ToolType::Pickaxe.get_destruction_pattern();
+    fn better_tiers_are_faster_and_harvest_more() {
+        let wood = ToolType::Pickaxe.properties(ToolTier::Wood);
+        let metal = ToolType::Pickaxe.properties(ToolTier::Metal);
+        assert!(metal.speed_multiplier > wood.speed_multiplier);
+        assert!(metal.max_durability > wood.max_durability);
+
+        assert!(!ToolType::None.can_harvest(ToolTier::Wood, VoxelType::Stone));
+        assert!(ToolType::Pickaxe.can_harvest(ToolTier::Wood, VoxelType::Stone));
+        assert!(!ToolType::Pickaxe.can_harvest(ToolTier::Wood, VoxelType::Metal));
+        assert!(ToolType::Pickaxe.can_harvest(ToolTier::Stone, VoxelType::Metal));
+        assert!(!ToolType::Axe.can_harvest(ToolTier::Metal, VoxelType::Metal));
+        assert!(ToolType::None.can_harvest(ToolTier::Wood, VoxelType::Dirt));
+    }
+
+    #[test]
+    fn hands_break_a_small_sphere() {
+        let pattern = ToolType::None
+            .destruction_shape(ToolTier::Wood)
+            .offsets(Vec3::NEG_Z, IVec3::Z);
         assert!(pattern.contains(&IVec3::ZERO)); // centro
-        assert!(pattern.contains(&IVec3::new(2, 0, 0))); // borde sobre eje (dist²=4)
-        assert!(!pattern.contains(&IVec3::new(2, 2, 2))); // esquina (dist²=12) fuera
+        assert!(pattern.contains(&IVec3::new(1, 0, 0))); // borde sobre eje
+        assert!(!pattern.contains(&IVec3::new(1, 1, 0))); // diagonal fuera
+    }
+
+    #[test]
+    fn tunnel_follows_the_camera_and_grows_with_tier() {
+        let shape = ToolType::Pickaxe.destruction_shape(ToolTier::Wood);
+        let tunnel = shape.offsets(Vec3::X, IVec3::NEG_X);
+        assert!(tunnel.contains(&IVec3::new(3, 0, 0))); // depth 4: 0..=3
+        assert!(!tunnel.contains(&IVec3::new(4, 0, 0)));
+        assert!(!tunnel.contains(&IVec3::new(-1, 0, 0))); // no rompe hacia atrás
+        assert!(tunnel.contains(&IVec3::new(2, 1, 0)) && !tunnel.contains(&IVec3::new(2, 2, 0)));
+
+        let metal = ToolType::Pickaxe.destruction_shape(ToolTier::Metal);
+        assert!(metal.offsets(Vec3::X, IVec3::NEG_X).len() > tunnel.len());
+    }
+
+    #[test]
+    fn shovel_disc_lies_on_the_hit_face() {
+        // Cara superior (normal +Y): disco horizontal que baja `depth` capas
+        let disc = DestructionShape::Disc { radius: 2, depth: 2 }.offsets(Vec3::NEG_Y, IVec3::Y);
+        assert!(disc.contains(&IVec3::new(2, 0, 0)) && disc.contains(&IVec3::new(0, -1, 2)));
+        assert!(!disc.iter().any(|o| o.y > 0 || o.y < -1));
+        // Cara lateral (normal -X): el disco es vertical y entra hacia +X
+        let wall = DestructionShape::Disc { radius: 2, depth: 1 }.offsets(Vec3::X, IVec3::NEG_X);
+        assert!(wall.iter().all(|o| o.x == 0) && wall.contains(&IVec3::new(0, 2, 0)));
+    }
+
+    #[test]
+    fn fell_follows_connected_wood_only() {
+        // Tronco vertical de 10 + rama diagonal con hojas, y un tronco separado
+        let wood: HashSet<IVec3> = (0..10)
+            .map(|y| IVec3::new(0, y, 0))
+            .chain([IVec3::new(1, 10, 0), IVec3::new(5, 0, 0)])
+            .collect();
+        let leaves = |v: IVec3| v == IVec3::new(1, 11, 0);
+        let felled = fell_tree(IVec3::new(0, 3, 0), 100, |v| wood.contains(&v), leaves);
+        assert_eq!(felled.len(), 11);
+        assert!(!felled.contains(&IVec3::new(5, 0, 0)));
+        // El tope corta por cercanía al golpe
+        let capped = fell_tree(IVec3::new(0, 3, 0), 3, |v| wood.contains(&v), leaves);
+        assert_eq!(capped.len(), 3);
+        assert!(capped.iter().all(|v| (v.y - 3).abs() <= 1));
+        // Sin copa no es un árbol: solo cae el voxel golpeado
+        let plank = fell_tree(IVec3::new(0, 3, 0), 100, |v| wood.contains(&v), |_| false);
+        assert_eq!(plank, vec![IVec3::new(0, 3, 0)]);
+    }
+
+    #[test]
+    fn fell_ignores_wood_structures_bigger_than_any_tree() {
+        // Un muro de madera de 70×70 con hojas encima: demasiado grande
+        let wall = |v: IVec3| v.z == 0 && (0..70).contains(&v.x) && (0..70).contains(&v.y);
+        let leaves = |v: IVec3| v.z == 0 && v.y == 70;
+        let shape = ToolType::Axe.destruction_shape(ToolTier::Metal);
+        assert_eq!(shape, DestructionShape::Fell { max_voxels: FELL_SEARCH_LIMIT });
+        assert_eq!(fell_tree(IVec3::new(3, 3, 0), FELL_SEARCH_LIMIT, wall, leaves).len(), 1);
     }
 }
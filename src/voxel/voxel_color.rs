@@ -134,9 +134,9 @@ fn grass_color(world_x: f32, world_y: f32, world_z: f32, slope: f32) -> [f32; 4]
     ]
 }
 
-/// Color (RGB lineal) de un vértice. El **alpha lleva el discriminante de
-/// `VoxelType`** (`id/255`): el shader lo usa para leer el rango tonal de ese
-/// material en el uniform `spreads` y aplicar la variación por fragmento (o
+/// Color (RGB lineal) de un vértice. El **alpha lleva el id del material**
+/// (`VoxelType::id`/255): el shader lo usa para leer el rango tonal de ese
+/// material en el storage buffer `spreads` y aplicar la variación por fragmento (o
 /// dejar el color plano si ese material no tiene paleta).
 ///
 /// El color base es plano y uniforme por quad (así el greedy meshing fusiona);
@@ -149,7 +149,7 @@ pub fn voxel_color(
     world_z: f32,
     slope: f32,
 ) -> [f32; 4] {
-    let id = voxel_type as u8 as f32 / 255.0;
+    let id = voxel_type.id() as f32 / 255.0;
     if voxel_type == VoxelType::Grass {
         let c = grass_color(world_x, world_y, world_z, slope);
         return [c[0], c[1], c[2], id];
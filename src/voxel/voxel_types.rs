@@ -1,91 +1,167 @@
 //! Sistema de tipos de voxels
 //!
 //! Define los diferentes materiales que pueden existir en el mundo,
-//! sus propiedades físicas, y cómo se comportan.
-
-use crate::vegetation::config;
+//! sus propiedades físicas, y cómo se comportan. Los datos de cada material
+//! vienen del registro de materiales (`assets/data/materials.ron`).
+
+use crate::voxel::explosion::Blast;
+use crate::voxel::loot::LootTable;
+use crate::voxel::materials::materials;
+use crate::voxel::palette::Palette;
+use crate::voxel::{ToolTier, ToolType};
 use bevy::prelude::*;
-
-/// Helper: convierte un color de config `[r,g,b]` (sRGB) en `Color`.
-#[inline]
-fn rgb(c: [f32; 3]) -> Color {
-    Color::srgb(c[0], c[1], c[2])
-}
+use serde::de::{self, Deserializer, Visitor};
+use serde::Deserialize;
+use std::fmt;
 
 // ============================================================================
-// VOXEL TYPE ENUM
+// VOXEL TYPE
 // ============================================================================
 
-/// Número de variantes de `VoxelType` (Air=0 .. PineWood=12). Dimensiona tablas
-/// indexadas por `VoxelType as usize` (materiales de drops, inventario).
-pub const VOXEL_TYPE_COUNT: usize = 13;
-
-/// Tipo de voxel que representa diferentes materiales del mundo.
+/// Tipo de voxel: el id de un material del registro ([`materials`]).
 ///
-/// Cada tipo tiene propiedades únicas como dureza, color, y drops.
+/// Cada material tiene propiedades únicas como dureza, color, y drops, que
+/// vienen de `assets/data/materials.ron` (ver [`VoxelType::properties`]).
 ///
 /// # Diseño
+/// - `u8`: 1 byte por voxel. El id es la posición del material en el archivo
+///   de materiales, y es lo que guardan los chunks y los region files.
+/// - Los materiales que el código nombra tienen constante propia
+///   (`VoxelType::Stone`), usable en patrones de `match`; un material nuevo
+///   solo necesita su entrada en los datos.
 /// - `Copy + Clone`: Para copiar rápidamente sin allocaciones
-/// - `PartialEq + Eq`: Para comparar tipos
 /// - `Default`: Air es el valor por defecto
-/// - `u8` repr: Optimización de memoria (1 byte por voxel en lugar de 8+)
-#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
-#[repr(u8)] // Usa solo 1 byte en memoria
-pub enum VoxelType {
+/// - `Debug`/`Deserialize`: por nombre, para logs y tablas de datos (recetas)
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct VoxelType(u8);
+
+// Una constante por material con nombre, aunque el código no las use todas (a
+// los niveles de lava se llega con `lava(level)`).
+#[allow(non_upper_case_globals, dead_code)]
+impl VoxelType {
     /// Aire - espacio vacío
-    #[default]
-    Air = 0,
+    pub const Air: Self = Self(0);
 
     /// Tierra - material común, fácil de excavar
-    Dirt = 1,
+    pub const Dirt: Self = Self(1);
 
     /// Piedra - material duro, requiere pico
-    Stone = 2,
+    pub const Stone: Self = Self(2);
 
     /// Madera - de árboles, requiere hacha
-    Wood = 3,
+    pub const Wood: Self = Self(3);
 
     /// Metal - muy duro, requiere pico avanzado
-    Metal = 4,
+    pub const Metal: Self = Self(4);
 
     /// Pasto - tierra con vegetación
-    Grass = 5,
+    pub const Grass: Self = Self(5);
 
     /// Arena - material suave del desierto
-    Sand = 6,
+    pub const Sand: Self = Self(6);
 
     /// Hojas -  Follajes de los arboles
-    Leaves = 7,
+    pub const Leaves: Self = Self(7);
 
     /// Follaje atravesable (pasto): se ve pero NO colisiona.
-    Foliage = 8,
+    pub const Foliage: Self = Self(8);
 
     /// Arbusto atravesable: como el follaje pero verde más oscuro (se distingue).
-    Bush = 9,
+    pub const Bush: Self = Self(9);
 
     /// Acículas de pino: como las hojas, pero color propio (verde oscuro).
-    PineNeedles = 10,
+    pub const PineNeedles: Self = Self(10);
 
     /// Hojas de árbol pequeño: como las hojas, pero color propio (verde claro).
-    SmallLeaves = 11,
+    pub const SmallLeaves: Self = Self(11);
 
     /// Madera de pino: como `Wood`, pero con su propia paleta tonal (más oscura).
-    PineWood = 12,
+    pub const PineWood: Self = Self(12);
+
+    /// Nieve - capa superior de las cumbres
+    pub const Snow: Self = Self(13);
+
+    /// Grava - superficie suelta de las laderas
+    pub const Gravel: Self = Self(14);
+
+    /// Agua - fluido translúcido: no es sólida, se atraviesa nadando
+    pub const Water: Self = Self(15);
+
+    /// Lava fuente (nivel 0): no se agota, alimenta a la lava que fluye.
+    pub const Lava: Self = Self(16);
+
+    /// Lava que fluye, niveles 1–7 (distancia a la fuente). El nivel va en el
+    /// propio tipo para que viaje en `VoxelDiffs` y en los region files sin
+    /// almacenamiento extra. Ver [`VoxelType::lava`] y [`VoxelType::lava_level`].
+    pub const Lava1: Self = Self(17);
+    pub const Lava2: Self = Self(18);
+    pub const Lava3: Self = Self(19);
+    pub const Lava4: Self = Self(20);
+    pub const Lava5: Self = Self(21);
+    pub const Lava6: Self = Self(22);
+    pub const Lava7: Self = Self(23);
+
+    /// Tierra labrada: la deja la azada sobre pasto o tierra.
+    pub const Farmland: Self = Self(24);
+}
+
+/// Nombres de los materiales con constante, en orden de id. El archivo de
+/// materiales tiene que empezar por ellos (lo valida el registro).
+pub(crate) const BUILTIN_NAMES: [&str; 25] = [
+    "Air", "Dirt", "Stone", "Wood", "Metal", "Grass", "Sand", "Leaves", "Foliage", "Bush",
+    "PineNeedles", "SmallLeaves", "PineWood", "Snow", "Gravel", "Water", "Lava", "Lava1",
+    "Lava2", "Lava3", "Lava4", "Lava5", "Lava6", "Lava7", "Farmland",
+];
+
+/// Nivel máximo de la lava que fluye: no se extiende más allá.
+pub const LAVA_MAX_LEVEL: u8 = 7;
+
+impl fmt::Debug for VoxelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.properties().name)
+    }
+}
+
+impl<'de> Deserialize<'de> for VoxelType {
+    /// Por nombre de material, como identificador (`Stone`, sin comillas).
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NameVisitor;
+
+        impl Visitor<'_> for NameVisitor {
+            type Value = VoxelType;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("un nombre de material")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<VoxelType, E> {
+                materials()
+                    .by_name(name)
+                    .ok_or_else(|| E::custom(format!("material desconocido: {}", name)))
+            }
+        }
+
+        deserializer.deserialize_identifier(NameVisitor)
+    }
 }
 
 // ============================================================================
 // VOXEL PROPERTIES
 // ============================================================================
 
-/// Propiedades físicas y de gameplay de un tipo de voxel.
+/// Propiedades físicas y de gameplay de un tipo de voxel (una entrada del
+/// archivo de materiales).
 ///
 /// # Campos
 /// - `hardness`: Resistencia a destrucción (0.0 = instantáneo, 10.0 = muy duro)
 /// - `color`: Color base para rendering
-/// - `is_solid`: Si tiene colisión física
-/// - `drops_self`: Si dropea el mismo material al destruirse
+/// - `palette`/`glow`: Variación tonal y brillo en el shader de chunks
+/// - `loot`/`tools`/`harvest`: Qué suelta y con qué se rompe
 #[derive(Clone, Debug)]
 pub struct VoxelProperties {
+    /// Nombre del material (único; el de las recetas)
+    pub name: String,
+
     /// Dureza del material (0.0 = muy suave, 10.0 = muy duro)
     ///
     /// Esto afecta:
@@ -97,22 +173,38 @@ pub struct VoxelProperties {
     /// Color base del voxel (usado para rendering)
     pub color: Color,
 
-    /// Si el voxel es sólido (tiene colisión)
-    // Los siguientes campos son parte del modelo de material para el sistema de
-    // colisión/drops/UI, aún no consumidos por completo.
-    #[allow(dead_code)]
-    pub is_solid: bool,
+    /// Densidad del voxel
+    pub density: f32,
 
-    /// Si dropea el mismo material al destruirse
-    #[allow(dead_code)]
-    pub drops_self: bool,
+    /// Si bloquea el movimiento (el follaje se ve pero se atraviesa)
+    pub collidable: bool,
 
-    /// Nombre legible del material
-    #[allow(dead_code)]
-    pub name: &'static str,
+    /// Si arde al tocar la lava
+    pub flammable: bool,
 
-    /// Densidad del voxel
-    pub density: f32,
+    /// Si cae cuando le quitan el apoyo (arena, grava)
+    pub falls: bool,
+
+    /// Carga explosiva: explota al romperse o arder (`None` = no explota)
+    pub explosive: Option<Blast>,
+
+    /// Paleta tonal por voxel; `None` = color plano
+    pub palette: Option<Palette>,
+
+    /// Brillo propio (multiplica el color); 0.0 = no emite
+    pub glow: f32,
+
+    /// Icono en la barra rápida (`assets/icons/<icon>.png`)
+    pub icon: String,
+
+    /// Qué suelta al romperse (tabla de botín; vacía = nada)
+    pub loot: LootTable,
+
+    /// Herramientas que lo rompen rápido
+    pub tools: Vec<ToolType>,
+
+    /// Tier mínimo de una de `tools` para que suelte algo (`None` = a mano)
+    pub harvest: Option<ToolTier>,
 }
 
 // ============================================================================
@@ -120,7 +212,8 @@ pub struct VoxelProperties {
 // ============================================================================
 
 impl VoxelType {
-    /// Obtiene las propiedades de este tipo de voxel.
+    /// Obtiene las propiedades de este tipo de voxel (del registro de
+    /// materiales).
     ///
     /// # Ejemplo
     /// ```ignore
@@ -128,141 +221,118 @@ impl VoxelType {
     /// let props = stone.properties();
     /// println!("Hardness: {}", props.hardness); // 5.0
     /// ```
-    pub fn properties(&self) -> VoxelProperties {
-        match self {
-            VoxelType::Air => VoxelProperties {
-                hardness: 0.0,
-                color: Color::srgba(0.0, 0.0, 0.0, 0.0), // Transparente
-                is_solid: false,
-                drops_self: false,
-                name: "Air",
-                density: 0.0,
-            },
-
-            VoxelType::Dirt => VoxelProperties {
-                hardness: 1.0,                       // Fácil de excavar
-                color: Color::srgb(0.55, 0.35, 0.2), // Marrón tierra
-                is_solid: true,
-                drops_self: true,
-                name: "Dirt",
-                density: 1.0,
-            },
-
-            VoxelType::Stone => VoxelProperties {
-                hardness: 5.0,                     // Requiere pico
-                color: Color::srgb(0.5, 0.5, 0.5), // Gris
-                is_solid: true,
-                drops_self: true,
-                name: "Stone",
-                density: 2.5,
-            },
-
-            VoxelType::Wood => VoxelProperties {
-                hardness: 2.0, // Requiere hacha (más eficiente)
-                color: rgb(config::WOOD_COLOR),
-                is_solid: true,
-                drops_self: true,
-                name: "Wood",
-                density: 1.5,
-            },
-
-            VoxelType::Metal => VoxelProperties {
-                hardness: 10.0,                    // Muy duro, requiere pico avanzado
-                color: Color::srgb(0.7, 0.7, 0.8), // Gris metálico
-                is_solid: true,
-                drops_self: true,
-                name: "Metal",
-                density: 4.0,
-            },
-
-            VoxelType::Grass => VoxelProperties {
-                hardness: 1.0,                     // Igual que tierra
-                color: Color::srgb(0.3, 0.6, 0.2), // Verde pasto
-                is_solid: true,
-                drops_self: false, // Dropea tierra en su lugar
-                name: "Grass",
-                density: 0.5,
-            },
-
-            VoxelType::Sand => VoxelProperties {
-                hardness: 0.5,                      // Muy fácil de excavar
-                color: Color::srgb(0.9, 0.85, 0.6), // Amarillo arena
-                is_solid: true,
-                drops_self: true,
-                name: "Sand",
-                density: 1.0,
-            },
-
-            VoxelType::Leaves => VoxelProperties {
-                hardness: 0.2, // Muy suave
-                color: rgb(config::LEAVES_COLOR),
-                is_solid: true,   // Tiene colisión
-                drops_self: true, // Dropea nada o sticks
-                name: "Leaves",
-                density: 0.1,
-            },
-
-            VoxelType::Foliage => VoxelProperties {
-                hardness: 0.1, // se rompe al instante
-                color: rgb(config::GRASS_COLOR),
-                is_solid: true, // ocupa la celda (se renderiza)
-                drops_self: false,
-                name: "Foliage",
-                density: 0.1,
-            },
-
-            VoxelType::Bush => VoxelProperties {
-                hardness: 0.2,
-                color: rgb(config::BUSH_COLOR),
-                is_solid: true, // se renderiza
-                drops_self: false,
-                name: "Bush",
-                density: 0.1,
-            },
-
-            VoxelType::PineNeedles => VoxelProperties {
-                hardness: 0.2,
-                color: rgb(config::PINE_COLOR),
-                is_solid: true,
-                drops_self: true,
-                name: "PineNeedles",
-                density: 0.1,
-            },
-
-            VoxelType::SmallLeaves => VoxelProperties {
-                hardness: 0.2,
-                color: rgb(config::SMALL_LEAVES_COLOR),
-                is_solid: true,
-                drops_self: true,
-                name: "SmallLeaves",
-                density: 0.1,
-            },
-
-            VoxelType::PineWood => VoxelProperties {
-                hardness: 2.0,
-                color: rgb(config::PINE_WOOD_COLOR),
-                is_solid: true,
-                drops_self: true,
-                name: "PineWood",
-                density: 1.5,
-            },
-        }
+    #[inline]
+    pub fn properties(&self) -> &'static VoxelProperties {
+        materials().get(*self)
+    }
+
+    /// Id del material (su posición en el archivo de materiales).
+    #[inline]
+    pub fn id(self) -> u8 {
+        self.0
+    }
+
+    /// Tipo de un id sin comprobar que exista (para el propio registro).
+    #[inline]
+    pub(crate) const fn from_id(id: u8) -> Self {
+        Self(id)
+    }
+
+    /// Número de materiales registrados (ids `0..count`).
+    #[inline]
+    pub fn count() -> usize {
+        materials().count()
     }
 
-    /// Verifica si este voxel es sólido (tiene colisión).
+    /// Verifica si este voxel es sólido (ocupa la celda: se malla opaco y el
+    /// raycast lo golpea). El aire y los líquidos no lo son.
     ///
     /// Útil para optimización: evita llamar a `properties()` completo.
     #[inline]
     pub fn is_solid(&self) -> bool {
-        !matches!(self, VoxelType::Air)
+        *self != VoxelType::Air && !self.is_liquid()
+    }
+
+    /// ¿Es un líquido? Se atraviesa (sin colisión), frena al jugador y permite
+    /// nadar. El agua se malla aparte, translúcida; la lava va en el mesh opaco.
+    #[inline]
+    pub fn is_liquid(&self) -> bool {
+        *self == VoxelType::Water || self.is_lava()
+    }
+
+    /// ¿Es lava (fuente o que fluye)?
+    #[inline]
+    pub fn is_lava(&self) -> bool {
+        self.lava_level().is_some()
+    }
+
+    /// Nivel de la lava: 0 = fuente, 1..=7 = fluyendo. `None` si no es lava.
+    #[inline]
+    pub fn lava_level(&self) -> Option<u8> {
+        (VoxelType::Lava.0..=VoxelType::Lava7.0)
+            .contains(&self.0)
+            .then(|| self.0 - VoxelType::Lava.0)
+    }
+
+    /// Lava del nivel dado (0 = fuente). Niveles por encima de
+    /// [`LAVA_MAX_LEVEL`] no existen: devuelve aire.
+    #[inline]
+    pub fn lava(level: u8) -> Self {
+        if level > LAVA_MAX_LEVEL {
+            VoxelType::Air
+        } else {
+            Self(VoxelType::Lava.0 + level)
+        }
+    }
+
+    /// ¿Arde al tocar la lava? Plantas y madera se queman (`flammable` en el
+    /// archivo de materiales).
+    #[inline]
+    pub fn is_flammable(&self) -> bool {
+        self.properties().flammable
+    }
+
+    /// ¿Cae sin apoyo debajo? (`falls` en el archivo de materiales)
+    #[inline]
+    pub fn falls(&self) -> bool {
+        self.properties().falls
+    }
+
+    /// Carga explosiva del material, si la tiene (`explosive` en el archivo de
+    /// materiales): la TNT.
+    #[inline]
+    pub fn explosive(&self) -> Option<Blast> {
+        self.properties().explosive
+    }
+
+    /// ¿Es madera de tronco? El hacha la tala entera.
+    #[inline]
+    pub fn is_wood(&self) -> bool {
+        matches!(*self, VoxelType::Wood | VoxelType::PineWood)
+    }
+
+    /// ¿Es copa de árbol (hojas, agujas)? El hacha solo tala la madera que la
+    /// toca: así distingue un árbol de una construcción.
+    #[inline]
+    pub fn is_leaves(&self) -> bool {
+        matches!(
+            *self,
+            VoxelType::Leaves | VoxelType::PineNeedles | VoxelType::SmallLeaves
+        )
+    }
+
+    /// ¿Se puede labrar con la azada (→ `Farmland`)?
+    #[inline]
+    pub fn is_tillable(&self) -> bool {
+        matches!(*self, VoxelType::Grass | VoxelType::Dirt)
     }
 
     /// ¿Bloquea el movimiento? El follaje (pasto/arbustos) se ve pero se
     /// atraviesa, así que el colisionador del chunk lo ignora: es sólido para el
-    /// render, no para la física.
+    /// render, no para la física (`collidable` en el archivo de materiales).
     #[inline]
     pub fn is_collidable(&self) -> bool {
-        self.is_solid() && !matches!(self, VoxelType::Foliage | VoxelType::Bush)
+        self.is_solid() && self.properties().collidable
     }
 
     /// Verifica si este voxel es aire.
@@ -270,29 +340,17 @@ impl VoxelType {
     #[allow(dead_code)]
     #[inline]
     pub fn is_air(&self) -> bool {
-        matches!(self, VoxelType::Air)
+        *self == VoxelType::Air
     }
 
     /// Convierte un u8 a VoxelType de forma segura.
-    /// Valores fuera de rango retornan Air.
-    #[allow(dead_code)]
+    /// Ids sin material registrado retornan Air.
     #[inline]
     pub fn from_u8(value: u8) -> Self {
-        match value {
-            0 => VoxelType::Air,
-            1 => VoxelType::Dirt,
-            2 => VoxelType::Stone,
-            3 => VoxelType::Wood,
-            4 => VoxelType::Metal,
-            5 => VoxelType::Grass,
-            6 => VoxelType::Sand,
-            7 => VoxelType::Leaves,
-            8 => VoxelType::Foliage,
-            9 => VoxelType::Bush,
-            10 => VoxelType::PineNeedles,
-            11 => VoxelType::SmallLeaves,
-            12 => VoxelType::PineWood,
-            _ => VoxelType::Air,
+        if (value as usize) < Self::count() {
+            Self(value)
+        } else {
+            VoxelType::Air
         }
     }
 
@@ -372,4 +430,32 @@ mod tests {
     fn test_from_depth_air_when_no_density() {
         assert_eq!(VoxelType::from_depth(-1.0, 0.0), VoxelType::Air);
     }
+
+    #[test]
+    fn test_water_is_liquid_not_collidable() {
+        assert!(VoxelType::Water.is_liquid());
+        assert!(!VoxelType::Water.is_solid());
+        assert!(!VoxelType::Water.is_collidable());
+        assert!(!VoxelType::Water.is_air());
+    }
+
+    #[test]
+    fn test_lava_levels_roundtrip() {
+        for level in 0..=LAVA_MAX_LEVEL {
+            let lava = VoxelType::lava(level);
+            assert_eq!(lava.lava_level(), Some(level));
+            assert!(lava.is_liquid() && !lava.is_collidable());
+        }
+        assert_eq!(VoxelType::lava(LAVA_MAX_LEVEL + 1), VoxelType::Air);
+        assert_eq!(VoxelType::Water.lava_level(), None);
+    }
+
+    #[test]
+    fn test_from_u8_roundtrips_every_type() {
+        for id in 0..VoxelType::count() as u8 {
+            assert_eq!(VoxelType::from_u8(id).id(), id);
+        }
+        // Un id sin material (p. ej. de un mundo con otro archivo) es aire
+        assert_eq!(VoxelType::from_u8(u8::MAX), VoxelType::Air);
+    }
 }
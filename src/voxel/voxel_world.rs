@@ -0,0 +1,338 @@
+//! API única para leer y editar voxels en coordenadas MUNDIALES.
+//!
+//! - [`VoxelReader`]: solo lectura (`get`, `get_at`, `raycast`). Para sistemas
+//!   que consultan el mundo (movimiento, mira, daño por lava...).
+//! - [`VoxelWorld`]: lectura + escritura (`set`, `set_batch`, `modify`,
+//!   `fill_box`, `fill_sphere`, `replace`). Cada edición resuelve su chunk con
+//!   `voxel_to_chunk`, escribe el `BaseChunk`, la registra en `VoxelDiffs`, marca
+//!   `DirtyChunk` el chunk y los vecinos cuya costura cambia (una vez por lote)
+//!   y emite un [`VoxelEdited`].
+//!
+//! Si el chunk aún no es un `BaseChunk`:
+//! - **Generándose** (tarea async en vuelo): la tarea copió los diffs al
+//!   lanzarse, así que la edición se encola en [`PendingVoxelEdits`] y se aplica
+//!   cuando el chunk llega (`apply_pending_voxel_edits`).
+//! - **Vacío** (`EmptyChunk`, todo aire): se materializa como un `BaseChunk` de
+//!   aire y la edición se aplica al llegar, igual que arriba.
+//! - **Sin cargar o LOD**: basta con el diff; se aplica al generarlo.
+
+use super::{
+    chunks_affected_by, mark_chunks_dirty, raycast_voxel, voxel_to_chunk, world_to_voxel,
+    BaseChunk, ChunkGenerationTask, ChunkLOD, ChunkMap, ChunkMaterials, DirtyChunk, EmptyChunk,
+    VoxelDiffs, VoxelType,
+};
+use crate::core::constants::BASE_CHUNK_SIZE;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+// ============================================================================
+// MESSAGES / RESOURCES
+// ============================================================================
+
+/// Un voxel cambió (ya aplicado en su `BaseChunk`). Lo leen los sistemas que
+/// reaccionan a ediciones, como la lava.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoxelEdited {
+    /// Coordenada de voxel mundial.
+    pub voxel: IVec3,
+    /// Tipo nuevo.
+    pub voxel_type: VoxelType,
+}
+
+/// Ediciones a chunks que aún no son `BaseChunk` (generándose o vacíos que se
+/// están materializando). Ya están en `VoxelDiffs`; aquí esperan a que el
+/// chunk llegue para escribirse en él.
+#[derive(Resource, Debug, Default)]
+pub struct PendingVoxelEdits {
+    /// chunk_pos -> (local_pos -> voxel_type)
+    chunks: HashMap<IVec3, HashMap<IVec3, VoxelType>>,
+    /// Chunks vacíos a los que ya se pidió un `BaseChunk` de aire.
+    materializing: HashSet<IVec3>,
+}
+
+impl PendingVoxelEdits {
+    fn get(&self, chunk_pos: IVec3, local_pos: IVec3) -> Option<VoxelType> {
+        self.chunks.get(&chunk_pos)?.get(&local_pos).copied()
+    }
+
+    /// Borra todo (al destruir el mundo).
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.materializing.clear();
+    }
+}
+
+// ============================================================================
+// FORMAS
+// ============================================================================
+
+/// Voxels de la caja `[min, max]` (ambos incluidos).
+pub fn box_voxels(min: IVec3, max: IVec3) -> impl Iterator<Item = IVec3> {
+    (min.x..=max.x).flat_map(move |x| {
+        (min.y..=max.y).flat_map(move |y| (min.z..=max.z).map(move |z| IVec3::new(x, y, z)))
+    })
+}
+
+/// Voxels cuyo centro está a `radius` voxels o menos del centro de `center`.
+pub fn sphere_voxels(center: IVec3, radius: f32) -> impl Iterator<Item = IVec3> {
+    let r = radius.ceil() as i32;
+    box_voxels(center - IVec3::splat(r), center + IVec3::splat(r))
+        .filter(move |v| (*v - center).as_vec3().length_squared() <= radius * radius)
+}
+
+// ============================================================================
+// SYSTEM PARAMS
+// ============================================================================
+
+/// Lectura del mundo por coordenadas mundiales.
+#[derive(SystemParam)]
+pub struct VoxelReader<'w, 's> {
+    chunk_map: Res<'w, ChunkMap>,
+    chunks: Query<'w, 's, &'static BaseChunk>,
+    empty: Query<'w, 's, (), With<EmptyChunk>>,
+}
+
+impl VoxelReader<'_, '_> {
+    /// Voxel en una coordenada de voxel mundial, o `None` si su chunk no está
+    /// cargado. Los chunks vacíos (`EmptyChunk`) son aire.
+    pub fn get(&self, voxel: IVec3) -> Option<VoxelType> {
+        let (chunk_pos, local) = voxel_to_chunk(voxel);
+        let entity = *self.chunk_map.chunks.get(&chunk_pos)?;
+        match self.chunks.get(entity) {
+            Ok(chunk) => Some(chunk.voxel_types[local.x as usize][local.y as usize][local.z as usize]),
+            Err(_) => self.empty.contains(entity).then_some(VoxelType::Air),
+        }
+    }
+
+    /// Voxel en una posición mundial (metros).
+    pub fn get_at(&self, world_pos: Vec3) -> Option<VoxelType> {
+        self.get(world_to_voxel(world_pos).2)
+    }
+
+    /// Primer voxel sólido en el rayo (ver [`raycast_voxel`]).
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<(IVec3, VoxelType, IVec3)> {
+        raycast_voxel(origin, direction, max_distance, |v| self.get(v))
+    }
+}
+
+/// Lectura y edición del mundo por coordenadas mundiales (ver el módulo).
+#[derive(SystemParam)]
+pub struct VoxelWorld<'w, 's> {
+    commands: Commands<'w, 's>,
+    chunk_map: Res<'w, ChunkMap>,
+    // Sin ella (servidor headless) los chunks materializados no se dibujan
+    chunk_materials: Option<Res<'w, ChunkMaterials>>,
+    chunks: Query<'w, 's, &'static mut BaseChunk>,
+    empty: Query<'w, 's, (), With<EmptyChunk>>,
+    generating: Query<'w, 's, (), With<ChunkGenerationTask>>,
+    diffs: ResMut<'w, VoxelDiffs>,
+    pending: ResMut<'w, PendingVoxelEdits>,
+    edited: MessageWriter<'w, VoxelEdited>,
+}
+
+impl VoxelWorld<'_, '_> {
+    /// Voxel en una coordenada de voxel mundial, incluidas las ediciones aún
+    /// pendientes. `None` si su chunk no está cargado.
+    pub fn get(&self, voxel: IVec3) -> Option<VoxelType> {
+        let (chunk_pos, local) = voxel_to_chunk(voxel);
+        let entity = *self.chunk_map.chunks.get(&chunk_pos)?;
+        if let Some(pending) = self.pending.get(chunk_pos, local) {
+            return Some(pending);
+        }
+        match self.chunks.get(entity) {
+            Ok(chunk) => Some(chunk.voxel_types[local.x as usize][local.y as usize][local.z as usize]),
+            Err(_) => self.empty.contains(entity).then_some(VoxelType::Air),
+        }
+    }
+
+    /// Primer voxel sólido en el rayo (ver [`raycast_voxel`]).
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<(IVec3, VoxelType, IVec3)> {
+        raycast_voxel(origin, direction, max_distance, |v| self.get(v))
+    }
+
+    /// Escribe un voxel. Devuelve el tipo que había (`None` si su chunk no está
+    /// cargado: la edición queda igualmente en `VoxelDiffs`).
+    pub fn set(&mut self, voxel: IVec3, voxel_type: VoxelType) -> Option<VoxelType> {
+        let mut touched = HashSet::new();
+        let previous = self.write(voxel, voxel_type, &mut touched);
+        mark_chunks_dirty(&mut self.commands, &self.chunk_map, touched);
+        previous
+    }
+
+    /// Escribe un lote de voxels marcando cada chunk tocado una sola vez.
+    /// Devuelve cuántos cambiaron.
+    pub fn set_batch(&mut self, edits: impl IntoIterator<Item = (IVec3, VoxelType)>) -> usize {
+        let mut touched = HashSet::new();
+        let mut changed = 0;
+        for (voxel, voxel_type) in edits {
+            if self.write(voxel, voxel_type, &mut touched) != Some(voxel_type) {
+                changed += 1;
+            }
+        }
+        mark_chunks_dirty(&mut self.commands, &self.chunk_map, touched);
+        changed
+    }
+
+    /// Edición por lote condicionada al contenido: para cada voxel CARGADO,
+    /// `f(voxel, actual)` decide el tipo nuevo (`None` = no tocarlo). Los voxels
+    /// sin cargar se saltan. Devuelve cuántos cambiaron.
+    pub fn modify(
+        &mut self,
+        voxels: impl IntoIterator<Item = IVec3>,
+        mut f: impl FnMut(IVec3, VoxelType) -> Option<VoxelType>,
+    ) -> usize {
+        let mut touched = HashSet::new();
+        let mut changed = 0;
+        for voxel in voxels {
+            let Some(current) = self.get(voxel) else {
+                continue;
+            };
+            match f(voxel, current) {
+                Some(new) if new != current => {
+                    self.write(voxel, new, &mut touched);
+                    changed += 1;
+                }
+                _ => {}
+            }
+        }
+        mark_chunks_dirty(&mut self.commands, &self.chunk_map, touched);
+        changed
+    }
+
+    /// Escribe sin marcar `DirtyChunk`: acumula en `touched` los chunks a
+    /// remallar. Devuelve el tipo previo (si se conocía).
+    fn write(
+        &mut self,
+        voxel: IVec3,
+        voxel_type: VoxelType,
+        touched: &mut HashSet<IVec3>,
+    ) -> Option<VoxelType> {
+        let previous = self.get(voxel);
+        if previous == Some(voxel_type) {
+            return previous;
+        }
+        let (chunk_pos, local) = voxel_to_chunk(voxel);
+        self.diffs.record(chunk_pos, local, voxel_type);
+
+        let entity = self.chunk_map.chunks.get(&chunk_pos).copied();
+        if let Some(mut chunk) = entity.and_then(|e| self.chunks.get_mut(e).ok()) {
+            chunk.voxel_types[local.x as usize][local.y as usize][local.z as usize] = voxel_type;
+            touched.extend(chunks_affected_by(chunk_pos, local));
+            self.edited.write(VoxelEdited { voxel, voxel_type });
+            return previous;
+        }
+
+        match entity {
+            // Generándose: la tarea ya copió los diffs, hay que esperarla
+            Some(e) if self.generating.contains(e) => {}
+            // Vacío: pedir un BaseChunk de aire (una sola vez)
+            Some(e) if self.empty.contains(e) => {
+                if self.pending.materializing.insert(chunk_pos) {
+                    let mut chunk = self.commands.entity(e);
+                    chunk.try_remove::<EmptyChunk>().try_insert((
+                        BaseChunk::empty(chunk_pos),
+                        Transform::default(),
+                        ChunkLOD::Ultra,
+                    ));
+                    if let Some(materials) = &self.chunk_materials {
+                        chunk.try_insert(MeshMaterial3d(materials.real_handle(ChunkLOD::Ultra)));
+                    }
+                }
+            }
+            // Sin cargar o LOD: el diff se aplica al generar el chunk real
+            _ => return previous,
+        }
+        self.pending
+            .chunks
+            .entry(chunk_pos)
+            .or_default()
+            .insert(local, voxel_type);
+        previous
+    }
+}
+
+/// Ediciones por formas (herramientas, explosiones, comandos...).
+impl VoxelWorld<'_, '_> {
+    /// Rellena la caja `[min, max]` (coordenadas de voxel mundiales).
+    pub fn fill_box(&mut self, min: IVec3, max: IVec3, voxel_type: VoxelType) -> usize {
+        self.set_batch(box_voxels(min, max).map(|v| (v, voxel_type)))
+    }
+
+    /// Rellena una esfera de `radius` voxels alrededor de `center`.
+    pub fn fill_sphere(&mut self, center: IVec3, radius: f32, voxel_type: VoxelType) -> usize {
+        self.set_batch(sphere_voxels(center, radius).map(|v| (v, voxel_type)))
+    }
+
+    /// Cambia `from` por `to` dentro de la caja `[min, max]` (solo lo cargado).
+    pub fn replace(&mut self, min: IVec3, max: IVec3, from: VoxelType, to: VoxelType) -> usize {
+        self.modify(box_voxels(min, max), |_, v| (v == from).then_some(to))
+    }
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Aplica las ediciones pendientes a los chunks que acaban de llegar.
+pub fn apply_pending_voxel_edits(
+    mut commands: Commands,
+    mut pending: ResMut<PendingVoxelEdits>,
+    mut added: Query<(Entity, &mut BaseChunk), Added<BaseChunk>>,
+    chunk_map: Res<ChunkMap>,
+    mut edited: MessageWriter<VoxelEdited>,
+) {
+    if pending.chunks.is_empty() {
+        return;
+    }
+    for (entity, mut chunk) in &mut added {
+        let chunk_pos = chunk.position;
+        pending.materializing.remove(&chunk_pos);
+        let Some(edits) = pending.chunks.remove(&chunk_pos) else {
+            continue;
+        };
+        let mut touched = HashSet::new();
+        let origin = chunk_pos * BASE_CHUNK_SIZE as i32;
+        for (local, voxel_type) in edits {
+            chunk.voxel_types[local.x as usize][local.y as usize][local.z as usize] = voxel_type;
+            touched.extend(chunks_affected_by(chunk_pos, local));
+            edited.write(VoxelEdited {
+                voxel: origin + local,
+                voxel_type,
+            });
+        }
+        // El propio chunk puede no tener mesh aún (materializado): DirtyChunk
+        // se lo crea.
+        commands.entity(entity).try_insert(DirtyChunk);
+        mark_chunks_dirty(&mut commands, &chunk_map, touched);
+    }
+}
+
+/// Vacía las ediciones pendientes al destruir el mundo.
+pub fn reset_pending_voxel_edits(mut pending: ResMut<PendingVoxelEdits>) {
+    pending.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_cover_the_expected_voxels() {
+        assert_eq!(box_voxels(IVec3::ZERO, IVec3::new(1, 2, 3)).count(), 2 * 3 * 4);
+        assert_eq!(box_voxels(IVec3::ONE, IVec3::ZERO).count(), 0);
+        let sphere: Vec<IVec3> = sphere_voxels(IVec3::new(5, 5, 5), 1.0).collect();
+        assert_eq!(sphere.len(), 7); // centro + 6 vecinas
+        assert!(sphere.contains(&IVec3::new(5, 6, 5)));
+    }
+}
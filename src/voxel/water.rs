@@ -0,0 +1,69 @@
+//! Agua: render translúcido del agua.
+//!
+//! El agua es un `VoxelType` más dentro de `BaseChunk` (la genera
+//! `generate_terrain` bajo `SEA_LEVEL`), pero no es sólida: el mesh opaco y el
+//! collider la ignoran. Su superficie se malla aparte
+//! (`greedy_mesh_basechunk_water`) en una entidad hija del chunk con un material
+//! con transparencia, y se rehace cada vez que cambia el mesh opaco del chunk
+//! (generación, remallado por destrucción...).
+
+use super::{greedy_mesh_basechunk_water, BaseChunk, ChunkMap};
+use bevy::prelude::*;
+
+/// Opacidad de la superficie del agua (0 = invisible, 1 = opaca).
+const WATER_ALPHA: f32 = 0.6;
+
+/// Marcador de la entidad hija que lleva el mesh de agua de un chunk.
+#[derive(Component)]
+pub struct WaterMesh;
+
+/// Material compartido por todos los meshes de agua: blanco translúcido (el
+/// color lo ponen los vertex colors), sin culling para verlo desde abajo.
+#[derive(Resource)]
+pub struct WaterMaterial(pub Handle<StandardMaterial>);
+
+impl FromWorld for WaterMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(materials.add(StandardMaterial {
+            base_color: Color::srgba(1.0, 1.0, 1.0, WATER_ALPHA),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.1,
+            cull_mode: None,
+            double_sided: true,
+            ..default()
+        }))
+    }
+}
+
+/// Rehace el mesh de agua de los chunks cuyo mesh opaco acaba de cambiar.
+///
+/// Se engancha a `Changed<Mesh3d>` en lugar de tocar cada sitio que malla un
+/// chunk: cualquier (re)mallado opaco arrastra el del agua.
+pub fn update_water_meshes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    water_material: Res<WaterMaterial>,
+    changed: Query<(Entity, &BaseChunk, Option<&Children>), Changed<Mesh3d>>,
+    water_meshes: Query<(), With<WaterMesh>>,
+    chunk_map: Res<ChunkMap>,
+    chunks: Query<&BaseChunk>,
+) {
+    for (entity, chunk, children) in &changed {
+        // Quitar el mesh de agua anterior (si lo había)
+        for child in children.into_iter().flatten() {
+            if water_meshes.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+
+        if let Some(mesh) = greedy_mesh_basechunk_water(chunk, &chunk_map, &chunks) {
+            commands.entity(entity).with_child((
+                WaterMesh,
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(water_material.0.clone()),
+                Transform::default(),
+            ));
+        }
+    }
+}
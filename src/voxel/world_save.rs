@@ -0,0 +1,849 @@
+//! Persistencia del mundo en disco: seed + `VoxelDiffs` en region files.
+//!
+//! El mundo autoritativo es "seed + diffs", así que basta con guardar eso. Cada
+//! mundo es una carpeta en `saves/` con un `world.meta` de texto (nombre, seed,
+//! última partida, tiempo jugado) y los diffs agrupados en regiones de
+//! `REGION_SIZE`×`REGION_SIZE` chunks (todas las Y de esa huella XZ en un
+//! fichero). Sustituye a la caché síncrona de chunks completos: aquí solo viaja
+//! lo que el jugador cambió.
+//!
+//! Las regiones guardan el id (`u8`) de cada material, que es su posición en
+//! `materials.ron`; por eso `world.meta` apunta el nombre de cada id con el que
+//! se escribieron y al abrir el mundo se traducen al registro actual: añadir o
+//! reordenar materiales no cambia los voxels guardados.
+//!
+//! La escritura es asíncrona (`AsyncComputeTaskPool`): cada guardado clona los
+//! diffs de las regiones sucias y las serializa en el hilo de fondo. Como mucho
+//! hay una escritura en vuelo por fichero: la siguiente espera a que termine la
+//! anterior, así una foto vieja nunca pisa a una más nueva. La lectura
+//! ocurre una sola vez al abrir el mundo desde la pantalla de selección
+//! (`ui/world_select.rs`), antes de generar el terreno, así
+//! `load_chunks_system` aplica los diffs exactamente igual que en memoria.
+//!
+//! ## Formato de región (little endian)
+//! ```text
+//! "VXRG" | version: u16 | n_chunks: u32
+//! por chunk: x,y,z: i32 | n_voxels: u32 | por voxel: local: u16 (x|y<<5|z<<10), tipo: u8
+//! ```
+
+use crate::core::WorldSeed;
+use crate::voxel::{materials, VoxelDiffs, VoxelType};
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Lado de una región, en chunks (huella XZ; la región abarca todas las Y).
+pub const REGION_SIZE: i32 = 16;
+
+/// Carpeta raíz de las partidas guardadas (relativa al directorio de trabajo).
+pub const SAVES_DIR: &str = "saves";
+
+/// Cada cuánto se vuelcan a disco las regiones modificadas (segundos de juego).
+const AUTOSAVE_INTERVAL_SECS: f32 = 10.0;
+
+const META_FILE: &str = "world.meta";
+const REGION_DIR: &str = "region";
+const REGION_MAGIC: [u8; 4] = *b"VXRG";
+const REGION_VERSION: u16 = 1;
+
+/// Diffs de una región lista para serializar: (chunk_pos, [(local_pos, tipo)]).
+pub type RegionDiffs = Vec<(IVec3, Vec<(IVec3, VoxelType)>)>;
+
+/// Mundo abierto actualmente: carpeta donde se lee y escribe y sus metadatos.
+///
+/// Solo existe mientras hay partida: lo inserta quien abre el mundo (pantalla
+/// de selección) y lo quita `save_world_now` al volver al menú.
+#[derive(Resource, Clone, Debug)]
+pub struct ActiveWorld {
+    pub dir: PathBuf,
+    pub meta: WorldMeta,
+}
+
+/// Estado del guardado asíncrono: escrituras en vuelo, reloj del autoguardado
+/// y tiempo jugado aún no volcado a `world.meta`.
+#[derive(Resource, Default)]
+pub struct WorldSaveState {
+    /// Última escritura lanzada de cada fichero
+    pending: HashMap<PathBuf, Task<io::Result<()>>>,
+    since_last_save: f32,
+    unsaved_play_time: f32,
+}
+
+impl WorldSaveState {
+    /// Segundos jugados desde el último volcado a `world.meta`.
+    pub fn unsaved_play_time(&self) -> f32 {
+        self.unsaved_play_time
+    }
+}
+
+// ============================================================================
+// FORMATO
+// ============================================================================
+
+/// Región (XZ) que contiene un chunk.
+pub fn region_of(chunk_pos: IVec3) -> IVec2 {
+    IVec2::new(
+        chunk_pos.x.div_euclid(REGION_SIZE),
+        chunk_pos.z.div_euclid(REGION_SIZE),
+    )
+}
+
+fn region_path(world_dir: &Path, region: IVec2) -> PathBuf {
+    world_dir
+        .join(REGION_DIR)
+        .join(format!("r.{}.{}.vxr", region.x, region.y))
+}
+
+/// Empaqueta una posición local (0..32 por eje) en 15 bits.
+pub(crate) fn pack_local(local: IVec3) -> u16 {
+    (local.x as u16) | (local.y as u16) << 5 | (local.z as u16) << 10
+}
+
+pub(crate) fn unpack_local(packed: u16) -> IVec3 {
+    IVec3::new(
+        (packed & 0x1f) as i32,
+        ((packed >> 5) & 0x1f) as i32,
+        ((packed >> 10) & 0x1f) as i32,
+    )
+}
+
+/// Serializa los diffs de una región.
+pub fn encode_region(chunks: &RegionDiffs) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&REGION_MAGIC);
+    out.extend_from_slice(&REGION_VERSION.to_le_bytes());
+    out.extend_from_slice(&(chunks.len() as u32).to_le_bytes());
+    for (chunk_pos, voxels) in chunks {
+        for c in chunk_pos.to_array() {
+            out.extend_from_slice(&c.to_le_bytes());
+        }
+        out.extend_from_slice(&(voxels.len() as u32).to_le_bytes());
+        for (local, voxel_type) in voxels {
+            out.extend_from_slice(&pack_local(*local).to_le_bytes());
+            out.push(voxel_type.id());
+        }
+    }
+    out
+}
+
+/// Lector secuencial mínimo sobre un buffer de bytes (little endian). Lo
+/// comparten las regiones y el protocolo de red (`net::protocol`).
+pub(crate) struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub(crate) fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let end = self.pos + N;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "datos truncados"))?;
+        self.pos = end;
+        Ok(slice.try_into().unwrap())
+    }
+
+    /// Los siguientes `len` bytes (para campos de longitud variable).
+    pub(crate) fn slice(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self.pos + len;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "datos truncados"))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub(crate) fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn f32(&mut self) -> io::Result<f32> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn f64(&mut self) -> io::Result<f64> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+}
+
+/// Deserializa una región escrita por `encode_region` con los ids del registro
+/// actual.
+pub fn decode_region(bytes: &[u8]) -> io::Result<RegionDiffs> {
+    decode_region_with(bytes, VoxelType::from_u8)
+}
+
+/// Deserializa una región traduciendo cada id guardado con `material`.
+fn decode_region_with(bytes: &[u8], material: impl Fn(u8) -> VoxelType) -> io::Result<RegionDiffs> {
+    let mut r = ByteReader::new(bytes);
+    if r.take::<4>()? != REGION_MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "no es una región"));
+    }
+    let version = r.u16()?;
+    if version != REGION_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("versión de región desconocida: {}", version),
+        ));
+    }
+
+    let n_chunks = r.u32()?;
+    let mut chunks = Vec::with_capacity(n_chunks as usize);
+    for _ in 0..n_chunks {
+        let chunk_pos = IVec3::new(r.i32()?, r.i32()?, r.i32()?);
+        let n_voxels = r.u32()?;
+        let mut voxels = Vec::with_capacity(n_voxels as usize);
+        for _ in 0..n_voxels {
+            let local = unpack_local(r.u16()?);
+            let voxel_type = material(r.u8()?);
+            voxels.push((local, voxel_type));
+        }
+        chunks.push((chunk_pos, voxels));
+    }
+    Ok(chunks)
+}
+
+/// Escribe un fichero de forma atómica (tmp + rename): un cierre a mitad de
+/// escritura nunca deja una región corrupta. Cada llamada usa su propio tmp
+/// (proceso + contador), así dos escrituras nunca comparten fichero a medias.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let n = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let tmp = path.with_extension(format!("{}-{}.tmp", std::process::id(), n));
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path)
+}
+
+// ============================================================================
+// METADATOS
+// ============================================================================
+
+/// Segundos UNIX actuales (para `last_played`).
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Contenido de `world.meta`: lo que muestra la pantalla de selección.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldMeta {
+    pub name: String,
+    pub seed: i32,
+    /// Última vez que se abrió (segundos UNIX).
+    pub last_played: u64,
+    /// Tiempo total jugado en segundos.
+    pub play_time_secs: u64,
+    /// Nombre del material de cada id con el que se escribieron las regiones.
+    /// Vacío en mundos antiguos: sus ids se leen tal cual.
+    pub materials: Vec<String>,
+}
+
+impl WorldMeta {
+    pub fn new(name: &str, seed: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            seed,
+            last_played: now_secs(),
+            play_time_secs: 0,
+            materials: material_names(),
+        }
+    }
+
+    /// Serializa como líneas `clave=valor`.
+    pub fn to_text(&self) -> String {
+        format!(
+            "name={}\nseed={}\nlast_played={}\nplay_time={}\nmaterials={}\n",
+            self.name,
+            self.seed,
+            self.last_played,
+            self.play_time_secs,
+            self.materials.join(",")
+        )
+    }
+
+    /// Parsea `world.meta`. Solo `seed` es obligatorio; el nombre cae a
+    /// `fallback_name` (la carpeta) para metas antiguos que no lo tenían.
+    pub fn parse(text: &str, fallback_name: &str) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut name = None;
+        let mut seed = None;
+        let mut last_played = 0;
+        let mut play_time_secs = 0;
+        let mut materials = Vec::new();
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "seed" => seed = Some(value.parse().map_err(|e| invalid(format!("seed: {}", e)))?),
+                "last_played" => last_played = value.parse().unwrap_or(0),
+                "play_time" => play_time_secs = value.parse().unwrap_or(0),
+                "materials" => {
+                    materials = value
+                        .split(',')
+                        .map(|name| name.trim().to_string())
+                        .filter(|name| !name.is_empty())
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            name: name.unwrap_or_else(|| fallback_name.to_string()),
+            seed: seed.ok_or_else(|| invalid("world.meta sin seed".to_string()))?,
+            last_played,
+            play_time_secs,
+            materials,
+        })
+    }
+}
+
+/// Nombres de los materiales del registro actual, en orden de id.
+fn material_names() -> Vec<String> {
+    materials().iter().map(|(_, material)| material.name.clone()).collect()
+}
+
+/// Traducción de los ids guardados con la tabla `saved` (nombre de cada id) a
+/// los del registro actual, indexada por id guardado. Un material que ya no
+/// existe pasa a aire (con aviso); sin tabla, los ids se leen tal cual.
+fn material_remap(saved: &[String]) -> Vec<VoxelType> {
+    if saved.is_empty() {
+        return (0..=u8::MAX).map(VoxelType::from_u8).collect();
+    }
+    saved
+        .iter()
+        .map(|name| {
+            materials().by_name(name).unwrap_or_else(|| {
+                warn!("El material guardado '{}' ya no existe: se lee como aire", name);
+                VoxelType::Air
+            })
+        })
+        .collect()
+}
+
+/// Lee `world.meta`. `None` si la carpeta no es un mundo.
+pub fn read_world_meta(world_dir: &Path) -> io::Result<Option<WorldMeta>> {
+    let text = match fs::read_to_string(world_dir.join(META_FILE)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let fallback = world_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    WorldMeta::parse(&text, &fallback).map(Some)
+}
+
+/// Escribe `world.meta`.
+pub fn write_world_meta(world_dir: &Path, meta: &WorldMeta) -> io::Result<()> {
+    write_atomic(&world_dir.join(META_FILE), meta.to_text().as_bytes())
+}
+
+/// Lee todas las regiones del mundo y las vuelca en un mapa de diffs, con los
+/// ids traducidos desde `saved_materials` (la tabla de su `world.meta`).
+pub fn read_world_diffs(
+    world_dir: &Path,
+    saved_materials: &[String],
+) -> io::Result<HashMap<IVec3, HashMap<IVec3, VoxelType>>> {
+    let remap = material_remap(saved_materials);
+    let material = |id: u8| remap.get(id as usize).copied().unwrap_or(VoxelType::Air);
+    let mut diffs: HashMap<IVec3, HashMap<IVec3, VoxelType>> = HashMap::new();
+    let entries = match fs::read_dir(world_dir.join(REGION_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(diffs),
+        Err(e) => return Err(e),
+    };
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "vxr") {
+            continue;
+        }
+        for (chunk_pos, voxels) in decode_region_with(&fs::read(&path)?, material)? {
+            diffs.entry(chunk_pos).or_default().extend(voxels);
+        }
+    }
+    Ok(diffs)
+}
+
+// ============================================================================
+// SLOTS DE GUARDADO
+// ============================================================================
+
+/// Un mundo guardado en disco: su carpeta y sus metadatos.
+#[derive(Clone, Debug)]
+pub struct SavedWorld {
+    pub dir: PathBuf,
+    pub meta: WorldMeta,
+}
+
+/// Lista los mundos de `saves/`, del jugado más recientemente al más antiguo.
+/// Las carpetas sin `world.meta` válido se ignoran (con aviso).
+pub fn list_worlds() -> Vec<SavedWorld> {
+    list_worlds_in(Path::new(SAVES_DIR)).unwrap_or_else(|e| {
+        if e.kind() != io::ErrorKind::NotFound {
+            error!("No se pudo listar {}: {}", SAVES_DIR, e);
+        }
+        Vec::new()
+    })
+}
+
+fn list_worlds_in(root: &Path) -> io::Result<Vec<SavedWorld>> {
+    let mut worlds = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let dir = entry?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        match read_world_meta(&dir) {
+            Ok(Some(meta)) => worlds.push(SavedWorld { dir, meta }),
+            Ok(None) => {}
+            Err(e) => warn!("Mundo ignorado en {:?}: {}", dir, e),
+        }
+    }
+    worlds.sort_by_key(|w| Reverse(w.meta.last_played));
+    Ok(worlds)
+}
+
+/// Nombre de carpeta seguro derivado del nombre del mundo, sin pisar otro.
+fn unique_world_dir(root: &Path, name: &str) -> PathBuf {
+    let mut slug: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if slug.is_empty() {
+        slug = "world".to_string();
+    }
+
+    let mut dir = root.join(&slug);
+    let mut n = 2;
+    while dir.exists() {
+        dir = root.join(format!("{}_{}", slug, n));
+        n += 1;
+    }
+    dir
+}
+
+/// Crea un mundo nuevo (solo `world.meta`: el terreno sale de la seed).
+pub fn create_world(name: &str, seed: i32) -> io::Result<SavedWorld> {
+    create_world_in(Path::new(SAVES_DIR), name, seed)
+}
+
+fn create_world_in(root: &Path, name: &str, seed: i32) -> io::Result<SavedWorld> {
+    // El meta es por líneas: un salto de línea en el nombre lo rompería
+    let name = name.replace(['\n', '\r'], " ");
+    let dir = unique_world_dir(root, &name);
+    let meta = WorldMeta::new(name.trim(), seed);
+    write_world_meta(&dir, &meta)?;
+    Ok(SavedWorld { dir, meta })
+}
+
+/// Borra un mundo y todas sus regiones.
+pub fn delete_world(world: &SavedWorld) -> io::Result<()> {
+    fs::remove_dir_all(&world.dir)
+}
+
+/// Copia un mundo completo con el nombre "<nombre> (copy)".
+pub fn duplicate_world(world: &SavedWorld) -> io::Result<SavedWorld> {
+    let root = world.dir.parent().unwrap_or(Path::new(SAVES_DIR));
+    let name = format!("{} (copy)", world.meta.name);
+    let dir = unique_world_dir(root, &name);
+    copy_dir_recursive(&world.dir, &dir)?;
+
+    let meta = WorldMeta {
+        name,
+        ..world.meta.clone()
+    };
+    write_world_meta(&dir, &meta)?;
+    Ok(SavedWorld { dir, meta })
+}
+
+fn copy_dir_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Abre un mundo guardado: fija `WorldSeed` y carga sus regiones en
+/// `VoxelDiffs`. Debe llamarse ANTES de la transición `MainMenu → InGame`, que
+/// es la que corre `setup` con esa seed. El llamador inserta el `ActiveWorld`.
+///
+/// Si los materiales cambiaron desde el último guardado, reescribe todas las
+/// regiones con los ids actuales antes de apuntar la tabla nueva en el meta.
+pub fn open_world(
+    world: &SavedWorld,
+    world_seed: &mut WorldSeed,
+    voxel_diffs: &mut VoxelDiffs,
+) -> io::Result<ActiveWorld> {
+    let diffs = read_world_diffs(&world.dir, &world.meta.materials)?;
+    info!(
+        "Mundo '{}' cargado de {:?}: seed {}, {} chunks modificados",
+        world.meta.name,
+        world.dir,
+        world.meta.seed,
+        diffs.len()
+    );
+
+    world_seed.0 = world.meta.seed;
+    voxel_diffs.clear();
+    voxel_diffs.chunks = diffs;
+
+    let mut meta = world.meta.clone();
+    let current = material_names();
+    if meta.materials != current {
+        let mut regions: HashMap<IVec2, RegionDiffs> = HashMap::new();
+        for (chunk_pos, voxels) in &voxel_diffs.chunks {
+            regions
+                .entry(region_of(*chunk_pos))
+                .or_default()
+                .push((*chunk_pos, voxels.iter().map(|(l, t)| (*l, *t)).collect()));
+        }
+        for (region, chunks) in regions {
+            write_atomic(&region_path(&world.dir, region), &encode_region(&chunks))?;
+        }
+        meta.materials = current;
+    }
+    meta.last_played = now_secs();
+    write_world_meta(&world.dir, &meta)?;
+
+    Ok(ActiveWorld {
+        dir: world.dir.clone(),
+        meta,
+    })
+}
+
+// ============================================================================
+// GUARDADO
+// ============================================================================
+
+/// Lanza en el hilo de fondo la escritura de `path` con los bytes que devuelva
+/// `encode`. Si ese fichero ya tenía una escritura en vuelo, la nueva la espera
+/// antes de escribir: el orden en disco es el orden de los guardados.
+fn queue_write(
+    save_state: &mut WorldSaveState,
+    path: PathBuf,
+    encode: impl FnOnce() -> Vec<u8> + Send + 'static,
+) {
+    let previous = save_state.pending.remove(&path);
+    let target = path.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let previous = match previous {
+            Some(task) => task.await,
+            None => Ok(()),
+        };
+        let written = write_atomic(&target, &encode());
+        previous.and(written)
+    });
+    save_state.pending.insert(path, task);
+}
+
+/// Lanza en el hilo de fondo la escritura de `world.meta` y de las regiones
+/// con cambios.
+///
+/// Se reescribe la región entera (todos sus chunks con diffs), no solo el chunk
+/// sucio: así cada fichero es autocontenido y no hace falta leer antes de escribir.
+fn spawn_world_writes(
+    active_world: &mut ActiveWorld,
+    voxel_diffs: &mut VoxelDiffs,
+    save_state: &mut WorldSaveState,
+) {
+    // Tiempo jugado: se acumula en segundos enteros, el resto queda para la próxima
+    let whole_secs = save_state.unsaved_play_time.floor();
+    save_state.unsaved_play_time -= whole_secs;
+    active_world.meta.play_time_secs += whole_secs as u64;
+    active_world.meta.last_played = now_secs();
+
+    let meta = active_world.meta.clone();
+    queue_write(save_state, active_world.dir.join(META_FILE), move || {
+        meta.to_text().into_bytes()
+    });
+
+    let dirty = voxel_diffs.take_dirty();
+    if dirty.is_empty() {
+        return;
+    }
+
+    let mut regions: HashMap<IVec2, RegionDiffs> = HashMap::new();
+    for chunk_pos in dirty {
+        regions.entry(region_of(chunk_pos)).or_default();
+    }
+    for (chunk_pos, voxels) in &voxel_diffs.chunks {
+        if let Some(region) = regions.get_mut(&region_of(*chunk_pos)) {
+            region.push((*chunk_pos, voxels.iter().map(|(l, t)| (*l, *t)).collect()));
+        }
+    }
+
+    for (region, chunks) in regions {
+        let path = region_path(&active_world.dir, region);
+        queue_write(save_state, path, move || encode_region(&chunks));
+    }
+}
+
+/// Recoge las escrituras terminadas y reporta errores.
+fn poll_pending_writes(save_state: &mut WorldSaveState) {
+    save_state.pending.retain(|_, task| {
+        match future::block_on(future::poll_once(task)) {
+            Some(Ok(())) => false,
+            Some(Err(e)) => {
+                error!("Error guardando el mundo: {}", e);
+                false
+            }
+            None => true,
+        }
+    });
+}
+
+// ============================================================================
+// BEVY SYSTEMS
+// ============================================================================
+
+/// Autoguardado periódico (solo en juego: el tiempo en pausa no cuenta).
+pub fn autosave_world_system(
+    time: Res<Time>,
+    active_world: Option<ResMut<ActiveWorld>>,
+    mut voxel_diffs: ResMut<VoxelDiffs>,
+    mut save_state: ResMut<WorldSaveState>,
+) {
+    poll_pending_writes(&mut save_state);
+
+    let Some(mut active_world) = active_world else {
+        return;
+    };
+
+    save_state.unsaved_play_time += time.delta_secs();
+    save_state.since_last_save += time.delta_secs();
+    if save_state.since_last_save < AUTOSAVE_INTERVAL_SECS {
+        return;
+    }
+    save_state.since_last_save = 0.0;
+
+    spawn_world_writes(&mut active_world, &mut voxel_diffs, &mut save_state);
+}
+
+/// Vuelca lo pendiente al salir al menú y cierra el mundo (debe correr ANTES
+/// de `teardown_world`, que borra los diffs). Las tareas siguen en el pool y
+/// terminan solas.
+pub fn save_world_now(
+    mut commands: Commands,
+    active_world: Option<ResMut<ActiveWorld>>,
+    mut voxel_diffs: ResMut<VoxelDiffs>,
+    mut save_state: ResMut<WorldSaveState>,
+) {
+    let Some(mut active_world) = active_world else {
+        return;
+    };
+    spawn_world_writes(&mut active_world, &mut voxel_diffs, &mut save_state);
+    save_state.since_last_save = 0.0;
+    commands.remove_resource::<ActiveWorld>();
+}
+
+/// Al cerrar la app, escribe lo pendiente y ESPERA a que termine: después de
+/// `AppExit` el pool de tareas ya no tiene garantizado seguir vivo.
+pub fn flush_world_on_exit(
+    mut exit: MessageReader<AppExit>,
+    active_world: Option<ResMut<ActiveWorld>>,
+    mut voxel_diffs: ResMut<VoxelDiffs>,
+    mut save_state: ResMut<WorldSaveState>,
+) {
+    if exit.read().next().is_none() {
+        return;
+    }
+
+    if let Some(mut active_world) = active_world {
+        spawn_world_writes(&mut active_world, &mut voxel_diffs, &mut save_state);
+    }
+    for (_, task) in save_state.pending.drain() {
+        if let Err(e) = future::block_on(task) {
+            error!("Error guardando el mundo: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::BASE_CHUNK_SIZE;
+
+    #[test]
+    fn region_roundtrip_preserves_diffs() {
+        let chunks: RegionDiffs = vec![
+            (
+                IVec3::new(-3, 1, 15),
+                vec![
+                    (IVec3::new(0, 0, 0), VoxelType::Air),
+                    (IVec3::new(31, 31, 31), VoxelType::Stone),
+                ],
+            ),
+            (IVec3::new(2, -1, 0), vec![(IVec3::new(5, 17, 9), VoxelType::Wood)]),
+        ];
+        assert_eq!(decode_region(&encode_region(&chunks)).unwrap(), chunks);
+    }
+
+    #[test]
+    fn region_of_groups_negative_chunks() {
+        assert_eq!(region_of(IVec3::new(-1, 5, 0)), IVec2::new(-1, 0));
+        assert_eq!(region_of(IVec3::new(REGION_SIZE, 0, -REGION_SIZE)), IVec2::new(1, -1));
+    }
+
+    #[test]
+    fn truncated_region_is_an_error() {
+        let bytes = encode_region(&vec![(IVec3::ZERO, vec![(IVec3::ONE, VoxelType::Dirt)])]);
+        assert!(decode_region(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn local_positions_fit_in_chunk_bits() {
+        let max = BASE_CHUNK_SIZE as i32 - 1;
+        let local = IVec3::new(max, 0, max);
+        assert_eq!(unpack_local(pack_local(local)), local);
+    }
+
+    #[test]
+    fn meta_roundtrip_and_legacy_seed_only() {
+        let meta = WorldMeta {
+            name: "Mi mundo = 1".to_string(),
+            seed: -42,
+            last_played: 1_700_000_000,
+            play_time_secs: 3725,
+            materials: vec!["Air".to_string(), "Tnt".to_string()],
+        };
+        assert_eq!(WorldMeta::parse(&meta.to_text(), "x").unwrap(), meta);
+
+        let legacy = WorldMeta::parse("seed=7\n", "carpeta").unwrap();
+        assert_eq!((legacy.name.as_str(), legacy.seed), ("carpeta", 7));
+        assert!(legacy.materials.is_empty());
+        assert!(WorldMeta::parse("name=a\n", "a").is_err());
+    }
+
+    #[test]
+    fn create_duplicate_list_delete_slots() {
+        let root = std::env::temp_dir().join(format!("bevy_game_slots_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let world = create_world_in(&root, "Isla", 123).unwrap();
+        let chunks: RegionDiffs = vec![(IVec3::ZERO, vec![(IVec3::ONE, VoxelType::Air)])];
+        write_atomic(&region_path(&world.dir, IVec2::ZERO), &encode_region(&chunks)).unwrap();
+
+        let copy = duplicate_world(&world).unwrap();
+        assert_ne!(copy.dir, world.dir);
+        assert_eq!(copy.meta.name, "Isla (copy)");
+        assert_eq!(
+            read_world_diffs(&copy.dir, &copy.meta.materials).unwrap(),
+            read_world_diffs(&world.dir, &world.meta.materials).unwrap()
+        );
+
+        // Mismo nombre → carpeta distinta
+        let twin = create_world_in(&root, "Isla", 5).unwrap();
+        assert_ne!(twin.dir, world.dir);
+        assert_eq!(list_worlds_in(&root).unwrap().len(), 3);
+
+        delete_world(&world).unwrap();
+        let seeds: Vec<_> = list_worlds_in(&root).unwrap().into_iter().map(|w| w.meta.seed).collect();
+        assert_eq!(seeds.len(), 2);
+        assert!(seeds.contains(&123) && seeds.contains(&5));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn saved_material_ids_are_remapped_by_name() {
+        let root = std::env::temp_dir().join(format!("bevy_game_remap_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        // Un mundo escrito con otro orden de materiales (y uno que ya no existe)
+        let mut world = create_world_in(&root, "Viejo", 1).unwrap();
+        world.meta.materials = ["Air", "Tnt", "Stone", "Glass"].map(String::from).to_vec();
+        write_world_meta(&world.dir, &world.meta).unwrap();
+        let raw: RegionDiffs = vec![(
+            IVec3::ZERO,
+            (1..=3u8).map(|id| (IVec3::new(id as i32, 0, 0), VoxelType::from_id(id))).collect(),
+        )];
+        write_atomic(&region_path(&world.dir, IVec2::ZERO), &encode_region(&raw)).unwrap();
+
+        let (mut seed, mut diffs) = (WorldSeed(0), VoxelDiffs::default());
+        let active = open_world(&world, &mut seed, &mut diffs).unwrap();
+        let tnt = materials().by_name("Tnt").unwrap();
+        let expected = HashMap::from([
+            (IVec3::new(1, 0, 0), tnt),
+            (IVec3::new(2, 0, 0), VoxelType::Stone),
+            (IVec3::new(3, 0, 0), VoxelType::Air),
+        ]);
+        assert_eq!(diffs.chunks[&IVec3::ZERO], expected);
+
+        // Regiones reescritas con los ids actuales y la tabla nueva en el meta
+        assert_eq!(active.meta.materials, material_names());
+        let reopened = read_world_meta(&world.dir).unwrap().unwrap();
+        let again = read_world_diffs(&world.dir, &reopened.materials).unwrap();
+        assert_eq!(again[&IVec3::ZERO], expected);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn queued_writes_to_one_file_land_in_order() {
+        AsyncComputeTaskPool::get_or_init(Default::default);
+        let root = std::env::temp_dir().join(format!("bevy_game_queue_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let path = root.join("r.0.0.vxr");
+
+        let mut save_state = WorldSaveState::default();
+        for i in 0..8u8 {
+            queue_write(&mut save_state, path.clone(), move || vec![i; 1 << 16]);
+        }
+        // Una sola escritura en vuelo por fichero: cada una espera a la anterior
+        assert_eq!(save_state.pending.len(), 1);
+        for (_, task) in save_state.pending.drain() {
+            future::block_on(task).unwrap();
+        }
+
+        assert_eq!(fs::read(&path).unwrap(), vec![7u8; 1 << 16]);
+        // Ningún tmp huérfano
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}